@@ -1,26 +1,39 @@
 use clap::{App, Arg, ArgMatches};
 use environment::Environment;
 use slashing_protection::{
-    interchange::Interchange, InterchangeError, InterchangeImportOutcome, SlashingDatabase,
-    SLASHING_PROTECTION_FILENAME,
+    interchange::{Interchange, InterchangeConflict},
+    InterchangeError, InterchangeImportOutcome, SlashingDatabase, SLASHING_PROTECTION_FILENAME,
 };
+use std::collections::HashMap;
 use std::fs::File;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
+use types::{Epoch, EthSpec, Hash256, PublicKeyBytes, Slot};
 
 pub const CMD: &str = "slashing-protection";
 pub const IMPORT_CMD: &str = "import";
 pub const EXPORT_CMD: &str = "export";
+pub const INSPECT_CMD: &str = "inspect";
+pub const MERGE_CMD: &str = "merge";
+pub const MINIFY_CMD: &str = "minify";
+pub const CROSS_CHECK_CMD: &str = "cross-check";
 
 pub const IMPORT_FILE_ARG: &str = "IMPORT-FILE";
 pub const EXPORT_FILE_ARG: &str = "EXPORT-FILE";
+pub const INTERCHANGE_FILE_ARG: &str = "INTERCHANGE-FILE";
+pub const INTERCHANGE_FILES_ARG: &str = "INTERCHANGE-FILES";
+pub const OUTPUT_FILE_ARG: &str = "OUTPUT-FILE";
 
 pub const PUBKEYS_FLAG: &str = "pubkeys";
+pub const MINIFY_FLAG: &str = "minify";
+pub const ALLOW_CONFLICTS_FLAG: &str = "allow-conflicts";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
-        .about("Import or export slashing protection data to or from another client")
+        .about(
+            "Import or export slashing protection data to or from another client, or inspect \
+             and combine interchange files offline",
+        )
         .subcommand(
             App::new(IMPORT_CMD)
                 .about("Import an interchange file")
@@ -51,6 +64,190 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         ),
                 )
         )
+        .subcommand(
+            App::new(INSPECT_CMD)
+                .about(
+                    "Show the latest block and attestation of each key in an interchange file, \
+                     along with any slashable records it contains",
+                )
+                .arg(
+                    Arg::with_name(INTERCHANGE_FILE_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("FILE")
+                        .help("The slashing protection interchange file to inspect (.json)"),
+                ),
+        )
+        .subcommand(
+            App::new(MERGE_CMD)
+                .about(
+                    "Merge several interchange files into one, e.g. when consolidating \
+                     validators from several machines",
+                )
+                .arg(
+                    Arg::with_name(OUTPUT_FILE_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("OUTPUT-FILE")
+                        .help("The filename to write the merged interchange file to"),
+                )
+                .arg(
+                    Arg::with_name(INTERCHANGE_FILES_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .multiple(true)
+                        .value_name("FILES")
+                        .help("The slashing protection interchange files to merge (.json)"),
+                )
+                .arg(
+                    Arg::with_name(MINIFY_FLAG)
+                        .long(MINIFY_FLAG)
+                        .takes_value(false)
+                        .help(
+                            "Only keep the latest block and attestation of each key in the merged \
+                             file",
+                        ),
+                )
+                .arg(
+                    Arg::with_name(ALLOW_CONFLICTS_FLAG)
+                        .long(ALLOW_CONFLICTS_FLAG)
+                        .takes_value(false)
+                        .help(
+                            "Write the merged file even if the files contain slashable records. \
+                             The merged file still protects against signing any message which \
+                             conflicts with the records of any of the files",
+                        ),
+                ),
+        )
+        .subcommand(
+            App::new(MINIFY_CMD)
+                .about(
+                    "Minify an interchange file to the latest block and attestation of each key",
+                )
+                .arg(
+                    Arg::with_name(INTERCHANGE_FILE_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("FILE")
+                        .help("The slashing protection interchange file to minify (.json)"),
+                )
+                .arg(
+                    Arg::with_name(OUTPUT_FILE_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("OUTPUT-FILE")
+                        .help("The filename to write the minified interchange file to"),
+                ),
+        )
+        .subcommand(
+            App::new(CROSS_CHECK_CMD)
+                .about(
+                    "Check that interchange files are for this network and that they contain no \
+                     slashable records, either within a file or between files. Exits with an \
+                     error if any are found",
+                )
+                .arg(
+                    Arg::with_name(INTERCHANGE_FILES_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .multiple(true)
+                        .value_name("FILES")
+                        .help("The slashing protection interchange files to check (.json)"),
+                ),
+        )
+}
+
+fn read_interchange(path: &Path, genesis_validators_root: Hash256) -> Result<Interchange, String> {
+    let file = File::open(path).map_err(|e| {
+        format!(
+            "Unable to open interchange file at {}: {:?}",
+            path.display(),
+            e
+        )
+    })?;
+    let interchange = Interchange::from_json_reader(&file)
+        .map_err(|e| format!("Error parsing {}: {:?}", path.display(), e))?;
+    interchange
+        .check_metadata(genesis_validators_root)
+        .map_err(|e| format!("Invalid interchange file {}: {:?}", path.display(), e))?;
+    Ok(interchange)
+}
+
+fn read_interchanges(
+    matches: &ArgMatches<'_>,
+    genesis_validators_root: Hash256,
+) -> Result<Vec<Interchange>, String> {
+    matches
+        .values_of(INTERCHANGE_FILES_ARG)
+        .ok_or_else(|| format!("{} is required", INTERCHANGE_FILES_ARG))?
+        .map(|path| read_interchange(Path::new(path), genesis_validators_root))
+        .collect()
+}
+
+fn write_interchange(interchange: &Interchange, path: &Path) -> Result<(), String> {
+    let output_file = File::create(path)
+        .map_err(|e| format!("Error creating output file {}: {:?}", path.display(), e))?;
+    interchange
+        .write_to(&output_file)
+        .map_err(|e| format!("Error writing output file {}: {:?}", path.display(), e))
+}
+
+/// The records of a single key in an interchange file, as shown by `inspect`.
+#[derive(Default)]
+struct InspectSummary {
+    blocks: usize,
+    attestations: usize,
+    max_block_slot: Option<Slot>,
+    max_attestation_source: Option<Epoch>,
+    max_attestation_target: Option<Epoch>,
+}
+
+fn display_slot(slot: Option<Slot>) -> String {
+    slot.map_or("none".to_string(), |slot| format!("slot {}", slot.as_u64()))
+}
+
+fn display_epoch(epoch: Option<Epoch>) -> String {
+    epoch.map_or("?".to_string(), |epoch| format!("epoch {}", epoch.as_u64()))
+}
+
+fn display_attestation(source: Option<Epoch>, target: Option<Epoch>) -> String {
+    match (source, target) {
+        (None, None) => "none".to_string(),
+        (source, target) => format!("{} => {}", display_epoch(source), display_epoch(target)),
+    }
+}
+
+/// Print the slashable records of `interchange`, returning the number of conflicts.
+fn print_conflicts(interchange: &Interchange) -> usize {
+    let conflicts = interchange.conflicts();
+    for conflict in &conflicts {
+        eprintln!("- {:?}", conflict.pubkey());
+        match conflict {
+            InterchangeConflict::DoubleBlockProposal { first, second, .. } => eprintln!(
+                "    - double block proposal at slot {}: {:?} and {:?}",
+                first.slot, first.signing_root, second.signing_root
+            ),
+            InterchangeConflict::DoubleVote { first, second, .. } => eprintln!(
+                "    - double vote for target epoch {}: {} and {}",
+                first.target_epoch,
+                display_attestation(Some(first.source_epoch), Some(first.target_epoch)),
+                display_attestation(Some(second.source_epoch), Some(second.target_epoch)),
+            ),
+            InterchangeConflict::SurroundVote {
+                surrounding,
+                surrounded,
+                ..
+            } => eprintln!(
+                "    - surround vote: {} surrounds {}",
+                display_attestation(
+                    Some(surrounding.source_epoch),
+                    Some(surrounding.target_epoch)
+                ),
+                display_attestation(Some(surrounded.source_epoch), Some(surrounded.target_epoch)),
+            ),
+        }
+    }
+    conflicts.len()
 }
 
 pub fn cli_run<T: EthSpec>(
@@ -92,19 +289,6 @@ pub fn cli_run<T: EthSpec>(
                     )
                 })?;
 
-            let display_slot = |slot: Option<Slot>| {
-                slot.map_or("none".to_string(), |slot| format!("slot {}", slot.as_u64()))
-            };
-            let display_epoch = |epoch: Option<Epoch>| {
-                epoch.map_or("?".to_string(), |epoch| format!("epoch {}", epoch.as_u64()))
-            };
-            let display_attestation = |source, target| match (source, target) {
-                (None, None) => "none".to_string(),
-                (source, target) => {
-                    format!("{} => {}", display_epoch(source), display_epoch(target))
-                }
-            };
-
             match slashing_protection_database
                 .import_interchange_info(interchange, genesis_validators_root)
             {
@@ -215,6 +399,150 @@ pub fn cli_run<T: EthSpec>(
 
             Ok(())
         }
+        (INSPECT_CMD, Some(matches)) => {
+            let path: PathBuf = clap_utils::parse_required(matches, INTERCHANGE_FILE_ARG)?;
+            let interchange = read_interchange(&path, genesis_validators_root)?;
+
+            // Summarise the records of each key in the order they first appear, merging
+            // duplicate entries for the same key as an import would.
+            let mut pubkeys = vec![];
+            let mut summaries = HashMap::<PublicKeyBytes, InspectSummary>::new();
+            for data in &interchange.data {
+                let summary = summaries.entry(data.pubkey).or_insert_with(|| {
+                    pubkeys.push(data.pubkey);
+                    InspectSummary::default()
+                });
+                summary.blocks += data.signed_blocks.len();
+                summary.attestations += data.signed_attestations.len();
+                summary.max_block_slot = data
+                    .signed_blocks
+                    .iter()
+                    .map(|block| block.slot)
+                    .chain(summary.max_block_slot)
+                    .max();
+                summary.max_attestation_source = data
+                    .signed_attestations
+                    .iter()
+                    .map(|attestation| attestation.source_epoch)
+                    .chain(summary.max_attestation_source)
+                    .max();
+                summary.max_attestation_target = data
+                    .signed_attestations
+                    .iter()
+                    .map(|attestation| attestation.target_epoch)
+                    .chain(summary.max_attestation_target)
+                    .max();
+            }
+
+            eprintln!("{} keys in {}:", pubkeys.len(), path.display());
+            for pubkey in &pubkeys {
+                let summary = &summaries[pubkey];
+                eprintln!("- {:?}", pubkey);
+                eprintln!(
+                    "    - blocks: {}, attestations: {}",
+                    summary.blocks, summary.attestations
+                );
+                eprintln!(
+                    "    - latest proposed block: {}",
+                    display_slot(summary.max_block_slot)
+                );
+                eprintln!(
+                    "    - latest attestation: {}",
+                    display_attestation(
+                        summary.max_attestation_source,
+                        summary.max_attestation_target
+                    )
+                );
+            }
+
+            eprintln!("Slashable records:");
+            if print_conflicts(&interchange) == 0 {
+                eprintln!("- none");
+            }
+
+            Ok(())
+        }
+        (MERGE_CMD, Some(matches)) => {
+            let output_path: PathBuf = clap_utils::parse_required(matches, OUTPUT_FILE_ARG)?;
+            let interchanges = read_interchanges(matches, genesis_validators_root)?;
+            let num_files = interchanges.len();
+
+            let mut merged = Interchange::merge(genesis_validators_root, interchanges)
+                .map_err(|e| format!("Unable to merge interchange files: {:?}", e))?;
+
+            let num_conflicts = print_conflicts(&merged);
+            if num_conflicts > 0 {
+                if !matches.is_present(ALLOW_CONFLICTS_FLAG) {
+                    return Err(format!(
+                        "The files contain {} slashable records, see above. Some validators may \
+                         have been active on several machines at once. Use --{} to merge them \
+                         anyway.",
+                        num_conflicts, ALLOW_CONFLICTS_FLAG
+                    ));
+                }
+                eprintln!(
+                    "WARNING: merging {} slashable records, see above",
+                    num_conflicts
+                );
+            }
+
+            if matches.is_present(MINIFY_FLAG) {
+                merged = merged
+                    .minify()
+                    .map_err(|e| format!("Unable to minify interchange: {:?}", e))?;
+            }
+            write_interchange(&merged, &output_path)?;
+
+            eprintln!(
+                "Merged {} files into {} ({} keys)",
+                num_files,
+                output_path.display(),
+                merged.len()
+            );
+
+            Ok(())
+        }
+        (MINIFY_CMD, Some(matches)) => {
+            let path: PathBuf = clap_utils::parse_required(matches, INTERCHANGE_FILE_ARG)?;
+            let output_path: PathBuf = clap_utils::parse_required(matches, OUTPUT_FILE_ARG)?;
+            let interchange = read_interchange(&path, genesis_validators_root)?;
+
+            let minified = interchange
+                .minify()
+                .map_err(|e| format!("Unable to minify {}: {:?}", path.display(), e))?;
+            write_interchange(&minified, &output_path)?;
+
+            eprintln!(
+                "Minified {} into {} ({} keys)",
+                path.display(),
+                output_path.display(),
+                minified.len()
+            );
+
+            Ok(())
+        }
+        (CROSS_CHECK_CMD, Some(matches)) => {
+            let interchanges = read_interchanges(matches, genesis_validators_root)?;
+            let num_files = interchanges.len();
+            let merged = Interchange::merge(genesis_validators_root, interchanges)
+                .map_err(|e| format!("Unable to merge interchange files: {:?}", e))?;
+
+            let num_conflicts = print_conflicts(&merged);
+            if num_conflicts > 0 {
+                return Err(format!(
+                    "Found {} slashable records, see above",
+                    num_conflicts
+                ));
+            }
+
+            eprintln!(
+                "No slashable records found in {} files ({} keys)",
+                num_files,
+                merged.len()
+            );
+
+            Ok(())
+        }
         ("", _) => Err("No subcommand provided, see --help for options".to_string()),
         (command, _) => Err(format!("No such subcommand `{}`", command)),
     }
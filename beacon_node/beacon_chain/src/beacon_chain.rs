@@ -30,6 +30,7 @@ use crate::eth1_finalization_cache::{Eth1FinalizationCache, Eth1FinalizationData
 use crate::events::ServerSentEventHandler;
 use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, PreparePayloadHandle};
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
+use crate::graffiti_calculator::{calculate_graffiti, GraffitiOrigin};
 use crate::head_tracker::{HeadTracker, HeadTrackerReader, SszHeadTracker};
 use crate::historical_blocks::HistoricalBlockError;
 use crate::light_client_finality_update_verification::{
@@ -474,7 +475,7 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// Logging to CLI, etc.
     pub(crate) log: Logger,
     /// Arbitrary bytes included in the blocks.
-    pub(crate) graffiti: Graffiti,
+    pub(crate) graffiti: GraffitiOrigin,
     /// Optional slasher.
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
     /// Provides monitoring of a set of explicitly defined validators.
@@ -3573,6 +3574,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::inc_counter(&metrics::BLOCK_PROCESSING_SUCCESSES);
 
+        // Record the deposits included by this block in the deposit index.
+        let deposit_count = block.body().deposits().len() as u64;
+        if deposit_count > 0 {
+            if let Some(eth1_chain) = self.eth1_chain.as_ref() {
+                let post_deposit_index = current_eth1_finalization_data.eth1_deposit_index;
+                eth1_chain.insert_deposit_inclusions(
+                    block_root,
+                    slot,
+                    post_deposit_index.saturating_sub(deposit_count)..post_deposit_index,
+                );
+            }
+        }
+
         // Update the deposit contract cache.
         self.import_block_update_deposit_contract_finalization(
             block,
@@ -4860,6 +4874,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Returns the graffiti for blocks which the validator client provides no graffiti for.
+    ///
+    /// Unless the user specified graffiti, this identifies the execution client.
+    pub fn default_graffiti(&self) -> Graffiti {
+        let execution_client = self
+            .execution_layer
+            .as_ref()
+            .and_then(|el| el.client_version());
+        calculate_graffiti(self.graffiti, execution_client.as_ref())
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn produce_partial_beacon_block(
         self: &Arc<Self>,
@@ -4972,7 +4997,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Override the beacon node's graffiti with graffiti from the validator, if present.
         let graffiti = match validator_graffiti {
             Some(graffiti) => graffiti,
-            None => self.graffiti,
+            None => self.default_graffiti(),
         };
 
         let attestation_packing_timer =
@@ -5378,6 +5403,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         drop(blobs_verification_timer);
 
+        self.record_block_proposal(&block);
+
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
 
         trace!(
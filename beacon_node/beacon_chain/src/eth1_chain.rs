@@ -1,5 +1,5 @@
 use crate::metrics;
-use eth1::{Config as Eth1Config, Eth1Block, Service as HttpService};
+use eth1::{Config as Eth1Config, DepositInclusion, Eth1Block, Service as HttpService};
 use eth2::lighthouse::Eth1SyncStatusData;
 use ethereum_hashing::hash;
 use int_to_bytes::int_to_bytes32;
@@ -10,6 +10,7 @@ use state_processing::per_block_processing::get_new_eth1_data;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::ops::Range;
 use std::time::{SystemTime, UNIX_EPOCH};
 use store::{DBColumn, Error as StoreError, StoreItem};
 use task_executor::TaskExecutor;
@@ -309,6 +310,24 @@ where
         self.backend.finalize_eth1_data(eth1_data);
     }
 
+    /// Records that the block with `block_root` at `slot` included the deposits with
+    /// `deposit_indices`. This method is called during block import so it should be fast.
+    pub fn insert_deposit_inclusions(
+        &self,
+        block_root: Hash256,
+        slot: Slot,
+        deposit_indices: Range<u64>,
+    ) {
+        let inclusions = deposit_indices
+            .map(|index| DepositInclusion {
+                index,
+                block_root,
+                slot,
+            })
+            .collect();
+        self.backend.insert_deposit_inclusions(inclusions);
+    }
+
     /// Consumes `self`, returning the backend.
     pub fn into_backend(self) -> T {
         self.backend
@@ -343,6 +362,10 @@ pub trait Eth1ChainBackend<T: EthSpec>: Sized + Send + Sync {
     /// so it should be fast.
     fn finalize_eth1_data(&self, eth1_data: Eth1Data);
 
+    /// Records the beacon blocks which included deposits, so that they are found by the deposit
+    /// index. This method is called during block import so it should be fast.
+    fn insert_deposit_inclusions(&self, inclusions: Vec<DepositInclusion>);
+
     /// Returns the block at the head of the chain (ignoring follow distance, etc). Used to obtain
     /// an idea of how up-to-date the remote eth1 node is.
     fn head_block(&self) -> Option<Eth1Block>;
@@ -399,6 +422,8 @@ impl<T: EthSpec> Eth1ChainBackend<T> for DummyEth1ChainBackend<T> {
 
     fn finalize_eth1_data(&self, _eth1_data: Eth1Data) {}
 
+    fn insert_deposit_inclusions(&self, _inclusions: Vec<DepositInclusion>) {}
+
     fn head_block(&self) -> Option<Eth1Block> {
         None
     }
@@ -574,6 +599,10 @@ impl<T: EthSpec> Eth1ChainBackend<T> for CachingEth1Backend<T> {
         self.core.set_to_finalize(Some(eth1_data));
     }
 
+    fn insert_deposit_inclusions(&self, inclusions: Vec<DepositInclusion>) {
+        self.core.insert_deposit_inclusions(inclusions);
+    }
+
     fn head_block(&self) -> Option<Eth1Block> {
         self.core.head_block()
     }
@@ -764,6 +793,8 @@ mod test {
                 block_number: i,
                 index: i,
                 signature_is_valid: true,
+                transaction_hash: Hash256::from_low_u64_be(i),
+                log_index: 0,
             }
         }
 
@@ -0,0 +1,167 @@
+//! Calculates the graffiti of blocks produced without graffiti from the validator client.
+//!
+//! Unless the user specified graffiti, the identity of the execution client is prepended to the
+//! default graffiti, in the format recommended by the engine API `ClientVersionV1`, e.g.
+//! `GEabcdLHef01 Lighthouse/v5.1.3`.
+
+use eth2::types::ExecutionClientVersion;
+use serde::{Deserialize, Serialize};
+use types::{Graffiti, GRAFFITI_BYTES_LEN};
+
+/// The graffiti of the beacon node and where it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GraffitiOrigin {
+    /// Graffiti specified by the user, which is used as-is.
+    UserSpecified(Graffiti),
+    /// The default graffiti, which is extended with the identity of the execution client.
+    Calculated(Graffiti),
+}
+
+impl Default for GraffitiOrigin {
+    fn default() -> Self {
+        GraffitiOrigin::Calculated(Graffiti::default())
+    }
+}
+
+impl GraffitiOrigin {
+    pub fn graffiti(&self) -> Graffiti {
+        match self {
+            GraffitiOrigin::UserSpecified(graffiti) | GraffitiOrigin::Calculated(graffiti) => {
+                *graffiti
+            }
+        }
+    }
+}
+
+/// Returns the first `len` hex characters of a `0x`-prefixed commit.
+fn commit_prefix(commit: &str, len: usize) -> &str {
+    let commit = commit.strip_prefix("0x").unwrap_or(commit);
+    commit.get(..len).unwrap_or(commit)
+}
+
+/// Returns the graffiti to use for a block produced without graffiti from the validator client.
+///
+/// The identity of `execution_client` is prepended to the default graffiti, abbreviating it as
+/// much as necessary to fit. The default graffiti is returned unchanged if the user specified
+/// the graffiti, or if the execution client is unknown.
+pub fn calculate_graffiti(
+    origin: GraffitiOrigin,
+    execution_client: Option<&ExecutionClientVersion>,
+) -> Graffiti {
+    let (default_graffiti, execution_client) = match (origin, execution_client) {
+        (GraffitiOrigin::Calculated(graffiti), Some(execution_client)) => {
+            (graffiti, execution_client)
+        }
+        (origin, _) => return origin.graffiti(),
+    };
+
+    let default_string = default_graffiti.as_utf8_lossy();
+    let el_code = &execution_client.code;
+    let el_commit = &execution_client.commit;
+    let lh_code = lighthouse_version::CLIENT_CODE;
+    let lh_commit = lighthouse_version::COMMIT_PREFIX;
+
+    let identities = [
+        format!(
+            "{}{}{}{}",
+            el_code,
+            commit_prefix(el_commit, 4),
+            lh_code,
+            commit_prefix(lh_commit, 4)
+        ),
+        format!(
+            "{}{}{}{}",
+            el_code,
+            commit_prefix(el_commit, 2),
+            lh_code,
+            commit_prefix(lh_commit, 2)
+        ),
+        format!("{}{}", el_code, lh_code),
+    ];
+
+    let graffiti_string = identities
+        .into_iter()
+        .map(|identity| {
+            if default_string.is_empty() {
+                identity
+            } else {
+                format!("{} {}", identity, default_string)
+            }
+        })
+        .find(|graffiti| graffiti.len() <= GRAFFITI_BYTES_LEN);
+
+    match graffiti_string {
+        Some(graffiti_string) => {
+            let mut graffiti = Graffiti::default();
+            graffiti.0[..graffiti_string.len()].copy_from_slice(graffiti_string.as_bytes());
+            graffiti
+        }
+        None => default_graffiti,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graffiti(s: &str) -> Graffiti {
+        let mut graffiti = Graffiti::default();
+        graffiti.0[..s.len()].copy_from_slice(s.as_bytes());
+        graffiti
+    }
+
+    fn geth() -> ExecutionClientVersion {
+        ExecutionClientVersion {
+            code: "GE".to_string(),
+            name: "Geth".to_string(),
+            version: "v1.13.14".to_string(),
+            commit: "0xfa4ff922".to_string(),
+        }
+    }
+
+    fn lh_commit(len: usize) -> &'static str {
+        commit_prefix(lighthouse_version::COMMIT_PREFIX, len)
+    }
+
+    #[test]
+    fn user_specified_graffiti_is_unchanged() {
+        let origin = GraffitiOrigin::UserSpecified(graffiti("hello"));
+        assert_eq!(calculate_graffiti(origin, Some(&geth())), graffiti("hello"));
+    }
+
+    #[test]
+    fn unknown_execution_client() {
+        let origin = GraffitiOrigin::Calculated(graffiti("Lighthouse/v5.1.3"));
+        assert_eq!(
+            calculate_graffiti(origin, None),
+            graffiti("Lighthouse/v5.1.3")
+        );
+    }
+
+    #[test]
+    fn abbreviates_to_fit() {
+        let origin = GraffitiOrigin::Calculated(graffiti("Lighthouse/v5.1.3"));
+        assert_eq!(
+            calculate_graffiti(origin, Some(&geth())),
+            graffiti(&format!("GEfa4fLH{} Lighthouse/v5.1.3", lh_commit(4)))
+        );
+
+        let origin = GraffitiOrigin::Calculated(graffiti("Lighthouse/v5.1.3-67da032"));
+        assert_eq!(
+            calculate_graffiti(origin, Some(&geth())),
+            graffiti("GELH Lighthouse/v5.1.3-67da032")
+        );
+
+        let origin = GraffitiOrigin::Calculated(graffiti("Lighthouse/v5.1.3-67da032/x86"));
+        assert_eq!(
+            calculate_graffiti(origin, Some(&geth())),
+            graffiti("Lighthouse/v5.1.3-67da032/x86")
+        );
+
+        let origin = GraffitiOrigin::Calculated(Graffiti::default());
+        assert_eq!(
+            calculate_graffiti(origin, Some(&geth())),
+            graffiti(&format!("GEfa4fLH{}", lh_commit(4)))
+        );
+    }
+}
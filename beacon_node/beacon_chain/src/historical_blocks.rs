@@ -1,5 +1,6 @@
 use crate::data_availability_checker::AvailableBlock;
 use crate::{errors::BeaconChainError as Error, metrics, BeaconChain, BeaconChainTypes};
+use execution_layer::verify_block_payload_block_hash;
 use itertools::Itertools;
 use slog::debug;
 use state_processing::{
@@ -26,6 +27,12 @@ pub enum HistoricalBlockError {
         block_root: Hash256,
         expected_block_root: Hash256,
     },
+    /// Execution payload block hash mismatch in a block of the canonical chain. Retrying with
+    /// different blocks won't help, since the block root has already been verified.
+    InvalidPayloadBlockHash {
+        block_root: Hash256,
+        error: execution_layer::Error,
+    },
     /// Bad signature, caller should retry with different blocks.
     SignatureSet(SignatureSetError),
     /// Bad signature, caller should retry with different blocks.
@@ -47,7 +54,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// The block roots and proposer signatures are verified. If any block doesn't match the parent
     /// root listed in its successor, then the whole batch will be discarded and
     /// `MismatchedBlockRoot` will be returned. If any proposer signature is invalid then
-    /// `SignatureSetError` or `InvalidSignature` will be returned.
+    /// `SignatureSetError` or `InvalidSignature` will be returned. If
+    /// `ChainConfig::verify_backfill_block_hashes` is set then the block hash of each execution
+    /// payload is also recomputed, and `InvalidPayloadBlockHash` returned if any doesn't match.
     ///
     /// To align with sync we allow some excess blocks with slots greater than or equal to
     /// `oldest_block_slot` to be provided. They will be ignored without being checked.
@@ -117,6 +126,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .into());
             }
 
+            if self.config.verify_backfill_block_hashes {
+                verify_block_payload_block_hash(block.message()).map_err(|error| {
+                    HistoricalBlockError::InvalidPayloadBlockHash { block_root, error }
+                })?;
+            }
+
             let blinded_block = block.clone_as_blinded();
             // Store block in the hot database without payload.
             self.store
@@ -0,0 +1,110 @@
+//! Persists how the execution payload of each block produced by this node was chosen, so that
+//! the choice between local and builder payloads can be audited after proposing.
+
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::ProposalRecord;
+use execution_layer::PayloadSelection;
+use itertools::process_results;
+use slog::warn;
+use ssz::{Decode, Encode};
+use store::{DBColumn, Error as StoreError, KeyValueStore, StoreItem};
+use types::{AbstractExecPayload, BeaconBlock, ExecPayload, Hash256, Slot};
+
+/// A `ProposalRecord` in the database, keyed by block root.
+struct PersistedProposalRecord(ProposalRecord);
+
+impl StoreItem for PersistedProposalRecord {
+    fn db_column() -> DBColumn {
+        DBColumn::ProposalRecord
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(Self(ProposalRecord::from_ssz_bytes(bytes)?))
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Persist how the payload of a newly produced `block` was chosen.
+    ///
+    /// Blocks without a payload, or whose payload was not chosen by the execution layer, are not
+    /// recorded.
+    pub(crate) fn record_block_proposal<Payload: AbstractExecPayload<T::EthSpec>>(
+        &self,
+        block: &BeaconBlock<T::EthSpec, Payload>,
+    ) {
+        let Some(execution_layer) = self.execution_layer.as_ref() else {
+            return;
+        };
+        let Ok(payload) = block.body().execution_payload() else {
+            return;
+        };
+        let Some(PayloadSelection {
+            source,
+            fee_recipient,
+            local_value,
+            builder_value,
+            builder_fallback_reason,
+        }) = execution_layer.take_payload_selection(&payload.block_hash())
+        else {
+            return;
+        };
+
+        let block_root = block.canonical_root();
+        let record = ProposalRecord {
+            slot: block.slot(),
+            block_root,
+            proposer_index: block.proposer_index(),
+            payload_source: source,
+            fee_recipient,
+            local_value,
+            builder_value,
+            builder_fallback_reason,
+        };
+
+        if let Err(e) = self
+            .store
+            .put_item(&block_root, &PersistedProposalRecord(record))
+        {
+            warn!(
+                self.log,
+                "Failed to persist proposal record";
+                "block_root" => ?block_root,
+                "error" => ?e
+            );
+        }
+    }
+
+    /// Returns the records of the blocks produced by this node between `start_slot` and
+    /// `end_slot` (inclusive), in slot order.
+    pub fn proposal_records(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Result<Vec<ProposalRecord>, BeaconChainError> {
+        let mut records = process_results(
+            self.store
+                .hot_db
+                .iter_column::<Hash256>(DBColumn::ProposalRecord),
+            |iter| {
+                iter.map(|(_, bytes)| {
+                    PersistedProposalRecord::from_store_bytes(&bytes).map(|record| record.0)
+                })
+                .filter(|record| {
+                    record.as_ref().map_or(true, |record| {
+                        start_slot.map_or(true, |start_slot| record.slot >= start_slot)
+                            && end_slot.map_or(true, |end_slot| record.slot <= end_slot)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+            },
+        )
+        .map_err(BeaconChainError::DBError)?
+        .map_err(BeaconChainError::DBError)?;
+        records.sort_by_key(|record| record.slot);
+        Ok(records)
+    }
+}
@@ -0,0 +1,68 @@
+use crate::beacon_chain::{BeaconChainTypes, ETH1_CACHE_DB_KEY};
+use crate::eth1_chain::SszEth1;
+use eth1::{SszEth1CacheV13, SszEth1CacheV20};
+use slog::{debug, info, Logger};
+use ssz::{Decode, Encode};
+use std::sync::Arc;
+use store::{Error, HotColdDB, KeyValueStoreOp, StoreItem};
+
+/// Applies `migrate` to the eth1 cache in the database, if there is one.
+///
+/// The dummy backend does not persist a cache, so it is left as is.
+fn migrate_eth1_cache<T: BeaconChainTypes, F>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    migrate: F,
+) -> Result<Vec<KeyValueStoreOp>, Error>
+where
+    F: FnOnce(&[u8]) -> Result<Vec<u8>, ssz::DecodeError>,
+{
+    let Some(mut ssz_eth1) = db.get_item::<SszEth1>(&ETH1_CACHE_DB_KEY)? else {
+        return Ok(vec![]);
+    };
+    if ssz_eth1.use_dummy_backend {
+        return Ok(vec![]);
+    }
+
+    ssz_eth1.backend_bytes = migrate(&ssz_eth1.backend_bytes).map_err(|e| {
+        Error::SchemaMigrationError(format!(
+            "Failed to decode eth1 cache during schema migration: {:?}",
+            e
+        ))
+    })?;
+
+    Ok(vec![ssz_eth1.as_kv_store_op(ETH1_CACHE_DB_KEY)])
+}
+
+pub fn upgrade_to_v20<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    debug!(
+        log,
+        "Indexing the deposits of the eth1 cache";
+        "info" => "deposits which are already finalized are not indexed"
+    );
+    let ops = migrate_eth1_cache::<T, _>(db, |bytes| {
+        Ok(SszEth1CacheV13::from_ssz_bytes(bytes)?
+            .upgrade()
+            .as_ssz_bytes())
+    })?;
+
+    info!(log, "Upgrading to v20 schema");
+    Ok(ops)
+}
+
+pub fn downgrade_from_v20<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    debug!(log, "Dropping the deposit index of the eth1 cache");
+    let ops = migrate_eth1_cache::<T, _>(db, |bytes| {
+        Ok(SszEth1CacheV20::from_ssz_bytes(bytes)?
+            .downgrade()
+            .as_ssz_bytes())
+    })?;
+
+    info!(log, "Downgrading to v19 schema");
+    Ok(ops)
+}
@@ -63,6 +63,10 @@ impl BuilderHttpClient {
         &self.user_agent
     }
 
+    pub fn server(&self) -> &SensitiveUrl {
+        &self.server
+    }
+
     async fn get_with_timeout<T: DeserializeOwned, U: IntoUrl>(
         &self,
         url: U,
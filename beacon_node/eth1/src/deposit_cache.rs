@@ -1,10 +1,12 @@
+use crate::deposit_index::{DepositInclusion, DepositIndex};
 use crate::{DepositLog, Eth1Block};
+use eth2::lighthouse::IndexedDeposit;
 use ssz_derive::{Decode, Encode};
 use state_processing::common::DepositDataTree;
 use std::cmp::Ordering;
 use superstruct::superstruct;
 use tree_hash::TreeHash;
-use types::{Deposit, DepositTreeSnapshot, Hash256, DEPOSIT_TREE_DEPTH};
+use types::{Deposit, DepositData, DepositTreeSnapshot, Hash256, DEPOSIT_TREE_DEPTH};
 
 #[derive(Debug, PartialEq)]
 pub enum Error {
@@ -51,24 +53,63 @@ pub enum Error {
     PleaseNotifyTheDevs,
 }
 
-pub type SszDepositCache = SszDepositCacheV13;
+/// A deposit log, as persisted before the transaction of the log was recorded.
+#[derive(Debug, PartialEq, Clone, Encode, Decode)]
+pub struct DepositLogV1 {
+    pub deposit_data: DepositData,
+    pub block_number: u64,
+    pub index: u64,
+    pub signature_is_valid: bool,
+}
+
+impl DepositLogV1 {
+    /// Upgrades the log, the transaction of which is unknown.
+    pub fn upgrade(self) -> DepositLog {
+        DepositLog {
+            deposit_data: self.deposit_data,
+            block_number: self.block_number,
+            index: self.index,
+            signature_is_valid: self.signature_is_valid,
+            transaction_hash: Hash256::zero(),
+            log_index: 0,
+        }
+    }
+
+    pub fn downgrade(log: DepositLog) -> Self {
+        Self {
+            deposit_data: log.deposit_data,
+            block_number: log.block_number,
+            index: log.index,
+            signature_is_valid: log.signature_is_valid,
+        }
+    }
+}
+
+pub type SszDepositCache = SszDepositCacheV20;
 
 #[superstruct(
-    variants(V1, V13),
+    variants(V1, V13, V20),
     variant_attributes(derive(Encode, Decode, Clone)),
     no_enum
 )]
 pub struct SszDepositCache {
+    #[superstruct(only(V1, V13))]
+    pub logs: Vec<DepositLogV1>,
+    #[superstruct(only(V20))]
     pub logs: Vec<DepositLog>,
     pub leaves: Vec<Hash256>,
     pub deposit_contract_deploy_block: u64,
-    #[superstruct(only(V13))]
+    #[superstruct(only(V13, V20))]
     pub finalized_deposit_count: u64,
-    #[superstruct(only(V13))]
+    #[superstruct(only(V13, V20))]
     pub finalized_block_height: u64,
-    #[superstruct(only(V13))]
+    #[superstruct(only(V13, V20))]
     pub deposit_tree_snapshot: Option<DepositTreeSnapshot>,
     pub deposit_roots: Vec<Hash256>,
+    #[superstruct(only(V20))]
+    pub indexed_deposits: Vec<IndexedDeposit>,
+    #[superstruct(only(V20))]
+    pub deposit_inclusions: Vec<DepositInclusion>,
 }
 
 impl SszDepositCache {
@@ -81,6 +122,8 @@ impl SszDepositCache {
             finalized_block_height: cache.finalized_block_height,
             deposit_tree_snapshot: cache.deposit_tree.get_snapshot(),
             deposit_roots: cache.deposit_roots.clone(),
+            indexed_deposits: cache.deposit_index.deposits().cloned().collect(),
+            deposit_inclusions: cache.deposit_index.inclusions().copied().collect(),
         }
     }
 
@@ -127,10 +170,52 @@ impl SszDepositCache {
             finalized_block_height: self.finalized_block_height,
             deposit_tree,
             deposit_roots: self.deposit_roots.clone(),
+            deposit_index: DepositIndex::from_persisted(
+                self.indexed_deposits.clone(),
+                self.deposit_inclusions.clone(),
+            ),
         })
     }
 }
 
+impl SszDepositCacheV13 {
+    /// Upgrades the cache, indexing the deposits which are not finalized.
+    pub fn upgrade(self) -> SszDepositCacheV20 {
+        let logs = self
+            .logs
+            .into_iter()
+            .map(DepositLogV1::upgrade)
+            .collect::<Vec<_>>();
+        let indexed_deposits = logs.iter().map(IndexedDeposit::from).collect();
+        SszDepositCacheV20 {
+            logs,
+            leaves: self.leaves,
+            deposit_contract_deploy_block: self.deposit_contract_deploy_block,
+            finalized_deposit_count: self.finalized_deposit_count,
+            finalized_block_height: self.finalized_block_height,
+            deposit_tree_snapshot: self.deposit_tree_snapshot,
+            deposit_roots: self.deposit_roots,
+            indexed_deposits,
+            deposit_inclusions: vec![],
+        }
+    }
+}
+
+impl SszDepositCacheV20 {
+    /// Downgrades the cache, dropping the deposit index.
+    pub fn downgrade(self) -> SszDepositCacheV13 {
+        SszDepositCacheV13 {
+            logs: self.logs.into_iter().map(DepositLogV1::downgrade).collect(),
+            leaves: self.leaves,
+            deposit_contract_deploy_block: self.deposit_contract_deploy_block,
+            finalized_deposit_count: self.finalized_deposit_count,
+            finalized_block_height: self.finalized_block_height,
+            deposit_tree_snapshot: self.deposit_tree_snapshot,
+            deposit_roots: self.deposit_roots,
+        }
+    }
+}
+
 /// Mirrors the merkle tree of deposits in the eth1 deposit contract.
 ///
 /// Provides `Deposit` objects with merkle proofs included.
@@ -147,6 +232,8 @@ pub struct DepositCache {
     /// Vector of deposit roots. `deposit_roots[i]` denotes `deposit_root` at
     /// `deposit_index` `i`.
     deposit_roots: Vec<Hash256>,
+    /// Indexes deposits by pubkey and transaction, including finalized deposits.
+    deposit_index: DepositIndex,
 }
 
 impl Default for DepositCache {
@@ -161,6 +248,7 @@ impl Default for DepositCache {
             finalized_block_height: 0,
             deposit_tree,
             deposit_roots,
+            deposit_index: DepositIndex::default(),
         }
     }
 }
@@ -196,6 +284,7 @@ impl DepositCache {
             finalized_block_height: snapshot.execution_block_height,
             deposit_tree,
             deposit_roots: vec![snapshot.deposit_root],
+            deposit_index: DepositIndex::default(),
         })
     }
 
@@ -232,6 +321,16 @@ impl DepositCache {
         }
     }
 
+    /// Returns the index of deposits by pubkey and transaction.
+    pub fn deposit_index(&self) -> &DepositIndex {
+        &self.deposit_index
+    }
+
+    /// Records that the deposit with `inclusion.index` was included by a beacon block.
+    pub fn insert_deposit_inclusion(&mut self, inclusion: DepositInclusion) {
+        self.deposit_index.insert_inclusion(inclusion)
+    }
+
     /// Returns the deposit root with DEPOSIT COUNT (not index) i
     pub fn get_root(&self, i: usize) -> Option<&Hash256> {
         let finalized_deposit_count = self.finalized_deposit_count as usize;
@@ -302,6 +401,7 @@ impl DepositCache {
                     .push_leaf(deposit)
                     .map_err(Error::DepositTree)?;
                 self.leaves.push(deposit);
+                self.deposit_index.insert_log(&log);
                 self.logs.push(log);
                 self.deposit_roots.push(self.deposit_tree.root());
                 Ok(DepositCacheInsertOutcome::Inserted)
@@ -316,7 +416,12 @@ impl DepositCache {
                 } else {
                     compare_index -= self.finalized_deposit_count as usize;
                 }
-                if self.logs[compare_index] == log {
+                let cached_log = &self.logs[compare_index];
+                // Logs persisted before their transaction was recorded lack it.
+                let is_upgraded_log = cached_log.transaction_hash.is_zero()
+                    && cached_log.deposit_data == log.deposit_data
+                    && cached_log.block_number == log.block_number;
+                if *cached_log == log || is_upgraded_log {
                     Ok(DepositCacheInsertOutcome::Duplicate)
                 } else {
                     Err(Error::DuplicateDistinctLog(log.index))
@@ -474,7 +579,7 @@ impl DepositCache {
 pub mod tests {
     use super::*;
     use execution_layer::http::deposit_log::Log;
-    use types::{EthSpec, MainnetEthSpec};
+    use types::{EthSpec, MainnetEthSpec, PublicKeyBytes, Slot};
 
     /// The data from a deposit event, using the v0.8.3 version of the deposit contract.
     pub const EXAMPLE_LOG: &[u8] = &[
@@ -507,6 +612,8 @@ pub mod tests {
 
         let log = Log {
             block_number: 42,
+            transaction_hash: Hash256::repeat_byte(42),
+            log_index: 0,
             data: EXAMPLE_LOG.to_vec(),
         };
         log.to_deposit_log(&spec).expect("should decode log")
@@ -573,6 +680,65 @@ pub mod tests {
         assert!(deposit_cache.insert_log(log).is_err());
     }
 
+    #[test]
+    fn deposit_index() {
+        let mut deposit_cache = get_cache_with_deposits(8);
+        let pubkey = example_log().deposit_data.pubkey;
+        let other_pubkey = PublicKeyBytes::empty();
+        let transaction_hash = example_log().transaction_hash;
+        let other_transaction_hash = Hash256::repeat_byte(1);
+
+        let mut log = example_log();
+        log.index = 8;
+        log.deposit_data.pubkey = other_pubkey;
+        log.transaction_hash = other_transaction_hash;
+        deposit_cache
+            .insert_log(log)
+            .expect("should add consecutive log");
+
+        let inclusion = DepositInclusion {
+            index: 8,
+            block_root: Hash256::repeat_byte(2),
+            slot: Slot::new(3),
+        };
+        deposit_cache.insert_deposit_inclusion(inclusion);
+
+        let check = |cache: &DepositCache| {
+            let indices = |deposits: Vec<&IndexedDeposit>| {
+                deposits
+                    .into_iter()
+                    .map(|deposit| deposit.index)
+                    .collect::<Vec<_>>()
+            };
+            let index = cache.deposit_index();
+            assert_eq!(
+                indices(index.get_by_pubkey(&pubkey)),
+                (0..8).collect::<Vec<_>>()
+            );
+            assert_eq!(indices(index.get_by_pubkey(&other_pubkey)), vec![8]);
+            assert_eq!(
+                indices(index.get_by_transaction(&transaction_hash)),
+                (0..8).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                indices(index.get_by_transaction(&other_transaction_hash)),
+                vec![8]
+            );
+            assert_eq!(index.get_inclusions(8), &[inclusion]);
+            assert!(index.get_inclusions(7).is_empty());
+        };
+        check(&deposit_cache);
+
+        // Finalized deposits are kept in the index.
+        let block = fake_eth1_block(&deposit_cache, 3).expect("should create fake eth1 block");
+        deposit_cache.finalize(block).expect("should finalize");
+        check(&deposit_cache);
+
+        // The index is persisted.
+        let recovered_cache = ssz_round_trip(&deposit_cache);
+        check(&recovered_cache);
+    }
+
     #[test]
     fn get_deposit_valid() {
         let n = 1_024;
@@ -1064,6 +1230,48 @@ pub mod tests {
         verify_equality(&deposit_cache, &recovered_cache);
     }
 
+    #[test]
+    fn ssz_upgrade_from_v13() {
+        use ssz::{Decode, Encode};
+        let mut deposit_cache = get_cache_with_deposits(16);
+        let bytes = SszDepositCache::from_deposit_cache(&deposit_cache)
+            .downgrade()
+            .as_ssz_bytes();
+        let ssz_cache = SszDepositCacheV13::from_ssz_bytes(&bytes)
+            .expect("should decode v13 from ssz bytes")
+            .upgrade();
+        let mut upgraded_cache = ssz_cache.to_deposit_cache().expect("should recover cache");
+
+        // Transaction hashes are not known for the deposits of the old schema.
+        let pubkey = example_log().deposit_data.pubkey;
+        let index = upgraded_cache.deposit_index();
+        assert_eq!(index.get_by_pubkey(&pubkey).len(), 16);
+        assert!(index
+            .get_by_transaction(&example_log().transaction_hash)
+            .is_empty());
+        assert!(index.get_by_transaction(&Hash256::zero()).is_empty());
+
+        // The logs are still recognised when they are downloaded again.
+        let mut log = example_log();
+        log.index = 15;
+        log.block_number = 15;
+        log.deposit_data.withdrawal_credentials = Hash256::from_low_u64_be(15);
+        assert_eq!(
+            upgraded_cache.insert_log(log.clone()),
+            Ok(DepositCacheInsertOutcome::Duplicate)
+        );
+
+        log.index = 16;
+        deposit_cache
+            .insert_log(log.clone())
+            .expect("should insert");
+        upgraded_cache.insert_log(log).expect("should insert");
+        assert_eq!(
+            deposit_cache.get_deposit_root_from_cache(15),
+            upgraded_cache.get_deposit_root_from_cache(15)
+        );
+    }
+
     #[test]
     fn ssz_encode_decode_with_finalization() {
         let mut deposit_cache = get_cache_with_deposits(512);
@@ -0,0 +1,116 @@
+use crate::DepositLog;
+use eth2::lighthouse::IndexedDeposit;
+use ssz_derive::{Decode, Encode};
+use std::collections::{BTreeMap, HashMap};
+use types::{Hash256, PublicKeyBytes, Slot};
+
+/// A beacon block which included a deposit.
+///
+/// A deposit may be included by blocks of several forks, the canonical one is selected when the
+/// index is read.
+#[derive(Debug, PartialEq, Clone, Copy, Encode, Decode)]
+pub struct DepositInclusion {
+    /// The index of the deposit in the deposit contract.
+    pub index: u64,
+    pub block_root: Hash256,
+    pub slot: Slot,
+}
+
+/// Indexes deposits by pubkey and by transaction hash.
+///
+/// Unlike the logs of the `DepositCache`, deposits are never pruned from the index when they are
+/// finalized.
+#[derive(Debug, Default, PartialEq)]
+pub struct DepositIndex {
+    deposits: BTreeMap<u64, IndexedDeposit>,
+    inclusions: HashMap<u64, Vec<DepositInclusion>>,
+    by_pubkey: HashMap<PublicKeyBytes, Vec<u64>>,
+    by_transaction: HashMap<Hash256, Vec<u64>>,
+}
+
+impl DepositIndex {
+    /// Rebuilds the index from the persisted `deposits` and `inclusions`.
+    pub fn from_persisted(
+        deposits: Vec<IndexedDeposit>,
+        inclusions: Vec<DepositInclusion>,
+    ) -> Self {
+        let mut index = Self::default();
+        for deposit in deposits {
+            index.insert(deposit);
+        }
+        for inclusion in inclusions {
+            index.insert_inclusion(inclusion);
+        }
+        index
+    }
+
+    /// Returns all deposits of the index, in index order.
+    pub fn deposits(&self) -> impl Iterator<Item = &IndexedDeposit> {
+        self.deposits.values()
+    }
+
+    /// Returns all inclusions of the index.
+    pub fn inclusions(&self) -> impl Iterator<Item = &DepositInclusion> {
+        self.inclusions.values().flatten()
+    }
+
+    /// Adds the deposit of `log`, unless it is already known.
+    pub fn insert_log(&mut self, log: &DepositLog) {
+        if !self.deposits.contains_key(&log.index) {
+            self.insert(log.into());
+        }
+    }
+
+    fn insert(&mut self, deposit: IndexedDeposit) {
+        self.by_pubkey
+            .entry(deposit.pubkey)
+            .or_default()
+            .push(deposit.index);
+        // Deposits migrated from a schema without transaction hashes have a zero hash.
+        if !deposit.transaction_hash.is_zero() {
+            self.by_transaction
+                .entry(deposit.transaction_hash)
+                .or_default()
+                .push(deposit.index);
+        }
+        self.deposits.insert(deposit.index, deposit);
+    }
+
+    /// Records that the deposit with `inclusion.index` was included by a block.
+    ///
+    /// The inclusion may be recorded before the deposit itself is known.
+    pub fn insert_inclusion(&mut self, inclusion: DepositInclusion) {
+        let inclusions = self.inclusions.entry(inclusion.index).or_default();
+        if !inclusions.contains(&inclusion) {
+            inclusions.push(inclusion);
+        }
+    }
+
+    /// Returns the deposits to `pubkey`, in index order.
+    pub fn get_by_pubkey(&self, pubkey: &PublicKeyBytes) -> Vec<&IndexedDeposit> {
+        self.get_indices(self.by_pubkey.get(pubkey))
+    }
+
+    /// Returns the deposits of the transaction with `transaction_hash`, in index order.
+    pub fn get_by_transaction(&self, transaction_hash: &Hash256) -> Vec<&IndexedDeposit> {
+        self.get_indices(self.by_transaction.get(transaction_hash))
+    }
+
+    /// Returns the blocks which included the deposit with `index`.
+    pub fn get_inclusions(&self, index: u64) -> &[DepositInclusion] {
+        self.inclusions
+            .get(&index)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn get_indices(&self, indices: Option<&Vec<u64>>) -> Vec<&IndexedDeposit> {
+        let mut deposits = indices
+            .into_iter()
+            .flatten()
+            .filter_map(|index| self.deposits.get(index))
+            .collect::<Vec<_>>();
+        deposits.sort_by_key(|deposit| deposit.index);
+        deposits
+    }
+}
@@ -2,7 +2,9 @@ use crate::service::endpoint_from_config;
 use crate::Config;
 use crate::{
     block_cache::{BlockCache, Eth1Block},
-    deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV1, SszDepositCacheV13},
+    deposit_cache::{
+        DepositCache, SszDepositCache, SszDepositCacheV1, SszDepositCacheV13, SszDepositCacheV20,
+    },
 };
 use execution_layer::HttpJsonRpc;
 use parking_lot::RwLock;
@@ -87,10 +89,10 @@ impl Inner {
     }
 }
 
-pub type SszEth1Cache = SszEth1CacheV13;
+pub type SszEth1Cache = SszEth1CacheV20;
 
 #[superstruct(
-    variants(V1, V13),
+    variants(V1, V13, V20),
     variant_attributes(derive(Encode, Decode, Clone)),
     no_enum
 )]
@@ -100,10 +102,32 @@ pub struct SszEth1Cache {
     pub deposit_cache: SszDepositCacheV1,
     #[superstruct(only(V13))]
     pub deposit_cache: SszDepositCacheV13,
+    #[superstruct(only(V20))]
+    pub deposit_cache: SszDepositCacheV20,
     #[ssz(with = "four_byte_option_u64")]
     pub last_processed_block: Option<u64>,
 }
 
+impl SszEth1CacheV13 {
+    pub fn upgrade(self) -> SszEth1CacheV20 {
+        SszEth1CacheV20 {
+            block_cache: self.block_cache,
+            deposit_cache: self.deposit_cache.upgrade(),
+            last_processed_block: self.last_processed_block,
+        }
+    }
+}
+
+impl SszEth1CacheV20 {
+    pub fn downgrade(self) -> SszEth1CacheV13 {
+        SszEth1CacheV13 {
+            block_cache: self.block_cache,
+            deposit_cache: self.deposit_cache.downgrade(),
+            last_processed_block: self.last_processed_block,
+        }
+    }
+}
+
 impl SszEth1Cache {
     pub fn from_inner(inner: &Inner) -> Self {
         let deposit_updater = inner.deposit_cache.read();
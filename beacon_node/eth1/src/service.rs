@@ -3,7 +3,9 @@ use crate::{
     block_cache::{BlockCache, Error as BlockCacheError, Eth1Block},
     deposit_cache::{DepositCacheInsertOutcome, Error as DepositCacheError},
     inner::{DepositUpdater, Inner},
+    DepositInclusion,
 };
+use eth2::lighthouse::IndexedDeposit;
 use execution_layer::auth::Auth;
 use execution_layer::http::{
     deposit_methods::{BlockQuery, Eth1Id},
@@ -20,7 +22,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Unsigned};
+use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Hash256, PublicKeyBytes, Unsigned};
 
 /// Indicates the default eth1 chain id we use for the deposit contract.
 pub const DEFAULT_CHAIN_ID: Eth1Id = Eth1Id::Goerli;
@@ -548,6 +550,54 @@ impl Service {
         self.deposits().read().cache.len()
     }
 
+    /// Returns the deposits to `pubkey` in the deposit index, along with the beacon blocks which
+    /// included each of them.
+    pub fn indexed_deposits_by_pubkey(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Vec<(IndexedDeposit, Vec<DepositInclusion>)> {
+        let deposits = self.deposits().read();
+        let index = deposits.cache.deposit_index();
+        index
+            .get_by_pubkey(pubkey)
+            .into_iter()
+            .map(|deposit| {
+                (
+                    deposit.clone(),
+                    index.get_inclusions(deposit.index).to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the deposits of the transaction with `transaction_hash` in the deposit index,
+    /// along with the beacon blocks which included each of them.
+    pub fn indexed_deposits_by_transaction(
+        &self,
+        transaction_hash: &Hash256,
+    ) -> Vec<(IndexedDeposit, Vec<DepositInclusion>)> {
+        let deposits = self.deposits().read();
+        let index = deposits.cache.deposit_index();
+        index
+            .get_by_transaction(transaction_hash)
+            .into_iter()
+            .map(|deposit| {
+                (
+                    deposit.clone(),
+                    index.get_inclusions(deposit.index).to_vec(),
+                )
+            })
+            .collect()
+    }
+
+    /// Records the beacon blocks which included deposits in the deposit index.
+    pub fn insert_deposit_inclusions(&self, inclusions: Vec<DepositInclusion>) {
+        let mut deposits = self.deposits().write();
+        for inclusion in inclusions {
+            deposits.cache.insert_deposit_inclusion(inclusion);
+        }
+    }
+
     /// Returns the number of deposits with valid signatures that have been observed.
     pub fn get_valid_signature_count(&self) -> Option<usize> {
         self.deposits()
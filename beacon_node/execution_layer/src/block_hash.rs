@@ -1,12 +1,13 @@
 use crate::{
     json_structures::JsonWithdrawal,
     keccak::{keccak256, KeccakHasher},
+    Error, NewPayloadRequest,
 };
 use ethers_core::utils::rlp::RlpStream;
 use keccak_hash::KECCAK_EMPTY_LIST_RLP;
 use triehash::ordered_trie_root;
 use types::{
-    map_execution_block_header_fields_base, Address, EthSpec, ExecutionBlockHash,
+    map_execution_block_header_fields_base, Address, BeaconBlockRef, EthSpec, ExecutionBlockHash,
     ExecutionBlockHeader, ExecutionPayloadRef, Hash256, Hash64, Uint256,
 };
 
@@ -58,6 +59,21 @@ pub fn calculate_execution_block_hash<T: EthSpec>(
     )
 }
 
+/// Verify the block hash of the execution payload of `block` without an execution engine, by
+/// recomputing it from the payload fields.
+///
+/// Blocks from before the Bellatrix fork and blocks with the default payload from before the
+/// merge are always valid.
+pub fn verify_block_payload_block_hash<E: EthSpec>(block: BeaconBlockRef<E>) -> Result<(), Error> {
+    let Ok(request) = NewPayloadRequest::try_from(block) else {
+        return Ok(());
+    };
+    if request.block_hash() == ExecutionBlockHash::zero() {
+        return Ok(());
+    }
+    request.verify_payload_block_hash()
+}
+
 /// RLP encode a withdrawal.
 pub fn rlp_encode_withdrawal(withdrawal: &JsonWithdrawal) -> Vec<u8> {
     let mut rlp_stream = RlpStream::new();
@@ -3,14 +3,17 @@
 use super::*;
 use crate::auth::Auth;
 use crate::json_structures::*;
+use crate::recorder::EngineRecorder;
 use reqwest::header::CONTENT_TYPE;
+use reqwest::RequestBuilder;
 use sensitive_url::SensitiveUrl;
 use serde::de::DeserializeOwned;
 use serde_json::json;
 use std::collections::HashSet;
+use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 pub use deposit_log::{DepositLog, Log};
 pub use reqwest::Client;
@@ -51,6 +54,9 @@ pub const ENGINE_GET_PAYLOAD_BODIES_TIMEOUT: Duration = Duration::from_secs(10);
 pub const ENGINE_EXCHANGE_CAPABILITIES: &str = "engine_exchangeCapabilities";
 pub const ENGINE_EXCHANGE_CAPABILITIES_TIMEOUT: Duration = Duration::from_secs(1);
 
+pub const ENGINE_GET_CLIENT_VERSION_V1: &str = "engine_getClientVersionV1";
+pub const ENGINE_GET_CLIENT_VERSION_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// This error is returned during a `chainId` call by Geth.
 pub const EIP155_ERROR_STR: &str = "chain not synced beyond EIP-155 replay-protection fork block";
 /// This code is returned by all clients when a method is not supported
@@ -69,6 +75,7 @@ pub static LIGHTHOUSE_CAPABILITIES: &[&str] = &[
     ENGINE_FORKCHOICE_UPDATED_V3,
     ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1,
     ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
+    ENGINE_GET_CLIENT_VERSION_V1,
 ];
 
 /// Contains methods to convert arbitrary bytes to an ETH2 deposit contract object.
@@ -96,6 +103,8 @@ pub mod deposit_log {
     #[derive(Debug, PartialEq, Clone)]
     pub struct Log {
         pub block_number: u64,
+        pub transaction_hash: Hash256,
+        pub log_index: u64,
         pub data: Vec<u8>,
     }
 
@@ -142,6 +151,8 @@ pub mod deposit_log {
                 index: u64::from_ssz_bytes(index)
                     .map_err(|e| format!("Invalid index ssz: {:?}", e))?,
                 signature_is_valid,
+                transaction_hash: self.transaction_hash,
+                log_index: self.log_index,
             })
         }
     }
@@ -182,6 +193,8 @@ pub mod deposit_log {
         fn can_parse_example_log() {
             let log = Log {
                 block_number: 42,
+                transaction_hash: Hash256::repeat_byte(42),
+                log_index: 0,
                 data: EXAMPLE_LOG.to_vec(),
             };
             log.to_deposit_log(&MainnetEthSpec::default_spec())
@@ -529,6 +542,18 @@ pub mod deposit_methods {
                         .as_str()
                         .ok_or("Block number was not string")?;
 
+                    let transaction_hash = value
+                        .get("transactionHash")
+                        .ok_or("No transaction hash field in log")?
+                        .as_str()
+                        .ok_or("Transaction hash was not string")?;
+
+                    let log_index = value
+                        .get("logIndex")
+                        .ok_or("No log index field in log")?
+                        .as_str()
+                        .ok_or("Log index was not string")?;
+
                     let data = value
                         .get("data")
                         .ok_or("No block number field in log")?
@@ -537,6 +562,9 @@ pub mod deposit_methods {
 
                     Ok(Log {
                         block_number: hex_to_u64_be(block_number)?,
+                        transaction_hash: Hash256::from_str(transaction_hash)
+                            .map_err(|e| format!("Invalid transaction hash: {:?}", e))?,
+                        log_index: hex_to_u64_be(log_index)?,
                         data: hex_to_bytes(data)?,
                     })
                 })
@@ -580,6 +608,7 @@ pub struct HttpJsonRpc {
     pub execution_timeout_multiplier: u32,
     pub engine_capabilities_cache: Mutex<Option<CapabilitiesCacheEntry>>,
     auth: Option<Auth>,
+    recorder: Option<Arc<EngineRecorder>>,
 }
 
 impl HttpJsonRpc {
@@ -593,6 +622,7 @@ impl HttpJsonRpc {
             execution_timeout_multiplier: execution_timeout_multiplier.unwrap_or(1),
             engine_capabilities_cache: Mutex::new(None),
             auth: None,
+            recorder: None,
         })
     }
 
@@ -607,9 +637,16 @@ impl HttpJsonRpc {
             execution_timeout_multiplier: execution_timeout_multiplier.unwrap_or(1),
             engine_capabilities_cache: Mutex::new(None),
             auth: Some(auth),
+            recorder: None,
         })
     }
 
+    /// Record every request and its response with `recorder`.
+    pub fn with_recorder(mut self, recorder: Arc<EngineRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     pub async fn rpc_request<D: DeserializeOwned>(
         &self,
         method: &str,
@@ -635,7 +672,13 @@ impl HttpJsonRpc {
             request = request.bearer_auth(auth.generate_token()?);
         };
 
-        let body: JsonResponseBody = request.send().await?.error_for_status()?.json().await?;
+        let body: JsonResponseBody = match &self.recorder {
+            Some(recorder) => {
+                self.send_recorded(recorder, request, method, &body.params)
+                    .await?
+            }
+            None => request.send().await?.error_for_status()?.json().await?,
+        };
 
         match (body.result, body.error) {
             (result, None) => serde_json::from_value(result).map_err(Into::into),
@@ -651,6 +694,36 @@ impl HttpJsonRpc {
             }
         }
     }
+
+    /// Send `request`, recording it and its response with `recorder`.
+    async fn send_recorded(
+        &self,
+        recorder: &EngineRecorder,
+        request: RequestBuilder,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<JsonResponseBody, Error> {
+        let started_at = SystemTime::now();
+        let start = Instant::now();
+        let response = match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response
+                .json::<serde_json::Value>()
+                .await
+                .map_err(Error::from),
+            Err(e) => Err(Error::from(e)),
+        };
+
+        recorder.record(
+            &self.url,
+            method,
+            params,
+            started_at,
+            start.elapsed(),
+            response.as_ref().map_err(|e| format!("{:?}", e)),
+        );
+
+        serde_json::from_value(response?).map_err(Into::into)
+    }
 }
 
 impl std::fmt::Display for HttpJsonRpc {
@@ -1017,9 +1090,31 @@ impl HttpJsonRpc {
             get_payload_v1: capabilities.contains(ENGINE_GET_PAYLOAD_V1),
             get_payload_v2: capabilities.contains(ENGINE_GET_PAYLOAD_V2),
             get_payload_v3: capabilities.contains(ENGINE_GET_PAYLOAD_V3),
+            get_client_version_v1: capabilities.contains(ENGINE_GET_CLIENT_VERSION_V1),
         })
     }
 
+    /// Returns the versions of the execution engine, identifying this beacon node in turn.
+    ///
+    /// Multiplexed execution engines may return several versions, one for each client.
+    pub async fn get_client_version_v1(&self) -> Result<Vec<JsonClientVersionV1>, Error> {
+        let engine_capabilities = self.get_engine_capabilities(None).await?;
+        if !engine_capabilities.get_client_version_v1 {
+            return Err(Error::RequiredMethodUnsupported(
+                ENGINE_GET_CLIENT_VERSION_V1,
+            ));
+        }
+
+        let params = json!([JsonClientVersionV1::lighthouse()]);
+
+        self.rpc_request(
+            ENGINE_GET_CLIENT_VERSION_V1,
+            params,
+            ENGINE_GET_CLIENT_VERSION_TIMEOUT * self.execution_timeout_multiplier,
+        )
+        .await
+    }
+
     pub async fn clear_exchange_capabilties_cache(&self) {
         *self.engine_capabilities_cache.lock().await = None;
     }
@@ -1,4 +1,5 @@
 use super::*;
+use eth2::types::ExecutionClientVersion;
 use serde::{Deserialize, Serialize};
 use strum::EnumString;
 use superstruct::superstruct;
@@ -638,6 +639,38 @@ impl<E: EthSpec> From<JsonExecutionPayloadBodyV1<E>> for ExecutionPayloadBodyV1<
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonClientVersionV1 {
+    pub code: String,
+    pub name: String,
+    pub version: String,
+    pub commit: String,
+}
+
+impl JsonClientVersionV1 {
+    /// The version of this build of Lighthouse.
+    pub fn lighthouse() -> Self {
+        let commit = lighthouse_version::COMMIT_PREFIX;
+        Self {
+            code: lighthouse_version::CLIENT_CODE.to_string(),
+            name: "Lighthouse".to_string(),
+            version: lighthouse_version::version_number().to_string(),
+            commit: format!("0x{}", commit.get(..8).unwrap_or(commit)),
+        }
+    }
+}
+
+impl From<JsonClientVersionV1> for ExecutionClientVersion {
+    fn from(value: JsonClientVersionV1) -> Self {
+        Self {
+            code: value.code,
+            name: value.name,
+            version: value.version,
+            commit: value.commit,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransitionConfigurationV1 {
@@ -180,9 +180,9 @@ impl<'a, E: EthSpec> TryFrom<ExecutionPayloadRef<'a, E>> for NewPayloadRequest<'
 #[cfg(test)]
 mod test {
     use crate::versioned_hashes::Error as VersionedHashError;
-    use crate::{Error, NewPayloadRequest};
+    use crate::{verify_block_payload_block_hash, Error, NewPayloadRequest};
     use state_processing::per_block_processing::deneb::kzg_commitment_to_versioned_hash;
-    use types::{BeaconBlock, ExecPayload, ExecutionBlockHash, Hash256, MainnetEthSpec};
+    use types::{BeaconBlock, ChainSpec, ExecPayload, ExecutionBlockHash, Hash256, MainnetEthSpec};
 
     #[test]
     fn test_optimistic_sync_verifications_valid_block() {
@@ -275,6 +275,36 @@ mod test {
         assert!(got_expected_result, "should return expected error");
     }
 
+    #[test]
+    fn test_verify_block_payload_block_hash() {
+        let mut beacon_block = get_valid_beacon_block();
+        assert!(verify_block_payload_block_hash(beacon_block.to_ref()).is_ok());
+
+        let payload = &mut beacon_block
+            .body_mut()
+            .execution_payload_deneb_mut()
+            .expect("should get payload")
+            .execution_payload;
+        payload.block_hash = ExecutionBlockHash(Hash256::repeat_byte(0x42));
+        assert!(matches!(
+            verify_block_payload_block_hash(beacon_block.to_ref()),
+            Err(Error::BlockHashMismatch { .. })
+        ));
+
+        // Payloads from before the merge have a zero block hash and are not verified.
+        let payload = &mut beacon_block
+            .body_mut()
+            .execution_payload_deneb_mut()
+            .expect("should get payload")
+            .execution_payload;
+        payload.block_hash = ExecutionBlockHash::zero();
+        assert!(verify_block_payload_block_hash(beacon_block.to_ref()).is_ok());
+
+        // Blocks without payloads are always valid.
+        let base_block = BeaconBlock::<MainnetEthSpec>::empty(&ChainSpec::mainnet());
+        assert!(verify_block_payload_block_hash(base_block.to_ref()).is_ok());
+    }
+
     fn get_valid_beacon_block() -> BeaconBlock<MainnetEthSpec> {
         BeaconBlock::Deneb(serde_json::from_str(r#"{
           "slot": "88160",
@@ -0,0 +1,342 @@
+//! Records engine API requests and their responses, so that disagreements with an execution
+//! engine can be diagnosed and reproduced offline with the `MockServer` in `test_utils`.
+//!
+//! Each exchange is written as one line of JSON to `engine_api.jsonl` in the recording
+//! directory. Once that file exceeds `max_file_size` it is rotated to `engine_api.1.jsonl`,
+//! `engine_api.2.jsonl`, etc., keeping at most `max_files` files.
+//!
+//! Exchanges are written by a dedicated thread so that recording never blocks the async runtime.
+//! If the thread falls behind by more than `MAX_PENDING_EXCHANGES`, further exchanges are dropped.
+
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use slog::{warn, Logger};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const RECORDING_FILE_PREFIX: &str = "engine_api";
+pub const RECORDING_FILE_EXTENSION: &str = "jsonl";
+pub const DEFAULT_MAX_RECORDING_FILE_SIZE: u64 = 100 * 1024 * 1024;
+pub const DEFAULT_MAX_RECORDING_FILES: usize = 10;
+/// The number of exchanges which may be waiting to be written before exchanges are dropped.
+pub const MAX_PENDING_EXCHANGES: usize = 1_024;
+
+/// A single engine API request and its response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    /// The time at which the request was sent, in milliseconds since the UNIX epoch.
+    pub timestamp_ms: u64,
+    /// The (redacted) URL of the execution engine.
+    pub endpoint: String,
+    pub method: String,
+    pub params: serde_json::Value,
+    pub duration_ms: u64,
+    /// The JSON-RPC response of the execution engine, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<serde_json::Value>,
+    /// The error which prevented a response, e.g. a timeout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct RecordingFile {
+    file: File,
+    size: u64,
+}
+
+/// A recorded exchange, serialized as a line of JSON.
+struct PendingExchange {
+    method: String,
+    line: Vec<u8>,
+}
+
+pub struct EngineRecorder {
+    dir: PathBuf,
+    sender: Option<SyncSender<PendingExchange>>,
+    writer: Option<JoinHandle<()>>,
+    log: Logger,
+}
+
+impl EngineRecorder {
+    /// Creates a recorder writing to `dir`, which is created if it does not exist.
+    pub fn new(
+        dir: PathBuf,
+        max_file_size: u64,
+        max_files: usize,
+        log: &Logger,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Unable to create {}: {:?}", dir.display(), e))?;
+
+        let mut writer = RecordingWriter {
+            dir: dir.clone(),
+            max_file_size,
+            max_files: max_files.max(1),
+            file: None,
+        };
+        let (sender, receiver) = mpsc::sync_channel::<PendingExchange>(MAX_PENDING_EXCHANGES);
+        let writer_log = log.clone();
+        let writer = thread::Builder::new()
+            .name("engine_recorder".to_string())
+            .spawn(move || {
+                for exchange in receiver {
+                    if let Err(e) = writer.write(&exchange.line) {
+                        warn!(
+                            writer_log,
+                            "Failed to record engine API request";
+                            "method" => exchange.method,
+                            "dir" => %writer.dir.display(),
+                            "error" => ?e,
+                        );
+                    }
+                }
+            })
+            .map_err(|e| format!("Unable to start the engine API recorder: {:?}", e))?;
+
+        Ok(Self {
+            dir,
+            sender: Some(sender),
+            writer: Some(writer),
+            log: log.clone(),
+        })
+    }
+
+    /// Returns the path of the recording file which is `age` rotations old.
+    pub fn recording_path(dir: &Path, age: usize) -> PathBuf {
+        if age == 0 {
+            dir.join(format!(
+                "{}.{}",
+                RECORDING_FILE_PREFIX, RECORDING_FILE_EXTENSION
+            ))
+        } else {
+            dir.join(format!(
+                "{}.{}.{}",
+                RECORDING_FILE_PREFIX, age, RECORDING_FILE_EXTENSION
+            ))
+        }
+    }
+
+    /// Record a request to `endpoint` and its response, or the error which prevented one.
+    ///
+    /// The exchange is written in the background. Failing to record is logged rather than
+    /// returned, since it must not affect the request.
+    pub fn record(
+        &self,
+        endpoint: &SensitiveUrl,
+        method: &str,
+        params: &serde_json::Value,
+        started_at: SystemTime,
+        duration: Duration,
+        response: Result<&serde_json::Value, String>,
+    ) {
+        let (response, error) = match response {
+            Ok(response) => (Some(response.clone()), None),
+            Err(error) => (None, Some(error)),
+        };
+        let exchange = RecordedExchange {
+            timestamp_ms: started_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            endpoint: endpoint.to_string(),
+            method: method.to_string(),
+            params: params.clone(),
+            duration_ms: duration.as_millis() as u64,
+            response,
+            error,
+        };
+        let mut line = match serde_json::to_vec(&exchange) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!(
+                    self.log,
+                    "Failed to record engine API request";
+                    "method" => method,
+                    "error" => ?e,
+                );
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let pending = PendingExchange {
+            method: exchange.method,
+            line,
+        };
+        match sender.try_send(pending) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => warn!(
+                self.log,
+                "Dropped engine API recording";
+                "info" => "the recording is not keeping up with requests",
+                "method" => method,
+                "dir" => %self.dir.display(),
+            ),
+            Err(TrySendError::Disconnected(_)) => warn!(
+                self.log,
+                "Dropped engine API recording";
+                "info" => "the recorder has stopped",
+                "method" => method,
+                "dir" => %self.dir.display(),
+            ),
+        }
+    }
+}
+
+impl Drop for EngineRecorder {
+    /// Wait for pending exchanges to be written.
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Writes recorded exchanges to the recording files, on the thread of the `EngineRecorder`.
+struct RecordingWriter {
+    dir: PathBuf,
+    max_file_size: u64,
+    max_files: usize,
+    file: Option<RecordingFile>,
+}
+
+impl RecordingWriter {
+    fn write(&mut self, line: &[u8]) -> io::Result<()> {
+        if self
+            .file
+            .as_ref()
+            .map_or(false, |file| file.size >= self.max_file_size)
+        {
+            self.file = None;
+            self.rotate()?;
+        }
+
+        let recording = match &mut self.file {
+            Some(recording) => recording,
+            None => {
+                let path = EngineRecorder::recording_path(&self.dir, 0);
+                let opened = OpenOptions::new().create(true).append(true).open(path)?;
+                let size = opened.metadata()?.len();
+                self.file.insert(RecordingFile { file: opened, size })
+            }
+        };
+
+        recording.file.write_all(line)?;
+        recording.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift each recording file to the next age, deleting the oldest.
+    fn rotate(&self) -> io::Result<()> {
+        let oldest = EngineRecorder::recording_path(&self.dir, self.max_files - 1);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+        for age in (0..self.max_files - 1).rev() {
+            let path = EngineRecorder::recording_path(&self.dir, age);
+            if path.exists() {
+                fs::rename(path, EngineRecorder::recording_path(&self.dir, age + 1))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read the exchanges of a recording file, in the order in which they were recorded.
+pub fn read_recording(path: &Path) -> Result<Vec<RecordedExchange>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Unable to open {}: {:?}", path.display(), e))?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(i, line)| {
+            let line = line.map_err(|e| format!("Unable to read {}: {:?}", path.display(), e))?;
+            serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid recording on line {}: {:?}", i + 1, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment::null_logger;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn record(recorder: &EngineRecorder, method: &str) {
+        let endpoint = SensitiveUrl::parse("http://localhost:8551").unwrap();
+        recorder.record(
+            &endpoint,
+            method,
+            &json!([]),
+            SystemTime::now(),
+            Duration::from_millis(3),
+            Ok(&json!({"jsonrpc": "2.0", "id": 1, "result": false})),
+        );
+    }
+
+    #[test]
+    fn records_and_reads_exchanges() {
+        let dir = tempdir().unwrap();
+        let recorder = EngineRecorder::new(
+            dir.path().to_path_buf(),
+            u64::MAX,
+            2,
+            &null_logger().unwrap(),
+        )
+        .unwrap();
+        record(&recorder, "eth_syncing");
+        recorder.record(
+            &SensitiveUrl::parse("http://localhost:8551").unwrap(),
+            "engine_newPayloadV3",
+            &json!([{}]),
+            SystemTime::now(),
+            Duration::from_secs(8),
+            Err("timeout".to_string()),
+        );
+        // Wait for the exchanges to be written.
+        drop(recorder);
+
+        let exchanges = read_recording(&EngineRecorder::recording_path(dir.path(), 0)).unwrap();
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(exchanges[0].method, "eth_syncing");
+        assert_eq!(exchanges[0].duration_ms, 3);
+        assert_eq!(exchanges[0].response.as_ref().unwrap()["result"], false);
+        assert_eq!(exchanges[1].method, "engine_newPayloadV3");
+        assert_eq!(exchanges[1].response, None);
+        assert_eq!(exchanges[1].error.as_deref(), Some("timeout"));
+    }
+
+    #[test]
+    fn rotates_files() {
+        let dir = tempdir().unwrap();
+        // Every exchange exceeds the maximum file size, so each is written to a new file.
+        let recorder =
+            EngineRecorder::new(dir.path().to_path_buf(), 1, 2, &null_logger().unwrap()).unwrap();
+        record(&recorder, "first");
+        record(&recorder, "second");
+        record(&recorder, "third");
+        drop(recorder);
+
+        let methods = |age| {
+            read_recording(&EngineRecorder::recording_path(dir.path(), age))
+                .unwrap()
+                .into_iter()
+                .map(|exchange| exchange.method)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(methods(0), vec!["third"]);
+        assert_eq!(methods(1), vec!["second"]);
+        assert!(!EngineRecorder::recording_path(dir.path(), 2).exists());
+    }
+}
@@ -2,9 +2,11 @@
 
 use crate::engine_api::{
     EngineCapabilities, Error as EngineApiError, ForkchoiceUpdatedResponse, PayloadAttributes,
-    PayloadId,
+    PayloadId, PayloadStatusV1, PayloadStatusV1Status,
 };
-use crate::HttpJsonRpc;
+use crate::{metrics, HttpJsonRpc};
+use futures::future::{join_all, select_all};
+use futures::stream::{FuturesUnordered, StreamExt};
 use lru::LruCache;
 use slog::{debug, error, info, warn, Logger};
 use std::future::Future;
@@ -33,6 +35,18 @@ enum EngineStateInternal {
     AuthFailed,
 }
 
+impl EngineStateInternal {
+    /// The preference of an engine in this state when choosing an engine for a request, lower is
+    /// better.
+    fn rank(self) -> u8 {
+        match self {
+            EngineStateInternal::Synced => 0,
+            EngineStateInternal::Syncing => 1,
+            EngineStateInternal::Offline | EngineStateInternal::AuthFailed => 2,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 enum CapabilitiesCacheAction {
     #[default]
@@ -317,6 +331,12 @@ impl Engine {
             CapabilitiesCacheAction::Clear => self.api.clear_exchange_capabilties_cache().await,
         }
 
+        metrics::set_gauge_vec(
+            &metrics::EXECUTION_LAYER_ENGINE_SYNCED,
+            &[&self.api.url.to_string()],
+            (state == EngineStateInternal::Synced) as i64,
+        );
+
         debug!(
             self.log,
             "Execution engine upcheck complete";
@@ -351,7 +371,20 @@ impl Engine {
         F: FnOnce(&'a Engine) -> G,
         G: Future<Output = Result<H, EngineApiError>>,
     {
-        match func(self).await {
+        let result = func(self).await;
+        metrics::inc_counter_vec(
+            &metrics::EXECUTION_LAYER_ENGINE_REQUESTS,
+            &[
+                &self.api.url.to_string(),
+                if result.is_ok() {
+                    metrics::SUCCESS
+                } else {
+                    metrics::FAILURE
+                },
+            ],
+        );
+
+        match result {
             Ok(result) => {
                 // Take a clone *without* holding the read-lock since the `upcheck` function will
                 // take a write-lock.
@@ -374,6 +407,7 @@ impl Engine {
                     self.log,
                     "Execution engine call failed";
                     "error" => ?error,
+                    "endpoint" => %self.api.url,
                 );
 
                 // The node just returned an error, run an upcheck so we can update the endpoint
@@ -392,6 +426,364 @@ impl Engine {
     }
 }
 
+/// A set of execution engines. Requests are sent to the healthiest engine, falling back to the
+/// others if it fails, whilst `forkchoiceUpdated` and `newPayload` are sent to all engines.
+pub struct Engines {
+    /// The engines, in the order of preference given by the user.
+    engines: Vec<Arc<Engine>>,
+    /// The number of engines which must agree on the validity of a payload, in response to either
+    /// `newPayload` or `forkchoiceUpdated`.
+    new_payload_quorum: usize,
+    executor: TaskExecutor,
+    log: Logger,
+}
+
+impl Engines {
+    pub fn new(
+        engines: Vec<Engine>,
+        new_payload_quorum: usize,
+        executor: TaskExecutor,
+        log: &Logger,
+    ) -> Self {
+        Self {
+            engines: engines.into_iter().map(Arc::new).collect(),
+            new_payload_quorum,
+            executor,
+            log: log.clone(),
+        }
+    }
+
+    /// Returns the engines ordered by health: synced engines first, then syncing engines, then
+    /// offline engines. Engines with the same state are kept in the order given by the user.
+    async fn ranked(&self) -> Vec<&Arc<Engine>> {
+        let mut ranked = Vec::with_capacity(self.engines.len());
+        for engine in &self.engines {
+            ranked.push((engine.state.read().await.rank(), engine));
+        }
+        ranked.sort_by_key(|(rank, _)| *rank);
+        ranked.into_iter().map(|(_, engine)| engine).collect()
+    }
+
+    /// Returns the healthiest engine.
+    pub async fn first_healthy(&self) -> &Arc<Engine> {
+        let mut first = &self.engines[0];
+        let mut first_rank = u8::MAX;
+        for engine in &self.engines {
+            let rank = engine.state.read().await.rank();
+            if rank < first_rank {
+                first = engine;
+                first_rank = rank;
+            }
+        }
+        first
+    }
+
+    /// Returns `true` if any engine has a "synced" status.
+    pub async fn is_synced(&self) -> bool {
+        for engine in &self.engines {
+            if engine.is_synced().await {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns `true` if all engines are offline.
+    pub async fn is_offline(&self) -> bool {
+        for engine in &self.engines {
+            if !engine.is_offline().await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Run `Engine::upcheck` on all engines.
+    pub async fn upcheck(&self) {
+        join_all(self.engines.iter().map(|engine| engine.upcheck())).await;
+    }
+
+    pub async fn set_latest_forkchoice_state(&self, state: ForkchoiceState) {
+        for engine in &self.engines {
+            engine.set_latest_forkchoice_state(state).await;
+        }
+    }
+
+    /// Gives access to a channel containing the combined engine state, which is online whilst any
+    /// engine is online.
+    pub async fn watch_state(&self) -> WatchStream<EngineState> {
+        if let [engine] = self.engines.as_slice() {
+            return engine.watch_state().await;
+        }
+
+        let mut receivers = Vec::with_capacity(self.engines.len());
+        for engine in &self.engines {
+            receivers.push(engine.state.read().await.notifier.subscribe());
+        }
+        let combined_state = |receivers: &[watch::Receiver<EngineState>]| {
+            if receivers
+                .iter()
+                .any(|receiver| *receiver.borrow() == EngineState::Online)
+            {
+                EngineState::Online
+            } else {
+                EngineState::Offline
+            }
+        };
+
+        let (notifier, receiver) = watch::channel(combined_state(&receivers));
+        self.executor.spawn(
+            async move {
+                loop {
+                    let (changed, _, _) = select_all(
+                        receivers
+                            .iter_mut()
+                            .map(|receiver| Box::pin(receiver.changed())),
+                    )
+                    .await;
+                    if changed.is_err() {
+                        break;
+                    }
+                    let new_state = combined_state(&receivers);
+                    notifier.send_if_modified(|last_state| {
+                        let changed = *last_state != new_state;
+                        *last_state = new_state;
+                        changed
+                    });
+                }
+            },
+            "engines_watch_state",
+        );
+        receiver.into()
+    }
+
+    /// Run `func` on the healthiest engine, falling back to the other engines in order of health
+    /// if it fails. Returns the error of the healthiest engine if all engines fail.
+    pub async fn request<'a, F, G, H>(&'a self, func: F) -> Result<H, EngineError>
+    where
+        F: Fn(&'a Engine) -> G,
+        G: Future<Output = Result<H, EngineApiError>>,
+    {
+        let mut first_error = None;
+        for engine in self.ranked().await {
+            match engine.request(&func).await {
+                Ok(result) => return Ok(result),
+                Err(error) => {
+                    first_error = first_error.or(Some(error));
+                }
+            }
+        }
+        Err(first_error.unwrap_or(EngineError::Offline))
+    }
+
+    /// Run `func` on all engines concurrently, returning the results ordered by health.
+    pub async fn broadcast<'a, F, G, H>(&'a self, func: F) -> Vec<Result<H, EngineError>>
+    where
+        F: Fn(&'a Engine) -> G,
+        G: Future<Output = Result<H, EngineApiError>>,
+    {
+        join_all(
+            self.ranked()
+                .await
+                .into_iter()
+                .map(|engine| engine.request(&func)),
+        )
+        .await
+    }
+
+    /// Run the `newPayload` call `func` on all engines and return the payload status which at
+    /// least `new_payload_quorum` engines agree on.
+    ///
+    /// If the engines do not reach a quorum on the validity of the payload, the payload is
+    /// treated as if the engines were syncing, so that the block is imported optimistically.
+    ///
+    /// Returns as soon as the responses of the remaining engines are unable to change the outcome,
+    /// dropping their requests, so that a slow engine does not hold up block import.
+    pub async fn new_payload<'a, F, G>(&'a self, func: F) -> Result<PayloadStatusV1, EngineError>
+    where
+        F: Fn(&'a Engine) -> G,
+        G: Future<Output = Result<PayloadStatusV1, EngineApiError>>,
+    {
+        let func = &func;
+        let mut requests = self
+            .ranked()
+            .await
+            .into_iter()
+            .enumerate()
+            .map(|(rank, engine)| async move { (rank, engine.request(func).await) })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut results = Vec::with_capacity(requests.len());
+        while let Some(result) = requests.next().await {
+            results.push(result);
+            if payload_status_quorum_decided(
+                results.iter().map(|(_, result)| result),
+                requests.len(),
+                self.new_payload_quorum,
+            ) {
+                break;
+            }
+        }
+        // Restore the order of health, which decides between statuses without a quorum.
+        results.sort_by_key(|(rank, _)| *rank);
+        let results = results.into_iter().map(|(_, result)| result).collect();
+
+        let outcome = payload_status_quorum(results, self.new_payload_quorum);
+        if let Ok(status) = &outcome {
+            if !is_verdict(status) && self.new_payload_quorum > 1 {
+                metrics::inc_counter(&metrics::EXECUTION_LAYER_NEW_PAYLOAD_NO_QUORUM);
+                debug!(
+                    self.log,
+                    "No quorum of engines validated payload";
+                    "status" => ?status.status,
+                    "quorum" => self.new_payload_quorum,
+                );
+            }
+        }
+        outcome
+    }
+
+    /// Run the `forkchoiceUpdated` call `func` on all engines and return the payload status of
+    /// the head which at least `new_payload_quorum` engines agree on.
+    ///
+    /// As for `Self::new_payload`, a head without a quorum is treated as if the engines were
+    /// syncing, so that a single faulty engine is unable to invalidate blocks.
+    pub async fn forkchoice_updated<'a, F, G>(
+        &'a self,
+        func: F,
+    ) -> Result<PayloadStatusV1, EngineError>
+    where
+        F: Fn(&'a Engine) -> G,
+        G: Future<Output = Result<ForkchoiceUpdatedResponse, EngineApiError>>,
+    {
+        let results = self
+            .broadcast(func)
+            .await
+            .into_iter()
+            .map(|result| result.map(|response| response.payload_status))
+            .collect();
+        let outcome = payload_status_quorum(results, self.new_payload_quorum);
+        if let Ok(status) = &outcome {
+            if !is_verdict(status) && self.new_payload_quorum > 1 {
+                debug!(
+                    self.log,
+                    "No quorum of engines validated head";
+                    "status" => ?status.status,
+                    "quorum" => self.new_payload_quorum,
+                );
+            }
+        }
+        outcome
+    }
+}
+
+/// Returns `true` if `status` is a decision on the validity of a payload.
+fn is_verdict(status: &PayloadStatusV1) -> bool {
+    matches!(
+        status.status,
+        PayloadStatusV1Status::Valid
+            | PayloadStatusV1Status::Invalid
+            | PayloadStatusV1Status::InvalidBlockHash
+    )
+}
+
+/// Returns `true` if two statuses are the same verdict on a payload.
+fn agrees(a: &PayloadStatusV1, b: &PayloadStatusV1) -> bool {
+    a.status == b.status && a.latest_valid_hash == b.latest_valid_hash
+}
+
+/// Returns `true` if the results of `pending` more engines are unable to change whether
+/// `payload_status_quorum` returns a verdict for `results`, and which one.
+///
+/// That is the case once a single verdict has a quorum which no other verdict is able to reach, or
+/// once no verdict is able to reach a quorum anymore.
+fn payload_status_quorum_decided<'a>(
+    results: impl Iterator<Item = &'a Result<PayloadStatusV1, EngineError>>,
+    pending: usize,
+    quorum: usize,
+) -> bool {
+    if pending == 0 {
+        return true;
+    }
+    let mut responded = false;
+    let mut tallies: Vec<(&PayloadStatusV1, usize)> = vec![];
+    for status in results.filter_map(|result| result.as_ref().ok()) {
+        responded = true;
+        if !is_verdict(status) {
+            continue;
+        }
+        match tallies
+            .iter_mut()
+            .find(|(verdict, _)| agrees(verdict, status))
+        {
+            Some((_, votes)) => *votes += 1,
+            None => tallies.push((status, 1)),
+        }
+    }
+
+    // A verdict which has not been seen yet may still reach the quorum.
+    if pending >= quorum {
+        return false;
+    }
+    let reached = tallies.iter().filter(|(_, votes)| *votes >= quorum).count();
+    let reachable = tallies
+        .iter()
+        .filter(|(_, votes)| *votes < quorum && votes + pending >= quorum)
+        .count();
+    match reached {
+        0 => responded && reachable == 0,
+        1 => reachable == 0,
+        // Conflicting verdicts are never trusted.
+        _ => true,
+    }
+}
+
+/// Returns the verdict which at least `quorum` of the `results` agree on. Without a single such
+/// verdict the first non-verdict status (e.g. `SYNCING`) is returned, or a `SYNCING` status if
+/// all engines gave a verdict. If no engine responded the first error is returned.
+fn payload_status_quorum(
+    results: Vec<Result<PayloadStatusV1, EngineError>>,
+    quorum: usize,
+) -> Result<PayloadStatusV1, EngineError> {
+    let mut statuses = vec![];
+    let mut first_error = None;
+    for result in results {
+        match result {
+            Ok(status) => statuses.push(status),
+            Err(error) => {
+                first_error = first_error.or(Some(error));
+            }
+        }
+    }
+    if statuses.is_empty() {
+        return Err(first_error.unwrap_or(EngineError::Offline));
+    }
+
+    let mut verdicts: Vec<&PayloadStatusV1> = vec![];
+    for status in statuses.iter().filter(|status| is_verdict(status)) {
+        let votes = statuses
+            .iter()
+            .filter(|other| agrees(status, other))
+            .count();
+        if votes >= quorum && !verdicts.iter().any(|verdict| agrees(verdict, status)) {
+            verdicts.push(status);
+        }
+    }
+
+    if let [verdict] = verdicts.as_slice() {
+        return Ok((*verdict).clone());
+    }
+    Ok(statuses
+        .iter()
+        .find(|status| !is_verdict(status))
+        .cloned()
+        .unwrap_or(PayloadStatusV1 {
+            status: PayloadStatusV1Status::Syncing,
+            latest_valid_hash: None,
+            validation_error: None,
+        }))
+}
+
 impl PayloadIdCacheKey {
     fn new(head_block_hash: &ExecutionBlockHash, attributes: &PayloadAttributes) -> Self {
         Self {
@@ -418,4 +810,94 @@ mod tests {
         let new_state = watcher.next().await.expect("Last state is always present");
         assert_eq!(new_state, EngineState::Online);
     }
+
+    fn status(status: PayloadStatusV1Status, latest_valid_hash: u8) -> PayloadStatusV1 {
+        PayloadStatusV1 {
+            status,
+            latest_valid_hash: Some(ExecutionBlockHash::repeat_byte(latest_valid_hash)),
+            validation_error: None,
+        }
+    }
+
+    #[test]
+    fn payload_status_quorum_single_engine() {
+        let valid = status(PayloadStatusV1Status::Valid, 1);
+        assert_eq!(
+            payload_status_quorum(vec![Ok(valid.clone())], 1).unwrap(),
+            valid
+        );
+        assert!(matches!(
+            payload_status_quorum(vec![Err(EngineError::Auth)], 1),
+            Err(EngineError::Auth)
+        ));
+    }
+
+    #[test]
+    fn payload_status_quorum_reached() {
+        let valid = status(PayloadStatusV1Status::Valid, 1);
+        let invalid = status(PayloadStatusV1Status::Invalid, 0);
+        let results = vec![
+            Ok(valid.clone()),
+            Err(EngineError::Offline),
+            Ok(valid.clone()),
+        ];
+        assert_eq!(payload_status_quorum(results, 2).unwrap(), valid);
+
+        let results = vec![Ok(invalid.clone()), Ok(valid), Ok(invalid.clone())];
+        assert_eq!(payload_status_quorum(results, 2).unwrap(), invalid);
+    }
+
+    #[test]
+    fn payload_status_quorum_decided_early() {
+        let valid = status(PayloadStatusV1Status::Valid, 1);
+        let invalid = status(PayloadStatusV1Status::Invalid, 0);
+        let syncing = status(PayloadStatusV1Status::Syncing, 1);
+        let decided = |results: Vec<Result<PayloadStatusV1, EngineError>>, pending, quorum| {
+            payload_status_quorum_decided(results.iter(), pending, quorum)
+        };
+
+        // A single engine decides.
+        assert!(decided(vec![Ok(valid.clone())], 0, 1));
+        // Two of three engines agree, the third is unable to change the outcome.
+        assert!(decided(vec![Ok(valid.clone()), Ok(valid.clone())], 1, 2));
+        // With a quorum of one, a pending engine could still contradict the verdict.
+        assert!(!decided(vec![Ok(valid.clone())], 1, 1));
+        // Two pending engines may still reach a quorum on a different verdict.
+        assert!(!decided(vec![Ok(valid.clone()), Ok(valid.clone())], 2, 2));
+        // One vote each, the pending engine decides between them.
+        assert!(!decided(vec![Ok(valid.clone()), Ok(invalid.clone())], 1, 2));
+        // No verdict is able to reach the quorum anymore.
+        assert!(decided(vec![Ok(valid.clone()), Ok(invalid.clone())], 0, 2));
+        assert!(decided(
+            vec![Ok(syncing.clone()), Err(EngineError::Offline)],
+            1,
+            2
+        ));
+        // Without any status, the pending engine is awaited.
+        assert!(!decided(vec![Err(EngineError::Offline)], 1, 2));
+        assert!(decided(vec![Err(EngineError::Offline)], 0, 2));
+    }
+
+    #[test]
+    fn payload_status_quorum_not_reached() {
+        let valid = status(PayloadStatusV1Status::Valid, 1);
+        let invalid = status(PayloadStatusV1Status::Invalid, 0);
+        let accepted = status(PayloadStatusV1Status::Accepted, 1);
+
+        let results = vec![Ok(valid.clone()), Err(EngineError::Offline)];
+        assert_eq!(
+            payload_status_quorum(results, 2).unwrap().status,
+            PayloadStatusV1Status::Syncing
+        );
+
+        let results = vec![Ok(valid.clone()), Ok(accepted.clone())];
+        assert_eq!(payload_status_quorum(results, 2).unwrap(), accepted);
+
+        // Conflicting verdicts which both reach the quorum are not trusted.
+        let results = vec![Ok(valid), Ok(invalid)];
+        assert_eq!(
+            payload_status_quorum(results, 1).unwrap().status,
+            PayloadStatusV1Status::Syncing
+        );
+    }
 }
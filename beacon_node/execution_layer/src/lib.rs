@@ -7,21 +7,26 @@
 use crate::payload_cache::PayloadCache;
 use arc_swap::ArcSwapOption;
 use auth::{strip_prefix, Auth, JwtKey};
-pub use block_hash::calculate_execution_block_hash;
-use builder_client::BuilderHttpClient;
+pub use block_hash::{calculate_execution_block_hash, verify_block_payload_block_hash};
+use engine_api::recorder::{
+    EngineRecorder, DEFAULT_MAX_RECORDING_FILES, DEFAULT_MAX_RECORDING_FILE_SIZE,
+};
 pub use engine_api::EngineCapabilities;
 use engine_api::Error as ApiError;
 pub use engine_api::*;
 pub use engine_api::{http, http::deposit_methods, http::HttpJsonRpc};
-use engines::{Engine, EngineError};
+use engines::{Engine, EngineError, Engines};
 pub use engines::{EngineState, ForkchoiceState};
-use eth2::types::FullPayloadContents;
+use eth2::lighthouse::{BuilderFallbackReason, PayloadSource};
 use eth2::types::{builder_bid::SignedBuilderBid, BlobsBundle, ForkVersionedResponse};
+use eth2::types::{ExecutionClientVersion, FullPayloadContents};
 use ethers_core::types::Transaction as EthersTransaction;
 use fork_choice::ForkchoiceUpdateParameters;
 use lru::LruCache;
 use payload_status::process_payload_status;
 pub use payload_status::PayloadStatus;
+use relays::HeaderResponse;
+pub use relays::Relays;
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -31,7 +36,7 @@ use std::fmt;
 use std::future::Future;
 use std::io::Write;
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use strum::AsRefStr;
@@ -61,6 +66,7 @@ mod keccak;
 mod metrics;
 pub mod payload_cache;
 mod payload_status;
+mod relays;
 pub mod test_utils;
 mod versioned_hashes;
 
@@ -74,6 +80,13 @@ pub const DEFAULT_JWT_FILE: &str = "jwt.hex";
 /// in an LRU cache to avoid redundant lookups. This is the size of that cache.
 const EXECUTION_BLOCKS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 
+/// The number of payload selections to remember until the blocks containing them are produced.
+const PAYLOAD_SELECTIONS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(16);
+
+/// The age after which the cached version of the execution engine is refreshed, so that upgrades
+/// of the execution engine are noticed.
+const CLIENT_VERSION_AGE_LIMIT: Duration = Duration::from_secs(900); // 15 minutes
+
 /// A fee recipient address for use during block production. Only used as a very last resort if
 /// there is no address provided by the user.
 ///
@@ -121,7 +134,13 @@ impl<E: EthSpec> TryFrom<BuilderBid<E>> for ProvenancedPayload<BlockProposalCont
 #[derive(Debug)]
 pub enum Error {
     NoEngine,
+    InvalidNewPayloadQuorum {
+        quorum: usize,
+        engines: usize,
+    },
     NoPayloadBuilder,
+    /// The relay which provided the header of a blinded block is not known.
+    UnknownBidRelay(ExecutionBlockHash),
     ApiError(ApiError),
     Builder(builder_client::Error),
     NoHeaderFromBuilder,
@@ -137,6 +156,7 @@ pub enum Error {
         transactions_root: Hash256,
     },
     InvalidJWTSecret(String),
+    EngineRecorder(String),
     InvalidForkForPayload,
     InvalidPayloadBody(String),
     InvalidPayloadConversion,
@@ -319,18 +339,72 @@ pub enum ChainHealth {
     PreMerge,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FailedCondition {
     Skips,
     SkipsPerEpoch,
     EpochsSinceFinalization,
 }
 
+impl From<FailedCondition> for BuilderFallbackReason {
+    fn from(condition: FailedCondition) -> Self {
+        match condition {
+            FailedCondition::Skips => BuilderFallbackReason::Skips,
+            FailedCondition::SkipsPerEpoch => BuilderFallbackReason::SkipsPerEpoch,
+            FailedCondition::EpochsSinceFinalization => {
+                BuilderFallbackReason::EpochsSinceFinalization
+            }
+        }
+    }
+}
+
+/// The values of the payloads which were available for a block, and which one was chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayloadSelection {
+    pub source: PayloadSource,
+    pub fee_recipient: Address,
+    pub local_value: Option<Uint256>,
+    pub builder_value: Option<Uint256>,
+    pub builder_fallback_reason: Option<BuilderFallbackReason>,
+}
+
+impl PayloadSelection {
+    fn local<E: EthSpec>(
+        local: &GetPayloadResponse<E>,
+        builder_value: Option<Uint256>,
+        builder_fallback_reason: Option<BuilderFallbackReason>,
+    ) -> Self {
+        Self {
+            source: PayloadSource::Local,
+            fee_recipient: local.fee_recipient(),
+            local_value: Some(*local.block_value()),
+            builder_value,
+            builder_fallback_reason,
+        }
+    }
+
+    fn builder<E: EthSpec>(bid: &BuilderBid<E>, local_value: Option<Uint256>) -> Self {
+        Self {
+            source: PayloadSource::Builder,
+            fee_recipient: bid.header().fee_recipient(),
+            local_value,
+            builder_value: Some(*bid.value()),
+            builder_fallback_reason: None,
+        }
+    }
+}
+
 type PayloadContentsRefTuple<'a, T> = (ExecutionPayloadRef<'a, T>, Option<&'a BlobsBundle<T>>);
 
+/// The version of an execution engine and when it was fetched.
+struct ClientVersionCacheEntry {
+    version: ExecutionClientVersion,
+    fetched_at: Instant,
+}
+
 struct Inner<E: EthSpec> {
-    engine: Arc<Engine>,
-    builder: ArcSwapOption<BuilderHttpClient>,
+    engines: Engines,
+    builder: ArcSwapOption<Relays>,
     execution_engine_forkchoice_lock: Mutex<()>,
     suggested_fee_recipient: Option<Address>,
     proposer_preparation_data: Mutex<HashMap<u64, ProposerPreparationDataEntry>>,
@@ -344,17 +418,26 @@ struct Inner<E: EthSpec> {
     /// This is used *only* in the informational sync status endpoint, so that a VC using this
     /// node can prefer another node with a healthier EL.
     last_new_payload_errored: RwLock<bool>,
+    /// The version of the execution engine, as reported by `engine_getClientVersionV1`.
+    ///
+    /// This is read during block production, so it is not behind an async lock.
+    client_version: parking_lot::RwLock<Option<ClientVersionCacheEntry>>,
+    /// How the payloads of recently produced blocks were chosen, by block hash.
+    payload_selections: parking_lot::Mutex<LruCache<ExecutionBlockHash, PayloadSelection>>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Config {
-    /// Endpoint urls for EL nodes that are running the engine api.
+    /// Endpoint urls for EL nodes that are running the engine api, the first being the primary.
     pub execution_endpoints: Vec<SensitiveUrl>,
+    /// The number of EL nodes which must agree on the validity of a payload, defaults to 1.
+    pub new_payload_quorum: Option<usize>,
     /// Endpoint urls for services providing the builder api.
-    pub builder_url: Option<SensitiveUrl>,
+    pub builder_urls: Vec<SensitiveUrl>,
     /// User agent to send with requests to the builder API.
     pub builder_user_agent: Option<String>,
-    /// JWT secrets for the above endpoints running the engine api.
+    /// JWT secrets for the above endpoints running the engine api. Either one secret for each
+    /// endpoint, or a single secret shared by all endpoints.
     pub secret_files: Vec<PathBuf>,
     /// The default fee recipient to use on the beacon node if none if provided from
     /// the validator client during block preparation.
@@ -366,21 +449,25 @@ pub struct Config {
     /// Default directory for the jwt secret if not provided through cli.
     pub default_datadir: PathBuf,
     pub execution_timeout_multiplier: Option<u32>,
+    /// Directory to which every engine API request and its response are recorded, if any.
+    pub engine_recording_dir: Option<PathBuf>,
 }
 
-/// Provides access to one execution engine and provides a neat interface for consumption by the
-/// `BeaconChain`.
+/// Provides access to one or more execution engines and provides a neat interface for consumption
+/// by the `BeaconChain`.
 #[derive(Clone)]
 pub struct ExecutionLayer<T: EthSpec> {
     inner: Arc<Inner<T>>,
 }
 
 impl<T: EthSpec> ExecutionLayer<T> {
-    /// Instantiate `Self` with an Execution engine specified in `Config`, using JSON-RPC via HTTP.
+    /// Instantiate `Self` with the Execution engines specified in `Config`, using JSON-RPC via
+    /// HTTP.
     pub fn from_config(config: Config, executor: TaskExecutor, log: Logger) -> Result<Self, Error> {
         let Config {
             execution_endpoints: urls,
-            builder_url,
+            new_payload_quorum,
+            builder_urls,
             builder_user_agent,
             secret_files,
             suggested_fee_recipient,
@@ -388,58 +475,71 @@ impl<T: EthSpec> ExecutionLayer<T> {
             jwt_version,
             default_datadir,
             execution_timeout_multiplier,
+            engine_recording_dir,
         } = config;
 
-        if urls.len() > 1 {
-            warn!(log, "Only the first execution engine url will be used");
+        if urls.is_empty() {
+            return Err(Error::NoEngine);
         }
-        let execution_url = urls.into_iter().next().ok_or(Error::NoEngine)?;
 
         // Use the default jwt secret path if not provided via cli.
-        let secret_file = secret_files
-            .into_iter()
-            .next()
-            .unwrap_or_else(|| default_datadir.join(DEFAULT_JWT_FILE));
-
-        let jwt_key = if secret_file.exists() {
-            // Read secret from file if it already exists
-            std::fs::read_to_string(&secret_file)
-                .map_err(|e| format!("Failed to read JWT secret file. Error: {:?}", e))
-                .and_then(|ref s| {
-                    let secret = JwtKey::from_slice(
-                        &hex::decode(strip_prefix(s.trim_end()))
-                            .map_err(|e| format!("Invalid hex string: {:?}", e))?,
-                    )?;
-                    Ok(secret)
-                })
-                .map_err(Error::InvalidJWTSecret)
+        let secret_files = if secret_files.is_empty() {
+            vec![default_datadir.join(DEFAULT_JWT_FILE)]
         } else {
-            // Create a new file and write a randomly generated secret to it if file does not exist
-            warn!(log, "No JWT found on disk. Generating"; "path" => %secret_file.display());
-            std::fs::File::options()
-                .write(true)
-                .create_new(true)
-                .open(&secret_file)
-                .map_err(|e| format!("Failed to open JWT secret file. Error: {:?}", e))
-                .and_then(|mut f| {
-                    let secret = auth::JwtKey::random();
-                    f.write_all(secret.hex_string().as_bytes())
-                        .map_err(|e| format!("Failed to write to JWT secret file: {:?}", e))?;
-                    Ok(secret)
-                })
-                .map_err(Error::InvalidJWTSecret)
-        }?;
-
-        let engine: Engine = {
-            let auth = Auth::new(jwt_key, jwt_id, jwt_version);
-            debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path());
-            let api = HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
-                .map_err(Error::ApiError)?;
-            Engine::new(api, executor.clone(), &log)
+            secret_files
         };
+        if secret_files.len() != 1 && secret_files.len() != urls.len() {
+            return Err(Error::InvalidJWTSecret(format!(
+                "{} JWT secrets were provided for {} execution endpoints, provide either one \
+                 secret for each endpoint or a single shared secret",
+                secret_files.len(),
+                urls.len()
+            )));
+        }
+
+        let new_payload_quorum = new_payload_quorum.unwrap_or(1);
+        if new_payload_quorum == 0 || new_payload_quorum > urls.len() {
+            return Err(Error::InvalidNewPayloadQuorum {
+                quorum: new_payload_quorum,
+                engines: urls.len(),
+            });
+        }
+
+        let recorder = engine_recording_dir
+            .map(|dir| {
+                info!(log, "Recording engine API requests"; "dir" => %dir.display());
+                EngineRecorder::new(
+                    dir,
+                    DEFAULT_MAX_RECORDING_FILE_SIZE,
+                    DEFAULT_MAX_RECORDING_FILES,
+                    &log,
+                )
+                .map(Arc::new)
+                .map_err(Error::EngineRecorder)
+            })
+            .transpose()?;
+
+        let engines = urls
+            .into_iter()
+            .enumerate()
+            .map(|(i, execution_url)| {
+                let secret_file = secret_files.get(i).unwrap_or(&secret_files[0]);
+                let jwt_key = load_jwt_key(secret_file, &log)?;
+                let auth = Auth::new(jwt_key, jwt_id.clone(), jwt_version.clone());
+                debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path());
+                let api =
+                    HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
+                        .map_err(Error::ApiError)?;
+                let api = match &recorder {
+                    Some(recorder) => api.with_recorder(recorder.clone()),
+                    None => api,
+                };
+                Ok(Engine::new(api, executor.clone(), &log))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
 
         let inner = Inner {
-            engine: Arc::new(engine),
+            engines: Engines::new(engines, new_payload_quorum, executor.clone(), &log),
             builder: ArcSwapOption::empty(),
             execution_engine_forkchoice_lock: <_>::default(),
             suggested_fee_recipient,
@@ -450,24 +550,28 @@ impl<T: EthSpec> ExecutionLayer<T> {
             payload_cache: PayloadCache::default(),
             log,
             last_new_payload_errored: RwLock::new(false),
+            client_version: parking_lot::RwLock::new(None),
+            payload_selections: parking_lot::Mutex::new(LruCache::new(
+                PAYLOAD_SELECTIONS_LRU_CACHE_SIZE,
+            )),
         };
 
         let el = Self {
             inner: Arc::new(inner),
         };
 
-        if let Some(builder_url) = builder_url {
-            el.set_builder_url(builder_url, builder_user_agent)?;
+        if !builder_urls.is_empty() {
+            el.set_builder_urls(builder_urls, builder_user_agent)?;
         }
 
         Ok(el)
     }
 
-    fn engine(&self) -> &Arc<Engine> {
-        &self.inner.engine
+    fn engines(&self) -> &Engines {
+        &self.inner.engines
     }
 
-    pub fn builder(&self) -> Option<Arc<BuilderHttpClient>> {
+    pub fn builder(&self) -> Option<Arc<Relays>> {
         self.inner.builder.load_full()
     }
 
@@ -480,15 +584,27 @@ impl<T: EthSpec> ExecutionLayer<T> {
         builder_url: SensitiveUrl,
         builder_user_agent: Option<String>,
     ) -> Result<(), Error> {
-        let builder_client = BuilderHttpClient::new(builder_url.clone(), builder_user_agent)
+        self.set_builder_urls(vec![builder_url], builder_user_agent)
+    }
+
+    /// Set the URLs of the builder relays after initialization.
+    pub fn set_builder_urls(
+        &self,
+        builder_urls: Vec<SensitiveUrl>,
+        builder_user_agent: Option<String>,
+    ) -> Result<(), Error> {
+        if builder_urls.is_empty() {
+            return Err(Error::NoPayloadBuilder);
+        }
+        let relays = Relays::new(builder_urls.clone(), builder_user_agent, self.log())
             .map_err(Error::Builder)?;
         info!(
             self.log(),
             "Using external block builder";
-            "builder_url" => ?builder_url,
-            "local_user_agent" => builder_client.get_user_agent(),
+            "builder_urls" => ?builder_urls,
+            "local_user_agent" => relays.get_user_agent(),
         );
-        self.inner.builder.swap(Some(Arc::new(builder_client)));
+        self.inner.builder.swap(Some(Arc::new(relays)));
         Ok(())
     }
 
@@ -518,6 +634,40 @@ impl<T: EthSpec> ExecutionLayer<T> {
         self.inner.payload_cache.get(root)
     }
 
+    /// Remember how the payload with `block_hash` was chosen, until the block is produced.
+    fn record_payload_selection(
+        &self,
+        block_hash: ExecutionBlockHash,
+        selection: PayloadSelection,
+    ) {
+        self.inner
+            .payload_selections
+            .lock()
+            .put(block_hash, selection);
+    }
+
+    /// Returns how the payload with `block_hash` was chosen by `get_payload`.
+    pub fn take_payload_selection(
+        &self,
+        block_hash: &ExecutionBlockHash,
+    ) -> Option<PayloadSelection> {
+        self.inner.payload_selections.lock().pop(block_hash)
+    }
+
+    /// Record the selection of a local payload, if one was produced.
+    fn record_local_payload_selection(
+        &self,
+        local: &GetPayloadResponseType<T>,
+        builder_fallback_reason: Option<BuilderFallbackReason>,
+    ) {
+        if let GetPayloadResponseType::Full(local) = local {
+            self.record_payload_selection(
+                local.block_hash(),
+                PayloadSelection::local(local, None, builder_fallback_reason),
+            );
+        }
+    }
+
     pub fn executor(&self) -> &TaskExecutor {
         &self.inner.executor
     }
@@ -525,7 +675,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
     /// Get the current difficulty of the PoW chain.
     pub async fn get_current_difficulty(&self) -> Result<Uint256, ApiError> {
         let block = self
-            .engine()
+            .engines()
+            .first_healthy()
+            .await
             .api
             .get_block_by_number(BlockByNumberQuery::Tag(LATEST_TAG))
             .await?
@@ -543,7 +695,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
     ///
     /// This can be called several times.
     pub async fn get_responsiveness_watch(&self) -> WatchStream<EngineState> {
-        self.engine().watch_state().await
+        self.engines().watch_state().await
     }
 
     /// Note: this function returns a mutex guard, be careful to avoid deadlocks.
@@ -595,7 +747,66 @@ impl<T: EthSpec> ExecutionLayer<T> {
 
     /// Performs a single execution of the watchdog routine.
     pub async fn watchdog_task(&self) {
-        self.engine().upcheck().await;
+        self.engines().upcheck().await;
+        self.update_client_version().await;
+    }
+
+    /// Returns the version of the execution engine, as last reported by
+    /// `engine_getClientVersionV1`.
+    pub fn client_version(&self) -> Option<ExecutionClientVersion> {
+        self.inner
+            .client_version
+            .read()
+            .as_ref()
+            .map(|entry| entry.version.clone())
+    }
+
+    /// Fetch the version of the healthiest execution engine, unless the cached version is recent.
+    pub async fn update_client_version(&self) {
+        let is_recent = self
+            .inner
+            .client_version
+            .read()
+            .as_ref()
+            .map_or(false, |entry| {
+                entry.fetched_at.elapsed() < CLIENT_VERSION_AGE_LIMIT
+            });
+        if is_recent {
+            return;
+        }
+
+        let engine = self.engines().first_healthy().await;
+        match engine.api.get_client_version_v1().await {
+            Ok(versions) => {
+                // Multiplexers report a version for each client, the first of which is used.
+                let version = versions
+                    .into_iter()
+                    .next()
+                    .map(ExecutionClientVersion::from);
+                if let Some(version) = &version {
+                    debug!(
+                        self.log(),
+                        "Fetched execution client version";
+                        "code" => &version.code,
+                        "version" => &version.version,
+                        "commit" => &version.commit,
+                    );
+                }
+                *self.inner.client_version.write() =
+                    version.map(|version| ClientVersionCacheEntry {
+                        version,
+                        fetched_at: Instant::now(),
+                    });
+            }
+            Err(e) => {
+                debug!(
+                    self.log(),
+                    "Unable to fetch execution client version";
+                    "endpoint" => %engine.api.url,
+                    "error" => ?e,
+                );
+            }
+        }
     }
 
     /// Spawns a routine which cleans the cached proposer data periodically.
@@ -637,9 +848,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
         self.spawn(preparation_cleaner, "exec_preparation_cleanup");
     }
 
-    /// Returns `true` if the execution engine is synced and reachable.
+    /// Returns `true` if any execution engine is synced and reachable.
     pub async fn is_synced(&self) -> bool {
-        self.engine().is_synced().await
+        self.engines().is_synced().await
     }
 
     /// Execution nodes return a "SYNCED" response when they do not have any peers.
@@ -652,7 +863,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
         let synced = self.is_synced().await;
         if synced {
             if let Ok(Some(block)) = self
-                .engine()
+                .engines()
+                .first_healthy()
+                .await
                 .api
                 .get_block_by_number(BlockByNumberQuery::Tag(LATEST_TAG))
                 .await
@@ -665,13 +878,13 @@ impl<T: EthSpec> ExecutionLayer<T> {
         synced
     }
 
-    /// Return `true` if the execution layer is offline or returning errors on `newPayload`.
+    /// Return `true` if all execution engines are offline or `newPayload` is returning errors.
     ///
     /// This function should never be used to prevent any operation in the beacon node, but can
     /// be used to give an indication on the HTTP API that the node's execution layer is struggling,
     /// which can in turn be used by the VC.
     pub async fn is_offline_or_erroring(&self) -> bool {
-        self.engine().is_offline().await || *self.inner.last_new_payload_errored.read().await
+        self.engines().is_offline().await || *self.inner.last_new_payload_errored.read().await
     }
 
     /// Updates the proposer preparation data provided by validators
@@ -841,6 +1054,10 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     noop,
                 )
                 .await
+                .map(|local| {
+                    self.record_local_payload_selection(&local, None);
+                    local
+                })
                 .and_then(GetPayloadResponseType::try_into)
                 .map(ProvenancedPayload::Local)?,
         };
@@ -885,32 +1102,29 @@ impl<T: EthSpec> ExecutionLayer<T> {
     /// Fetches local and builder paylaods concurrently, Logs and returns results.
     async fn fetch_builder_and_local_payloads(
         &self,
-        builder: &BuilderHttpClient,
+        builder: &Relays,
         parent_hash: ExecutionBlockHash,
         builder_params: &BuilderParams,
         payload_attributes: &PayloadAttributes,
         forkchoice_update_params: ForkchoiceUpdateParameters,
         current_fork: ForkName,
-    ) -> (
-        Result<Option<ForkVersionedResponse<SignedBuilderBid<T>>>, builder_client::Error>,
-        Result<GetPayloadResponse<T>, Error>,
-    ) {
+    ) -> (Vec<HeaderResponse<T>>, Result<GetPayloadResponse<T>, Error>) {
         let slot = builder_params.slot;
         let pubkey = &builder_params.pubkey;
 
         info!(
             self.log(),
-            "Requesting blinded header from connected builder";
+            "Requesting blinded header from connected builders";
             "slot" => ?slot,
             "pubkey" => ?pubkey,
             "parent_hash" => ?parent_hash,
         );
 
-        // Wait for the builder *and* local EL to produce a payload (or return an error).
-        let ((relay_result, relay_duration), (local_result, local_duration)) = tokio::join!(
+        // Wait for the builders *and* local EL to produce a payload (or return an error).
+        let ((relay_responses, relays_duration), (local_result, local_duration)) = tokio::join!(
             timed_future(metrics::GET_BLINDED_PAYLOAD_BUILDER, async {
                 builder
-                    .get_builder_header::<T>(slot, parent_hash, pubkey)
+                    .get_builder_headers::<T>(slot, parent_hash, pubkey)
                     .await
             }),
             timed_future(metrics::GET_BLINDED_PAYLOAD_LOCAL, async {
@@ -928,24 +1142,116 @@ impl<T: EthSpec> ExecutionLayer<T> {
             })
         );
 
+        for response in &relay_responses {
+            info!(
+                self.log(),
+                "Requested blinded execution payload";
+                "relay" => ?builder.url(response.relay),
+                "relay_fee_recipient" => match &response.result {
+                    Ok(Some(r)) => format!("{:?}", r.data.message.header().fee_recipient()),
+                    Ok(None) => "empty response".to_string(),
+                    Err(_) => "request failed".to_string(),
+                },
+                "relay_response_ms" => response.duration.as_millis(),
+                "parent_hash" => ?parent_hash,
+            );
+        }
         info!(
             self.log(),
-            "Requested blinded execution payload";
-            "relay_fee_recipient" => match &relay_result {
-                Ok(Some(r)) => format!("{:?}", r.data.message.header().fee_recipient()),
-                Ok(None) => "empty response".to_string(),
-                Err(_) => "request failed".to_string(),
-            },
-            "relay_response_ms" => relay_duration.as_millis(),
+            "Requested local execution payload";
             "local_fee_recipient" => match &local_result {
                 Ok(get_payload_response) => format!("{:?}", get_payload_response.fee_recipient()),
                 Err(_) => "request failed".to_string()
             },
             "local_response_ms" => local_duration.as_millis(),
+            "relays_response_ms" => relays_duration.as_millis(),
             "parent_hash" => ?parent_hash,
         );
 
-        (relay_result, local_result)
+        (relay_responses, local_result)
+    }
+
+    /// Returns the most valuable valid bid of `relay_responses`, remembering the relay which
+    /// provided it so that the blinded block is only revealed to that relay.
+    ///
+    /// Returns the first error if all relays failed to respond.
+    #[allow(clippy::too_many_arguments)]
+    fn select_builder_bid(
+        &self,
+        builder: &Relays,
+        relay_responses: Vec<HeaderResponse<T>>,
+        parent_hash: ExecutionBlockHash,
+        payload_attributes: &PayloadAttributes,
+        block_number: Option<u64>,
+        current_fork: ForkName,
+        spec: &ChainSpec,
+    ) -> Result<Option<ForkVersionedResponse<SignedBuilderBid<T>>>, builder_client::Error> {
+        let mut best_bid: Option<(usize, ForkVersionedResponse<SignedBuilderBid<T>>)> = None;
+        let mut first_error = None;
+        let mut any_response = false;
+
+        for response in relay_responses {
+            let bid = match response.result {
+                Ok(Some(bid)) => bid,
+                Ok(None) => {
+                    any_response = true;
+                    continue;
+                }
+                Err(e) => {
+                    warn!(
+                        self.log(),
+                        "Relay error when requesting payload";
+                        "relay" => ?builder.url(response.relay),
+                        "error" => ?e,
+                        "parent_hash" => ?parent_hash,
+                    );
+                    first_error = first_error.or(Some(e));
+                    continue;
+                }
+            };
+            any_response = true;
+
+            if let Err(reason) = verify_builder_bid(
+                &bid,
+                parent_hash,
+                payload_attributes,
+                block_number,
+                current_fork,
+                spec,
+            ) {
+                metrics::inc_counter_vec(
+                    &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
+                    &[reason.as_ref().as_ref()],
+                );
+                warn!(
+                    self.log(),
+                    "Builder returned invalid payload";
+                    "relay" => ?builder.url(response.relay),
+                    "reason" => %reason,
+                    "relay_block_hash" => ?bid.data.message.header().block_hash(),
+                    "parent_hash" => ?parent_hash,
+                );
+                continue;
+            }
+
+            let is_best = best_bid.as_ref().map_or(true, |(_, best)| {
+                bid.data.message.value() > best.data.message.value()
+            });
+            if is_best {
+                best_bid = Some((response.relay, bid));
+            }
+        }
+
+        match best_bid {
+            Some((relay, bid)) => {
+                builder.record_bid_relay(bid.data.message.header().block_hash(), relay);
+                Ok(Some(bid))
+            }
+            None => match first_error {
+                Some(e) if !any_response => Err(e),
+                _ => Ok(None),
+            },
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -969,6 +1275,10 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     current_fork,
                 )
                 .await
+                .map(|local| {
+                    self.record_local_payload_selection(&local, None);
+                    local
+                })
                 .and_then(GetPayloadResponseType::try_into)
                 .map(ProvenancedPayload::Local);
         };
@@ -976,6 +1286,11 @@ impl<T: EthSpec> ExecutionLayer<T> {
         // check chain health
         if builder_params.chain_health != ChainHealth::Healthy {
             // chain is unhealthy, gotta use local payload
+            let fallback_reason = match builder_params.chain_health {
+                ChainHealth::Unhealthy(condition) => Some(condition.into()),
+                ChainHealth::Optimistic => Some(BuilderFallbackReason::Optimistic),
+                ChainHealth::PreMerge | ChainHealth::Healthy => None,
+            };
             match builder_params.chain_health {
                 ChainHealth::Unhealthy(condition) => info!(
                     self.log(),
@@ -1005,11 +1320,15 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     current_fork,
                 )
                 .await
+                .map(|local| {
+                    self.record_local_payload_selection(&local, fallback_reason);
+                    local
+                })
                 .and_then(GetPayloadResponseType::try_into)
                 .map(ProvenancedPayload::Local);
         }
 
-        let (relay_result, local_result) = self
+        let (relay_responses, local_result) = self
             .fetch_builder_and_local_payloads(
                 builder.as_ref(),
                 parent_hash,
@@ -1019,6 +1338,15 @@ impl<T: EthSpec> ExecutionLayer<T> {
                 current_fork,
             )
             .await;
+        let relay_result = self.select_builder_bid(
+            builder.as_ref(),
+            relay_responses,
+            parent_hash,
+            payload_attributes,
+            local_result.as_ref().ok().map(|local| local.block_number()),
+            current_fork,
+            spec,
+        );
 
         match (relay_result, local_result) {
             (Err(e), Ok(local)) => {
@@ -1030,6 +1358,14 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     "local_block_hash" => ?local.block_hash(),
                     "parent_hash" => ?parent_hash,
                 );
+                self.record_payload_selection(
+                    local.block_hash(),
+                    PayloadSelection::local(
+                        &local,
+                        None,
+                        Some(BuilderFallbackReason::BuilderError),
+                    ),
+                );
                 Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                     local.try_into()?,
                 )))
@@ -1042,6 +1378,10 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     "local_block_hash" => ?local.block_hash(),
                     "parent_hash" => ?parent_hash,
                 );
+                self.record_payload_selection(
+                    local.block_hash(),
+                    PayloadSelection::local(&local, None, Some(BuilderFallbackReason::NoBid)),
+                );
                 Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                     local.try_into()?,
                 )))
@@ -1081,33 +1421,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     "parent_hash" => ?parent_hash,
                 );
 
-                // check relay payload validity
-                if let Err(reason) = verify_builder_bid(
-                    &relay,
-                    parent_hash,
-                    payload_attributes,
-                    Some(local.block_number()),
-                    current_fork,
-                    spec,
-                ) {
-                    // relay payload invalid -> return local
-                    metrics::inc_counter_vec(
-                        &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
-                        &[reason.as_ref().as_ref()],
-                    );
-                    warn!(
-                        self.log(),
-                        "Builder returned invalid payload";
-                        "info" => "using local payload",
-                        "reason" => %reason,
-                        "relay_block_hash" => ?header.block_hash(),
-                        "parent_hash" => ?parent_hash,
-                    );
-                    return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
-                        local.try_into()?,
-                    )));
-                }
-
+                // The relay payload has already been verified by `select_builder_bid`.
                 let relay_value = *relay.data.message.value();
 
                 let boosted_relay_value = match builder_boost_factor {
@@ -1128,6 +1442,14 @@ impl<T: EthSpec> ExecutionLayer<T> {
                         "boosted_relay_value" => %boosted_relay_value,
                         "builder_boost_factor" => ?builder_boost_factor,
                     );
+                    self.record_payload_selection(
+                        local.block_hash(),
+                        PayloadSelection::local(
+                            &local,
+                            Some(relay_value),
+                            Some(BuilderFallbackReason::LocalMoreValuable),
+                        ),
+                    );
                     return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                         local.try_into()?,
                     )));
@@ -1140,6 +1462,14 @@ impl<T: EthSpec> ExecutionLayer<T> {
                         "local_block_value" => %local_value,
                         "relay_value" => %relay_value
                     );
+                    self.record_payload_selection(
+                        local.block_hash(),
+                        PayloadSelection::local(
+                            &local,
+                            Some(relay_value),
+                            Some(BuilderFallbackReason::ExecutionEngineOverride),
+                        ),
+                    );
                     return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                         local.try_into()?,
                     )));
@@ -1154,6 +1484,10 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     "builder_boost_factor" => ?builder_boost_factor
                 );
 
+                self.record_payload_selection(
+                    header.block_hash(),
+                    PayloadSelection::builder(&relay.data.message, Some(local_value)),
+                );
                 Ok(ProvenancedPayload::try_from(relay.data.message)?)
             }
             (Ok(Some(relay)), Err(local_error)) => {
@@ -1167,31 +1501,12 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     "parent_hash" => ?parent_hash,
                 );
 
-                match verify_builder_bid(
-                    &relay,
-                    parent_hash,
-                    payload_attributes,
-                    None,
-                    current_fork,
-                    spec,
-                ) {
-                    Ok(()) => Ok(ProvenancedPayload::try_from(relay.data.message)?),
-                    Err(reason) => {
-                        metrics::inc_counter_vec(
-                            &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
-                            &[reason.as_ref().as_ref()],
-                        );
-                        crit!(
-                            self.log(),
-                            "Builder returned invalid payload";
-                            "info" => "no local payload either - unable to propose block",
-                            "reason" => %reason,
-                            "relay_block_hash" => ?header.block_hash(),
-                            "parent_hash" => ?parent_hash,
-                        );
-                        Err(Error::CannotProduceHeader)
-                    }
-                }
+                // The relay payload has already been verified by `select_builder_bid`.
+                self.record_payload_selection(
+                    header.block_hash(),
+                    PayloadSelection::builder(&relay.data.message, None),
+                );
+                Ok(ProvenancedPayload::try_from(relay.data.message)?)
             }
         }
     }
@@ -1225,7 +1540,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
             PayloadContentsRefTuple<T>,
         ) -> Option<FullPayloadContents<T>>,
     ) -> Result<GetPayloadResponseType<T>, Error> {
-        self.engine()
+        self.engines()
             .request(move |engine| async move {
                 let payload_id = if let Some(id) = engine
                     .get_payload_id(&parent_hash, payload_attributes)
@@ -1321,7 +1636,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
             .map_err(Error::EngineError)
     }
 
-    /// Maps to the `engine_newPayload` JSON-RPC call.
+    /// Maps to the `engine_newPayload` JSON-RPC call, sent to all execution engines.
     pub async fn notify_new_payload(
         &self,
         new_payload_request: NewPayloadRequest<'_, T>,
@@ -1341,8 +1656,8 @@ impl<T: EthSpec> ExecutionLayer<T> {
         );
 
         let result = self
-            .engine()
-            .request(|engine| engine.api.new_payload(new_payload_request))
+            .engines()
+            .new_payload(|engine| engine.api.new_payload(new_payload_request.clone()))
             .await;
 
         if let Ok(status) = &result {
@@ -1358,9 +1673,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
             .map_err(Error::EngineError)
     }
 
-    /// Update engine sync status.
+    /// Update the sync status of all engines.
     pub async fn upcheck(&self) {
-        self.engine().upcheck().await;
+        self.engines().upcheck().await;
     }
 
     /// Register that the given `validator_index` is going to produce a block at `slot`.
@@ -1421,7 +1736,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
         Some(proposer.payload_attributes)
     }
 
-    /// Maps to the `engine_consensusValidated` JSON-RPC call.
+    /// Maps to the `engine_forkchoiceUpdated` JSON-RPC call, sent to all execution engines.
+    ///
+    /// The response of the healthiest engine which responded is returned.
     pub async fn notify_forkchoice_updated(
         &self,
         head_block_hash: ExecutionBlockHash,
@@ -1474,33 +1791,32 @@ impl<T: EthSpec> ExecutionLayer<T> {
             finalized_block_hash,
         };
 
-        self.engine()
+        self.engines()
             .set_latest_forkchoice_state(forkchoice_state)
             .await;
 
         let result = self
-            .engine()
-            .request(|engine| async move {
-                engine
-                    .notify_forkchoice_updated(forkchoice_state, payload_attributes, self.log())
-                    .await
+            .engines()
+            .forkchoice_updated(|engine| {
+                let payload_attributes = payload_attributes.clone();
+                async move {
+                    engine
+                        .notify_forkchoice_updated(forkchoice_state, payload_attributes, self.log())
+                        .await
+                }
             })
             .await;
 
         if let Ok(status) = &result {
             metrics::inc_counter_vec(
                 &metrics::EXECUTION_LAYER_PAYLOAD_STATUS,
-                &["forkchoice_updated", status.payload_status.status.into()],
+                &["forkchoice_updated", status.status.into()],
             );
         }
 
-        process_payload_status(
-            head_block_hash,
-            result.map(|response| response.payload_status),
-            self.log(),
-        )
-        .map_err(Box::new)
-        .map_err(Error::EngineError)
+        process_payload_status(head_block_hash, result, self.log())
+            .map_err(Box::new)
+            .map_err(Error::EngineError)
     }
 
     /// Returns the execution engine capabilities resulting from a call to
@@ -1516,7 +1832,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         &self,
         age_limit: Option<Duration>,
     ) -> Result<EngineCapabilities, Error> {
-        self.engine()
+        self.engines()
             .request(|engine| engine.get_engine_capabilities(age_limit))
             .await
             .map_err(Box::new)
@@ -1541,7 +1857,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         );
 
         let hash_opt = self
-            .engine()
+            .engines()
             .request(|engine| async move {
                 let terminal_block_hash = spec.terminal_block_hash;
                 if terminal_block_hash != ExecutionBlockHash::zero() {
@@ -1668,7 +1984,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
             &[metrics::IS_VALID_TERMINAL_POW_BLOCK_HASH],
         );
 
-        self.engine()
+        self.engines()
             .request(|engine| async move {
                 if let Some(pow_block) = self.get_pow_block(engine, block_hash).await? {
                     if let Some(pow_parent) =
@@ -1727,10 +2043,8 @@ impl<T: EthSpec> ExecutionLayer<T> {
         &self,
         hashes: Vec<ExecutionBlockHash>,
     ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
-        self.engine()
-            .request(|engine: &Engine| async move {
-                engine.api.get_payload_bodies_by_hash_v1(hashes).await
-            })
+        self.engines()
+            .request(|engine: &Engine| engine.api.get_payload_bodies_by_hash_v1(hashes.clone()))
             .await
             .map_err(Box::new)
             .map_err(Error::EngineError)
@@ -1742,7 +2056,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         count: u64,
     ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
         let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_RANGE);
-        self.engine()
+        self.engines()
             .request(|engine: &Engine| async move {
                 engine
                     .api
@@ -1804,7 +2118,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         &self,
         query: BlockByNumberQuery<'_>,
     ) -> Result<Option<ExecutionBlock>, Error> {
-        self.engine()
+        self.engines()
             .request(|engine| async move { engine.api.get_block_by_number(query).await })
             .await
             .map_err(Box::new)
@@ -1816,7 +2130,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         hash: ExecutionBlockHash,
         fork: ForkName,
     ) -> Result<Option<ExecutionPayload<T>>, Error> {
-        self.engine()
+        self.engines()
             .request(|engine| async move {
                 self.get_payload_by_hash_from_engine(engine, hash, fork)
                     .await
@@ -1955,15 +2269,29 @@ impl<T: EthSpec> ExecutionLayer<T> {
         );
 
         if let Some(builder) = self.builder() {
-            let (payload_result, duration) =
-                timed_future(metrics::POST_BLINDED_PAYLOAD_BUILDER, async {
-                    builder
-                        .post_builder_blinded_blocks(block)
-                        .await
-                        .map_err(Error::Builder)
-                        .map(|d| d.data)
-                })
-                .await;
+            let (response, duration) = timed_future(metrics::POST_BLINDED_PAYLOAD_BUILDER, async {
+                builder.post_builder_blinded_blocks(block).await
+            })
+            .await;
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    metrics::inc_counter_vec(
+                        &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
+                        &[metrics::FAILURE],
+                    );
+                    crit!(
+                        self.log(),
+                        "Unable to send block to builder";
+                        "info" => "the block has not been revealed to any relay",
+                        "error" => ?e,
+                        "block_root" => ?block_root,
+                    );
+                    return Err(e);
+                }
+            };
+            let relay = builder.url(response.relay);
+            let payload_result = response.result.map_err(Error::Builder).map(|d| d.data);
 
             match &payload_result {
                 Ok(unblinded_response) => {
@@ -1975,6 +2303,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     info!(
                         self.log(),
                         "Builder successfully revealed payload";
+                        "relay" => ?relay,
                         "relay_response_ms" => duration.as_millis(),
                         "block_root" => ?block_root,
                         "fee_recipient" => ?payload.fee_recipient(),
@@ -1992,6 +2321,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                         "Builder failed to reveal payload";
                         "info" => "this is common behaviour for some builders and may not indicate an issue",
                         "error" => ?e,
+                        "relay" => ?relay,
                         "relay_response_ms" => duration.as_millis(),
                         "block_root" => ?block_root,
                         "parent_hash" => ?block
@@ -2152,6 +2482,39 @@ fn verify_builder_bid<T: EthSpec>(
     }
 }
 
+/// Read the JWT secret from `secret_file`, or write a randomly generated secret to it if it does
+/// not exist.
+fn load_jwt_key(secret_file: &Path, log: &Logger) -> Result<JwtKey, Error> {
+    if secret_file.exists() {
+        // Read secret from file if it already exists
+        std::fs::read_to_string(secret_file)
+            .map_err(|e| format!("Failed to read JWT secret file. Error: {:?}", e))
+            .and_then(|ref s| {
+                let secret = JwtKey::from_slice(
+                    &hex::decode(strip_prefix(s.trim_end()))
+                        .map_err(|e| format!("Invalid hex string: {:?}", e))?,
+                )?;
+                Ok(secret)
+            })
+            .map_err(Error::InvalidJWTSecret)
+    } else {
+        // Create a new file and write a randomly generated secret to it if file does not exist
+        warn!(log, "No JWT found on disk. Generating"; "path" => %secret_file.display());
+        std::fs::File::options()
+            .write(true)
+            .create_new(true)
+            .open(secret_file)
+            .map_err(|e| format!("Failed to open JWT secret file. Error: {:?}", e))
+            .and_then(|mut f| {
+                let secret = auth::JwtKey::random();
+                f.write_all(secret.hex_string().as_bytes())
+                    .map_err(|e| format!("Failed to write to JWT secret file: {:?}", e))?;
+                Ok(secret)
+            })
+            .map_err(Error::InvalidJWTSecret)
+    }
+}
+
 /// A helper function to record the time it takes to execute a future.
 async fn timed_future<F: Future<Output = T>, T>(metric: &str, future: F) -> (T, Duration) {
     let start = Instant::now();
@@ -2199,6 +2562,18 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    async fn caches_client_version() {
+        let runtime = TestRuntime::default();
+        let mock = MockExecutionLayer::default_params(runtime.task_executor.clone());
+        assert_eq!(mock.el.client_version(), None);
+
+        mock.el.update_client_version().await;
+        let version = mock.el.client_version().unwrap();
+        assert_eq!(version.code, crate::test_utils::MOCK_EL_CLIENT_CODE);
+        assert_eq!(version.commit, "0x00000000");
+    }
+
     #[tokio::test]
     async fn test_forked_terminal_block() {
         let runtime = TestRuntime::default();
@@ -2219,7 +2594,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_block_prior_to_terminal_block()
             .with_terminal_block(|spec, el, _| async move {
-                el.engine().upcheck().await;
+                el.engines().upcheck().await;
                 assert_eq!(
                     el.get_terminal_pow_block_hash(&spec, timestamp_now())
                         .await
@@ -2246,7 +2621,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_block_prior_to_terminal_block()
             .with_terminal_block(|spec, el, _| async move {
-                el.engine().upcheck().await;
+                el.engines().upcheck().await;
                 assert_eq!(
                     el.get_terminal_pow_block_hash(&spec, timestamp_now())
                         .await
@@ -2274,7 +2649,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_terminal_block()
             .with_terminal_block(|spec, el, terminal_block| async move {
-                el.engine().upcheck().await;
+                el.engines().upcheck().await;
                 assert_eq!(
                     el.is_valid_terminal_pow_block_hash(terminal_block.unwrap().block_hash, &spec)
                         .await
@@ -2291,7 +2666,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_terminal_block()
             .with_terminal_block(|spec, el, terminal_block| async move {
-                el.engine().upcheck().await;
+                el.engines().upcheck().await;
                 let invalid_terminal_block = terminal_block.unwrap().parent_hash;
 
                 assert_eq!(
@@ -2310,7 +2685,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_terminal_block()
             .with_terminal_block(|spec, el, _| async move {
-                el.engine().upcheck().await;
+                el.engines().upcheck().await;
                 let missing_terminal_block = ExecutionBlockHash::repeat_byte(42);
 
                 assert_eq!(
@@ -7,6 +7,7 @@ pub const GET_BLINDED_PAYLOAD: &str = "get_blinded_payload";
 pub const GET_BLINDED_PAYLOAD_LOCAL: &str = "get_blinded_payload_local";
 pub const GET_BLINDED_PAYLOAD_BUILDER: &str = "get_blinded_payload_builder";
 pub const POST_BLINDED_PAYLOAD_BUILDER: &str = "post_blinded_payload_builder";
+pub const POST_VALIDATORS_BUILDER: &str = "post_validators_builder";
 pub const NEW_PAYLOAD: &str = "new_payload";
 pub const FORKCHOICE_UPDATED: &str = "forkchoice_updated";
 pub const GET_TERMINAL_POW_BLOCK_HASH: &str = "get_terminal_pow_block_hash";
@@ -79,6 +80,32 @@ lazy_static::lazy_static! {
         "The reasons why a payload from a builder was rejected",
         &["reason"]
     );
+    pub static ref EXECUTION_LAYER_ENGINE_SYNCED: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "execution_layer_engine_synced",
+        "Set to 1 if the execution engine is synced, 0 otherwise",
+        &["endpoint"]
+    );
+    pub static ref EXECUTION_LAYER_ENGINE_REQUESTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_engine_requests",
+        "The success/failure outcomes of requests to each execution engine",
+        &["endpoint", "outcome"]
+    );
+    pub static ref EXECUTION_LAYER_NEW_PAYLOAD_NO_QUORUM: Result<IntCounter> = try_create_int_counter(
+        "execution_layer_new_payload_no_quorum",
+        "Count of payloads for which the execution engines did not reach a quorum on validity",
+    );
+    pub static ref EXECUTION_LAYER_RELAY_REQUESTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_relay_requests",
+        "The success/failure outcomes of requests to each builder relay",
+        &["relay", "method", "outcome"]
+    );
+    pub static ref EXECUTION_LAYER_RELAY_REQUEST_TIMES: Result<HistogramVec> =
+        try_create_histogram_vec_with_buckets(
+        "execution_layer_relay_request_times",
+        "Duration of requests to each builder relay",
+        decimal_buckets(-2, 1),
+        &["relay", "method"]
+    );
     pub static ref EXECUTION_LAYER_PAYLOAD_BIDS: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "execution_layer_payload_bids",
         "The gwei bid value of payloads received by local EEs or builders. Only shows values up to i64::max_value.",
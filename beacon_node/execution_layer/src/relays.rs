@@ -0,0 +1,202 @@
+//! Provides access to one or more builder relays.
+//!
+//! Validator registrations are sent to all relays and headers are requested from all relays in
+//! parallel. A blinded block is only ever revealed to the relay which provided its header.
+
+use crate::{metrics, Error};
+use builder_client::BuilderHttpClient;
+use eth2::types::builder_bid::SignedBuilderBid;
+use eth2::types::{ForkVersionedResponse, FullPayloadContents, SignedBlindedBeaconBlock};
+use futures::future::join_all;
+use lru::LruCache;
+use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
+use slog::{warn, Logger};
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+use types::non_zero_usize::new_non_zero_usize;
+use types::{EthSpec, ExecutionBlockHash, PublicKeyBytes, SignedValidatorRegistrationData, Slot};
+
+/// The number of selected bids for which the providing relay is remembered.
+const BID_RELAYS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(64);
+
+/// The response of a single relay.
+pub struct RelayResponse<T> {
+    /// The index of the relay in `Relays`.
+    pub relay: usize,
+    pub result: Result<T, builder_client::Error>,
+    pub duration: Duration,
+}
+
+pub type HeaderResponse<E> = RelayResponse<Option<ForkVersionedResponse<SignedBuilderBid<E>>>>;
+
+pub struct Relays {
+    relays: Vec<BuilderHttpClient>,
+    user_agent: String,
+    /// The relay which provided each recently selected bid, keyed by the block hash of its
+    /// payload.
+    bid_relays: Mutex<LruCache<ExecutionBlockHash, usize>>,
+    log: Logger,
+}
+
+impl Relays {
+    pub fn new(
+        urls: Vec<SensitiveUrl>,
+        user_agent: Option<String>,
+        log: &Logger,
+    ) -> Result<Self, builder_client::Error> {
+        let user_agent = user_agent.unwrap_or(builder_client::DEFAULT_USER_AGENT.to_string());
+        let relays = urls
+            .into_iter()
+            .map(|url| BuilderHttpClient::new(url, Some(user_agent.clone())))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            relays,
+            user_agent,
+            bid_relays: Mutex::new(LruCache::new(BID_RELAYS_LRU_CACHE_SIZE)),
+            log: log.clone(),
+        })
+    }
+
+    pub fn get_user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    pub fn url(&self, relay: usize) -> Option<&SensitiveUrl> {
+        self.relays.get(relay).map(BuilderHttpClient::server)
+    }
+
+    /// Await `future`, recording its outcome and latency in the metrics of `relay`.
+    async fn timed<T, F>(&self, relay: usize, method: &str, future: F) -> RelayResponse<T>
+    where
+        F: Future<Output = Result<T, builder_client::Error>>,
+    {
+        let start = Instant::now();
+        let result = future.await;
+        let duration = start.elapsed();
+
+        let relay_label = self.relays[relay].server().to_string();
+        metrics::observe_timer_vec(
+            &metrics::EXECUTION_LAYER_RELAY_REQUEST_TIMES,
+            &[&relay_label, method],
+            duration,
+        );
+        metrics::inc_counter_vec(
+            &metrics::EXECUTION_LAYER_RELAY_REQUESTS,
+            &[
+                &relay_label,
+                method,
+                if result.is_ok() {
+                    metrics::SUCCESS
+                } else {
+                    metrics::FAILURE
+                },
+            ],
+        );
+
+        RelayResponse {
+            relay,
+            result,
+            duration,
+        }
+    }
+
+    /// `POST /eth/v1/builder/validators` to all relays, succeeding if any relay accepts the
+    /// registrations.
+    pub async fn post_builder_validators(
+        &self,
+        validators: &[SignedValidatorRegistrationData],
+    ) -> Result<(), builder_client::Error> {
+        let responses = join_all(self.relays.iter().enumerate().map(|(i, relay)| {
+            self.timed(
+                i,
+                metrics::POST_VALIDATORS_BUILDER,
+                relay.post_builder_validators(validators),
+            )
+        }))
+        .await;
+
+        let mut accepted = false;
+        let mut first_error = None;
+        for response in responses {
+            match response.result {
+                Ok(()) => accepted = true,
+                Err(e) => {
+                    warn!(
+                        self.log,
+                        "Relay failed to register validators";
+                        "relay" => %self.relays[response.relay].server(),
+                        "error" => ?e,
+                    );
+                    first_error = first_error.or(Some(e));
+                }
+            }
+        }
+        match first_error {
+            Some(e) if !accepted => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// `GET /eth/v1/builder/header` from all relays in parallel.
+    ///
+    /// Each request is bounded by the `get_header` timeout of the builder client, which is the
+    /// deadline for all relays to respond.
+    pub async fn get_builder_headers<E: EthSpec>(
+        &self,
+        slot: Slot,
+        parent_hash: ExecutionBlockHash,
+        pubkey: &PublicKeyBytes,
+    ) -> Vec<HeaderResponse<E>> {
+        join_all(self.relays.iter().enumerate().map(|(i, relay)| {
+            self.timed(
+                i,
+                metrics::GET_BLINDED_PAYLOAD_BUILDER,
+                relay.get_builder_header::<E>(slot, parent_hash, pubkey),
+            )
+        }))
+        .await
+    }
+
+    /// Remember that the bid for the payload with `block_hash` was provided by `relay`.
+    pub fn record_bid_relay(&self, block_hash: ExecutionBlockHash, relay: usize) {
+        self.bid_relays.lock().put(block_hash, relay);
+    }
+
+    /// `POST /eth/v1/builder/blinded_blocks` to the relay which provided the header of `block`.
+    ///
+    /// If there are several relays and the relay of `block` is unknown, e.g. because the header
+    /// was requested before a restart, an error is returned rather than revealing the signed block
+    /// to relays which did not bid for it.
+    pub async fn post_builder_blinded_blocks<E: EthSpec>(
+        &self,
+        block: &SignedBlindedBeaconBlock<E>,
+    ) -> Result<RelayResponse<ForkVersionedResponse<FullPayloadContents<E>>>, Error> {
+        let block_hash = block
+            .message()
+            .execution_payload()
+            .map_err(|_| Error::InvalidForkForPayload)?
+            .block_hash();
+
+        let bid_relay = match self.relays.as_slice() {
+            [] => return Err(Error::NoPayloadBuilder),
+            [_] => 0,
+            _ => self
+                .bid_relays
+                .lock()
+                .get(&block_hash)
+                .copied()
+                .filter(|relay| *relay < self.relays.len())
+                .ok_or(Error::UnknownBidRelay(block_hash))?,
+        };
+
+        Ok(self
+            .timed(
+                bid_relay,
+                metrics::POST_BLINDED_PAYLOAD_BUILDER,
+                self.relays[bid_relay].post_builder_blinded_blocks(block),
+            )
+            .await)
+    }
+}
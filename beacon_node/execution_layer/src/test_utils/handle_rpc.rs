@@ -1,7 +1,7 @@
 use super::Context;
 use crate::engine_api::{http::*, *};
 use crate::json_structures::*;
-use crate::test_utils::DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
+use crate::test_utils::{DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI, MOCK_EL_CLIENT_CODE};
 use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
@@ -456,6 +456,15 @@ pub async fn handle_rpc<T: EthSpec>(
             let engine_capabilities = ctx.engine_capabilities.read();
             Ok(serde_json::to_value(engine_capabilities.to_response()).unwrap())
         }
+        ENGINE_GET_CLIENT_VERSION_V1 => {
+            let client_version = JsonClientVersionV1 {
+                code: MOCK_EL_CLIENT_CODE.to_string(),
+                name: "Lighthouse mock execution engine".to_string(),
+                version: lighthouse_version::version_number().to_string(),
+                commit: "0x00000000".to_string(),
+            };
+            Ok(serde_json::to_value(vec![client_version]).unwrap())
+        }
         ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1 => {
             #[derive(Deserialize)]
             #[serde(transparent)]
@@ -9,6 +9,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::future::Future;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use task_executor::TaskExecutor;
@@ -192,6 +193,10 @@ pub struct MockBuilder<E: EthSpec> {
     builder_sk: SecretKey,
     operations: Arc<RwLock<Vec<Operation>>>,
     invalidate_signatures: Arc<RwLock<bool>>,
+    bid_value: Arc<RwLock<Uint256>>,
+    header_delay: Arc<RwLock<Duration>>,
+    withhold_bids: Arc<RwLock<bool>>,
+    withhold_payloads: Arc<RwLock<bool>>,
 }
 
 impl<E: EthSpec> MockBuilder<E> {
@@ -205,26 +210,38 @@ impl<E: EthSpec> MockBuilder<E> {
         let path = file.path().into();
         std::fs::write(&path, hex::encode(DEFAULT_JWT_SECRET)).unwrap();
 
+        let builder = MockBuilder::from_endpoints(mock_el_url, path, beacon_url, spec, executor)
+            .expect("mock builder should connect to the EL");
+        let host: Ipv4Addr = Ipv4Addr::LOCALHOST;
+        let port = 0;
+        let server = serve(host, port, builder.clone()).expect("mock builder server should start");
+        (builder, server)
+    }
+
+    /// Create a builder which builds payloads with the execution engine at `execution_endpoint`,
+    /// and follows the head of the beacon node at `beacon_url`.
+    pub fn from_endpoints(
+        execution_endpoint: SensitiveUrl,
+        jwt_secret_path: PathBuf,
+        beacon_url: SensitiveUrl,
+        spec: ChainSpec,
+        executor: TaskExecutor,
+    ) -> Result<Self, crate::Error> {
         // This EL should not talk to a builder
         let config = Config {
-            execution_endpoints: vec![mock_el_url],
-            secret_files: vec![path],
+            execution_endpoints: vec![execution_endpoint],
+            secret_files: vec![jwt_secret_path],
             suggested_fee_recipient: None,
             ..Default::default()
         };
 
-        let el =
-            ExecutionLayer::from_config(config, executor.clone(), executor.log().clone()).unwrap();
+        let el = ExecutionLayer::from_config(config, executor.clone(), executor.log().clone())?;
 
-        let builder = MockBuilder::new(
+        Ok(MockBuilder::new(
             el,
             BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(Duration::from_secs(1))),
             spec,
-        );
-        let host: Ipv4Addr = Ipv4Addr::LOCALHOST;
-        let port = 0;
-        let server = serve(host, port, builder.clone()).expect("mock builder server should start");
-        (builder, server)
+        ))
     }
 
     pub fn new(
@@ -242,6 +259,12 @@ impl<E: EthSpec> MockBuilder<E> {
             builder_sk: sk,
             operations: Arc::new(RwLock::new(vec![])),
             invalidate_signatures: Arc::new(RwLock::new(false)),
+            bid_value: Arc::new(RwLock::new(Uint256::from(
+                DEFAULT_BUILDER_PAYLOAD_VALUE_WEI,
+            ))),
+            header_delay: Arc::new(RwLock::new(Duration::ZERO)),
+            withhold_bids: Arc::new(RwLock::new(false)),
+            withhold_payloads: Arc::new(RwLock::new(false)),
         }
     }
 
@@ -259,6 +282,26 @@ impl<E: EthSpec> MockBuilder<E> {
         *self.invalidate_signatures.write() = false;
     }
 
+    /// Set the value of all future bids.
+    pub fn set_bid_value(&self, value: Uint256) {
+        *self.bid_value.write() = value;
+    }
+
+    /// Delay responses to requests for bids, to simulate a slow builder.
+    pub fn set_header_delay(&self, delay: Duration) {
+        *self.header_delay.write() = delay;
+    }
+
+    /// Respond to requests for bids with no bid.
+    pub fn set_withhold_bids(&self, withhold: bool) {
+        *self.withhold_bids.write() = withhold;
+    }
+
+    /// Refuse to reveal payloads for signed blinded blocks.
+    pub fn set_withhold_payloads(&self, withhold: bool) {
+        *self.withhold_payloads.write() = withhold;
+    }
+
     fn apply_operations<B: BidStuff<E>>(&self, bid: &mut B) {
         let mut guard = self.operations.write();
         while let Some(op) = guard.pop() {
@@ -306,6 +349,10 @@ pub fn serve<E: EthSpec>(
         .and(ctx_filter.clone())
         .and_then(
             |block: SignedBlindedBeaconBlock<E>, builder: MockBuilder<E>| async move {
+                if *builder.withhold_payloads.read() {
+                    return Err(reject("withholding payload"));
+                }
+
                 let slot = block.slot();
                 let root = match block {
                     SignedBlindedBeaconBlock::Base(_) | types::SignedBeaconBlock::Altair(_) => {
@@ -369,6 +416,19 @@ pub fn serve<E: EthSpec>(
              parent_hash: ExecutionBlockHash,
              pubkey: PublicKeyBytes,
              builder: MockBuilder<E>| async move {
+                let header_delay = *builder.header_delay.read();
+                if !header_delay.is_zero() {
+                    tokio::time::sleep(header_delay).await;
+                }
+
+                // The builder API specifies a 204 response when there is no bid.
+                if *builder.withhold_bids.read() {
+                    return Ok(warp::http::Response::builder()
+                        .status(204)
+                        .body(String::new())
+                        .unwrap());
+                }
+
                 let fork = builder.spec.fork_name_at_slot::<E>(slot);
                 let signed_cached_data = builder
                     .val_registration_cache
@@ -512,6 +572,7 @@ pub fn serve<E: EthSpec>(
                     .await
                     .map_err(|_| reject("couldn't get payload"))?;
 
+                let value = *builder.bid_value.read();
                 let mut message = match payload_response_type {
                     crate::GetPayloadResponseType::Full(payload_response) => {
                         let (payload, _block_value, maybe_blobs_bundle): (
@@ -529,7 +590,7 @@ pub fn serve<E: EthSpec>(
                                 blob_kzg_commitments: maybe_blobs_bundle
                                     .map(|b| b.commitments)
                                     .unwrap_or_default(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Capella => BuilderBid::Capella(BuilderBidCapella {
@@ -537,7 +598,7 @@ pub fn serve<E: EthSpec>(
                                     .as_capella()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Merge => BuilderBid::Merge(BuilderBidMerge {
@@ -545,7 +606,7 @@ pub fn serve<E: EthSpec>(
                                     .as_merge()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Base | ForkName::Altair => {
@@ -568,7 +629,7 @@ pub fn serve<E: EthSpec>(
                                 blob_kzg_commitments: maybe_blobs_bundle
                                     .map(|b| b.commitments)
                                     .unwrap_or_default(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Capella => BuilderBid::Capella(BuilderBidCapella {
@@ -576,7 +637,7 @@ pub fn serve<E: EthSpec>(
                                     .as_capella()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Merge => BuilderBid::Merge(BuilderBidMerge {
@@ -584,7 +645,7 @@ pub fn serve<E: EthSpec>(
                                     .as_merge()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Base | ForkName::Altair => {
@@ -29,14 +29,17 @@ pub use execution_block_generator::{
     static_valid_tx, Block, ExecutionBlockGenerator,
 };
 pub use hook::Hook;
-pub use mock_builder::{MockBuilder, Operation};
+pub use mock_builder::{serve as serve_mock_builder, MockBuilder, Operation};
 pub use mock_execution_layer::MockExecutionLayer;
+pub use replay::Replay;
 
 pub const DEFAULT_TERMINAL_DIFFICULTY: u64 = 6400;
 pub const DEFAULT_TERMINAL_BLOCK: u64 = 64;
 pub const DEFAULT_JWT_SECRET: [u8; 32] = [42; 32];
 pub const DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI: u128 = 10_000_000_000_000_000;
 pub const DEFAULT_BUILDER_PAYLOAD_VALUE_WEI: u128 = 20_000_000_000_000_000;
+/// The client code reported by the mock execution engine in `engine_getClientVersionV1`.
+pub const MOCK_EL_CLIENT_CODE: &str = "MK";
 pub const DEFAULT_ENGINE_CAPABILITIES: EngineCapabilities = EngineCapabilities {
     new_payload_v1: true,
     new_payload_v2: true,
@@ -49,6 +52,7 @@ pub const DEFAULT_ENGINE_CAPABILITIES: EngineCapabilities = EngineCapabilities {
     get_payload_v1: true,
     get_payload_v2: true,
     get_payload_v3: true,
+    get_client_version_v1: true,
 };
 
 mod execution_block_generator;
@@ -56,6 +60,7 @@ mod handle_rpc;
 mod hook;
 mod mock_builder;
 mod mock_execution_layer;
+mod replay;
 
 /// Configuration for the MockExecutionLayer.
 pub struct MockExecutionConfig {
@@ -136,6 +141,7 @@ impl<T: EthSpec> MockServer<T> {
             execution_block_generator: RwLock::new(execution_block_generator),
             previous_request: <_>::default(),
             preloaded_responses,
+            replay: <_>::default(),
             static_new_payload_response: <_>::default(),
             static_forkchoice_updated_response: <_>::default(),
             static_get_block_by_hash_response: <_>::default(),
@@ -227,6 +233,11 @@ impl<T: EthSpec> MockServer<T> {
         self.ctx.preloaded_responses.lock().push(response)
     }
 
+    /// Answer all subsequent requests from `replay` rather than the block generator.
+    pub fn set_replay(&self, replay: Replay) {
+        *self.ctx.replay.lock() = Some(replay);
+    }
+
     pub fn take_previous_request(&self) -> Option<serde_json::Value> {
         self.ctx.previous_request.lock().take()
     }
@@ -502,6 +513,8 @@ pub struct Context<T: EthSpec> {
     pub execution_block_generator: RwLock<ExecutionBlockGenerator<T>>,
     pub preloaded_responses: Arc<Mutex<Vec<serde_json::Value>>>,
     pub previous_request: Arc<Mutex<Option<serde_json::Value>>>,
+    /// Answers all requests from a recording when set.
+    pub replay: Arc<Mutex<Option<Replay>>>,
     pub static_new_payload_response: Arc<Mutex<Option<StaticNewPayloadResponse>>>,
     pub static_forkchoice_updated_response: Arc<Mutex<Option<PayloadStatusV1>>>,
     pub static_get_block_by_hash_response: Arc<Mutex<Option<Option<ExecutionBlock>>>>,
@@ -651,14 +664,22 @@ pub fn serve<T: EthSpec>(
                 .get("id")
                 .and_then(serde_json::Value::as_u64)
                 .ok_or_else(|| warp::reject::custom(MissingIdField))?;
-            let preloaded_response = {
+            let replayed_response = ctx.replay.lock().as_mut().map(|replay| {
+                let method = body.get("method").and_then(|m| m.as_str()).unwrap_or("");
+                let params = body
+                    .get("params")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                replay.response(method, &params, id)
+            });
+            let preloaded_response = replayed_response.or_else(|| {
                 let mut preloaded_responses = ctx.preloaded_responses.lock();
                 if !preloaded_responses.is_empty() {
                     Some(preloaded_responses.remove(0))
                 } else {
                     None
                 }
-            };
+            });
 
             let response = if let Some(preloaded_response) = preloaded_response {
                 preloaded_response
@@ -0,0 +1,167 @@
+//! Answers engine API requests from a recording made by the `EngineRecorder`, so that the
+//! behaviour of an execution engine during an incident can be reproduced offline.
+//!
+//! Requests are matched by method and params. If the same request was recorded several times its
+//! responses are returned in the order in which they were recorded, repeating the last one.
+
+use crate::engine_api::http::JSONRPC_VERSION;
+use crate::engine_api::recorder::{read_recording, EngineRecorder, RecordedExchange};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+/// The JSON-RPC error code returned when no response was recorded for a request.
+pub const NO_RECORDED_RESPONSE_CODE: i64 = -32001;
+/// The JSON-RPC error code returned when the recorded request failed without a response.
+pub const RECORDED_ERROR_CODE: i64 = -32002;
+
+#[derive(Debug, Default)]
+pub struct Replay {
+    exchanges: HashMap<(String, String), VecDeque<RecordedExchange>>,
+}
+
+impl Replay {
+    pub fn new(exchanges: impl IntoIterator<Item = RecordedExchange>) -> Self {
+        let mut replay = Self::default();
+        for exchange in exchanges {
+            replay
+                .exchanges
+                .entry((exchange.method.clone(), exchange.params.to_string()))
+                .or_default()
+                .push_back(exchange);
+        }
+        replay
+    }
+
+    /// Load a recording file, or all of the rotated recording files in a recording directory.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        if !path.is_dir() {
+            return read_recording(path).map(Self::new);
+        }
+
+        // Rotated files are read oldest first, so that responses are replayed in order.
+        let mut files = (0..)
+            .map(|age| EngineRecorder::recording_path(path, age))
+            .take_while(|file| file.exists())
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return Err(format!("No recordings found in {}", path.display()));
+        }
+        files.reverse();
+
+        let mut exchanges = vec![];
+        for file in files {
+            exchanges.extend(read_recording(&file)?);
+        }
+        Ok(Self::new(exchanges))
+    }
+
+    /// The number of recorded exchanges which have not been replayed.
+    pub fn len(&self) -> usize {
+        self.exchanges.values().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the JSON-RPC response to a request with `method`, `params` and `id`.
+    pub fn response(
+        &mut self,
+        method: &str,
+        params: &serde_json::Value,
+        id: u64,
+    ) -> serde_json::Value {
+        let exchange = self
+            .exchanges
+            .get_mut(&(method.to_string(), params.to_string()))
+            .and_then(|exchanges| {
+                if exchanges.len() > 1 {
+                    exchanges.pop_front()
+                } else {
+                    exchanges.front().cloned()
+                }
+            });
+
+        match exchange {
+            Some(RecordedExchange {
+                response: Some(mut response),
+                ..
+            }) => {
+                response["id"] = json!(id);
+                response
+            }
+            Some(RecordedExchange { error, .. }) => json!({
+                "id": id,
+                "jsonrpc": JSONRPC_VERSION,
+                "error": {
+                    "code": RECORDED_ERROR_CODE,
+                    "message": format!("recorded error: {}", error.unwrap_or_default())
+                }
+            }),
+            None => json!({
+                "id": id,
+                "jsonrpc": JSONRPC_VERSION,
+                "error": {
+                    "code": NO_RECORDED_RESPONSE_CODE,
+                    "message": format!("no recorded response to {}", method)
+                }
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(method: &str, params: serde_json::Value, result: u64) -> RecordedExchange {
+        RecordedExchange {
+            timestamp_ms: 0,
+            endpoint: "http://localhost:8551/".to_string(),
+            method: method.to_string(),
+            params,
+            duration_ms: 0,
+            response: Some(json!({"jsonrpc": JSONRPC_VERSION, "id": 1, "result": result})),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn replays_responses_in_order() {
+        let mut replay = Replay::new(vec![
+            exchange("eth_blockNumber", json!([]), 1),
+            exchange("eth_getBlockByNumber", json!(["0x1", false]), 10),
+            exchange("eth_blockNumber", json!([]), 2),
+        ]);
+        assert_eq!(replay.len(), 3);
+
+        let params = json!([]);
+        assert_eq!(replay.response("eth_blockNumber", &params, 7)["result"], 1);
+        assert_eq!(replay.response("eth_blockNumber", &params, 7)["result"], 2);
+        // The last response is repeated.
+        let response = replay.response("eth_blockNumber", &params, 8);
+        assert_eq!(response["result"], 2);
+        assert_eq!(response["id"], 8);
+
+        let params = json!(["0x1", false]);
+        assert_eq!(
+            replay.response("eth_getBlockByNumber", &params, 9)["result"],
+            10
+        );
+    }
+
+    #[test]
+    fn replays_errors() {
+        let mut failed = exchange("engine_newPayloadV3", json!([{}]), 0);
+        failed.response = None;
+        failed.error = Some("timeout".to_string());
+        let mut replay = Replay::new(vec![failed]);
+
+        let response = replay.response("engine_newPayloadV3", &json!([{}]), 1);
+        assert_eq!(response["error"]["code"], RECORDED_ERROR_CODE);
+
+        let response = replay.response("engine_newPayloadV3", &json!([]), 1);
+        assert_eq!(response["error"]["code"], NO_RECORDED_RESPONSE_CODE);
+    }
+}
@@ -0,0 +1,86 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes, WhenSlotSkipped};
+use eth1::DepositInclusion;
+use eth2::lighthouse::{IndexedDeposit, TransactionDeposits, ValidatorDeposit, ValidatorDeposits};
+use types::{Hash256, PublicKeyBytes, Slot};
+use warp_utils::reject::beacon_chain_error;
+
+/// Returns the deposits to `pubkey` which are in the deposit index, and whether they have been
+/// processed by the head state.
+pub fn get_validator_deposits<T: BeaconChainTypes>(
+    pubkey: PublicKeyBytes,
+    chain: &BeaconChain<T>,
+    eth1_service: &eth1::Service,
+) -> Result<ValidatorDeposits, warp::Rejection> {
+    let validator_index = chain
+        .validator_index(&pubkey)
+        .map_err(beacon_chain_error)?
+        .map(|index| index as u64);
+    let deposits =
+        into_validator_deposits(chain, eth1_service.indexed_deposits_by_pubkey(&pubkey))?;
+
+    Ok(ValidatorDeposits {
+        pubkey,
+        validator_index,
+        deposits,
+    })
+}
+
+/// Returns the deposits of the transaction with `transaction_hash` which are in the deposit
+/// index, and whether they have been processed by the head state.
+pub fn get_transaction_deposits<T: BeaconChainTypes>(
+    transaction_hash: Hash256,
+    chain: &BeaconChain<T>,
+    eth1_service: &eth1::Service,
+) -> Result<TransactionDeposits, warp::Rejection> {
+    let deposits = into_validator_deposits(
+        chain,
+        eth1_service.indexed_deposits_by_transaction(&transaction_hash),
+    )?;
+
+    Ok(TransactionDeposits {
+        transaction_hash,
+        deposits,
+    })
+}
+
+fn into_validator_deposits<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    indexed_deposits: Vec<(IndexedDeposit, Vec<DepositInclusion>)>,
+) -> Result<Vec<ValidatorDeposit>, warp::Rejection> {
+    let processed_deposit_count = chain.head_snapshot().beacon_state.eth1_deposit_index();
+
+    indexed_deposits
+        .into_iter()
+        .map(|(deposit, inclusions)| {
+            let processed = deposit.index < processed_deposit_count;
+            let inclusion_slot = if processed {
+                canonical_inclusion_slot(chain, &inclusions)?
+            } else {
+                None
+            };
+            Ok(ValidatorDeposit {
+                deposit,
+                processed,
+                inclusion_slot,
+            })
+        })
+        .collect()
+}
+
+/// Returns the slot of the block of `inclusions` which is in the canonical chain.
+///
+/// A deposit is included once per fork, so at most one of its inclusions is canonical.
+fn canonical_inclusion_slot<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    inclusions: &[DepositInclusion],
+) -> Result<Option<Slot>, warp::Rejection> {
+    for inclusion in inclusions {
+        let canonical_root = chain
+            .block_root_at_slot(inclusion.slot, WhenSlotSkipped::None)
+            .map_err(beacon_chain_error)?;
+        if canonical_root == Some(inclusion.block_root) {
+            return Ok(Some(inclusion.slot));
+        }
+    }
+    Ok(None)
+}
@@ -13,6 +13,7 @@ mod block_rewards;
 mod build_block_contents;
 mod builder_states;
 mod database;
+mod eth1_deposits;
 mod metrics;
 mod produce_block;
 mod proposer_duties;
@@ -81,8 +82,8 @@ use tokio_stream::{
 use types::{
     fork_versioned_response::EmptyMetadata, Attestation, AttestationData, AttestationShufflingId,
     AttesterSlashing, BeaconStateError, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName,
-    ForkVersionedResponse, Hash256, ProposerPreparationData, ProposerSlashing, RelativeEpoch,
-    SignedAggregateAndProof, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
+    ForkVersionedResponse, Hash256, ProposerPreparationData, ProposerSlashing, PublicKeyBytes,
+    RelativeEpoch, SignedAggregateAndProof, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
     SignedContributionAndProof, SignedValidatorRegistrationData, SignedVoluntaryExit, Slot,
     SyncCommitteeMessage, SyncContributionData,
 };
@@ -2767,10 +2768,15 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("node"))
         .and(warp::path("version"))
         .and(warp::path::end())
-        // Bypass the `task_spawner` since this method returns a static string.
-        .then(|| async {
+        .and(chain_filter.clone())
+        // Bypass the `task_spawner` since this method only reads cached values.
+        .then(|chain: Arc<BeaconChain<T>>| async move {
             warp::reply::json(&api_types::GenericResponse::from(api_types::VersionData {
                 version: version_with_platform(),
+                execution_client: chain
+                    .execution_layer
+                    .as_ref()
+                    .and_then(|el| el.client_version()),
             }))
             .into_response()
         });
@@ -3852,13 +3858,20 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("health"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .then(|task_spawner: TaskSpawner<T::EthSpec>| {
-            task_spawner.blocking_json_task(Priority::P0, move || {
-                eth2::lighthouse::Health::observe()
-                    .map(api_types::GenericResponse::from)
-                    .map_err(warp_utils::reject::custom_bad_request)
-            })
-        });
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    let mut health = eth2::lighthouse::Health::observe()
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    health.execution_client = chain
+                        .execution_layer
+                        .as_ref()
+                        .and_then(|el| el.client_version());
+                    Ok(api_types::GenericResponse::from(health))
+                })
+            },
+        );
 
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
@@ -3998,7 +4011,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("connected"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(network_globals)
+        .and(network_globals.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
@@ -4016,6 +4029,84 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/network/bandwidth
+    let get_lighthouse_network_bandwidth = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("bandwidth"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.bandwidth.summary(),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/network/inbound_rate_limits
+    let get_lighthouse_network_inbound_rate_limits = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("inbound_rate_limits"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    network_globals
+                        .inbound_rate_limiter_config
+                        .read()
+                        .as_ref()
+                        .map(|config| {
+                            api_types::GenericResponse::from(
+                                eth2::lighthouse::InboundRateLimits::from(config),
+                            )
+                        })
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(
+                                "inbound rate limiting is disabled".to_string(),
+                            )
+                        })
+                })
+            },
+        );
+
+    // POST lighthouse/network/inbound_rate_limits
+    let post_lighthouse_network_inbound_rate_limits = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("inbound_rate_limits"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(network_globals)
+        .and(network_tx_filter.clone())
+        .then(
+            |limits: eth2::lighthouse::InboundRateLimits,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    if network_globals.inbound_rate_limiter_config.read().is_none() {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "inbound rate limiting is disabled".to_string(),
+                        ));
+                    }
+                    let config = limits
+                        .to_config()
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    publish_network_message(
+                        &network_tx,
+                        NetworkMessage::UpdateInboundRateLimiter(config),
+                    )
+                })
+            },
+        );
+
     // GET lighthouse/proto_array
     let get_lighthouse_proto_array = warp::path("lighthouse")
         .and(warp::path("proto_array"))
@@ -4038,6 +4129,44 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/proto_array/graph
+    let get_lighthouse_proto_array_graph = warp::path("lighthouse")
+        .and(warp::path("proto_array"))
+        .and(warp::path("graph"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::ProtoArrayGraphQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::ProtoArrayGraphQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let graph = proto_array::ProtoArrayGraph::from(
+                        chain
+                            .canonical_head
+                            .fork_choice_read_lock()
+                            .proto_array()
+                            .core_proto_array(),
+                    );
+                    match query.format.unwrap_or_default() {
+                        eth2::lighthouse::ProtoArrayGraphFormat::Json => Ok::<_, warp::Rejection>(
+                            warp::reply::json(&api_types::GenericResponse::from(graph))
+                                .into_response(),
+                        ),
+                        eth2::lighthouse::ProtoArrayGraphFormat::Dot => {
+                            Ok(warp::reply::with_header(
+                                graph.to_dot(),
+                                "Content-Type",
+                                "text/vnd.graphviz",
+                            )
+                            .into_response())
+                        }
+                    }
+                })
+            },
+        );
+
     // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -4136,7 +4265,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("deposit_cache"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(eth1_service_filter)
+        .and(eth1_service_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>, eth1_service: eth1::Service| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
@@ -4153,6 +4282,57 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/eth1/deposits/{pubkey}
+    let get_lighthouse_eth1_deposits = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("deposits"))
+        .and(warp::path::param::<PublicKeyBytes>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid validator pubkey".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(eth1_service_filter.clone())
+        .then(
+            |pubkey: PublicKeyBytes,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             eth1_service: eth1::Service| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    eth1_deposits::get_validator_deposits(pubkey, &chain, &eth1_service)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/eth1/transactions/{transaction_hash}/deposits
+    let get_lighthouse_eth1_transaction_deposits = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("transactions"))
+        .and(warp::path::param::<Hash256>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid transaction hash".to_string(),
+            ))
+        }))
+        .and(warp::path("deposits"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(eth1_service_filter)
+        .then(
+            |transaction_hash: Hash256,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             eth1_service: eth1::Service| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    eth1_deposits::get_transaction_deposits(transaction_hash, &chain, &eth1_service)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // GET lighthouse/staking
     let get_lighthouse_staking = warp::path("lighthouse")
         .and(warp::path("staking"))
@@ -4220,6 +4400,26 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/proposals
+    let get_lighthouse_proposals = warp::path("lighthouse")
+        .and(warp::path("proposals"))
+        .and(warp::query::<eth2::lighthouse::ProposalsQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::ProposalsQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .proposal_records(query.start_slot, query.end_slot)
+                        .map(api_types::GenericResponse::from)
+                        .map_err(warp_utils::reject::beacon_chain_error)
+                })
+            },
+        );
+
     // POST lighthouse/analysis/block_rewards
     let post_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4481,15 +4681,21 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_nat)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
+                .uor(get_lighthouse_network_bandwidth)
+                .uor(get_lighthouse_network_inbound_rate_limits)
                 .uor(get_lighthouse_proto_array)
+                .uor(get_lighthouse_proto_array_graph)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
                 .uor(get_lighthouse_eth1_syncing)
                 .uor(get_lighthouse_eth1_block_cache)
                 .uor(get_lighthouse_eth1_deposit_cache)
+                .uor(get_lighthouse_eth1_deposits)
+                .uor(get_lighthouse_eth1_transaction_deposits)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
                 .uor(get_lighthouse_block_rewards)
+                .uor(get_lighthouse_proposals)
                 .uor(get_lighthouse_attestation_performance)
                 .uor(
                     enable(ctx.config.enable_light_client_server)
@@ -4545,6 +4751,7 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_register_validator)
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
+                    .uor(post_lighthouse_network_inbound_rate_limits)
                     .uor(post_lighthouse_database_reconstruct)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
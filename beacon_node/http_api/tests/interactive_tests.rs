@@ -1,13 +1,18 @@
 //! Generic tests that make use of the (newer) `InteractiveApiTester`
 use beacon_chain::{
     chain_config::{DisallowedReOrgOffsets, ReOrgThreshold},
-    test_utils::{AttestationStrategy, BlockStrategy, SyncCommitteeStrategy},
+    eth1_chain::CachingEth1Backend,
+    test_utils::{
+        AttestationStrategy, BlockStrategy, SyncCommitteeStrategy, DEFAULT_ETH1_BLOCK_HASH,
+        HARNESS_GENESIS_TIME,
+    },
     ChainConfig,
 };
 use beacon_processor::work_reprocessing_queue::ReprocessQueueMessage;
 use eth2::types::ProduceBlockV3Response;
 use eth2::types::{DepositContractData, StateId};
-use execution_layer::{ForkchoiceState, PayloadAttributes};
+use execution_layer::{test_utils::generate_genesis_header, ForkchoiceState, PayloadAttributes};
+use genesis::interop_genesis_state_with_eth1;
 use http_api::test_utils::InteractiveTester;
 use parking_lot::Mutex;
 use slot_clock::SlotClock;
@@ -19,8 +24,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use tree_hash::TreeHash;
 use types::{
-    Address, Epoch, EthSpec, ExecPayload, ExecutionBlockHash, ForkName, MainnetEthSpec,
-    MinimalEthSpec, ProposerPreparationData, Slot,
+    test_utils::{generate_deterministic_keypair, generate_deterministic_keypairs},
+    Address, DepositData, Epoch, Eth1Data, EthSpec, ExecPayload, ExecutionBlockHash, ForkName,
+    Hash256, MainnetEthSpec, MinimalEthSpec, ProposerPreparationData, SignatureBytes, Slot,
 };
 
 type E = MainnetEthSpec;
@@ -51,6 +57,188 @@ async fn deposit_contract_custom_network() {
     assert_eq!(result, expected);
 }
 
+// Test that the deposits of a validator are reported as processed, along with the slot of the
+// block which included them, once they have been included in the beacon chain.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn eth1_deposits_processed_by_beacon_chain() {
+    let validator_count = 24;
+    let spec = E::default_spec();
+
+    // Deposits 0 and 1 create new validators, deposit 2 tops up the first of them.
+    let deposit_keypairs = [0, 1, 0].map(|i| generate_deterministic_keypair(validator_count + i));
+    let deposit_logs = deposit_keypairs
+        .iter()
+        .enumerate()
+        .map(|(index, keypair)| {
+            let mut deposit_data = DepositData {
+                pubkey: keypair.pk.clone().into(),
+                withdrawal_credentials: Hash256::zero(),
+                amount: spec.max_effective_balance,
+                signature: SignatureBytes::empty(),
+            };
+            deposit_data.signature = deposit_data.create_signature(&keypair.sk, &spec);
+            eth1::DepositLog {
+                deposit_data,
+                block_number: index as u64,
+                index: index as u64,
+                signature_is_valid: true,
+                // Deposits 1 and 2 are made by the same transaction.
+                transaction_hash: Hash256::from_low_u64_be(index.min(1) as u64 + 1),
+                log_index: index as u64,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The eth1 data of the genesis state requires the first two deposits to be included in the
+    // first block.
+    let included_deposits = 2;
+    let eth1_service = eth1::Service::new(
+        eth1::Config::default(),
+        logging::test_logger(),
+        spec.clone(),
+    )
+    .unwrap();
+    for deposit_log in &deposit_logs[..included_deposits] {
+        eth1_service
+            .deposits()
+            .write()
+            .cache
+            .insert_log(deposit_log.clone())
+            .unwrap();
+    }
+    let (deposit_root, _) = eth1_service
+        .deposits()
+        .read()
+        .cache
+        .get_deposits(0, included_deposits as u64, included_deposits as u64)
+        .unwrap();
+
+    let validator_keypairs = generate_deterministic_keypairs(validator_count);
+    let mut genesis_state = interop_genesis_state_with_eth1::<E>(
+        &validator_keypairs,
+        HARNESS_GENESIS_TIME,
+        Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH),
+        generate_genesis_header(&spec, false),
+        &spec,
+    )
+    .unwrap();
+    *genesis_state.eth1_data_mut() = Eth1Data {
+        deposit_root,
+        deposit_count: included_deposits as u64,
+        block_hash: genesis_state.eth1_data().block_hash,
+    };
+    *genesis_state.eth1_deposit_index_mut() = 0;
+
+    let chain_eth1_service = eth1_service.clone();
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        Some(spec.clone()),
+        validator_count,
+        Some(Box::new(|harness_builder| {
+            harness_builder
+                .keypairs(validator_keypairs)
+                .genesis_state_ephemeral_store(genesis_state)
+        })),
+        Some(Box::new(move |builder| {
+            builder.eth1_backend(Some(CachingEth1Backend::from_service(chain_eth1_service)))
+        })),
+    )
+    .await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            4,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    assert_eq!(
+        harness
+            .chain
+            .head_snapshot()
+            .beacon_state
+            .eth1_deposit_index(),
+        included_deposits as u64
+    );
+
+    // The API has its own deposit cache, which also holds the deposit which hasn't been included.
+    // It is given the inclusions recorded by the beacon chain during block import.
+    let api_eth1_service = tester.ctx.eth1_service.as_ref().unwrap();
+    for deposit_log in &deposit_logs {
+        api_eth1_service
+            .deposits()
+            .write()
+            .cache
+            .insert_log(deposit_log.clone())
+            .unwrap();
+    }
+    let inclusions = eth1_service
+        .deposits()
+        .read()
+        .cache
+        .deposit_index()
+        .inclusions()
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(inclusions.len(), included_deposits);
+    api_eth1_service.insert_deposit_inclusions(inclusions);
+
+    let first = client
+        .get_lighthouse_eth1_deposits(&deposit_logs[0].deposit_data.pubkey)
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(first.validator_index, Some(validator_count as u64));
+    let deposits = first
+        .deposits
+        .iter()
+        .map(|deposit| {
+            (
+                deposit.deposit.index,
+                deposit.processed,
+                deposit.inclusion_slot,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        deposits,
+        vec![(0, true, Some(Slot::new(1))), (2, false, None)]
+    );
+
+    let second = client
+        .get_lighthouse_eth1_deposits(&deposit_logs[1].deposit_data.pubkey)
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(second.validator_index, Some(validator_count as u64 + 1));
+    assert_eq!(second.deposits.len(), 1);
+    assert!(second.deposits[0].processed);
+    assert_eq!(second.deposits[0].inclusion_slot, Some(Slot::new(1)));
+
+    let transaction_hash = deposit_logs[1].transaction_hash;
+    let transaction = client
+        .get_lighthouse_eth1_transaction_deposits(transaction_hash)
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(transaction.transaction_hash, transaction_hash);
+    let deposits = transaction
+        .deposits
+        .iter()
+        .map(|deposit| (deposit.deposit.index, deposit.processed))
+        .collect::<Vec<_>>();
+    assert_eq!(deposits, vec![(1, true), (2, false)]);
+
+    let unknown = client
+        .get_lighthouse_eth1_transaction_deposits(Hash256::repeat_byte(0xff))
+        .await
+        .unwrap()
+        .data;
+    assert!(unknown.deposits.is_empty());
+}
+
 // Test that state lookups by root function correctly for states that are finalized but still
 // present in the hot database, and have had their block pruned from fork choice.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -21,7 +21,7 @@ use http_api::{
     test_utils::{create_api_server, ApiServer},
     BlockId, StateId,
 };
-use lighthouse_network::{Enr, EnrExt, PeerId};
+use lighthouse_network::{BandwidthSummary, Enr, EnrExt, PeerId};
 use network::NetworkReceivers;
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
@@ -74,6 +74,7 @@ struct ApiTester {
     local_enr: Enr,
     external_peer_id: PeerId,
     mock_builder: Option<Arc<MockBuilder<E>>>,
+    second_mock_builder: Option<Arc<MockBuilder<E>>>,
 }
 
 struct ApiTesterConfig {
@@ -293,6 +294,7 @@ impl ApiTester {
             local_enr,
             external_peer_id,
             mock_builder,
+            second_mock_builder: None,
         }
     }
 
@@ -377,6 +379,7 @@ impl ApiTester {
             local_enr,
             external_peer_id,
             mock_builder: None,
+            second_mock_builder: None,
         }
     }
 
@@ -415,6 +418,39 @@ impl ApiTester {
         tester
     }
 
+    /// Create a tester whose execution layer uses two relays, `mock_builder` and
+    /// `second_mock_builder`.
+    pub async fn new_mev_tester_two_relays() -> Self {
+        let mut tester = Self::new_with_hard_forks(true, true).await;
+
+        let mock_el = tester.harness.mock_execution_layer.as_ref().unwrap();
+        let mock_el_url = SensitiveUrl::parse(mock_el.server.url().as_str()).unwrap();
+        let beacon_url = SensitiveUrl::parse(tester.client.as_ref()).unwrap();
+        let (second_mock_builder, (addr, second_mock_builder_server)) =
+            MockBuilder::new_for_testing(
+                mock_el_url,
+                beacon_url,
+                tester.chain.spec.clone(),
+                tester.harness.runtime.task_executor.clone(),
+            );
+        tester.harness.runtime.task_executor.spawn(
+            async move { second_mock_builder_server.await },
+            "second_mock_builder_server",
+        );
+
+        let execution_layer = tester.chain.execution_layer.as_ref().unwrap();
+        let first_url = execution_layer.builder().unwrap().url(0).unwrap().clone();
+        let second_url =
+            SensitiveUrl::parse(format!("http://127.0.0.1:{}", addr.port()).as_str()).unwrap();
+        execution_layer
+            .set_builder_urls(vec![first_url, second_url], None)
+            .unwrap();
+        tester.second_mock_builder = Some(Arc::new(second_mock_builder));
+
+        // Register the validators with both relays.
+        tester.test_post_validator_register_validator().await
+    }
+
     fn skip_slots(self, count: u64) -> Self {
         for _ in 0..count {
             self.chain
@@ -1965,6 +2001,11 @@ impl ApiTester {
 
         let expected = VersionData {
             version: lighthouse_version::version_with_platform(),
+            execution_client: self
+                .chain
+                .execution_layer
+                .as_ref()
+                .and_then(|el| el.client_version()),
         };
 
         assert_eq!(result, expected);
@@ -4242,6 +4283,36 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_payload_falls_back_when_builder_withholds_bid(self) -> Self {
+        self.mock_builder.as_ref().unwrap().set_withhold_bids(true);
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // If this cache is populated, it indicates fallback to the local EE was correctly used.
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_some());
+        self
+    }
+
     pub async fn test_payload_v3_rejects_invalid_signature(self) -> Self {
         self.mock_builder.as_ref().unwrap().invalid_signatures();
 
@@ -4903,6 +4974,244 @@ impl ApiTester {
         self
     }
 
+    /// Set the value and gas limit of the next bid of each relay. The gas limit identifies the
+    /// relay which provided a payload.
+    fn set_relay_bids(&self, first_value: Uint256, second_value: Uint256) {
+        let first = self.mock_builder.as_ref().unwrap();
+        first.set_bid_value(first_value);
+        first.add_operation(Operation::GasLimit(30_000_000));
+
+        let second = self.second_mock_builder.as_ref().unwrap();
+        second.set_bid_value(second_value);
+        second.add_operation(Operation::GasLimit(29_000_000));
+    }
+
+    async fn get_blinded_payload(&self) -> BlindedPayload<E> {
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        self.client
+            .get_validator_blinded_blocks::<E>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into()
+    }
+
+    fn is_local_payload(&self, payload: &BlindedPayload<E>) -> bool {
+        self.chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_some()
+    }
+
+    pub async fn test_most_valuable_relay_bid_chosen(self) -> Self {
+        let local_value = DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
+        self.set_relay_bids(
+            Uint256::from(local_value + 2),
+            Uint256::from(local_value + 1),
+        );
+
+        let payload = self.get_blinded_payload().await;
+        assert!(!self.is_local_payload(&payload));
+        assert_eq!(payload.gas_limit(), 30_000_000);
+
+        self.set_relay_bids(
+            Uint256::from(local_value + 1),
+            Uint256::from(local_value + 2),
+        );
+
+        let payload = self.get_blinded_payload().await;
+        assert!(!self.is_local_payload(&payload));
+        assert_eq!(payload.gas_limit(), 29_000_000);
+
+        self
+    }
+
+    pub async fn test_invalid_relay_bid_skipped(self) -> Self {
+        let local_value = DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
+        self.set_relay_bids(
+            Uint256::from(local_value + 1),
+            Uint256::from(local_value * 2),
+        );
+        self.second_mock_builder
+            .as_ref()
+            .unwrap()
+            .invalid_signatures();
+
+        // The more valuable bid of the second relay is invalid, so the first relay's bid is used.
+        let payload = self.get_blinded_payload().await;
+        assert!(!self.is_local_payload(&payload));
+        assert_eq!(payload.gas_limit(), 30_000_000);
+
+        self
+    }
+
+    pub async fn test_builder_boost_factor_applied_to_best_relay_bid(self) -> Self {
+        let local_value = DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
+        self.set_relay_bids(
+            Uint256::from(local_value + 1),
+            Uint256::from(local_value * 3 / 2),
+        );
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        // Both bids are more valuable than the local payload, but not once halved.
+        let (payload_type, _) = self
+            .client
+            .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, Some(50))
+            .await
+            .unwrap();
+        match payload_type.data {
+            ProduceBlockV3Response::Full(_) => (),
+            ProduceBlockV3Response::Blinded(_) => panic!("Expecting a full payload"),
+        };
+
+        self.set_relay_bids(
+            Uint256::from(local_value + 1),
+            Uint256::from(local_value * 3 / 2),
+        );
+
+        // Doubled, the best bid is used.
+        let (payload_type, _) = self
+            .client
+            .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, Some(200))
+            .await
+            .unwrap();
+        let payload: BlindedPayload<E> = match payload_type.data {
+            ProduceBlockV3Response::Blinded(block) => {
+                block.body().execution_payload().unwrap().into()
+            }
+            ProduceBlockV3Response::Full(_) => panic!("Expecting a blinded payload"),
+        };
+        assert_eq!(payload.gas_limit(), 29_000_000);
+
+        self
+    }
+
+    pub async fn test_relay_errors(self) -> Self {
+        let local_value = DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
+        let first = self.mock_builder.as_ref().unwrap();
+        let second = self.second_mock_builder.as_ref().unwrap();
+
+        // A relay which fails to respond in time does not prevent the bid of another relay from
+        // being used.
+        self.set_relay_bids(
+            Uint256::from(local_value * 2),
+            Uint256::from(local_value + 1),
+        );
+        first.set_header_delay(Duration::from_secs(2));
+
+        let payload = self.get_blinded_payload().await;
+        assert!(!self.is_local_payload(&payload));
+        assert_eq!(payload.gas_limit(), 29_000_000);
+
+        // If all relays fail, the local payload is used.
+        second.set_header_delay(Duration::from_secs(2));
+
+        let payload = self.get_blinded_payload().await;
+        assert!(self.is_local_payload(&payload));
+
+        first.set_header_delay(Duration::ZERO);
+        second.set_header_delay(Duration::ZERO);
+        self
+    }
+
+    pub async fn test_proposal_records_payload_selection(self) -> Self {
+        use eth2::lighthouse::{BuilderFallbackReason, PayloadSource};
+
+        // Local payload is more valuable than the builder's.
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI - 1,
+            )));
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let block = self
+            .client
+            .get_validator_blinded_blocks::<E>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data;
+
+        let records = self
+            .client
+            .get_lighthouse_proposals(Some(slot), Some(slot))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.slot, slot);
+        assert_eq!(record.block_root, block.canonical_root());
+        assert_eq!(record.proposer_index, block.proposer_index());
+        assert_eq!(record.payload_source, PayloadSource::Local);
+        assert_eq!(
+            record.fee_recipient,
+            block.body().execution_payload().unwrap().fee_recipient()
+        );
+        assert_eq!(
+            record.local_value,
+            Some(Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI))
+        );
+        assert_eq!(
+            record.builder_value,
+            Some(Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI - 1))
+        );
+        assert_eq!(
+            record.builder_fallback_reason,
+            Some(BuilderFallbackReason::LocalMoreValuable)
+        );
+
+        // Builder payload is more valuable than the local one.
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+            )));
+
+        let block = self
+            .client
+            .get_validator_blinded_blocks::<E>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data;
+
+        let records = self
+            .client
+            .get_lighthouse_proposals(None, None)
+            .await
+            .unwrap()
+            .data;
+        let record = records
+            .iter()
+            .find(|record| record.block_root == block.canonical_root())
+            .expect("builder block should be recorded");
+        assert_eq!(record.payload_source, PayloadSource::Builder);
+        assert_eq!(
+            record.builder_value,
+            Some(Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1))
+        );
+        assert_eq!(record.builder_fallback_reason, None);
+
+        self
+    }
+
     pub async fn test_builder_works_post_capella(self) -> Self {
         // Ensure builder payload is chosen
         self.mock_builder
@@ -5059,12 +5368,76 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_network_bandwidth(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_network_bandwidth()
+            .await
+            .unwrap()
+            .data;
+
+        // The test network does not exchange any traffic with its peers.
+        assert_eq!(result, BandwidthSummary::default());
+
+        self
+    }
+
+    pub async fn test_lighthouse_network_inbound_rate_limits_disabled(self) -> Self {
+        // The test network is started without an inbound rate limiter.
+        let err = self
+            .client
+            .get_lighthouse_network_inbound_rate_limits()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 404);
+
+        let limits = eth2::lighthouse::InboundRateLimits {
+            default: "ping:2/10".to_string(),
+            trusted: None,
+            ip_range: Some("ping:20/10".to_string()),
+            ip_ranges: vec!["10.0.0.0/8".to_string()],
+        };
+        let err = self
+            .client
+            .post_lighthouse_network_inbound_rate_limits(&limits)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_proto_array(self) -> Self {
         self.client.get_lighthouse_proto_array().await.unwrap();
 
         self
     }
 
+    pub async fn test_get_lighthouse_proto_array_graph(self) -> Self {
+        let graph = self
+            .client
+            .get_lighthouse_proto_array_graph()
+            .await
+            .unwrap()
+            .data;
+
+        let expected = proto_array::ProtoArrayGraph::from(
+            self.chain
+                .canonical_head
+                .fork_choice_read_lock()
+                .proto_array()
+                .core_proto_array(),
+        );
+        assert_eq!(graph, expected);
+
+        let dot = self.client.get_lighthouse_proto_array_dot().await.unwrap();
+        assert!(dot.starts_with("digraph proto_array {"));
+        let head_root = self.chain.head_beacon_block_root();
+        assert!(dot.contains(&format!("\"{:?}\" [", head_root)));
+
+        self
+    }
+
     pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
         let epoch = self.chain.epoch().unwrap() - 1;
         self.client
@@ -5111,6 +5484,55 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_eth1_deposits(self) -> Self {
+        let pubkey = self
+            .chain
+            .head_snapshot()
+            .beacon_state
+            .validators()
+            .get(0)
+            .unwrap()
+            .pubkey;
+
+        let deposits = self
+            .client
+            .get_lighthouse_eth1_deposits(&pubkey)
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(deposits.pubkey, pubkey);
+        assert_eq!(deposits.validator_index, Some(0));
+        assert!(deposits.deposits.is_empty());
+
+        let unknown = self
+            .client
+            .get_lighthouse_eth1_deposits(&PublicKeyBytes::empty())
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(unknown.validator_index, None);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_transaction_deposits(self) -> Self {
+        let transaction_hash = Hash256::repeat_byte(42);
+
+        let deposits = self
+            .client
+            .get_lighthouse_eth1_transaction_deposits(transaction_hash)
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(deposits.transaction_hash, transaction_hash);
+        assert!(deposits.deposits.is_empty());
+
+        self
+    }
+
     pub async fn test_get_lighthouse_staking(self) -> Self {
         let result = self.client.get_lighthouse_staking().await.unwrap();
 
@@ -6221,6 +6643,22 @@ async fn get_blinded_block_invalid_signature() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_blinded_block_builder_withholds_bid() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_payload_falls_back_when_builder_withholds_bid()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_lighthouse_proposals() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_proposal_records_payload_selection()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_full_block_invalid_signature_v3() {
     ApiTester::new_mev_tester()
@@ -6293,6 +6731,38 @@ async fn builder_chain_health_optimistic_head_v3() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn most_valuable_relay_bid_chosen() {
+    ApiTester::new_mev_tester_two_relays()
+        .await
+        .test_most_valuable_relay_bid_chosen()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn invalid_relay_bid_skipped() {
+    ApiTester::new_mev_tester_two_relays()
+        .await
+        .test_invalid_relay_bid_skipped()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_boost_factor_applied_to_best_relay_bid() {
+    ApiTester::new_mev_tester_two_relays()
+        .await
+        .test_builder_boost_factor_applied_to_best_relay_bid()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn relay_errors() {
+    ApiTester::new_mev_tester_two_relays()
+        .await
+        .test_relay_errors()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_payload_chosen_by_profit() {
     ApiTester::new_mev_tester_default_payload_value()
@@ -6395,8 +6865,14 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_network_bandwidth()
+        .await
+        .test_lighthouse_network_inbound_rate_limits_disabled()
+        .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_proto_array_graph()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
         .test_get_lighthouse_validator_inclusion_global()
@@ -6407,6 +6883,10 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_eth1_deposit_cache()
         .await
+        .test_get_lighthouse_eth1_deposits()
+        .await
+        .test_get_lighthouse_eth1_transaction_deposits()
+        .await
         .test_get_lighthouse_staking()
         .await
         .test_get_lighthouse_database_info()
@@ -31,6 +31,7 @@ use std::{
 
 use futures::StreamExt;
 use futures_ticker::Ticker;
+use hashlink::LinkedHashMap;
 use prometheus_client::registry::Registry;
 use rand::{seq::SliceRandom, thread_rng};
 
@@ -57,10 +58,10 @@ use super::time_cache::DuplicateCache;
 use super::topic::{Hasher, Topic, TopicHash};
 use super::transform::{DataTransform, IdentityTransform};
 use super::types::{
-    ControlAction, FailedMessages, Message, MessageAcceptance, MessageId, PeerInfo, RawMessage,
-    Subscription, SubscriptionAction,
+    ControlAction, FailedMessages, Message, MessageAcceptance, MessageBandwidth, MessageId,
+    PeerInfo, RawMessage, Subscription, SubscriptionAction,
 };
-use super::types::{Graft, IHave, IWant, PeerConnections, PeerKind, Prune};
+use super::types::{Graft, IDontWant, IHave, IWant, PeerConnections, PeerKind, Prune};
 use super::{backoff::BackoffStorage, types::RpcSender};
 use super::{
     config::{Config, ValidationMode},
@@ -74,6 +75,15 @@ use std::{cmp::Ordering::Equal, fmt::Debug};
 #[cfg(test)]
 mod tests;
 
+/// IDONTWANT cache capacity.
+const IDONTWANT_CAP: usize = 10_000;
+
+/// IDONTWANT timeout before removal.
+const IDONTWANT_TIMEOUT: Duration = Duration::new(3, 0);
+
+/// Returns `true` if validated messages on the topic should currently not be forwarded.
+type ForwardThrottle = Box<dyn Fn(&TopicHash) -> bool + Send>;
+
 /// Determines if published messages should be signed or not.
 ///
 /// Without signing, a number of privacy preserving modes can be selected.
@@ -332,6 +342,14 @@ pub struct Behaviour<D = IdentityTransform, F = AllowAllSubscriptionFilter> {
 
     /// Tracks the numbers of failed messages per peer-id.
     failed_messages: HashMap<PeerId, FailedMessages>,
+
+    /// Bytes of messages exchanged per peer and topic since the last call to
+    /// [`Behaviour::take_message_bandwidth`].
+    message_bandwidth: HashMap<(PeerId, TopicHash), MessageBandwidth>,
+
+    /// Decides whether validated messages on a topic are currently not forwarded, set with
+    /// [`Behaviour::set_forward_throttle`].
+    forward_throttle: Option<ForwardThrottle>,
 }
 
 impl<D, F> Behaviour<D, F>
@@ -468,6 +486,8 @@ where
             subscription_filter,
             data_transform,
             failed_messages: Default::default(),
+            message_bandwidth: HashMap::new(),
+            forward_throttle: None,
         })
     }
 }
@@ -514,6 +534,22 @@ where
             .map(|(score, ..)| score.score(peer_id))
     }
 
+    /// Returns the bytes of messages exchanged with each peer on each topic since the last call
+    /// and resets the counters.
+    pub fn take_message_bandwidth(&mut self) -> HashMap<(PeerId, TopicHash), MessageBandwidth> {
+        std::mem::take(&mut self.message_bandwidth)
+    }
+
+    /// Sets a function which is consulted before forwarding a validated message on a topic. If it
+    /// returns `true` the message is not forwarded to any peer, although it is still delivered to
+    /// the peer score and kept in the memcache to be served to IWANT requests.
+    ///
+    /// This allows the application to shed forwarding traffic, e.g. when it runs out of upload
+    /// bandwidth. Messages we publish ourselves are never throttled.
+    pub fn set_forward_throttle(&mut self, throttle: impl Fn(&TopicHash) -> bool + Send + 'static) {
+        self.forward_throttle = Some(Box::new(throttle));
+    }
+
     /// Subscribe to a topic.
     ///
     /// Returns [`Ok(true)`] if the subscription worked. Returns [`Ok(false)`] if we were already
@@ -596,7 +632,8 @@ where
         });
 
         // check that the size doesn't exceed the max transmission size
-        if raw_message.raw_protobuf_len() > self.config.max_transmit_size() {
+        let message_len = raw_message.raw_protobuf_len();
+        if message_len > self.config.max_transmit_size() {
             return Err(PublishError::MessageTooLarge);
         }
 
@@ -736,7 +773,13 @@ where
                     self.config.publish_queue_duration(),
                     self.metrics.as_mut(),
                 ) {
-                    Ok(_) => publish_failed = false,
+                    Ok(_) => {
+                        publish_failed = false;
+                        self.message_bandwidth
+                            .entry((*peer_id, topic_hash.clone()))
+                            .or_default()
+                            .outbound += message_len as u64;
+                    }
                     Err(_) => {
                         self.failed_messages.entry(*peer_id).or_default().priority += 1;
 
@@ -1099,7 +1142,7 @@ where
             None => {
                 tracing::error!("Attempted to Prune an unknown peer");
             }
-            _ => {} // Gossipsub 1.1 peer perform the `Prune`
+            _ => {} // Gossipsub 1.1 and 1.2 peers perform the `Prune`
         }
 
         // Select peers for peer exchange
@@ -1371,6 +1414,8 @@ where
                     );
                 } else if let Some(peer) = &mut self.connected_peers.get_mut(peer_id) {
                     tracing::debug!(peer=%peer_id, "IWANT: Sending cached messages to peer");
+                    let topic = msg.topic.clone();
+                    let message_len = msg.raw_protobuf_len();
                     if peer
                         .sender
                         .forward(
@@ -1378,8 +1423,13 @@ where
                             self.config.forward_queue_duration(),
                             self.metrics.as_mut(),
                         )
-                        .is_err()
+                        .is_ok()
                     {
+                        self.message_bandwidth
+                            .entry((*peer_id, topic))
+                            .or_default()
+                            .outbound += message_len as u64;
+                    } else {
                         // Downscore the peer
                         if let Some((peer_score, ..)) = &mut self.peer_score {
                             peer_score.failed_message_slow_peer(peer_id);
@@ -1399,6 +1449,28 @@ where
         tracing::debug!(peer=%peer_id, "Completed IWANT handling for peer");
     }
 
+    /// Handles an IDONTWANT control message. Stores the message ids so that we don't forward
+    /// these messages to the peer.
+    fn handle_idontwant(&mut self, peer_id: &PeerId, idontwant: IDontWant) {
+        let Some(peer) = self.connected_peers.get_mut(peer_id) else {
+            tracing::error!(peer = %peer_id,
+                "Could not handle IDONTWANT, peer doesn't exist in connected peer list");
+            return;
+        };
+
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.register_idontwant(idontwant.message_ids.len());
+        }
+
+        for message_id in idontwant.message_ids {
+            peer.dont_send.insert(message_id, Instant::now());
+            // Don't exceed capacity.
+            if peer.dont_send.len() > IDONTWANT_CAP {
+                peer.dont_send.pop_front();
+            }
+        }
+    }
+
     /// Handles GRAFT control messages. If subscribed to the topic, adds the peer to mesh, if not,
     /// responds with PRUNE messages.
     fn handle_graft(&mut self, peer_id: &PeerId, topics: Vec<TopicHash>) {
@@ -1774,9 +1846,14 @@ where
         propagation_source: &PeerId,
     ) {
         // Record the received metric
+        let message_len = raw_message.raw_protobuf_len();
         if let Some(metrics) = self.metrics.as_mut() {
-            metrics.msg_recvd_unfiltered(&raw_message.topic, raw_message.raw_protobuf_len());
+            metrics.msg_recvd_unfiltered(&raw_message.topic, message_len);
         }
+        self.message_bandwidth
+            .entry((*propagation_source, raw_message.topic.clone()))
+            .or_default()
+            .inbound += message_len as u64;
 
         // Try and perform the data transform to the message. If it fails, consider it invalid.
         let message = match self.data_transform.inbound_transform(raw_message.clone()) {
@@ -1828,6 +1905,12 @@ where
             gossip_promises.message_delivered(&msg_id);
         }
 
+        // Tell our mesh peers not to send us this message again, if it is large enough for the
+        // saved bandwidth to outweigh the cost of the control message.
+        if message_len > self.config.idontwant_message_size_threshold() {
+            self.send_idontwant(&raw_message, &msg_id, propagation_source);
+        }
+
         // Add the message to our memcache
         self.mcache.put(&msg_id, raw_message.clone());
 
@@ -1865,6 +1948,66 @@ where
         }
     }
 
+    /// Sends IDONTWANT messages for a received message to all Gossipsub 1.2 peers in the topic's
+    /// mesh, apart from the peer we received it from and its author.
+    fn send_idontwant(
+        &mut self,
+        message: &RawMessage,
+        msg_id: &MessageId,
+        propagation_source: &PeerId,
+    ) {
+        let Some(mesh_peers) = self.mesh.get(&message.topic) else {
+            return;
+        };
+
+        let recipient_peers = mesh_peers
+            .iter()
+            .filter(|peer_id| {
+                *peer_id != propagation_source && Some(*peer_id) != message.source.as_ref()
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut sent = 0;
+        for peer_id in recipient_peers {
+            let Some(peer) = self.connected_peers.get_mut(&peer_id) else {
+                tracing::error!(peer = %peer_id,
+                    "Could not IDONTWANT, peer doesn't exist in connected peer list");
+                continue;
+            };
+
+            // Only gossipsub 1.2 peers support IDONTWANT.
+            if peer.kind != PeerKind::Gossipsubv1_2 {
+                continue;
+            }
+
+            if peer
+                .sender
+                .idontwant(IDontWant {
+                    message_ids: vec![msg_id.clone()],
+                })
+                .is_err()
+            {
+                tracing::warn!(peer=%peer_id, "Send Queue full. Could not send IDONTWANT");
+
+                if let Some((peer_score, ..)) = &mut self.peer_score {
+                    peer_score.failed_message_slow_peer(&peer_id);
+                }
+                // Increment failed message count
+                self.failed_messages
+                    .entry(peer_id)
+                    .or_default()
+                    .non_priority += 1;
+            } else {
+                sent += 1;
+            }
+        }
+
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.register_idontwant_sent(sent);
+        }
+    }
+
     // Handles invalid messages received.
     fn handle_invalid_message(
         &mut self,
@@ -1960,7 +2103,10 @@ where
                     }
                     // if the mesh needs peers add the peer to the mesh
                     if !self.explicit_peers.contains(propagation_source)
-                        && matches!(peer.kind, PeerKind::Gossipsubv1_1 | PeerKind::Gossipsub)
+                        && matches!(
+                            peer.kind,
+                            PeerKind::Gossipsubv1_2 | PeerKind::Gossipsubv1_1 | PeerKind::Gossipsub
+                        )
                         && !Self::score_below_threshold_from_scores(
                             &self.peer_score,
                             propagation_source,
@@ -2108,6 +2254,17 @@ where
         // apply iwant penalties
         self.apply_iwant_penalties();
 
+        // clean up expired IDONTWANT entries
+        let now = Instant::now();
+        for peer in self.connected_peers.values_mut() {
+            while let Some((_, received)) = peer.dont_send.front() {
+                if *received + IDONTWANT_TIMEOUT >= now {
+                    break;
+                }
+                peer.dont_send.pop_front();
+            }
+        }
+
         // check connections to explicit peers
         if self.heartbeat_ticks % self.config.check_explicit_peers_ticks() == 0 {
             for p in self.explicit_peers.clone() {
@@ -2673,6 +2830,15 @@ where
             }
         }
 
+        if self
+            .forward_throttle
+            .as_ref()
+            .is_some_and(|throttle| throttle(&message.topic))
+        {
+            tracing::debug!(message=%msg_id, "Forwarding throttled, not forwarding message");
+            return Ok(false);
+        }
+
         tracing::debug!(message=%msg_id, "Forwarding message");
         let mut recipient_peers = HashSet::new();
 
@@ -2707,8 +2873,16 @@ where
 
         // forward the message to peers
         if !recipient_peers.is_empty() {
+            let message_len = message.raw_protobuf_len();
             for peer_id in recipient_peers.iter() {
                 if let Some(peer) = self.connected_peers.get_mut(peer_id) {
+                    if peer.dont_send.contains_key(msg_id) {
+                        tracing::debug!(%peer_id, message=%msg_id, "Peer doesn't want message");
+                        if let Some(metrics) = self.metrics.as_mut() {
+                            metrics.idontwant_msg_skipped(&message.topic, message_len);
+                        }
+                        continue;
+                    }
                     tracing::debug!(%peer_id, message=%msg_id, "Sending message to peer");
                     if peer
                         .sender
@@ -2717,8 +2891,13 @@ where
                             self.config.forward_queue_duration(),
                             self.metrics.as_mut(),
                         )
-                        .is_err()
+                        .is_ok()
                     {
+                        self.message_bandwidth
+                            .entry((*peer_id, message.topic.clone()))
+                            .or_default()
+                            .outbound += message_len as u64;
+                    } else {
                         // Downscore the peer
                         if let Some((peer_score, ..)) = &mut self.peer_score {
                             peer_score.failed_message_slow_peer(peer_id);
@@ -3058,6 +3237,7 @@ where
                 connections: vec![],
                 sender: RpcSender::new(self.config.connection_handler_queue_len()),
                 topics: Default::default(),
+                dont_send: LinkedHashMap::new(),
             });
         // Add the new connection
         connected_peer.connections.push(connection_id);
@@ -3088,6 +3268,7 @@ where
                 connections: vec![],
                 sender: RpcSender::new(self.config.connection_handler_queue_len()),
                 topics: Default::default(),
+                dont_send: LinkedHashMap::new(),
             });
         // Add the new connection
         connected_peer.connections.push(connection_id);
@@ -3246,6 +3427,9 @@ where
                             peers,
                             backoff,
                         }) => prune_msgs.push((topic_hash, peers, backoff)),
+                        ControlAction::IDontWant(idontwant) => {
+                            self.handle_idontwant(&propagation_source, idontwant)
+                        }
                     }
                 }
                 if !ihave_msgs.is_empty() {
@@ -3396,7 +3580,12 @@ fn get_random_peers_dynamic(
         .iter()
         .filter(|(_, p)| p.topics.contains(topic_hash))
         .filter(|(peer_id, _)| f(peer_id))
-        .filter(|(_, p)| p.kind == PeerKind::Gossipsub || p.kind == PeerKind::Gossipsubv1_1)
+        .filter(|(_, p)| {
+            matches!(
+                p.kind,
+                PeerKind::Gossipsub | PeerKind::Gossipsubv1_1 | PeerKind::Gossipsubv1_2
+            )
+        })
         .map(|(peer_id, _)| *peer_id)
         .collect::<Vec<PeerId>>();
 
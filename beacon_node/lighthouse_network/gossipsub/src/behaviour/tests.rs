@@ -51,6 +51,7 @@ where
     scoring: Option<(PeerScoreParams, PeerScoreThresholds)>,
     data_transform: D,
     subscription_filter: F,
+    peer_kind: Option<PeerKind>,
 }
 
 impl<D, F> InjectNodes<D, F>
@@ -98,7 +99,7 @@ where
 
         let empty = vec![];
         for i in 0..self.peer_no {
-            let (peer, receiver) = add_peer(
+            let (peer, receiver) = add_peer_with_addr_and_kind(
                 &mut gs,
                 if self.to_subscribe {
                     &topic_hashes
@@ -107,6 +108,8 @@ where
                 },
                 i < self.outbound,
                 i < self.explicit,
+                Multiaddr::empty(),
+                self.peer_kind.clone().or(Some(PeerKind::Gossipsubv1_1)),
             );
             peers.push(peer);
             receivers.insert(peer, receiver);
@@ -155,6 +158,11 @@ where
         self.subscription_filter = subscription_filter;
         self
     }
+
+    fn peer_kind(mut self, peer_kind: PeerKind) -> Self {
+        self.peer_kind = Some(peer_kind);
+        self
+    }
 }
 
 fn inject_nodes<D, F>() -> InjectNodes<D, F>
@@ -239,6 +247,7 @@ where
             kind: kind.clone().unwrap_or(PeerKind::Floodsub),
             connections: vec![connection_id],
             topics: Default::default(),
+            dont_send: LinkedHashMap::new(),
             sender,
         },
     );
@@ -386,10 +395,25 @@ fn proto_to_message(rpc: &proto::RPC) -> Rpc {
             }));
         }
 
+        let idontwant_msgs: Vec<ControlAction> = rpc_control
+            .idontwant
+            .into_iter()
+            .map(|idontwant| {
+                ControlAction::IDontWant(IDontWant {
+                    message_ids: idontwant
+                        .message_ids
+                        .into_iter()
+                        .map(MessageId::from)
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+
         control_msgs.extend(ihave_msgs);
         control_msgs.extend(iwant_msgs);
         control_msgs.extend(graft_msgs);
         control_msgs.extend(prune_msgs);
+        control_msgs.extend(idontwant_msgs);
     }
 
     Rpc {
@@ -624,6 +648,7 @@ fn test_join() {
                 kind: PeerKind::Floodsub,
                 connections: vec![connection_id],
                 topics: Default::default(),
+                dont_send: LinkedHashMap::new(),
                 sender,
             },
         );
@@ -1018,6 +1043,7 @@ fn test_get_random_peers() {
                 kind: PeerKind::Gossipsubv1_1,
                 connections: vec![ConnectionId::new_unchecked(0)],
                 topics: topics.clone(),
+                dont_send: LinkedHashMap::new(),
                 sender: RpcSender::new(gs.config.connection_handler_queue_len()),
             },
         );
@@ -5240,3 +5266,266 @@ fn test_graft_without_subscribe() {
     // We unsubscribe from the topic.
     let _ = gs.unsubscribe(&Topic::new(topic));
 }
+
+/// Test that a node sends IDONTWANT messages to the mesh peers
+/// that run Gossipsub v1.2.
+#[test]
+fn sends_idontwant() {
+    let (mut gs, peers, receivers, topic_hashes) = inject_nodes1()
+        .peer_no(5)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .explicit(1)
+        .peer_kind(PeerKind::Gossipsubv1_2)
+        .create_network();
+
+    let local_id = PeerId::random();
+
+    let message = RawMessage {
+        source: Some(peers[1]),
+        data: vec![12; 1024],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    gs.handle_received_message(message.clone(), &local_id);
+
+    let (idontwants, _) = count_control_msgs(receivers, |peer_id, action| {
+        if matches!(action, RpcOut::IDontWant(_)) {
+            assert_ne!(*peer_id, peers[1]);
+            true
+        } else {
+            false
+        }
+    });
+    assert_eq!(idontwants, 3, "IDONTWANT was not sent");
+}
+
+/// Test that a node doesn't send IDONTWANT messages for messages below the size threshold.
+#[test]
+fn doesnt_send_idontwant_below_threshold() {
+    let (mut gs, peers, receivers, topic_hashes) = inject_nodes1()
+        .peer_no(5)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .explicit(1)
+        .peer_kind(PeerKind::Gossipsubv1_2)
+        .create_network();
+
+    let local_id = PeerId::random();
+
+    let message = RawMessage {
+        source: Some(peers[1]),
+        data: vec![12],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    gs.handle_received_message(message.clone(), &local_id);
+
+    let (idontwants, _) = count_control_msgs(receivers, |_, action| {
+        matches!(action, RpcOut::IDontWant(_))
+    });
+    assert_eq!(idontwants, 0, "IDONTWANT was sent for a small message");
+}
+
+/// Test that a node doesn't send IDONTWANT messages to peers that don't support Gossipsub v1.2.
+#[test]
+fn doesnt_send_idontwant_to_old_peers() {
+    let (mut gs, peers, receivers, topic_hashes) = inject_nodes1()
+        .peer_no(5)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .explicit(1)
+        .peer_kind(PeerKind::Gossipsubv1_1)
+        .create_network();
+
+    let local_id = PeerId::random();
+
+    let message = RawMessage {
+        source: Some(peers[1]),
+        data: vec![12; 1024],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    gs.handle_received_message(message.clone(), &local_id);
+
+    let (idontwants, _) = count_control_msgs(receivers, |_, action| {
+        matches!(action, RpcOut::IDontWant(_))
+    });
+    assert_eq!(idontwants, 0, "IDONTWANT was sent to a Gossipsub v1.1 peer");
+}
+
+/// Test that a node doesn't forward a message to a peer that sent us an IDONTWANT for it.
+#[test]
+fn doesnt_forward_idontwant() {
+    let (mut gs, peers, receivers, topic_hashes) = inject_nodes1()
+        .peer_no(4)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .explicit(1)
+        .peer_kind(PeerKind::Gossipsubv1_2)
+        .create_network();
+
+    let local_id = PeerId::random();
+
+    let raw_message = RawMessage {
+        source: Some(peers[1]),
+        data: vec![12],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    let message = gs
+        .data_transform
+        .inbound_transform(raw_message.clone())
+        .unwrap();
+    let message_id = gs.config.message_id(&message);
+
+    gs.handle_idontwant(
+        &peers[2],
+        IDontWant {
+            message_ids: vec![message_id.clone()],
+        },
+    );
+    assert!(gs.connected_peers[&peers[2]]
+        .dont_send
+        .contains_key(&message_id));
+
+    gs.handle_received_message(raw_message.clone(), &local_id);
+
+    let (forwards, _) = count_control_msgs(receivers, |peer_id, action| {
+        if matches!(action, RpcOut::Forward { .. }) {
+            assert_ne!(
+                *peer_id, peers[2],
+                "Message was forwarded despite IDONTWANT"
+            );
+            true
+        } else {
+            false
+        }
+    });
+    // The explicit peer and the remaining mesh peer.
+    assert_eq!(forwards, 2);
+}
+
+/// Test that a node clears stale IDONTWANT messages.
+#[test]
+fn clear_stale_idontwant() {
+    let (mut gs, peers, _receivers, _topic_hashes) = inject_nodes1()
+        .peer_no(4)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .explicit(1)
+        .peer_kind(PeerKind::Gossipsubv1_2)
+        .create_network();
+
+    let peer = gs.connected_peers.get_mut(&peers[2]).unwrap();
+    peer.dont_send
+        .insert(MessageId::new(&[1, 2, 3, 4]), Instant::now());
+    sleep(Duration::from_secs(3));
+    gs.heartbeat();
+    let peer = gs.connected_peers.get_mut(&peers[2]).unwrap();
+    assert!(peer.dont_send.is_empty());
+}
+
+/// Test that the bytes of received and forwarded messages are accounted per peer and topic.
+#[test]
+fn records_message_bandwidth() {
+    let (mut gs, peers, _receivers, topic_hashes) = inject_nodes1()
+        .peer_no(4)
+        .topics(vec![String::from("topic1")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .create_network();
+
+    let raw_message = RawMessage {
+        source: Some(peers[1]),
+        data: vec![12; 64],
+        sequence_number: Some(0),
+        topic: topic_hashes[0].clone(),
+        signature: None,
+        key: None,
+        validated: true,
+    };
+    let message_len = raw_message.raw_protobuf_len() as u64;
+
+    gs.handle_received_message(raw_message, &peers[0]);
+
+    let bandwidth = gs.take_message_bandwidth();
+    assert_eq!(
+        bandwidth[&(peers[0], topic_hashes[0].clone())],
+        MessageBandwidth {
+            inbound: message_len,
+            outbound: 0
+        }
+    );
+    // The message is neither sent back to the propagation source nor to its author.
+    assert!(!bandwidth.contains_key(&(peers[1], topic_hashes[0].clone())));
+    for peer in &peers[2..] {
+        assert_eq!(
+            bandwidth[&(*peer, topic_hashes[0].clone())],
+            MessageBandwidth {
+                inbound: 0,
+                outbound: message_len
+            }
+        );
+    }
+
+    // The counters are reset once taken.
+    assert!(gs.take_message_bandwidth().is_empty());
+}
+
+/// Test that messages are not forwarded on the topics for which forwarding is throttled.
+#[test]
+fn doesnt_forward_throttled_topics() {
+    let (mut gs, peers, receivers, topic_hashes) = inject_nodes1()
+        .peer_no(4)
+        .topics(vec![String::from("topic1"), String::from("topic2")])
+        .to_subscribe(true)
+        .gs_config(Config::default())
+        .create_network();
+
+    let throttled_topic = topic_hashes[0].clone();
+    gs.set_forward_throttle(move |topic| *topic == throttled_topic);
+
+    for topic in &topic_hashes {
+        let raw_message = RawMessage {
+            source: Some(peers[1]),
+            data: vec![12],
+            sequence_number: Some(0),
+            topic: topic.clone(),
+            signature: None,
+            key: None,
+            validated: true,
+        };
+        gs.handle_received_message(raw_message, &peers[0]);
+    }
+
+    let (forwards, _) = count_control_msgs(receivers, |_, action| match action {
+        RpcOut::Forward { message, .. } => {
+            assert_eq!(
+                message.topic, topic_hashes[1],
+                "Message was forwarded on a throttled topic"
+            );
+            true
+        }
+        _ => false,
+    });
+    // The mesh peers which are neither the propagation source nor the author.
+    assert_eq!(forwards, 2);
+}
@@ -57,6 +57,7 @@ pub enum ValidationMode {
 pub enum Version {
     V1_0,
     V1_1,
+    V1_2,
 }
 
 /// Configuration parameters that define the performance of the gossipsub network.
@@ -98,6 +99,7 @@ pub struct Config {
     connection_handler_queue_len: usize,
     connection_handler_publish_duration: Duration,
     connection_handler_forward_duration: Duration,
+    idontwant_message_size_threshold: usize,
 }
 
 impl Config {
@@ -370,6 +372,16 @@ impl Config {
     pub fn forward_queue_duration(&self) -> Duration {
         self.connection_handler_forward_duration
     }
+
+    /// The message size threshold for which IDONTWANT messages are sent.
+    /// Sending IDONTWANT messages for small messages can have a negative effect to the overall
+    /// traffic and CPU load. This acts as a lower bound cutoff for the message size to which
+    /// IDONTWANT won't be sent to peers. Only works if the peers support Gossipsub1.2
+    /// (see <https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.2.md#idontwant-message>).
+    /// The default is 1kB.
+    pub fn idontwant_message_size_threshold(&self) -> usize {
+        self.idontwant_message_size_threshold
+    }
 }
 
 impl Default for Config {
@@ -440,6 +452,7 @@ impl Default for ConfigBuilder {
                 connection_handler_queue_len: 5000,
                 connection_handler_publish_duration: Duration::from_secs(5),
                 connection_handler_forward_duration: Duration::from_millis(1000),
+                idontwant_message_size_threshold: 1000,
             },
             invalid_protocol: false,
         }
@@ -456,7 +469,8 @@ impl From<Config> for ConfigBuilder {
 }
 
 impl ConfigBuilder {
-    /// The protocol id prefix to negotiate this protocol (default is `/meshsub/1.1.0` and `/meshsub/1.0.0`).
+    /// The protocol id prefix to negotiate this protocol (default is `/meshsub/1.2.0`,
+    /// `/meshsub/1.1.0` and `/meshsub/1.0.0`).
     pub fn protocol_id_prefix(
         &mut self,
         protocol_id_prefix: impl Into<Cow<'static, str>>,
@@ -464,17 +478,22 @@ impl ConfigBuilder {
         let cow = protocol_id_prefix.into();
 
         match (
+            StreamProtocol::try_from_owned(format!("{}/1.2.0", cow)),
             StreamProtocol::try_from_owned(format!("{}/1.1.0", cow)),
             StreamProtocol::try_from_owned(format!("{}/1.0.0", cow)),
         ) {
-            (Ok(p1), Ok(p2)) => {
+            (Ok(p1), Ok(p2), Ok(p3)) => {
                 self.config.protocol.protocol_ids = vec![
                     ProtocolId {
                         protocol: p1,
-                        kind: PeerKind::Gossipsubv1_1,
+                        kind: PeerKind::Gossipsubv1_2,
                     },
                     ProtocolId {
                         protocol: p2,
+                        kind: PeerKind::Gossipsubv1_1,
+                    },
+                    ProtocolId {
+                        protocol: p3,
                         kind: PeerKind::Gossipsub,
                     },
                 ]
@@ -487,7 +506,8 @@ impl ConfigBuilder {
         self
     }
 
-    /// The full protocol id to negotiate this protocol (does not append `/1.0.0` or `/1.1.0`).
+    /// The full protocol id to negotiate this protocol (does not append `/1.0.0`, `/1.1.0` or
+    /// `/1.2.0`).
     pub fn protocol_id(
         &mut self,
         protocol_id: impl Into<Cow<'static, str>>,
@@ -500,6 +520,7 @@ impl ConfigBuilder {
                 self.config.protocol.protocol_ids = vec![ProtocolId {
                     protocol,
                     kind: match custom_id_version {
+                        Version::V1_2 => PeerKind::Gossipsubv1_2,
                         Version::V1_1 => PeerKind::Gossipsubv1_1,
                         Version::V1_0 => PeerKind::Gossipsub,
                     },
@@ -825,6 +846,17 @@ impl ConfigBuilder {
         self
     }
 
+    /// The message size threshold for which IDONTWANT messages are sent.
+    /// Sending IDONTWANT messages for small messages can have a negative effect to the overall
+    /// traffic and CPU load. This acts as a lower bound cutoff for the message size to which
+    /// IDONTWANT won't be sent to peers. Only works if the peers support Gossipsub1.2
+    /// (see <https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.2.md#idontwant-message>).
+    /// The default is 1kB.
+    pub fn idontwant_message_size_threshold(&mut self, size: usize) -> &mut Self {
+        self.config.idontwant_message_size_threshold = size;
+        self
+    }
+
     /// Constructs a [`Config`] from the given configuration and validates the settings.
     pub fn build(&self) -> Result<Config, ConfigBuilderError> {
         // check all constraints on config
@@ -895,6 +927,10 @@ impl std::fmt::Debug for Config {
             "published_message_ids_cache_time",
             &self.published_message_ids_cache_time,
         );
+        let _ = builder.field(
+            "idontwant_message_size_threshold",
+            &self.idontwant_message_size_threshold,
+        );
         builder.finish()
     }
 }
@@ -969,19 +1005,25 @@ mod test {
 
         let protocol_ids = protocol_config.protocol_info();
 
-        assert_eq!(protocol_ids.len(), 2);
+        assert_eq!(protocol_ids.len(), 3);
 
         assert_eq!(
             protocol_ids[0].protocol,
-            StreamProtocol::new("/purple/1.1.0")
+            StreamProtocol::new("/purple/1.2.0")
         );
-        assert_eq!(protocol_ids[0].kind, PeerKind::Gossipsubv1_1);
+        assert_eq!(protocol_ids[0].kind, PeerKind::Gossipsubv1_2);
 
         assert_eq!(
             protocol_ids[1].protocol,
+            StreamProtocol::new("/purple/1.1.0")
+        );
+        assert_eq!(protocol_ids[1].kind, PeerKind::Gossipsubv1_1);
+
+        assert_eq!(
+            protocol_ids[2].protocol,
             StreamProtocol::new("/purple/1.0.0")
         );
-        assert_eq!(protocol_ids[1].kind, PeerKind::Gossipsub);
+        assert_eq!(protocol_ids[2].kind, PeerKind::Gossipsub);
     }
 
     #[test]
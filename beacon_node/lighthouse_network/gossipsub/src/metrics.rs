@@ -163,9 +163,9 @@ pub(crate) struct Metrics {
     scoring_penalties: Family<PenaltyLabel, Counter>,
 
     /* General Metrics */
-    /// Gossipsub supports floodsub, gossipsub v1.0 and gossipsub v1.1. Peers are classified based
-    /// on which protocol they support. This metric keeps track of the number of peers that are
-    /// connected of each type.
+    /// Gossipsub supports floodsub, gossipsub v1.0, gossipsub v1.1 and gossipsub v1.2. Peers are
+    /// classified based on which protocol they support. This metric keeps track of the number of
+    /// peers that are connected of each type.
     peers_per_protocol: Family<ProtocolLabel, Gauge>,
     /// The time it takes to complete one iteration of the heartbeat.
     heartbeat_duration: Histogram,
@@ -179,6 +179,17 @@ pub(crate) struct Metrics {
     /// topic. A very high metric might indicate an underperforming network.
     topic_iwant_msgs: Family<TopicHash, Counter>,
 
+    /// The number of IDONTWANT messages received.
+    idontwant_msgs: Counter,
+    /// The number of message ids received in IDONTWANT messages.
+    idontwant_msgs_ids: Counter,
+    /// The number of IDONTWANT messages sent.
+    idontwant_msgs_sent: Counter,
+    /// The number of messages not forwarded to a peer because it sent us an IDONTWANT for it.
+    topic_idontwant_skipped_msgs: Family<TopicHash, Counter>,
+    /// Bytes of gossip messages not forwarded to a peer because it sent us an IDONTWANT for it.
+    topic_idontwant_saved_bytes: Family<TopicHash, Counter>,
+
     /// The size of the priority queue.
     priority_queue_size: Histogram,
     /// The size of the non-priority queue.
@@ -321,6 +332,42 @@ impl Metrics {
             metric
         };
 
+        let idontwant_msgs = {
+            let metric = Counter::default();
+            registry.register(
+                "idontwant_msgs",
+                "The number of IDONTWANT messages received",
+                metric.clone(),
+            );
+            metric
+        };
+        let idontwant_msgs_ids = {
+            let metric = Counter::default();
+            registry.register(
+                "idontwant_msgs_ids",
+                "The number of message ids received in IDONTWANT messages",
+                metric.clone(),
+            );
+            metric
+        };
+        let idontwant_msgs_sent = {
+            let metric = Counter::default();
+            registry.register(
+                "idontwant_msgs_sent",
+                "The number of IDONTWANT messages sent",
+                metric.clone(),
+            );
+            metric
+        };
+        let topic_idontwant_skipped_msgs = register_family!(
+            "topic_idontwant_skipped_msgs",
+            "Number of gossip messages not forwarded to peers due to IDONTWANT for each topic"
+        );
+        let topic_idontwant_saved_bytes = register_family!(
+            "topic_idontwant_saved_bytes",
+            "Bytes of gossip messages not forwarded to peers due to IDONTWANT for each topic"
+        );
+
         let priority_queue_size = Histogram::new(linear_buckets(0.0, 25.0, 100));
         registry.register(
             "priority_queue_size",
@@ -362,6 +409,11 @@ impl Metrics {
             heartbeat_duration,
             memcache_misses,
             topic_iwant_msgs,
+            idontwant_msgs,
+            idontwant_msgs_ids,
+            idontwant_msgs_sent,
+            topic_idontwant_skipped_msgs,
+            topic_idontwant_saved_bytes,
             priority_queue_size,
             non_priority_queue_size,
         }
@@ -560,6 +612,27 @@ impl Metrics {
         }
     }
 
+    /// Register receiving an IDONTWANT msg containing `ids` message ids.
+    pub(crate) fn register_idontwant(&mut self, ids: usize) {
+        self.idontwant_msgs.inc();
+        self.idontwant_msgs_ids.inc_by(ids as u64);
+    }
+
+    /// Register sending `count` IDONTWANT msgs.
+    pub(crate) fn register_idontwant_sent(&mut self, count: usize) {
+        self.idontwant_msgs_sent.inc_by(count as u64);
+    }
+
+    /// Register not forwarding a message of `bytes` size over a topic due to an IDONTWANT.
+    pub(crate) fn idontwant_msg_skipped(&mut self, topic: &TopicHash, bytes: usize) {
+        if self.register_topic(topic).is_ok() {
+            self.topic_idontwant_skipped_msgs.get_or_create(topic).inc();
+            self.topic_idontwant_saved_bytes
+                .get_or_create(topic)
+                .inc_by(bytes as u64);
+        }
+    }
+
     /// Observes a heartbeat duration.
     pub(crate) fn observe_heartbeat_duration(&mut self, millis: u64) {
         self.heartbeat_duration.observe(millis as f64);
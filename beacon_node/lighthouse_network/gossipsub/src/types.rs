@@ -25,7 +25,8 @@ use async_channel::{Receiver, Sender};
 use futures::stream::Peekable;
 use futures::{Future, Stream, StreamExt};
 use futures_timer::Delay;
-use instant::Duration;
+use hashlink::LinkedHashMap;
+use instant::{Duration, Instant};
 use libp2p::identity::PeerId;
 use libp2p::swarm::ConnectionId;
 use prometheus_client::encoding::EncodeLabelValue;
@@ -54,6 +55,15 @@ pub struct FailedMessages {
     pub non_priority: usize,
 }
 
+/// The number of message bytes exchanged with a peer on a topic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MessageBandwidth {
+    /// Bytes of messages received from the peer, including duplicates.
+    pub inbound: u64,
+    /// Bytes of messages published or forwarded to the peer.
+    pub outbound: u64,
+}
+
 impl FailedMessages {
     /// The total number of messages that expired due a timeout.
     pub fn total_timeout(&self) -> usize {
@@ -121,11 +131,15 @@ pub(crate) struct PeerConnections {
     pub(crate) sender: RpcSender,
     /// Subscribed topics.
     pub(crate) topics: BTreeSet<TopicHash>,
+    /// Don't send messages, received via IDONTWANT, along with the time they were received.
+    pub(crate) dont_send: LinkedHashMap<MessageId, Instant>,
 }
 
 /// Describes the types of peers that can exist in the gossipsub context.
 #[derive(Debug, Clone, PartialEq, Hash, EncodeLabelValue, Eq)]
 pub enum PeerKind {
+    /// A gossipsub 1.2 peer.
+    Gossipsubv1_2,
     /// A gossipsub 1.1 peer.
     Gossipsubv1_1,
     /// A gossipsub 1.0 peer.
@@ -257,6 +271,8 @@ pub enum ControlAction {
     Graft(Graft),
     /// The node has been removed from the mesh - Prune control message.
     Prune(Prune),
+    /// The node requests us to not forward message ids (peer_id + sequence _number) - IDontWant control message.
+    IDontWant(IDontWant),
 }
 
 /// Node broadcasts known messages per topic - IHave control message.
@@ -293,6 +309,13 @@ pub struct Prune {
     pub(crate) backoff: Option<u64>,
 }
 
+/// The node requests us to not forward message ids - IDontWant control message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IDontWant {
+    /// A list of known message ids.
+    pub(crate) message_ids: Vec<MessageId>,
+}
+
 /// A Gossipsub RPC message sent.
 #[derive(Debug)]
 pub enum RpcOut {
@@ -314,6 +337,8 @@ pub enum RpcOut {
     IHave(IHave),
     /// Send a IWant control message.
     IWant(IWant),
+    /// Send a IDontWant control message.
+    IDontWant(IDontWant),
 }
 
 impl RpcOut {
@@ -374,6 +399,7 @@ impl From<RpcOut> for proto::RPC {
                     iwant: vec![],
                     graft: vec![],
                     prune: vec![],
+                    idontwant: vec![],
                 }),
             },
             RpcOut::IWant(IWant { message_ids }) => proto::RPC {
@@ -386,6 +412,7 @@ impl From<RpcOut> for proto::RPC {
                     }],
                     graft: vec![],
                     prune: vec![],
+                    idontwant: vec![],
                 }),
             },
             RpcOut::Graft(Graft { topic_hash }) => proto::RPC {
@@ -398,6 +425,7 @@ impl From<RpcOut> for proto::RPC {
                         topic_id: Some(topic_hash.into_string()),
                     }],
                     prune: vec![],
+                    idontwant: vec![],
                 }),
             },
             RpcOut::Prune(Prune {
@@ -424,9 +452,23 @@ impl From<RpcOut> for proto::RPC {
                                 .collect(),
                             backoff,
                         }],
+                        idontwant: vec![],
                     }),
                 }
             }
+            RpcOut::IDontWant(IDontWant { message_ids }) => proto::RPC {
+                publish: Vec::new(),
+                subscriptions: Vec::new(),
+                control: Some(proto::ControlMessage {
+                    ihave: vec![],
+                    iwant: vec![],
+                    graft: vec![],
+                    prune: vec![],
+                    idontwant: vec![proto::ControlIDontWant {
+                        message_ids: message_ids.into_iter().map(|msg_id| msg_id.0).collect(),
+                    }],
+                }),
+            },
         }
     }
 }
@@ -485,6 +527,7 @@ impl From<Rpc> for proto::RPC {
             iwant: Vec::new(),
             graft: Vec::new(),
             prune: Vec::new(),
+            idontwant: Vec::new(),
         };
 
         let empty_control_msg = rpc.control_msgs.is_empty();
@@ -533,6 +576,12 @@ impl From<Rpc> for proto::RPC {
                     };
                     control.prune.push(rpc_prune);
                 }
+                ControlAction::IDontWant(IDontWant { message_ids }) => {
+                    let rpc_idontwant = proto::ControlIDontWant {
+                        message_ids: message_ids.into_iter().map(|msg_id| msg_id.0).collect(),
+                    };
+                    control.idontwant.push(rpc_idontwant);
+                }
             }
         }
 
@@ -571,6 +620,7 @@ impl PeerKind {
             Self::Floodsub => "Floodsub",
             Self::Gossipsub => "Gossipsub v1.0",
             Self::Gossipsubv1_1 => "Gossipsub v1.1",
+            Self::Gossipsubv1_2 => "Gossipsub v1.2",
         }
     }
 }
@@ -657,6 +707,15 @@ impl RpcSender {
             .map_err(|err| err.into_inner())
     }
 
+    /// Send a `RpcOut::IDontWant` message to the `RpcReceiver`
+    /// this is low priority, if the queue is full an Err is returned.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn idontwant(&mut self, idontwant: IDontWant) -> Result<(), RpcOut> {
+        self.non_priority_sender
+            .try_send(RpcOut::IDontWant(idontwant))
+            .map_err(|err| err.into_inner())
+    }
+
     /// Send a `RpcOut::Subscribe` message to the `RpcReceiver`
     /// this is high priority.
     pub(crate) fn subscribe(&mut self, topic: TopicHash) {
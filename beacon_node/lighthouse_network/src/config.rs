@@ -115,6 +115,12 @@ pub struct Config {
     /// lower the value the less bandwidth used, but the slower messages will be received.
     pub network_load: u8,
 
+    /// The maximum number of bytes per second uploaded by the p2p stack, if limited.
+    pub upload_bandwidth_limit: Option<u64>,
+
+    /// The maximum number of bytes per second downloaded by the p2p stack, if limited.
+    pub download_bandwidth_limit: Option<u64>,
+
     /// Indicates if the user has set the network to be in private mode. Currently this
     /// prevents sending client identifying information over identify.
     pub private: bool,
@@ -140,6 +146,14 @@ pub struct Config {
     /// Configures if/where invalid blocks should be stored.
     pub invalid_block_storage: Option<PathBuf>,
 
+    /// A beacon node HTTP API to download historical blocks from when no synced peer is able to
+    /// serve them during backfill sync.
+    pub backfill_archive_url: Option<String>,
+
+    /// A directory of era files to read historical blocks from when no synced peer is able to
+    /// serve them during backfill sync.
+    pub backfill_era_dir: Option<PathBuf>,
+
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
 }
@@ -348,6 +362,8 @@ impl Default for Config {
             disable_quic_support: false,
             upnp_enabled: true,
             network_load: 4,
+            upload_bandwidth_limit: None,
+            download_bandwidth_limit: None,
             private: false,
             subscribe_all_subnets: false,
             import_all_attestations: false,
@@ -358,6 +374,8 @@ impl Default for Config {
             enable_light_client_server: false,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
+            backfill_archive_url: None,
+            backfill_era_dir: None,
             inbound_rate_limiter_config: None,
         }
     }
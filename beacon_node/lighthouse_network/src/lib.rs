@@ -105,8 +105,8 @@ impl<'a> std::fmt::Display for ClearDialError<'a> {
 }
 
 pub use crate::types::{
-    error, Enr, EnrSyncCommitteeBitfield, GossipTopic, NetworkGlobals, PubsubMessage, Subnet,
-    SubnetDiscovery,
+    error, BandwidthCount, BandwidthSummary, BandwidthTracker, Enr, EnrSyncCommitteeBitfield,
+    GossipTopic, NetworkGlobals, PubsubMessage, Subnet, SubnetDiscovery,
 };
 
 pub use prometheus_client;
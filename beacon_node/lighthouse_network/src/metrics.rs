@@ -80,6 +80,22 @@ lazy_static! {
         "RPC requests total",
         &["type"]
     );
+    pub static ref RPC_BANDWIDTH_BYTES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_rpc_bytes_total",
+        "Bytes exchanged on RPC substreams, per protocol",
+        &["protocol", "direction"]
+    );
+    pub static ref GOSSIP_BANDWIDTH_BYTES_PER_TOPIC_KIND: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "gossipsub_bytes_per_topic_kind_total",
+            "Bytes of gossipsub messages exchanged with peers, per topic kind",
+            &["topic_kind", "direction"]
+        );
+    pub static ref BANDWIDTH_THROTTLED_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_bandwidth_throttled_total",
+        "Messages and requests not served because the upload bandwidth cap was reached",
+        &["kind"]
+    );
     pub static ref PEER_ACTION_EVENTS_PER_CLIENT: Result<IntCounterVec> =
         try_create_int_counter_vec(
             "libp2p_peer_actions_per_client",
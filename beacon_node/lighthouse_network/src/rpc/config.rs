@@ -1,12 +1,13 @@
 use std::{
     fmt::{Debug, Display},
+    net::IpAddr,
     str::FromStr,
     time::Duration,
 };
 
 use super::{rate_limiter::Quota, Protocol};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Auxiliary struct to aid on configuration parsing.
 ///
@@ -48,6 +49,9 @@ impl FromStr for ProtocolQuota {
         let seconds = time_str
             .parse::<u64>()
             .map_err(|_| "Failed to parse time in seconds from quota.")?;
+        if tokens == 0 || seconds == 0 {
+            return Err("Quota tokens and time in seconds must be positive.");
+        }
         Ok(ProtocolQuota {
             protocol,
             quota: Quota {
@@ -61,8 +65,94 @@ impl FromStr for ProtocolQuota {
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
 pub struct OutboundRateLimiterConfig(pub RateLimiterConfig);
 
+/// Configuration of the inbound rate limiter, with separate quotas for each class of peer.
+///
+/// A peer is trusted if it is one of the configured trusted peers, otherwise it belongs to the
+/// IP range class if it is connected from an address within `ip_ranges`. Classes without quotas
+/// of their own use the `default` quotas.
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
-pub struct InboundRateLimiterConfig(pub RateLimiterConfig);
+pub struct InboundRateLimiterConfig {
+    /// Quotas for peers that do not belong to any other class.
+    pub default: RateLimiterConfig,
+    /// Quotas for trusted peers.
+    pub trusted: Option<RateLimiterConfig>,
+    /// Quotas for peers connected from an address within `ip_ranges`.
+    pub ip_range: Option<RateLimiterConfig>,
+    /// The address ranges of the IP range class.
+    pub ip_ranges: Vec<IpRange>,
+}
+
+/// A range of IP addresses in CIDR notation, e.g. `10.0.0.0/8`. A single address is parsed as
+/// a range containing only that address.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IpRange {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpRange {
+    /// Returns `true` if `address` is within the range.
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match (self.address, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let diff = u32::from(network) ^ u32::from(*address);
+                diff.checked_shr(32 - self.prefix_len as u32).unwrap_or(0) == 0
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let diff = u128::from(network) ^ u128::from(*address);
+                diff.checked_shr(128 - self.prefix_len as u32).unwrap_or(0) == 0
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address_str, prefix_len_str) = match s.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s, None),
+        };
+        let address: IpAddr = address_str
+            .parse()
+            .map_err(|_| "Failed to parse the address of an IP range.")?;
+        let max_prefix_len = if address.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len_str {
+            Some(prefix_len) => prefix_len
+                .parse()
+                .map_err(|_| "Failed to parse the prefix length of an IP range.")?,
+            None => max_prefix_len,
+        };
+        if prefix_len > max_prefix_len {
+            return Err("IP range prefix length is too long.");
+        }
+        Ok(IpRange {
+            address,
+            prefix_len,
+        })
+    }
+}
+
+impl Serialize for IpRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
 
 impl FromStr for OutboundRateLimiterConfig {
     type Err = &'static str;
@@ -72,11 +162,15 @@ impl FromStr for OutboundRateLimiterConfig {
     }
 }
 
+/// Parses the quotas of the `default` class. Other classes are left unconfigured.
 impl FromStr for InboundRateLimiterConfig {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        RateLimiterConfig::from_str(s).map(Self)
+        Ok(InboundRateLimiterConfig {
+            default: RateLimiterConfig::from_str(s)?,
+            ..Default::default()
+        })
     }
 }
 
@@ -147,6 +241,37 @@ impl Debug for RateLimiterConfig {
     }
 }
 
+/// Formats the quotas of all protocols as accepted by [`RateLimiterConfig::from_str`].
+impl Display for RateLimiterConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let quotas = [
+            (Protocol::Ping, &self.ping_quota),
+            (Protocol::MetaData, &self.meta_data_quota),
+            (Protocol::Status, &self.status_quota),
+            (Protocol::Goodbye, &self.goodbye_quota),
+            (Protocol::BlocksByRange, &self.blocks_by_range_quota),
+            (Protocol::BlocksByRoot, &self.blocks_by_root_quota),
+            (Protocol::BlobsByRange, &self.blobs_by_range_quota),
+            (Protocol::BlobsByRoot, &self.blobs_by_root_quota),
+            (
+                Protocol::LightClientBootstrap,
+                &self.light_client_bootstrap_quota,
+            ),
+        ];
+        for (i, (protocol, quota)) in quotas.into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ";")?;
+            }
+            let protocol_quota = ProtocolQuota {
+                protocol,
+                quota: quota.clone(),
+            };
+            write!(f, "{}", protocol_quota)?;
+        }
+        Ok(())
+    }
+}
+
 /// Parse configurations for the outbound rate limiter. Protocols that are not specified use
 /// the default values. Protocol specified more than once use only the first given Quota.
 ///
@@ -215,4 +340,34 @@ mod tests {
         };
         assert_eq!(quota.to_string().parse(), Ok(quota))
     }
+
+    #[test]
+    fn test_rate_limiter_config_inverse() {
+        let config: RateLimiterConfig = "ping:4/10;beacon_blocks_by_range:16/2".parse().unwrap();
+        assert_eq!(config.to_string().parse(), Ok(config));
+    }
+
+    #[test]
+    fn test_zero_quota_rejected() {
+        assert!("ping:0/10".parse::<ProtocolQuota>().is_err());
+        assert!("ping:1/0".parse::<ProtocolQuota>().is_err());
+    }
+
+    #[test]
+    fn test_ip_range_contains() {
+        let range: IpRange = "10.1.0.0/16".parse().unwrap();
+        assert!(range.contains(&"10.1.200.3".parse().unwrap()));
+        assert!(!range.contains(&"10.2.0.1".parse().unwrap()));
+        assert!(!range.contains(&"::1".parse().unwrap()));
+
+        let host: IpRange = "192.168.0.1".parse().unwrap();
+        assert_eq!(host.to_string(), "192.168.0.1/32");
+        assert!(host.contains(&"192.168.0.1".parse().unwrap()));
+        assert!(!host.contains(&"192.168.0.2".parse().unwrap()));
+
+        let all: IpRange = "::/0".parse().unwrap();
+        assert!(all.contains(&"2001:db8::1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+    }
 }
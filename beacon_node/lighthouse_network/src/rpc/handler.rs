@@ -807,12 +807,15 @@ where
             self.dial_negotiated += 1;
             let (id, req) = self.dial_queue.remove(0);
             self.dial_queue.shrink_to_fit();
+            let listen_protocol = self.listen_protocol.upgrade();
             return Poll::Ready(ConnectionHandlerEvent::OutboundSubstreamRequest {
                 protocol: SubstreamProtocol::new(
                     OutboundRequestContainer {
                         req: req.clone(),
                         fork_context: self.fork_context.clone(),
-                        max_rpc_size: self.listen_protocol().upgrade().max_rpc_size,
+                        max_rpc_size: listen_protocol.max_rpc_size,
+                        peer_id: listen_protocol.peer_id,
+                        bandwidth: listen_protocol.bandwidth.clone(),
                     },
                     (),
                 )
@@ -0,0 +1,112 @@
+//! A substream wrapper that attributes the bytes read and written to a peer and RPC protocol.
+
+use super::protocol::Protocol;
+use crate::types::{BandwidthCount, BandwidthTracker};
+use futures::prelude::{AsyncRead, AsyncWrite};
+use libp2p::PeerId;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Bytes accumulated locally before they are reported to the shared `BandwidthTracker`.
+///
+/// Keeps lock contention low for large responses (e.g. `BlocksByRange`) which are read and
+/// written in many small chunks.
+const REPORT_THRESHOLD: u64 = 64 * 1024;
+
+/// Counts the bytes read from and written to an RPC substream.
+///
+/// Pending counts are reported when they exceed `REPORT_THRESHOLD`, when the substream is
+/// closed and when the socket is dropped.
+pub struct MeteredSocket<TSocket> {
+    socket: TSocket,
+    peer_id: PeerId,
+    protocol: Protocol,
+    tracker: Arc<BandwidthTracker>,
+    pending: BandwidthCount,
+}
+
+impl<TSocket> MeteredSocket<TSocket> {
+    pub fn new(
+        socket: TSocket,
+        peer_id: PeerId,
+        protocol: Protocol,
+        tracker: Arc<BandwidthTracker>,
+    ) -> Self {
+        MeteredSocket {
+            socket,
+            peer_id,
+            protocol,
+            tracker,
+            pending: BandwidthCount::default(),
+        }
+    }
+
+    fn report(&mut self) {
+        if !self.pending.is_empty() {
+            self.tracker.record_rpc(
+                &self.peer_id,
+                self.protocol,
+                std::mem::take(&mut self.pending),
+            );
+        }
+    }
+
+    fn maybe_report(&mut self) {
+        if self.pending.inbound + self.pending.outbound >= REPORT_THRESHOLD {
+            self.report();
+        }
+    }
+}
+
+impl<TSocket> Drop for MeteredSocket<TSocket> {
+    fn drop(&mut self) {
+        self.report();
+    }
+}
+
+impl<TSocket: AsyncRead + Unpin> AsyncRead for MeteredSocket<TSocket> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let poll = Pin::new(&mut this.socket).poll_read(cx, buf);
+        if let Poll::Ready(Ok(read)) = poll {
+            this.pending.inbound += read as u64;
+            this.maybe_report();
+        }
+        poll
+    }
+}
+
+impl<TSocket: AsyncWrite + Unpin> AsyncWrite for MeteredSocket<TSocket> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        let poll = Pin::new(&mut this.socket).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = poll {
+            this.pending.outbound += written as u64;
+            this.maybe_report();
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.socket).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        let poll = Pin::new(&mut this.socket).poll_close(cx);
+        if poll.is_ready() {
+            this.report();
+        }
+        poll
+    }
+}
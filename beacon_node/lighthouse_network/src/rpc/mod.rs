@@ -10,10 +10,11 @@ use libp2p::swarm::{
     handler::ConnectionHandler, CloseConnection, ConnectionId, NetworkBehaviour, NotifyHandler,
     ToSwarm,
 };
-use libp2p::swarm::{FromSwarm, SubstreamProtocol, THandlerInEvent};
+use libp2p::swarm::{ConnectionClosed, FromSwarm, SubstreamProtocol, THandlerInEvent};
 use libp2p::PeerId;
-use rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr};
+use rate_limiter::{InboundRateLimiter, RateLimitedErr};
 use slog::{crit, debug, o};
+use std::collections::HashSet;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -35,10 +36,14 @@ pub use protocol::{max_rpc_size, Protocol, RPCError};
 use self::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
 use self::protocol::RPCProtocol;
 use self::self_limiter::SelfRateLimiter;
+use crate::metrics;
+use crate::service::bandwidth_limiter::BandwidthLimiter;
+use crate::types::BandwidthTracker;
 
 pub(crate) mod codec;
 pub mod config;
 mod handler;
+mod metered_socket;
 pub mod methods;
 mod outbound;
 mod protocol;
@@ -118,7 +123,7 @@ pub struct NetworkParams {
 /// logic.
 pub struct RPC<Id: ReqId, TSpec: EthSpec> {
     /// Rate limiter
-    limiter: Option<RateLimiter>,
+    limiter: Option<InboundRateLimiter>,
     /// Rate limiter for our own requests.
     self_limiter: Option<SelfRateLimiter<Id, TSpec>>,
     /// Queue of events to be processed.
@@ -129,6 +134,10 @@ pub struct RPC<Id: ReqId, TSpec: EthSpec> {
     log: slog::Logger,
     /// Networking constant values
     network_params: NetworkParams,
+    /// Accounts the bytes exchanged on RPC substreams.
+    bandwidth: Arc<BandwidthTracker>,
+    /// Upload caps used to throttle the serving of range requests.
+    bandwidth_limiter: Option<Arc<BandwidthLimiter>>,
 }
 
 impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
@@ -137,14 +146,17 @@ impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
         enable_light_client_server: bool,
         inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
         outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
+        trusted_peers: HashSet<PeerId>,
         log: slog::Logger,
         network_params: NetworkParams,
+        bandwidth: Arc<BandwidthTracker>,
+        bandwidth_limiter: Option<Arc<BandwidthLimiter>>,
     ) -> Self {
         let log = log.new(o!("service" => "libp2p_rpc"));
 
         let inbound_limiter = inbound_rate_limiter_config.map(|config| {
             debug!(log, "Using inbound rate limiting params"; "config" => ?config);
-            RateLimiter::new_with_config(config.0)
+            InboundRateLimiter::new(config, trusted_peers)
                 .expect("Inbound limiter configuration parameters are valid")
         });
 
@@ -160,6 +172,8 @@ impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
             enable_light_client_server,
             log,
             network_params,
+            bandwidth,
+            bandwidth_limiter,
         }
     }
 
@@ -202,6 +216,21 @@ impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
         self.events.push(event);
     }
 
+    /// Replaces the quotas of the inbound rate limiter.
+    ///
+    /// Fails if the configuration is invalid or if inbound rate limiting is disabled.
+    pub fn update_inbound_rate_limiter_config(
+        &mut self,
+        config: InboundRateLimiterConfig,
+    ) -> Result<(), &'static str> {
+        let limiter = self
+            .limiter
+            .as_mut()
+            .ok_or("Inbound rate limiting is disabled")?;
+        debug!(self.log, "Updating inbound rate limiting params"; "config" => ?config);
+        limiter.update_config(config)
+    }
+
     /// Lighthouse wishes to disconnect from this peer by sending a Goodbye message. This
     /// gracefully terminates the RPC behaviour with a goodbye message.
     pub fn shutdown(&mut self, peer_id: PeerId, id: Id, reason: GoodbyeReason) {
@@ -226,8 +255,11 @@ where
         connection_id: ConnectionId,
         peer_id: PeerId,
         _local_addr: &libp2p::Multiaddr,
-        _remote_addr: &libp2p::Multiaddr,
+        remote_addr: &libp2p::Multiaddr,
     ) -> Result<libp2p::swarm::THandler<Self>, libp2p::swarm::ConnectionDenied> {
+        if let Some(limiter) = self.limiter.as_mut() {
+            limiter.connection_established(peer_id, remote_addr);
+        }
         let protocol = SubstreamProtocol::new(
             RPCProtocol {
                 fork_context: self.fork_context.clone(),
@@ -235,6 +267,8 @@ where
                 enable_light_client_server: self.enable_light_client_server,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
+                peer_id,
+                bandwidth: self.bandwidth.clone(),
             },
             (),
         );
@@ -255,9 +289,12 @@ where
         &mut self,
         connection_id: ConnectionId,
         peer_id: PeerId,
-        _addr: &libp2p::Multiaddr,
+        addr: &libp2p::Multiaddr,
         _role_override: libp2p::core::Endpoint,
     ) -> Result<libp2p::swarm::THandler<Self>, libp2p::swarm::ConnectionDenied> {
+        if let Some(limiter) = self.limiter.as_mut() {
+            limiter.connection_established(peer_id, addr);
+        }
         let protocol = SubstreamProtocol::new(
             RPCProtocol {
                 fork_context: self.fork_context.clone(),
@@ -265,6 +302,8 @@ where
                 enable_light_client_server: self.enable_light_client_server,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
+                peer_id,
+                bandwidth: self.bandwidth.clone(),
             },
             (),
         );
@@ -283,9 +322,19 @@ where
         Ok(handler)
     }
 
-    fn on_swarm_event(&mut self, _event: FromSwarm) {
+    fn on_swarm_event(&mut self, event: FromSwarm) {
         // NOTE: FromSwarm is a non exhaustive enum so updates should be based on release notes more
         // than compiler feedback
+        if let FromSwarm::ConnectionClosed(ConnectionClosed {
+            peer_id,
+            remaining_established: 0,
+            ..
+        }) = event
+        {
+            if let Some(limiter) = self.limiter.as_mut() {
+                limiter.peer_disconnected(&peer_id);
+            }
+        }
     }
 
     fn on_connection_handler_event(
@@ -296,6 +345,32 @@ where
     ) {
         match event {
             HandlerEvent::Ok(RPCReceived::Request(ref id, ref req)) => {
+                // Serving range requests is throttled before our own traffic when the upload cap
+                // is reached.
+                let protocol = req.versioned_protocol().protocol();
+                if matches!(protocol, Protocol::BlocksByRange | Protocol::BlobsByRange)
+                    && self
+                        .bandwidth_limiter
+                        .as_ref()
+                        .is_some_and(|limiter| !limiter.has_upload_headroom())
+                {
+                    debug!(self.log, "Upload bandwidth exhausted, throttling request";
+                        "request" => %req, "peer_id" => %peer_id);
+                    metrics::inc_counter_vec(
+                        &metrics::BANDWIDTH_THROTTLED_TOTAL,
+                        &[protocol.as_ref()],
+                    );
+                    self.send_response(
+                        peer_id,
+                        (conn_id, *id),
+                        RPCCodedResponse::Error(
+                            RPCResponseErrorCode::RateLimited,
+                            "Rate limited. Upload bandwidth exhausted".into(),
+                        ),
+                    );
+                    return;
+                }
+
                 if let Some(limiter) = self.limiter.as_mut() {
                     // check if the request is conformant to the quota
                     match limiter.allows(&peer_id, req) {
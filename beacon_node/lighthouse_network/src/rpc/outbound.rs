@@ -1,3 +1,4 @@
+use super::metered_socket::MeteredSocket;
 use super::methods::*;
 use super::protocol::ProtocolId;
 use super::protocol::SupportedProtocol;
@@ -6,10 +7,12 @@ use crate::rpc::codec::{
     base::BaseOutboundCodec, ssz_snappy::SSZSnappyOutboundCodec, OutboundCodec,
 };
 use crate::rpc::protocol::Encoding;
+use crate::types::BandwidthTracker;
 use futures::future::BoxFuture;
 use futures::prelude::{AsyncRead, AsyncWrite};
 use futures::{FutureExt, SinkExt};
 use libp2p::core::{OutboundUpgrade, UpgradeInfo};
+use libp2p::PeerId;
 use std::sync::Arc;
 use tokio_util::{
     codec::Framed,
@@ -26,6 +29,8 @@ pub struct OutboundRequestContainer<TSpec: EthSpec> {
     pub req: OutboundRequest<TSpec>,
     pub fork_context: Arc<ForkContext>,
     pub max_rpc_size: usize,
+    pub peer_id: PeerId,
+    pub bandwidth: Arc<BandwidthTracker>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -150,7 +155,8 @@ impl<TSpec: EthSpec> OutboundRequest<TSpec> {
 
 /* Outbound upgrades */
 
-pub type OutboundFramed<TSocket, TSpec> = Framed<Compat<TSocket>, OutboundCodec<TSpec>>;
+pub type OutboundFramed<TSocket, TSpec> =
+    Framed<Compat<MeteredSocket<TSocket>>, OutboundCodec<TSpec>>;
 
 impl<TSocket, TSpec> OutboundUpgrade<TSocket> for OutboundRequestContainer<TSpec>
 where
@@ -162,8 +168,14 @@ where
     type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
 
     fn upgrade_outbound(self, socket: TSocket, protocol: Self::Info) -> Self::Future {
-        // convert to a tokio compatible socket
-        let socket = socket.compat();
+        // account the bytes of the substream and convert it to a tokio compatible socket
+        let socket = MeteredSocket::new(
+            socket,
+            self.peer_id,
+            protocol.versioned_protocol.protocol(),
+            self.bandwidth,
+        )
+        .compat();
         let codec = match protocol.encoding {
             Encoding::SSZSnappy => {
                 let ssz_snappy_codec = BaseOutboundCodec::new(SSZSnappyOutboundCodec::new(
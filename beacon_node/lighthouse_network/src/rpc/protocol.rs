@@ -1,9 +1,12 @@
+use super::metered_socket::MeteredSocket;
 use super::methods::*;
 use crate::rpc::codec::{base::BaseInboundCodec, ssz_snappy::SSZSnappyInboundCodec, InboundCodec};
+use crate::types::BandwidthTracker;
 use futures::future::BoxFuture;
 use futures::prelude::{AsyncRead, AsyncWrite};
 use futures::{FutureExt, StreamExt};
 use libp2p::core::{InboundUpgrade, UpgradeInfo};
+use libp2p::PeerId;
 use ssz::Encode;
 use ssz_types::VariableList;
 use std::io;
@@ -286,6 +289,10 @@ pub struct RPCProtocol<TSpec: EthSpec> {
     pub enable_light_client_server: bool,
     pub phantom: PhantomData<TSpec>,
     pub ttfb_timeout: Duration,
+    /// The peer on the other end of the connection.
+    pub peer_id: PeerId,
+    /// Accounts the bytes exchanged on the negotiated substreams.
+    pub bandwidth: Arc<BandwidthTracker>,
 }
 
 impl<TSpec: EthSpec> UpgradeInfo for RPCProtocol<TSpec> {
@@ -458,7 +465,7 @@ pub fn rpc_blob_limits<T: EthSpec>() -> RpcLimits {
 
 pub type InboundOutput<TSocket, TSpec> = (InboundRequest<TSpec>, InboundFramed<TSocket, TSpec>);
 pub type InboundFramed<TSocket, TSpec> =
-    Framed<std::pin::Pin<Box<TimeoutStream<Compat<TSocket>>>>, InboundCodec<TSpec>>;
+    Framed<std::pin::Pin<Box<TimeoutStream<Compat<MeteredSocket<TSocket>>>>>, InboundCodec<TSpec>>;
 
 impl<TSocket, TSpec> InboundUpgrade<TSocket> for RPCProtocol<TSpec>
 where
@@ -472,8 +479,14 @@ where
     fn upgrade_inbound(self, socket: TSocket, protocol: ProtocolId) -> Self::Future {
         async move {
             let versioned_protocol = protocol.versioned_protocol;
-            // convert the socket to tokio compatible socket
-            let socket = socket.compat();
+            // account the bytes of the substream and convert it to a tokio compatible socket
+            let socket = MeteredSocket::new(
+                socket,
+                self.peer_id,
+                versioned_protocol.protocol(),
+                self.bandwidth.clone(),
+            )
+            .compat();
             let codec = match protocol.encoding {
                 Encoding::SSZSnappy => {
                     let ssz_snappy_codec = BaseInboundCodec::new(SSZSnappyInboundCodec::new(
@@ -1,10 +1,14 @@
-use super::config::RateLimiterConfig;
+use super::config::{InboundRateLimiterConfig, IpRange, RateLimiterConfig};
 use crate::rpc::Protocol;
 use fnv::FnvHashMap;
-use libp2p::PeerId;
+use futures::FutureExt;
+use libp2p::multiaddr::Protocol as MultiaddrProtocol;
+use libp2p::{Multiaddr, PeerId};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::hash::Hash;
+use std::net::IpAddr;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
@@ -312,6 +316,122 @@ impl Future for RPCRateLimiter {
     }
 }
 
+/// The classes of peers that may be given quotas of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PeerClass {
+    Trusted,
+    IpRange,
+    Default,
+}
+
+/// Rate limits inbound requests, applying the quotas of the class each peer belongs to.
+pub struct InboundRateLimiter {
+    /// Limiter for peers that don't belong to another class.
+    default_rl: RPCRateLimiter,
+    /// Limiter for trusted peers, if they have their own quotas.
+    trusted_rl: Option<RPCRateLimiter>,
+    /// Limiter for peers connected from `ip_ranges`, if they have their own quotas.
+    ip_range_rl: Option<RPCRateLimiter>,
+    /// The address ranges of the IP range class.
+    ip_ranges: Vec<IpRange>,
+    /// Peers in the trusted class.
+    trusted_peers: HashSet<PeerId>,
+    /// The address of the last connection established by each connected peer.
+    peer_addresses: HashMap<PeerId, IpAddr>,
+}
+
+impl InboundRateLimiter {
+    pub fn new(
+        config: InboundRateLimiterConfig,
+        trusted_peers: HashSet<PeerId>,
+    ) -> Result<Self, &'static str> {
+        let InboundRateLimiterConfig {
+            default,
+            trusted,
+            ip_range,
+            ip_ranges,
+        } = config;
+
+        Ok(InboundRateLimiter {
+            default_rl: RPCRateLimiter::new_with_config(default)?,
+            trusted_rl: trusted.map(RPCRateLimiter::new_with_config).transpose()?,
+            ip_range_rl: ip_range.map(RPCRateLimiter::new_with_config).transpose()?,
+            ip_ranges,
+            trusted_peers,
+            peer_addresses: HashMap::new(),
+        })
+    }
+
+    /// Replaces the quotas of all classes. The request history of all peers is reset.
+    pub fn update_config(&mut self, config: InboundRateLimiterConfig) -> Result<(), &'static str> {
+        let mut limiter = Self::new(config, std::mem::take(&mut self.trusted_peers))?;
+        limiter.peer_addresses = std::mem::take(&mut self.peer_addresses);
+        *self = limiter;
+        Ok(())
+    }
+
+    /// Records the remote address of a new connection to `peer_id`.
+    pub fn connection_established(&mut self, peer_id: PeerId, address: &Multiaddr) {
+        let ip = address.iter().find_map(|protocol| match protocol {
+            MultiaddrProtocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+            MultiaddrProtocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+            _ => None,
+        });
+        if let Some(ip) = ip {
+            self.peer_addresses.insert(peer_id, ip);
+        }
+    }
+
+    /// Forgets the address of a peer once all its connections are closed.
+    pub fn peer_disconnected(&mut self, peer_id: &PeerId) {
+        self.peer_addresses.remove(peer_id);
+    }
+
+    fn peer_class(&self, peer_id: &PeerId) -> PeerClass {
+        if self.trusted_peers.contains(peer_id) {
+            PeerClass::Trusted
+        } else if self
+            .peer_addresses
+            .get(peer_id)
+            .is_some_and(|ip| self.ip_ranges.iter().any(|range| range.contains(ip)))
+        {
+            PeerClass::IpRange
+        } else {
+            PeerClass::Default
+        }
+    }
+
+    pub fn allows<Item: RateLimiterItem>(
+        &mut self,
+        peer_id: &PeerId,
+        request: &Item,
+    ) -> Result<(), RateLimitedErr> {
+        let limiter = match self.peer_class(peer_id) {
+            PeerClass::Trusted => self.trusted_rl.as_mut(),
+            PeerClass::IpRange => self.ip_range_rl.as_mut(),
+            PeerClass::Default => None,
+        }
+        .unwrap_or(&mut self.default_rl);
+        limiter.allows(peer_id, request)
+    }
+}
+
+impl Future for InboundRateLimiter {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let _ = self.default_rl.poll_unpin(cx);
+        if let Some(limiter) = self.trusted_rl.as_mut() {
+            let _ = limiter.poll_unpin(cx);
+        }
+        if let Some(limiter) = self.ip_range_rl.as_mut() {
+            let _ = limiter.poll_unpin(cx);
+        }
+
+        Poll::Pending
+    }
+}
+
 /// Per key rate limiter using the token bucket / leaky bucket as a meter rate limiting algorithm,
 /// with the GCRA implementation.
 pub struct Limiter<Key: Hash + Eq + Clone> {
@@ -392,7 +512,10 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::rate_limiter::{Limiter, Quota};
+    use crate::rpc::config::InboundRateLimiterConfig;
+    use crate::rpc::rate_limiter::{InboundRateLimiter, Limiter, PeerClass, Quota};
+    use libp2p::PeerId;
+    use std::collections::HashSet;
     use std::time::Duration;
 
     #[test]
@@ -460,4 +583,36 @@ mod tests {
             .allows(Duration::from_secs_f32(0.4), &key, 1)
             .is_err());
     }
+
+    #[tokio::test]
+    async fn peers_are_classified() {
+        let trusted_peer = PeerId::random();
+        let local_peer = PeerId::random();
+        let remote_peer = PeerId::random();
+        let config = InboundRateLimiterConfig {
+            ip_ranges: vec!["10.0.0.0/8".parse().unwrap()],
+            ..Default::default()
+        };
+        let mut limiter = InboundRateLimiter::new(config, HashSet::from([trusted_peer])).unwrap();
+
+        limiter.connection_established(trusted_peer, &"/ip4/1.2.3.4/tcp/9000".parse().unwrap());
+        limiter.connection_established(local_peer, &"/ip4/10.1.2.3/tcp/9000".parse().unwrap());
+        limiter.connection_established(remote_peer, &"/ip4/1.2.3.5/tcp/9000".parse().unwrap());
+
+        assert_eq!(limiter.peer_class(&trusted_peer), PeerClass::Trusted);
+        assert_eq!(limiter.peer_class(&local_peer), PeerClass::IpRange);
+        assert_eq!(limiter.peer_class(&remote_peer), PeerClass::Default);
+
+        // Peers keep their class when the quotas are updated, but not once disconnected.
+        limiter
+            .update_config(InboundRateLimiterConfig {
+                ip_ranges: vec!["10.1.0.0/16".parse().unwrap()],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(limiter.peer_class(&trusted_peer), PeerClass::Trusted);
+        assert_eq!(limiter.peer_class(&local_peer), PeerClass::IpRange);
+        limiter.peer_disconnected(&local_peer);
+        assert_eq!(limiter.peer_class(&local_peer), PeerClass::Default);
+    }
 }
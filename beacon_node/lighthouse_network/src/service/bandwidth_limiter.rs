@@ -0,0 +1,340 @@
+//! Enforces the upload and download bandwidth caps of the p2p stack.
+//!
+//! Every substream opened over the libp2p transport is wrapped in a [`ThrottledStream`] which
+//! draws from a shared token bucket per direction. Once a bucket is exhausted, reads and writes
+//! are suspended until it has been refilled, which applies back-pressure to the muxer and in turn
+//! to the remote peer.
+//!
+//! Traffic that we can choose not to send (forwarding gossip on [low priority
+//! topics](is_low_priority_topic) and serving `BlocksByRange`/`BlobsByRange` requests) is
+//! expected to consult [`BandwidthLimiter::has_upload_headroom`] first. It is throttled as soon as
+//! the upload bucket drops below a reserve, leaving that reserve for the publication of our own
+//! blocks and attestations.
+use crate::types::{GossipKind, GossipTopic};
+use crate::TopicHash;
+use futures::prelude::{AsyncRead, AsyncWrite};
+use futures::ready;
+use libp2p::core::muxing::{StreamMuxer, StreamMuxerBox, StreamMuxerEvent, SubstreamBox};
+use parking_lot::Mutex;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::time::Sleep;
+
+/// The fraction of the upload bucket that low priority traffic may not use.
+const LOW_PRIORITY_RESERVE: f64 = 0.5;
+
+/// Returns `true` if forwarding gossip on `topic` may be throttled when the upload cap is reached.
+///
+/// Blocks and blobs are always forwarded, since the network needs them to make progress. Unknown
+/// topics are treated as low priority.
+pub fn is_low_priority_topic(topic: &TopicHash) -> bool {
+    GossipTopic::decode(topic.as_str()).map_or(true, |topic| {
+        !matches!(
+            topic.kind(),
+            GossipKind::BeaconBlock | GossipKind::BlobSidecar(_)
+        )
+    })
+}
+
+/// The direction of the traffic being limited.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Upload,
+    Download,
+}
+
+/// A token bucket holding up to one second worth of traffic.
+///
+/// Bytes are only counted once they have been transferred, so the bucket may go into debt by up
+/// to the size of a single read or write. Further transfers wait until the debt has been repaid.
+#[derive(Debug)]
+struct TokenBucket {
+    /// The number of bytes per second refilled into the bucket.
+    rate: f64,
+    /// The number of bytes currently available. Negative when in debt.
+    tokens: f64,
+    /// The last time the bucket was refilled.
+    last_update: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u64, now: Instant) -> Self {
+        TokenBucket {
+            rate: rate as f64,
+            tokens: rate as f64,
+            last_update: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_update);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.rate);
+        self.last_update = now;
+    }
+
+    fn consume(&mut self, bytes: usize, now: Instant) {
+        self.refill(now);
+        self.tokens -= bytes as f64;
+    }
+
+    /// Returns the time to wait before the bucket is out of debt, if it is in debt.
+    fn delay(&mut self, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        if self.tokens >= 0.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(-self.tokens / self.rate))
+        }
+    }
+
+    /// Returns `true` if the bucket holds more than the reserve kept for high priority traffic.
+    fn has_headroom(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.rate * LOW_PRIORITY_RESERVE
+    }
+}
+
+/// Shared upload and download caps, in bytes per second.
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+    upload: Option<Mutex<TokenBucket>>,
+    download: Option<Mutex<TokenBucket>>,
+}
+
+impl BandwidthLimiter {
+    /// Creates a limiter for the given caps in bytes per second. Returns `None` if neither
+    /// direction is limited.
+    pub fn new(upload_limit: Option<u64>, download_limit: Option<u64>) -> Option<Self> {
+        let now = Instant::now();
+        let bucket = |limit: Option<u64>| {
+            limit
+                .filter(|limit| *limit > 0)
+                .map(|limit| Mutex::new(TokenBucket::new(limit, now)))
+        };
+        let limiter = BandwidthLimiter {
+            upload: bucket(upload_limit),
+            download: bucket(download_limit),
+        };
+        (limiter.upload.is_some() || limiter.download.is_some()).then_some(limiter)
+    }
+
+    /// Returns `true` if low priority traffic may currently be uploaded.
+    pub fn has_upload_headroom(&self) -> bool {
+        self.upload
+            .as_ref()
+            .map_or(true, |bucket| bucket.lock().has_headroom(Instant::now()))
+    }
+
+    fn bucket(&self, direction: Direction) -> Option<&Mutex<TokenBucket>> {
+        match direction {
+            Direction::Upload => self.upload.as_ref(),
+            Direction::Download => self.download.as_ref(),
+        }
+    }
+
+    fn consume(&self, direction: Direction, bytes: usize) {
+        if let Some(bucket) = self.bucket(direction) {
+            bucket.lock().consume(bytes, Instant::now());
+        }
+    }
+
+    /// Resolves once bytes may be transferred in the given direction. `delay` holds the timer of
+    /// the caller between polls.
+    fn poll_ready(
+        &self,
+        direction: Direction,
+        delay: &mut Option<Pin<Box<Sleep>>>,
+        cx: &mut Context<'_>,
+    ) -> Poll<()> {
+        let Some(bucket) = self.bucket(direction) else {
+            return Poll::Ready(());
+        };
+
+        loop {
+            if let Some(sleep) = delay.as_mut() {
+                ready!(sleep.as_mut().poll(cx));
+                *delay = None;
+            }
+
+            match bucket.lock().delay(Instant::now()) {
+                None => return Poll::Ready(()),
+                Some(wait) => *delay = Some(Box::pin(tokio::time::sleep(wait))),
+            }
+        }
+    }
+}
+
+/// A substream whose reads and writes are subject to the caps of a [`BandwidthLimiter`].
+pub struct ThrottledStream<S> {
+    inner: S,
+    limiter: Arc<BandwidthLimiter>,
+    read_delay: Option<Pin<Box<Sleep>>>,
+    write_delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> ThrottledStream<S> {
+    fn new(inner: S, limiter: Arc<BandwidthLimiter>) -> Self {
+        ThrottledStream {
+            inner,
+            limiter,
+            read_delay: None,
+            write_delay: None,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for ThrottledStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this
+            .limiter
+            .poll_ready(Direction::Download, &mut this.read_delay, cx));
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(read)) = poll {
+            this.limiter.consume(Direction::Download, read);
+        }
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for ThrottledStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this
+            .limiter
+            .poll_ready(Direction::Upload, &mut this.write_delay, cx));
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = poll {
+            this.limiter.consume(Direction::Upload, written);
+        }
+        poll
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+/// Wraps every substream of a connection in a [`ThrottledStream`].
+pub struct ThrottledMuxer {
+    inner: StreamMuxerBox,
+    limiter: Arc<BandwidthLimiter>,
+}
+
+impl ThrottledMuxer {
+    pub fn new(inner: StreamMuxerBox, limiter: Arc<BandwidthLimiter>) -> Self {
+        ThrottledMuxer { inner, limiter }
+    }
+}
+
+impl StreamMuxer for ThrottledMuxer {
+    type Substream = ThrottledStream<SubstreamBox>;
+    type Error = io::Error;
+
+    fn poll_inbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.get_mut();
+        let substream = ready!(Pin::new(&mut this.inner).poll_inbound(cx))?;
+        Poll::Ready(Ok(ThrottledStream::new(substream, this.limiter.clone())))
+    }
+
+    fn poll_outbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.get_mut();
+        let substream = ready!(Pin::new(&mut this.inner).poll_outbound(cx))?;
+        Poll::Ready(Ok(ThrottledStream::new(substream, this.limiter.clone())))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_goes_into_debt_and_recovers() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(1000, now);
+        assert_eq!(bucket.delay(now), None);
+
+        // Consuming more than the bucket holds puts it into debt for the time needed to refill
+        // the excess.
+        bucket.consume(1500, now);
+        assert_eq!(bucket.delay(now), Some(Duration::from_millis(500)));
+        assert_eq!(bucket.delay(now + Duration::from_millis(500)), None);
+    }
+
+    #[test]
+    fn bucket_never_exceeds_one_second_of_traffic() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(1000, now);
+        bucket.refill(now + Duration::from_secs(10));
+        bucket.consume(1001, now + Duration::from_secs(10));
+        assert!(bucket.delay(now + Duration::from_secs(10)).is_some());
+    }
+
+    #[test]
+    fn low_priority_traffic_keeps_a_reserve() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(1000, now);
+        assert!(bucket.has_headroom(now));
+
+        bucket.consume(400, now);
+        assert!(bucket.has_headroom(now));
+
+        // High priority traffic may still be sent, but low priority traffic is throttled.
+        bucket.consume(200, now);
+        assert!(!bucket.has_headroom(now));
+        assert_eq!(bucket.delay(now), None);
+
+        assert!(bucket.has_headroom(now + Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn blocks_and_blobs_are_high_priority() {
+        let topic = |name: &str| TopicHash::from_raw(format!("/eth2/e1925f3b/{}/ssz_snappy", name));
+        assert!(!is_low_priority_topic(&topic("beacon_block")));
+        assert!(!is_low_priority_topic(&topic("blob_sidecar_0")));
+        assert!(is_low_priority_topic(&topic("beacon_attestation_42")));
+        assert!(is_low_priority_topic(&topic("beacon_aggregate_and_proof")));
+        assert!(is_low_priority_topic(&TopicHash::from_raw("unknown")));
+    }
+
+    #[test]
+    fn no_limiter_without_caps() {
+        assert!(BandwidthLimiter::new(None, None).is_none());
+        assert!(BandwidthLimiter::new(Some(0), None).is_none());
+        let limiter = BandwidthLimiter::new(None, Some(1000)).unwrap();
+        assert!(limiter.has_upload_headroom());
+    }
+}
@@ -9,19 +9,22 @@ use crate::peer_manager::{
     ConnectionDirection, PeerManager, PeerManagerEvent,
 };
 use crate::peer_manager::{MIN_OUTBOUND_ONLY_FACTOR, PEER_EXCESS_FACTOR, PRIORITY_PEER_EXCESS};
+use crate::rpc::config::InboundRateLimiterConfig;
 use crate::rpc::methods::MetadataRequest;
 use crate::rpc::*;
 use crate::service::behaviour::BehaviourEvent;
 pub use crate::service::behaviour::Gossipsub;
 use crate::types::{
-    attestation_sync_committee_topics, fork_core_topics, subnet_from_topic_hash, GossipEncoding,
-    GossipKind, GossipTopic, SnappyTransform, Subnet, SubnetDiscovery, ALTAIR_CORE_TOPICS,
-    BASE_CORE_TOPICS, CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS, LIGHT_CLIENT_GOSSIP_TOPICS,
+    attestation_sync_committee_topics, fork_core_topics, subnet_from_topic_hash, BandwidthCount,
+    GossipEncoding, GossipKind, GossipTopic, SnappyTransform, Subnet, SubnetDiscovery,
+    ALTAIR_CORE_TOPICS, BASE_CORE_TOPICS, CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS,
+    LIGHT_CLIENT_GOSSIP_TOPICS,
 };
 use crate::EnrExt;
 use crate::Eth2Enr;
 use crate::{error, metrics, Enr, NetworkGlobals, PubsubMessage, TopicHash};
 use api_types::{PeerRequestId, Request, RequestId, Response};
+use bandwidth_limiter::{is_low_priority_topic, BandwidthLimiter};
 use futures::stream::StreamExt;
 use gossipsub::{
     IdentTopic as Topic, MessageAcceptance, MessageAuthenticity, MessageId, PublishError,
@@ -46,6 +49,7 @@ use types::{
 use utils::{build_transport, strip_peer_id, Context as ServiceContext, MAX_CONNECTIONS_PER_PEER};
 
 pub mod api_types;
+pub mod bandwidth_limiter;
 mod behaviour;
 mod gossip_cache;
 pub mod gossipsub_scoring_parameters;
@@ -167,10 +171,12 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             let globals = NetworkGlobals::new(
                 enr,
                 meta_data,
-                trusted_peers,
+                trusted_peers.clone(),
                 config.disable_peer_scoring,
                 &log,
             );
+            *globals.inbound_rate_limiter_config.write() =
+                config.inbound_rate_limiter_config.clone();
             Arc::new(globals)
         };
 
@@ -203,7 +209,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
 
         let local_peer_id = network_globals.local_peer_id();
 
-        let (gossipsub, update_gossipsub_scores) = {
+        let (mut gossipsub, update_gossipsub_scores) = {
             let thresholds = lighthouse_gossip_thresholds();
 
             // Prepare scoring parameters
@@ -311,13 +317,37 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             ttfb_timeout: ctx.chain_spec.ttfb_timeout(),
             resp_timeout: ctx.chain_spec.resp_timeout(),
         };
+        let bandwidth_limiter = BandwidthLimiter::new(
+            config.upload_bandwidth_limit,
+            config.download_bandwidth_limit,
+        )
+        .map(Arc::new);
+        if let Some(limiter) = bandwidth_limiter.clone() {
+            info!(log, "Limiting p2p bandwidth";
+                "upload_bytes_per_sec" => ?config.upload_bandwidth_limit,
+                "download_bytes_per_sec" => ?config.download_bandwidth_limit);
+
+            // Forwarding gossip is the first traffic to be dropped when the upload cap is reached.
+            // Blocks and blobs are always forwarded as the network needs them to make progress.
+            gossipsub.set_forward_throttle(move |topic| {
+                if limiter.has_upload_headroom() || !is_low_priority_topic(topic) {
+                    return false;
+                }
+                metrics::inc_counter_vec(&metrics::BANDWIDTH_THROTTLED_TOTAL, &["gossip_forward"]);
+                true
+            });
+        }
+
         let eth2_rpc = RPC::new(
             ctx.fork_context.clone(),
             config.enable_light_client_server,
             config.inbound_rate_limiter_config.clone(),
             config.outbound_rate_limiter_config.clone(),
+            trusted_peers.into_iter().collect(),
             log.clone(),
             network_params,
+            network_globals.bandwidth.clone(),
+            bandwidth_limiter.clone(),
         );
 
         let discovery = {
@@ -400,8 +430,12 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         };
 
         // Set up the transport - tcp/quic with noise and mplex
-        let transport = build_transport(local_keypair.clone(), !config.disable_quic_support)
-            .map_err(|e| format!("Failed to build transport: {:?}", e))?;
+        let transport = build_transport(
+            local_keypair.clone(),
+            !config.disable_quic_support,
+            bandwidth_limiter.clone(),
+        )
+        .map_err(|e| format!("Failed to build transport: {:?}", e))?;
 
         // use the executor for libp2p
         struct Executor(task_executor::TaskExecutor);
@@ -972,6 +1006,17 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             .goodbye_peer(peer_id, reason, source);
     }
 
+    /// Replaces the quotas of the inbound RPC rate limiter.
+    pub fn update_inbound_rate_limiter_config(
+        &mut self,
+        config: InboundRateLimiterConfig,
+    ) -> Result<(), &'static str> {
+        self.eth2_rpc_mut()
+            .update_inbound_rate_limiter_config(config.clone())?;
+        *self.network_globals.inbound_rate_limiter_config.write() = Some(config);
+        Ok(())
+    }
+
     /// Returns an iterator over all enr entries in the DHT.
     pub fn enr_entries(&self) -> Vec<Enr> {
         self.discovery().table_entries_enr()
@@ -1691,6 +1736,27 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
 
     /* Networking polling */
 
+    /// Moves the gossipsub message byte counts into the shared bandwidth tracker and drops the
+    /// per-peer counters of peers that are no longer known to the peer manager.
+    fn update_bandwidth_counters(&mut self) {
+        let bandwidth = &self.network_globals.bandwidth;
+        for ((peer_id, topic), count) in self
+            .swarm
+            .behaviour_mut()
+            .gossipsub
+            .take_message_bandwidth()
+        {
+            bandwidth.record_gossip(
+                &peer_id,
+                &topic,
+                BandwidthCount::new(count.inbound, count.outbound),
+            );
+        }
+
+        let peers = self.network_globals.peers.read();
+        bandwidth.retain_peers(|peer_id| peers.peer_info(peer_id).is_some());
+    }
+
     /// Poll the p2p networking stack.
     ///
     /// This will poll the swarm and do maintenance routines.
@@ -1817,6 +1883,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         while self.update_gossipsub_scores.poll_tick(cx).is_ready() {
             let this = self.swarm.behaviour_mut();
             this.peer_manager.update_gossipsub_scores(&this.gossipsub);
+            self.update_bandwidth_counters();
         }
 
         // poll the gossipsub cache to clear expired messages
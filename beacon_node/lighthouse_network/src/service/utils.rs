@@ -1,5 +1,6 @@
 use crate::multiaddr::Protocol;
 use crate::rpc::{MetaData, MetaDataV1, MetaDataV2};
+use crate::service::bandwidth_limiter::{BandwidthLimiter, ThrottledMuxer};
 use crate::types::{
     error, EnrAttestationBitfield, EnrSyncCommitteeBitfield, GossipEncoding, GossipKind,
 };
@@ -38,9 +39,12 @@ type BoxedTransport = Boxed<(PeerId, StreamMuxerBox)>;
 
 /// The implementation supports TCP/IP, QUIC (experimental) over UDP, noise as the encryption layer, and
 /// mplex/yamux as the multiplexing layer (when using TCP).
+///
+/// If a `bandwidth_limiter` is given, all substreams are throttled to its upload and download caps.
 pub fn build_transport(
     local_private_key: Keypair,
     quic_support: bool,
+    bandwidth_limiter: Option<Arc<BandwidthLimiter>>,
 ) -> std::io::Result<BoxedTransport> {
     // mplex config
     let mut mplex_config = libp2p_mplex::MplexConfig::new();
@@ -77,6 +81,17 @@ pub fn build_transport(
     // Enables DNS over the transport.
     let transport = libp2p::dns::tokio::Transport::system(transport)?.boxed();
 
+    // Enforces the bandwidth caps on every substream.
+    let transport = match bandwidth_limiter {
+        Some(limiter) => transport
+            .map(move |(peer_id, muxer), _| {
+                let muxer = ThrottledMuxer::new(muxer, limiter.clone());
+                (peer_id, StreamMuxerBox::new(muxer))
+            })
+            .boxed(),
+        None => transport,
+    };
+
     Ok(transport)
 }
 
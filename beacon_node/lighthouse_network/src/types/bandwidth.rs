@@ -0,0 +1,204 @@
+//! Byte counters for the traffic exchanged with peers, broken down per peer, per RPC protocol
+//! and per gossipsub topic.
+//!
+//! Transport level totals are already reported by libp2p. This module attributes the bytes of
+//! each RPC substream and each gossipsub message to the peer and protocol/topic they belong to.
+use crate::metrics;
+use crate::rpc::Protocol;
+use crate::types::GossipTopic;
+use crate::PeerId;
+use gossipsub::TopicHash;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Label used when a gossipsub topic cannot be decoded into a known `GossipTopic`.
+const UNKNOWN_TOPIC: &str = "unknown";
+
+/// The number of bytes received from and sent to the network.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BandwidthCount {
+    /// Bytes received.
+    pub inbound: u64,
+    /// Bytes sent.
+    pub outbound: u64,
+}
+
+impl BandwidthCount {
+    pub fn new(inbound: u64, outbound: u64) -> Self {
+        BandwidthCount { inbound, outbound }
+    }
+
+    /// Returns `true` if no bytes have been counted.
+    pub fn is_empty(&self) -> bool {
+        self.inbound == 0 && self.outbound == 0
+    }
+
+    fn add(&mut self, other: BandwidthCount) {
+        self.inbound = self.inbound.saturating_add(other.inbound);
+        self.outbound = self.outbound.saturating_add(other.outbound);
+    }
+}
+
+/// A snapshot of the bandwidth counters, suitable for serving over the HTTP API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BandwidthSummary {
+    /// Bytes exchanged over RPC and gossipsub with all peers.
+    pub total: BandwidthCount,
+    /// Bytes exchanged with each known peer, keyed by peer id.
+    pub peers: BTreeMap<String, BandwidthCount>,
+    /// Bytes exchanged per RPC protocol.
+    pub protocols: BTreeMap<String, BandwidthCount>,
+    /// Bytes exchanged per gossipsub topic, keyed by topic kind (including the subnet id).
+    pub topics: BTreeMap<String, BandwidthCount>,
+}
+
+#[derive(Debug, Default)]
+struct BandwidthCounters {
+    total: BandwidthCount,
+    peers: HashMap<PeerId, BandwidthCount>,
+    protocols: HashMap<Protocol, BandwidthCount>,
+    topics: HashMap<String, BandwidthCount>,
+}
+
+/// Accumulates the bytes exchanged with peers over RPC and gossipsub.
+///
+/// The tracker is shared between the network behaviours and the HTTP API via `NetworkGlobals`.
+/// Per-peer counters are kept for as long as the peer is known to the `PeerDB`, see
+/// `BandwidthTracker::retain_peers`.
+#[derive(Debug, Default)]
+pub struct BandwidthTracker {
+    counters: RwLock<BandwidthCounters>,
+}
+
+impl BandwidthTracker {
+    /// Records bytes exchanged with `peer_id` on an RPC substream of the given protocol.
+    pub fn record_rpc(&self, peer_id: &PeerId, protocol: Protocol, count: BandwidthCount) {
+        if count.is_empty() {
+            return;
+        }
+
+        let protocol_label = protocol.as_ref();
+        metrics::inc_counter_vec_by(
+            &metrics::RPC_BANDWIDTH_BYTES,
+            &[protocol_label, "inbound"],
+            count.inbound,
+        );
+        metrics::inc_counter_vec_by(
+            &metrics::RPC_BANDWIDTH_BYTES,
+            &[protocol_label, "outbound"],
+            count.outbound,
+        );
+
+        let mut counters = self.counters.write();
+        counters.total.add(count);
+        counters.peers.entry(*peer_id).or_default().add(count);
+        counters.protocols.entry(protocol).or_default().add(count);
+    }
+
+    /// Records bytes of gossipsub messages exchanged with `peer_id` on the given topic.
+    pub fn record_gossip(&self, peer_id: &PeerId, topic: &TopicHash, count: BandwidthCount) {
+        if count.is_empty() {
+            return;
+        }
+
+        let (kind_label, topic_key) = match GossipTopic::decode(topic.as_str()) {
+            Ok(topic) => (topic.kind().as_ref().to_string(), topic.kind().to_string()),
+            Err(_) => (UNKNOWN_TOPIC.to_string(), UNKNOWN_TOPIC.to_string()),
+        };
+        metrics::inc_counter_vec_by(
+            &metrics::GOSSIP_BANDWIDTH_BYTES_PER_TOPIC_KIND,
+            &[&kind_label, "inbound"],
+            count.inbound,
+        );
+        metrics::inc_counter_vec_by(
+            &metrics::GOSSIP_BANDWIDTH_BYTES_PER_TOPIC_KIND,
+            &[&kind_label, "outbound"],
+            count.outbound,
+        );
+
+        let mut counters = self.counters.write();
+        counters.total.add(count);
+        counters.peers.entry(*peer_id).or_default().add(count);
+        counters.topics.entry(topic_key).or_default().add(count);
+    }
+
+    /// Drops the per-peer counters of all peers for which `f` returns `false`.
+    pub fn retain_peers(&self, mut f: impl FnMut(&PeerId) -> bool) {
+        self.counters.write().peers.retain(|peer_id, _| f(peer_id));
+    }
+
+    /// Returns the bytes exchanged with a single peer, if any have been recorded.
+    pub fn peer(&self, peer_id: &PeerId) -> Option<BandwidthCount> {
+        self.counters.read().peers.get(peer_id).copied()
+    }
+
+    /// Returns a snapshot of all counters.
+    pub fn summary(&self) -> BandwidthSummary {
+        let counters = self.counters.read();
+        BandwidthSummary {
+            total: counters.total,
+            peers: counters
+                .peers
+                .iter()
+                .map(|(peer_id, count)| (peer_id.to_string(), *count))
+                .collect(),
+            protocols: counters
+                .protocols
+                .iter()
+                .map(|(protocol, count)| (protocol.to_string(), *count))
+                .collect(),
+            topics: counters.topics.clone().into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_rpc_and_gossip_bytes() {
+        let tracker = BandwidthTracker::default();
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+        let topic = TopicHash::from_raw("/eth2/e1925f3b/beacon_block/ssz_snappy");
+
+        tracker.record_rpc(
+            &peer_a,
+            Protocol::BlocksByRange,
+            BandwidthCount::new(100, 10),
+        );
+        tracker.record_rpc(&peer_b, Protocol::BlocksByRange, BandwidthCount::new(50, 5));
+        tracker.record_gossip(&peer_a, &topic, BandwidthCount::new(0, 1000));
+
+        let summary = tracker.summary();
+        assert_eq!(summary.total, BandwidthCount::new(150, 1015));
+        assert_eq!(
+            summary.peers.get(&peer_a.to_string()),
+            Some(&BandwidthCount::new(100, 1010))
+        );
+        assert_eq!(
+            summary.protocols.get("beacon_blocks_by_range"),
+            Some(&BandwidthCount::new(150, 15))
+        );
+        assert_eq!(
+            summary.topics.get("beacon_block"),
+            Some(&BandwidthCount::new(0, 1000))
+        );
+    }
+
+    #[test]
+    fn retain_peers_keeps_totals() {
+        let tracker = BandwidthTracker::default();
+        let peer = PeerId::random();
+        tracker.record_rpc(&peer, Protocol::Status, BandwidthCount::new(1, 1));
+
+        tracker.retain_peers(|_| false);
+
+        assert_eq!(tracker.peer(&peer), None);
+        let summary = tracker.summary();
+        assert!(summary.peers.is_empty());
+        assert_eq!(summary.total, BandwidthCount::new(1, 1));
+    }
+}
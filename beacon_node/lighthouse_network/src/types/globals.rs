@@ -1,12 +1,14 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::peerdb::PeerDB;
+use crate::rpc::config::InboundRateLimiterConfig;
 use crate::rpc::{MetaData, MetaDataV2};
-use crate::types::{BackFillState, SyncState};
+use crate::types::{BackFillState, BandwidthTracker, SyncState};
 use crate::Client;
 use crate::EnrExt;
 use crate::{Enr, GossipTopic, Multiaddr, PeerId};
 use parking_lot::RwLock;
 use std::collections::HashSet;
+use std::sync::Arc;
 use types::EthSpec;
 
 pub struct NetworkGlobals<TSpec: EthSpec> {
@@ -26,6 +28,10 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub sync_state: RwLock<SyncState>,
     /// The current state of the backfill sync.
     pub backfill_state: RwLock<BackFillState>,
+    /// Bytes exchanged with peers, per peer, RPC protocol and gossipsub topic.
+    pub bandwidth: Arc<BandwidthTracker>,
+    /// The quotas of the inbound RPC rate limiter, if enabled.
+    pub inbound_rate_limiter_config: RwLock<Option<InboundRateLimiterConfig>>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -45,6 +51,8 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::NotRequired),
+            bandwidth: Arc::new(BandwidthTracker::default()),
+            inbound_rate_limiter_config: RwLock::new(None),
         }
     }
 
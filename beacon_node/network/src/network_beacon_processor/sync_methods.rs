@@ -17,7 +17,7 @@ use beacon_processor::{
     AsyncFn, BlockingFn, DuplicateCache,
 };
 use lighthouse_network::PeerAction;
-use slog::{debug, error, info, warn};
+use slog::{crit, debug, error, info, warn};
 use std::sync::Arc;
 use std::time::Duration;
 use store::KzgCommitment;
@@ -45,6 +45,20 @@ struct ChainSegmentFailed {
     peer_action: Option<PeerAction>,
 }
 
+/// Returned when a backfill batch can't be imported.
+enum BackfillBatchFailed {
+    /// The batch was rejected, and may be retried with blocks from other peers.
+    Rejected(ChainSegmentFailed),
+    /// The batch is part of our canonical history but can't be imported, so retrying won't help.
+    Fatal(String),
+}
+
+impl From<ChainSegmentFailed> for BackfillBatchFailed {
+    fn from(e: ChainSegmentFailed) -> Self {
+        Self::Rejected(e)
+    }
+}
+
 impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     /// Returns an async closure which processes a beacon block received via RPC.
     ///
@@ -378,7 +392,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             was_non_empty: sent_blocks > 0,
                         }
                     }
-                    (_, Err(e)) => {
+                    (_, Err(BackfillBatchFailed::Rejected(e))) => {
                         debug!(self.log, "Backfill batch processing failed";
                             "batch_epoch" => epoch,
                             "first_block_slot" => start_slot,
@@ -394,6 +408,15 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             None => BatchProcessResult::NonFaultyFailure,
                         }
                     }
+                    (_, Err(BackfillBatchFailed::Fatal(message))) => {
+                        debug!(self.log, "Backfill batch processing failed fatally";
+                            "batch_epoch" => epoch,
+                            "first_block_slot" => start_slot,
+                            "last_block_slot" => end_slot,
+                            "error" => %message,
+                            "service" => "sync");
+                        BatchProcessResult::FatalFailure
+                    }
                 }
             }
             // this is a parent lookup request from the sync manager
@@ -473,7 +496,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     fn process_backfill_blocks(
         &self,
         downloaded_blocks: Vec<RpcBlock<T::EthSpec>>,
-    ) -> (usize, Result<(), ChainSegmentFailed>) {
+    ) -> (usize, Result<(), BackfillBatchFailed>) {
         let total_blocks = downloaded_blocks.len();
         let available_blocks = match self
             .chain
@@ -495,7 +518,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         Err(ChainSegmentFailed {
                             peer_action: None,
                             message: "Failed to check block availability".into(),
-                        }),
+                        }
+                        .into()),
                     );
                 }
                 e => {
@@ -504,7 +528,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         Err(ChainSegmentFailed {
                             peer_action: Some(PeerAction::LowToleranceError),
                             message: format!("Failed to check block availability : {:?}", e),
-                        }),
+                        }
+                        .into()),
                     )
                 }
             },
@@ -520,7 +545,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         (total_blocks - available_blocks.len()),
                         total_blocks
                     ),
-                }),
+                }
+                .into()),
             );
         }
 
@@ -556,6 +582,26 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                                 peer_action: Some(PeerAction::LowToleranceError),
                             }
                         }
+                        HistoricalBlockError::InvalidPayloadBlockHash { block_root, error } => {
+                            // The block root has already been matched against our canonical
+                            // history, so the peer sent the block we asked for and isn't at
+                            // fault. Either the canonical chain contains an invalid payload or
+                            // our verification of it is wrong, and other peers won't do better.
+                            crit!(
+                                self.log,
+                                "Canonical block has an invalid payload block hash";
+                                "info" => "backfill sync has stopped, please report this to \
+                                    the Lighthouse developers",
+                                "block_root" => ?block_root,
+                                "reason" => ?error
+                            );
+                            return (
+                                0,
+                                Err(BackfillBatchFailed::Fatal(String::from(
+                                    "invalid_payload_block_hash",
+                                ))),
+                            );
+                        }
                         HistoricalBlockError::InvalidSignature
                         | HistoricalBlockError::SignatureSet(_) => {
                             warn!(
@@ -627,7 +673,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         }
                     }
                 };
-                (0, Err(err))
+                (0, Err(err.into()))
             }
         }
     }
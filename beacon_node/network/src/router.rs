@@ -10,7 +10,7 @@ use crate::network_beacon_processor::{InvalidBlockStorage, NetworkBeaconProcesso
 use crate::service::{NetworkMessage, RequestId};
 use crate::status::status_message;
 use crate::sync::manager::RequestId as SyncId;
-use crate::sync::SyncMessage;
+use crate::sync::{ArchiveSource, SyncMessage};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use beacon_processor::{
     work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend, DuplicateCache,
@@ -87,6 +87,7 @@ impl<T: BeaconChainTypes> Router<T> {
         network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
         executor: task_executor::TaskExecutor,
         invalid_block_storage: InvalidBlockStorage,
+        backfill_archive: Option<ArchiveSource>,
         beacon_processor_send: BeaconProcessorSend<T::EthSpec>,
         beacon_processor_reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
         log: slog::Logger,
@@ -121,6 +122,7 @@ impl<T: BeaconChainTypes> Router<T> {
             network_send.clone(),
             network_beacon_processor.clone(),
             sync_recv,
+            backfill_archive,
             sync_logger,
         );
 
@@ -4,6 +4,7 @@ use crate::network_beacon_processor::InvalidBlockStorage;
 use crate::persisted_dht::{clear_dht, load_dht, persist_dht};
 use crate::router::{Router, RouterMessage};
 use crate::subnet_service::SyncCommitteeService;
+use crate::sync::ArchiveSource;
 use crate::{error, metrics};
 use crate::{
     subnet_service::{AttestationService, SubnetServiceMessage},
@@ -19,7 +20,7 @@ use lighthouse_network::service::Network;
 use lighthouse_network::types::GossipKind;
 use lighthouse_network::{prometheus_client::registry::Registry, MessageAcceptance};
 use lighthouse_network::{
-    rpc::{GoodbyeReason, RPCResponseErrorCode},
+    rpc::{config::InboundRateLimiterConfig, GoodbyeReason, RPCResponseErrorCode},
     Context, PeerAction, PeerRequestId, PubsubMessage, ReportSource, Request, Response, Subnet,
 };
 use lighthouse_network::{
@@ -108,6 +109,8 @@ pub enum NetworkMessage<T: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Replaces the quotas of the inbound RPC rate limiter.
+    UpdateInboundRateLimiter(InboundRateLimiterConfig),
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -305,6 +308,11 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             .map(InvalidBlockStorage::Enabled)
             .unwrap_or(InvalidBlockStorage::Disabled);
 
+        let backfill_archive = ArchiveSource::from_config::<T::EthSpec>(config)?;
+        if let Some(archive) = &backfill_archive {
+            info!(network_log, "Backfill archive enabled"; "source" => %archive);
+        }
+
         // launch derived network services
 
         // router task
@@ -314,6 +322,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             network_senders.network_send(),
             executor.clone(),
             invalid_block_storage,
+            backfill_archive,
             beacon_processor_send,
             beacon_processor_reprocess_tx,
             network_log.clone(),
@@ -672,6 +681,14 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::UpdateInboundRateLimiter(config) => {
+                match self.libp2p.update_inbound_rate_limiter_config(config) {
+                    Ok(()) => info!(self.log, "Updated inbound rate limiter"),
+                    Err(e) => {
+                        warn!(self.log, "Failed to update inbound rate limiter"; "error" => e)
+                    }
+                }
+            }
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
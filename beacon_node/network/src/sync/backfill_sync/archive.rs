@@ -0,0 +1,164 @@
+//! A fallback source of historical blocks for backfill sync.
+//!
+//! Backfill sync downloads blocks from synced peers, however on some networks few peers keep the
+//! full block history. When all synced peers have failed to serve a batch, it can instead be
+//! fetched from a trusted beacon node HTTP API or from a local directory of era files.
+
+use super::era::EraDirectory;
+use eth2::types::BlockId;
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use lighthouse_network::NetworkConfig;
+use sensitive_url::SensitiveUrl;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use types::{ChainSpec, EthSpec, SignedBeaconBlock, Slot};
+
+/// The timeout for a single block request to a beacon node.
+const BEACON_NODE_TIMEOUT: Duration = Duration::from_secs(12);
+
+/// Where historical blocks are fetched from when synced peers cannot serve them.
+#[derive(Clone)]
+pub enum ArchiveSource {
+    /// A beacon node serving `/eth/v2/beacon/blocks/{block_id}`.
+    BeaconNode(BeaconNodeHttpClient),
+    /// A local directory of era files.
+    EraDirectory(EraDirectory),
+}
+
+impl fmt::Display for ArchiveSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveSource::BeaconNode(client) => write!(f, "{}", client),
+            ArchiveSource::EraDirectory(dir) => write!(f, "era directory ({} files)", dir.len()),
+        }
+    }
+}
+
+impl ArchiveSource {
+    /// Creates the archive configured by `--backfill-archive-url` or `--backfill-era-dir`, if
+    /// any.
+    pub fn from_config<E: EthSpec>(config: &NetworkConfig) -> Result<Option<Self>, String> {
+        if let Some(url) = &config.backfill_archive_url {
+            let url = SensitiveUrl::parse(url)
+                .map_err(|e| format!("Invalid backfill archive URL: {e:?}"))?;
+            let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(BEACON_NODE_TIMEOUT));
+            return Ok(Some(ArchiveSource::BeaconNode(client)));
+        }
+
+        if let Some(path) = &config.backfill_era_dir {
+            let dir = EraDirectory::open(path, E::slots_per_historical_root() as u64)?;
+            if dir.is_empty() {
+                return Err(format!("No era files found in {}", path.display()));
+            }
+            return Ok(Some(ArchiveSource::EraDirectory(dir)));
+        }
+
+        Ok(None)
+    }
+
+    /// Fetches the blocks of the slots `[start_slot, start_slot + count)` in ascending slot
+    /// order, and checks that they form a chain.
+    pub async fn blocks_by_range<E: EthSpec>(
+        &self,
+        start_slot: Slot,
+        count: u64,
+        spec: Arc<ChainSpec>,
+    ) -> Result<Vec<Arc<SignedBeaconBlock<E>>>, String> {
+        let blocks = match self {
+            ArchiveSource::BeaconNode(client) => {
+                let mut blocks = Vec::new();
+                for slot in (start_slot.as_u64()..start_slot.as_u64() + count).map(Slot::new) {
+                    // Empty slots are reported as missing.
+                    if let Some(block) = client
+                        .get_beacon_blocks_ssz::<E>(BlockId::Slot(slot), &spec)
+                        .await
+                        .map_err(|e| format!("Unable to fetch block at slot {slot}: {e:?}"))?
+                    {
+                        blocks.push(Arc::new(block));
+                    }
+                }
+                blocks
+            }
+            ArchiveSource::EraDirectory(dir) => {
+                let dir = dir.clone();
+                tokio::task::spawn_blocking(move || {
+                    dir.blocks_by_range::<E>(start_slot.as_u64(), count, &spec)
+                })
+                .await
+                .map_err(|e| format!("Era file reader failed: {e:?}"))??
+            }
+        };
+
+        verify_parent_roots(&blocks)?;
+        Ok(blocks)
+    }
+}
+
+/// Checks that `blocks` are in strictly ascending slot order and that each block is the parent
+/// of the next one.
+pub fn verify_parent_roots<E: EthSpec>(blocks: &[Arc<SignedBeaconBlock<E>>]) -> Result<(), String> {
+    for pair in blocks.windows(2) {
+        let (parent, child) = (&pair[0], &pair[1]);
+        if child.slot() <= parent.slot() {
+            return Err(format!(
+                "Block at slot {} follows block at slot {}",
+                child.slot(),
+                parent.slot()
+            ));
+        }
+        let parent_root = parent.canonical_root();
+        if child.parent_root() != parent_root {
+            return Err(format!(
+                "Block at slot {} has parent root {:?}, expected {:?}",
+                child.slot(),
+                child.parent_root(),
+                parent_root
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{BeaconBlock, MainnetEthSpec, Signature};
+
+    type E = MainnetEthSpec;
+
+    fn chain(slots: &[u64], spec: &ChainSpec) -> Vec<Arc<SignedBeaconBlock<E>>> {
+        let mut parent_root = Default::default();
+        slots
+            .iter()
+            .map(|&slot| {
+                let mut block = BeaconBlock::<E>::empty(spec);
+                *block.slot_mut() = Slot::new(slot);
+                *block.parent_root_mut() = parent_root;
+                let block = SignedBeaconBlock::from_block(block, Signature::empty());
+                parent_root = block.canonical_root();
+                Arc::new(block)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_chain_of_blocks() {
+        let spec = E::default_spec();
+        assert!(verify_parent_roots(&chain(&[1, 2, 5], &spec)).is_ok());
+        assert!(verify_parent_roots::<E>(&[]).is_ok());
+    }
+
+    #[test]
+    fn rejects_unlinked_or_unordered_blocks() {
+        let spec = E::default_spec();
+
+        let mut blocks = chain(&[1, 2, 5], &spec);
+        blocks.remove(1);
+        assert!(verify_parent_roots(&blocks).is_err());
+
+        let mut blocks = chain(&[1, 2], &spec);
+        blocks.reverse();
+        assert!(verify_parent_roots(&blocks).is_err());
+    }
+}
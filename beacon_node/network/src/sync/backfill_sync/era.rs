@@ -0,0 +1,291 @@
+//! Reads historical blocks from a directory of era files.
+//!
+//! An era file is an e2store file holding the blocks of one period of `SLOTS_PER_HISTORICAL_ROOT`
+//! slots, followed by the state at the end of that period and two slot indices. Each e2store
+//! record starts with an 8 byte header: a 2 byte type, a 4 byte little endian length and 2
+//! reserved bytes. Blocks are stored as snappy framed SSZ.
+//!
+//! The file of era `N` holds the blocks of slots `[(N - 1) * SLOTS_PER_HISTORICAL_ROOT,
+//! N * SLOTS_PER_HISTORICAL_ROOT)` and is named `<config-name>-<era-number>-<short-root>.era`.
+
+use snap::read::FrameDecoder;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use types::{ChainSpec, EthSpec, SignedBeaconBlock};
+
+/// The length of the header of an e2store record.
+const HEADER_LEN: u64 = 8;
+/// The record type of a snappy compressed `SignedBeaconBlock`.
+const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+/// The record type of a slot index.
+const SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+/// The length of the slot index of the single state at the end of an era file.
+const STATE_INDEX_LEN: u64 = HEADER_LEN + 3 * 8;
+
+/// The era files of a directory, keyed by era number.
+#[derive(Debug, Clone)]
+pub struct EraDirectory {
+    files: Arc<BTreeMap<u64, PathBuf>>,
+    slots_per_historical_root: u64,
+}
+
+impl EraDirectory {
+    /// Indexes the era files found in `path`. Files that do not follow the era naming scheme are
+    /// ignored.
+    pub fn open(path: &Path, slots_per_historical_root: u64) -> Result<Self, String> {
+        let entries = std::fs::read_dir(path)
+            .map_err(|e| format!("Unable to read era directory {}: {e:?}", path.display()))?;
+
+        let mut files = BTreeMap::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Unable to read era directory entry: {e:?}"))?;
+            let file_path = entry.path();
+            if let Some(era) = file_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(parse_era_number)
+            {
+                files.insert(era, file_path);
+            }
+        }
+
+        Ok(EraDirectory {
+            files: Arc::new(files),
+            slots_per_historical_root,
+        })
+    }
+
+    /// Returns the number of era files in the directory.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns `true` if the directory contains no era files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Reads the blocks of the slots `[start_slot, start_slot + count)`, in ascending slot order.
+    ///
+    /// This performs blocking file I/O.
+    pub fn blocks_by_range<E: EthSpec>(
+        &self,
+        start_slot: u64,
+        count: u64,
+        spec: &ChainSpec,
+    ) -> Result<Vec<Arc<SignedBeaconBlock<E>>>, String> {
+        let end_slot = start_slot.saturating_add(count);
+        let mut blocks = Vec::new();
+        let mut slot = start_slot;
+        while slot < end_slot {
+            let era = slot / self.slots_per_historical_root + 1;
+            let era_end_slot = era * self.slots_per_historical_root;
+            let path = self
+                .files
+                .get(&era)
+                .ok_or_else(|| format!("No era file for era {era}"))?;
+            let mut file = File::open(path)
+                .map_err(|e| format!("Unable to open era file {}: {e:?}", path.display()))?;
+
+            let range_end = end_slot.min(era_end_slot);
+            let era_blocks = read_blocks::<E, _>(
+                &mut file,
+                slot,
+                range_end,
+                self.slots_per_historical_root,
+                spec,
+            )
+            .map_err(|e| format!("Unable to read era file {}: {e}", path.display()))?;
+            blocks.extend(era_blocks);
+            slot = range_end;
+        }
+        Ok(blocks)
+    }
+}
+
+/// Parses the era number out of a file name of the form `<config-name>-<era-number>-<root>.era`.
+fn parse_era_number(file_name: &str) -> Option<u64> {
+    let stem = file_name.strip_suffix(".era")?;
+    let mut parts = stem.rsplitn(3, '-');
+    let _short_root = parts.next()?;
+    let era = parts.next()?;
+    let _config_name = parts.next()?;
+    era.parse().ok()
+}
+
+/// Reads the blocks of the slots `[start_slot, end_slot)` from a single era file.
+fn read_blocks<E: EthSpec, R: Read + Seek>(
+    reader: &mut R,
+    start_slot: u64,
+    end_slot: u64,
+    slots_per_historical_root: u64,
+    spec: &ChainSpec,
+) -> Result<Vec<Arc<SignedBeaconBlock<E>>>, String> {
+    let file_len = reader.seek(SeekFrom::End(0)).map_err(io_error)?;
+
+    // The block index directly precedes the state index at the end of the file.
+    let block_index_len = HEADER_LEN + (slots_per_historical_root + 2) * 8;
+    let block_index_offset = file_len
+        .checked_sub(STATE_INDEX_LEN + block_index_len)
+        .ok_or("file too short to contain a block index")?;
+
+    let (record_type, index) = read_record(reader, block_index_offset)?;
+    if record_type != SLOT_INDEX || index.len() as u64 != block_index_len - HEADER_LEN {
+        return Err("missing block index".to_string());
+    }
+    let read_u64 = |position: u64| {
+        let position = position as usize * 8;
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&index[position..position + 8]);
+        u64::from_le_bytes(bytes)
+    };
+    let index_start_slot = read_u64(0);
+    let index_count = read_u64(slots_per_historical_root + 1);
+    if index_count != slots_per_historical_root
+        || start_slot < index_start_slot
+        || end_slot > index_start_slot + index_count
+    {
+        return Err(format!(
+            "block index covers {index_count} slots from slot {index_start_slot}, \
+             slots {start_slot}..{end_slot} requested"
+        ));
+    }
+
+    let mut blocks = Vec::new();
+    for slot in start_slot..end_slot {
+        // Offsets are relative to the start of the index record. Empty slots have an offset of 0.
+        let offset = read_u64(slot - index_start_slot + 1) as i64;
+        if offset == 0 {
+            continue;
+        }
+        let block_offset = block_index_offset
+            .checked_add_signed(offset)
+            .ok_or_else(|| format!("invalid block offset {offset} for slot {slot}"))?;
+
+        let (record_type, compressed) = read_record(reader, block_offset)?;
+        if record_type != COMPRESSED_SIGNED_BEACON_BLOCK {
+            return Err(format!("no block record for slot {slot}"));
+        }
+        let mut ssz_bytes = Vec::new();
+        FrameDecoder::new(compressed.as_slice())
+            .read_to_end(&mut ssz_bytes)
+            .map_err(|e| format!("invalid snappy encoding for slot {slot}: {e:?}"))?;
+        let block = SignedBeaconBlock::from_ssz_bytes(&ssz_bytes, spec)
+            .map_err(|e| format!("invalid block at slot {slot}: {e:?}"))?;
+        if block.slot() != slot {
+            return Err(format!(
+                "block at index of slot {slot} has slot {}",
+                block.slot()
+            ));
+        }
+        blocks.push(Arc::new(block));
+    }
+    Ok(blocks)
+}
+
+/// Reads the e2store record at `offset`, returning its type and data.
+fn read_record<R: Read + Seek>(reader: &mut R, offset: u64) -> Result<([u8; 2], Vec<u8>), String> {
+    reader.seek(SeekFrom::Start(offset)).map_err(io_error)?;
+    let mut header = [0; HEADER_LEN as usize];
+    reader.read_exact(&mut header).map_err(io_error)?;
+
+    let record_type = [header[0], header[1]];
+    let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+    let mut data = vec![0; len as usize];
+    reader.read_exact(&mut data).map_err(io_error)?;
+    Ok((record_type, data))
+}
+
+fn io_error(e: io::Error) -> String {
+    format!("{e:?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snap::write::FrameEncoder;
+    use ssz::Encode;
+    use std::io::{Cursor, Write};
+    use types::{BeaconBlock, MainnetEthSpec, Signature, Slot};
+
+    type E = MainnetEthSpec;
+
+    /// The number of slots per era used by the tests, to keep the indices small.
+    const SLOTS_PER_ERA: u64 = 8;
+
+    fn record(record_type: [u8; 2], data: &[u8]) -> Vec<u8> {
+        let mut bytes = record_type.to_vec();
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    /// Builds an era file holding blocks at `block_slots` of the era starting at `start_slot`.
+    fn era_file(start_slot: u64, block_slots: &[u64], spec: &ChainSpec) -> Vec<u8> {
+        let mut file = record([0x65, 0x32], &[]);
+        let mut block_offsets = vec![0; SLOTS_PER_ERA as usize];
+        for &slot in block_slots {
+            let mut block = BeaconBlock::<E>::empty(spec);
+            *block.slot_mut() = Slot::new(slot);
+            let signed_block = SignedBeaconBlock::from_block(block, Signature::empty());
+
+            let mut encoder = FrameEncoder::new(Vec::new());
+            encoder.write_all(&signed_block.as_ssz_bytes()).unwrap();
+            let compressed = encoder.into_inner().unwrap();
+
+            block_offsets[(slot - start_slot) as usize] = file.len();
+            file.extend(record(COMPRESSED_SIGNED_BEACON_BLOCK, &compressed));
+        }
+
+        let block_index_offset = file.len();
+        let mut index = start_slot.to_le_bytes().to_vec();
+        for offset in block_offsets {
+            let relative = if offset == 0 {
+                0
+            } else {
+                offset as i64 - block_index_offset as i64
+            };
+            index.extend_from_slice(&relative.to_le_bytes());
+        }
+        index.extend_from_slice(&SLOTS_PER_ERA.to_le_bytes());
+        file.extend(record(SLOT_INDEX, &index));
+
+        let mut state_index = (start_slot + SLOTS_PER_ERA).to_le_bytes().to_vec();
+        state_index.extend_from_slice(&0i64.to_le_bytes());
+        state_index.extend_from_slice(&1u64.to_le_bytes());
+        file.extend(record(SLOT_INDEX, &state_index));
+        file
+    }
+
+    #[test]
+    fn parses_era_file_names() {
+        assert_eq!(parse_era_number("mainnet-00042-a1b2c3d4.era"), Some(42));
+        assert_eq!(parse_era_number("my-devnet-00001-a1b2c3d4.era"), Some(1));
+        assert_eq!(parse_era_number("mainnet-00042-a1b2c3d4.era1"), None);
+        assert_eq!(parse_era_number("mainnet.era"), None);
+    }
+
+    #[test]
+    fn reads_blocks_and_skips_empty_slots() {
+        let spec = E::default_spec();
+        let file = era_file(8, &[8, 9, 11, 15], &spec);
+
+        let blocks = read_blocks::<E, _>(&mut Cursor::new(&file), 9, 15, SLOTS_PER_ERA, &spec)
+            .expect("should read blocks");
+        let slots = blocks.iter().map(|block| block.slot()).collect::<Vec<_>>();
+        assert_eq!(slots, vec![Slot::new(9), Slot::new(11)]);
+    }
+
+    #[test]
+    fn rejects_slots_outside_of_the_era() {
+        let spec = E::default_spec();
+        let file = era_file(8, &[8], &spec);
+
+        assert!(read_blocks::<E, _>(&mut Cursor::new(&file), 0, 8, SLOTS_PER_ERA, &spec).is_err());
+        assert!(read_blocks::<E, _>(&mut Cursor::new(&file), 8, 17, SLOTS_PER_ERA, &spec).is_err());
+    }
+}
@@ -7,12 +7,16 @@
 //!
 //! If a batch fails, the backfill sync cannot progress. In this scenario, we mark the backfill
 //! sync as failed, log an error and attempt to retry once a new peer joins the node.
+//!
+//! If an [`ArchiveSource`] is configured, batches that no synced peer is able to serve are
+//! downloaded from the archive instead.
 
 use crate::network_beacon_processor::ChainSegmentProcessId;
 use crate::sync::manager::{BatchProcessResult, Id};
 use crate::sync::network_context::SyncNetworkContext;
 use crate::sync::range_sync::{
     BatchConfig, BatchId, BatchInfo, BatchOperationOutcome, BatchProcessingResult, BatchState,
+    ByRangeRequestType,
 };
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
@@ -25,7 +29,12 @@ use std::collections::{
     HashMap, HashSet,
 };
 use std::sync::Arc;
-use types::{Epoch, EthSpec};
+use types::{Epoch, EthSpec, SignedBeaconBlock};
+
+mod archive;
+mod era;
+
+pub use archive::ArchiveSource;
 
 /// Blocks are downloaded in batches from peers. This constant specifies how many epochs worth of
 /// blocks per batch are requested _at most_. A batch may request less blocks to account for
@@ -92,6 +101,8 @@ pub enum BackFillError {
     BatchDownloadFailed(BatchId),
     /// A batch could not be processed.
     BatchProcessingFailed(BatchId),
+    /// A batch of our canonical history could not be processed, which retrying won't fix.
+    BatchProcessingFatal(BatchId),
     /// A batch entered an invalid state.
     BatchInvalidState(BatchId, String),
     /// The sync algorithm entered an invalid state.
@@ -137,6 +148,9 @@ pub struct BackFillSync<T: BeaconChainTypes> {
     /// This signifies that we are able to attempt to restart a failed chain.
     restart_failed_sync: bool,
 
+    /// Set when backfill sync failed in a way that restarting won't fix.
+    halted: bool,
+
     /// Reference to the beacon chain to obtain initial starting points for the backfill sync.
     beacon_chain: Arc<BeaconChain<T>>,
 
@@ -144,6 +158,13 @@ pub struct BackFillSync<T: BeaconChainTypes> {
     /// (i.e synced peers).
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
 
+    /// A source of blocks for batches that no synced peer is able to serve.
+    archive: Option<ArchiveSource>,
+
+    /// A placeholder peer id that batches downloaded from the `archive` are attributed to. It is
+    /// never reported to the peer manager.
+    archive_peer: PeerId,
+
     /// A logger for backfill sync.
     log: slog::Logger,
 }
@@ -152,6 +173,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
     pub fn new(
         beacon_chain: Arc<BeaconChain<T>>,
         network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+        archive: Option<ArchiveSource>,
         log: slog::Logger,
     ) -> Self {
         // Determine if backfill is enabled or not.
@@ -188,7 +210,10 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             validated_batches: 0,
             participating_peers: HashSet::new(),
             restart_failed_sync: false,
+            halted: false,
             beacon_chain,
+            archive,
+            archive_peer: PeerId::random(),
             log,
         };
 
@@ -297,7 +322,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
     /// If we are in a failed state, update a local variable to indicate we are able to restart
     /// the failed sync on the next attempt.
     pub fn fully_synced_peer_joined(&mut self) {
-        if matches!(self.state(), BackFillState::Failed) {
+        if matches!(self.state(), BackFillState::Failed) && !self.halted {
             self.restart_failed_sync = true;
         }
     }
@@ -473,6 +498,40 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         }
     }
 
+    /// The archive has responded to a batch request.
+    ///
+    /// The blocks are added to the batch as if they had been received from a peer. A failed
+    /// request is handled like an RPC error.
+    #[must_use = "A failure here indicates the backfill sync has failed and the global sync state should be updated"]
+    pub fn on_archive_response(
+        &mut self,
+        network: &mut SyncNetworkContext<T>,
+        batch_id: BatchId,
+        request_id: Id,
+        result: Result<Vec<Arc<SignedBeaconBlock<T::EthSpec>>>, String>,
+    ) -> Result<ProcessResult, BackFillError> {
+        let archive_peer = self.archive_peer;
+        match result {
+            Ok(blocks) => {
+                for block in blocks {
+                    self.on_block_response(
+                        network,
+                        batch_id,
+                        &archive_peer,
+                        request_id,
+                        Some(RpcBlock::new_without_blobs(None, block)),
+                    )?;
+                }
+                self.on_block_response(network, batch_id, &archive_peer, request_id, None)
+            }
+            Err(e) => {
+                warn!(self.log, "Archive failed to serve batch"; "epoch" => batch_id, "error" => e);
+                self.inject_error(network, batch_id, &archive_peer, request_id)
+                    .map(|_| ProcessResult::Successful)
+            }
+        }
+    }
+
     /// The syncing process has failed.
     ///
     /// This resets past variables, to allow for a fresh start when resuming.
@@ -484,6 +543,9 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
         // Set the state
         self.set_state(BackFillState::Failed);
+        if matches!(error, BackFillError::BatchProcessingFatal(_)) {
+            self.halted = true;
+        }
         // Remove all batches and active requests and participating peers.
         self.batches.clear();
         self.active_requests.clear();
@@ -700,6 +762,11 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
                 self.retry_batch_download(network, batch_id)
                     .map(|_| ProcessResult::Successful)
             }
+            BatchProcessResult::FatalFailure => {
+                // Don't penalize the peers, they sent the blocks of our canonical history.
+                self.fail_sync(BackFillError::BatchProcessingFatal(batch_id))
+                    .map(|_| ProcessResult::Successful)
+            }
         }
     }
 
@@ -786,8 +853,11 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             match batch.state() {
                 BatchState::AwaitingValidation(ref processed_attempt) => {
                     for attempt in batch.attempts() {
-                        // The validated batch has been re-processed
-                        if attempt.hash != processed_attempt.hash {
+                        // The validated batch has been re-processed. The archive is trusted and
+                        // never scored.
+                        if attempt.hash != processed_attempt.hash
+                            && attempt.peer_id != self.archive_peer
+                        {
                             // The re-downloaded version was different.
                             if processed_attempt.peer_id != attempt.peer_id {
                                 // A different peer sent the correct batch, the previous peer did not
@@ -938,10 +1008,19 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
                 .collect::<Vec<_>>();
             // Sort peers prioritizing unrelated peers with less active requests.
             priorized_peers.sort_unstable();
-            priorized_peers.first().map(|&(_, _, peer)| peer)
+            priorized_peers.first().copied()
         };
 
-        if let Some(peer) = new_peer {
+        // Fall back to the archive once every synced peer has failed to serve this batch.
+        let (_, batch_type) = batch.to_blocks_by_range_request();
+        let use_archive = self.archive.is_some()
+            && matches!(batch_type, ByRangeRequestType::Blocks)
+            && new_peer.map_or(true, |(peer_failed, _, _)| peer_failed);
+
+        if use_archive {
+            debug!(self.log, "Requesting batch from archive"; "epoch" => batch_id);
+            self.send_batch(network, batch_id, self.archive_peer)
+        } else if let Some((_, _, peer)) = new_peer {
             self.participating_peers.insert(peer);
             self.send_batch(network, batch_id, peer)
         } else {
@@ -961,7 +1040,15 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
     ) -> Result<(), BackFillError> {
         if let Some(batch) = self.batches.get_mut(&batch_id) {
             let (request, is_blob_batch) = batch.to_blocks_by_range_request();
-            match network.backfill_blocks_by_range_request(peer, is_blob_batch, request, batch_id) {
+            let result = match &self.archive {
+                Some(archive) if peer == self.archive_peer => {
+                    network.backfill_archive_request(archive.clone(), request, batch_id)
+                }
+                _ => {
+                    network.backfill_blocks_by_range_request(peer, is_blob_batch, request, batch_id)
+                }
+            };
+            match result {
                 Ok(request_id) => {
                     // inform the batch about the new request
                     if let Err(e) = batch.start_downloading_from_peer(peer, request_id) {
@@ -1231,7 +1231,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                     cx.report_peer(peer_source, penalty, "parent_chain_failure")
                 }
             }
-            BatchProcessResult::NonFaultyFailure => {
+            BatchProcessResult::NonFaultyFailure | BatchProcessResult::FatalFailure => {
                 // We might request this chain again if there is need but otherwise, don't try again
             }
         }
@@ -33,7 +33,7 @@
 //! needs to be searched for (i.e if an attestation references an unknown block) this manager can
 //! search for the block and subsequently search for parents if needed.
 
-use super::backfill_sync::{BackFillSync, ProcessResult, SyncStart};
+use super::backfill_sync::{ArchiveSource, BackFillSync, ProcessResult, SyncStart};
 use super::block_lookups::BlockLookups;
 use super::network_context::{BlockOrBlob, SyncNetworkContext};
 use super::peer_sync_info::{remote_sync_type, PeerSyncType};
@@ -44,7 +44,7 @@ use crate::status::ToStatusMessage;
 use crate::sync::block_lookups::common::{Current, Parent};
 use crate::sync::block_lookups::{BlobRequestState, BlockRequestState};
 use crate::sync::network_context::BlocksAndBlobsByRangeRequest;
-use crate::sync::range_sync::ByRangeRequestType;
+use crate::sync::range_sync::{BatchId, ByRangeRequestType};
 use beacon_chain::block_verification_types::AsBlock;
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::data_availability_checker::ChildComponents;
@@ -158,6 +158,13 @@ pub enum SyncMessage<T: EthSpec> {
         process_type: BlockProcessType,
         result: BlockProcessingResult<T>,
     },
+
+    /// The backfill archive has responded to a batch request.
+    BackFillArchiveResponse {
+        batch_id: BatchId,
+        request_id: Id,
+        result: Result<Vec<Arc<SignedBeaconBlock<T>>>, String>,
+    },
 }
 
 /// The type of processing specified for a received block.
@@ -188,6 +195,9 @@ pub enum BatchProcessResult {
         penalty: PeerAction,
     },
     NonFaultyFailure,
+    /// The batch could not be processed because of a fault of this node which retrying won't fix,
+    /// so sync should stop.
+    FatalFailure,
 }
 
 /// The primary object for handling and driving all the current syncing logic. It maintains the
@@ -225,6 +235,7 @@ pub fn spawn<T: BeaconChainTypes>(
     network_send: mpsc::UnboundedSender<NetworkMessage<T::EthSpec>>,
     beacon_processor: Arc<NetworkBeaconProcessor<T>>,
     sync_recv: mpsc::UnboundedReceiver<SyncMessage<T::EthSpec>>,
+    backfill_archive: Option<ArchiveSource>,
     log: slog::Logger,
 ) {
     assert!(
@@ -244,7 +255,12 @@ pub fn spawn<T: BeaconChainTypes>(
             log.clone(),
         ),
         range_sync: RangeSync::new(beacon_chain.clone(), log.clone()),
-        backfill_sync: BackFillSync::new(beacon_chain.clone(), network_globals, log.clone()),
+        backfill_sync: BackFillSync::new(
+            beacon_chain.clone(),
+            network_globals,
+            backfill_archive,
+            log.clone(),
+        ),
         block_lookups: BlockLookups::new(
             beacon_chain.data_availability_checker.clone(),
             log.clone(),
@@ -712,6 +728,23 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     .block_lookups
                     .parent_chain_processed(chain_hash, result, &self.network),
             },
+            SyncMessage::BackFillArchiveResponse {
+                batch_id,
+                request_id,
+                result,
+            } => match self.backfill_sync.on_archive_response(
+                &mut self.network,
+                batch_id,
+                request_id,
+                result,
+            ) {
+                Ok(ProcessResult::SyncCompleted) => self.update_sync_state(),
+                Ok(ProcessResult::Successful) => {}
+                Err(_error) => {
+                    // The backfill sync has failed, errors are reported within.
+                    self.update_sync_state();
+                }
+            },
         }
     }
 
@@ -1,8 +1,9 @@
 //! Provides network functionality for the Syncing thread. This fundamentally wraps a network
 //! channel and stores a global RPC ID to perform requests.
 
+use super::backfill_sync::ArchiveSource;
 use super::block_sidecar_coupling::BlocksAndBlobsRequestInfo;
-use super::manager::{Id, RequestId as SyncRequestId};
+use super::manager::{Id, RequestId as SyncRequestId, SyncMessage};
 use super::range_sync::{BatchId, ByRangeRequestType, ChainId};
 use crate::network_beacon_processor::NetworkBeaconProcessor;
 use crate::service::{NetworkMessage, RequestId};
@@ -19,7 +20,7 @@ use slog::{debug, trace, warn};
 use std::collections::hash_map::Entry;
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use types::{BlobSidecar, EthSpec, SignedBeaconBlock};
+use types::{BlobSidecar, EthSpec, SignedBeaconBlock, Slot};
 
 pub struct BlocksAndBlobsByRangeResponse<T: EthSpec> {
     pub batch_id: BatchId,
@@ -284,6 +285,44 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
         }
     }
 
+    /// A blocks by range request for the backfill sync, served by the backfill archive rather
+    /// than a peer. The response is sent to the sync manager as a
+    /// `SyncMessage::BackFillArchiveResponse`.
+    pub fn backfill_archive_request(
+        &mut self,
+        archive: ArchiveSource,
+        request: BlocksByRangeRequest,
+        batch_id: BatchId,
+    ) -> Result<Id, &'static str> {
+        let id = self.next_id();
+        let start_slot = Slot::new(*request.start_slot());
+        let count = *request.count();
+        trace!(
+            self.log,
+            "Sending backfill archive request";
+            "source" => %archive,
+            "start_slot" => start_slot,
+            "count" => count,
+        );
+
+        let spec = Arc::new(self.chain.spec.clone());
+        let sync_tx = self.network_beacon_processor.sync_tx.clone();
+        self.network_beacon_processor.executor.spawn(
+            async move {
+                let result = archive
+                    .blocks_by_range::<T::EthSpec>(start_slot, count, spec)
+                    .await;
+                let _ = sync_tx.send(SyncMessage::BackFillArchiveResponse {
+                    batch_id,
+                    request_id: id,
+                    result,
+                });
+            },
+            "backfill_archive_request",
+        );
+        Ok(id)
+    }
+
     /// Response for a request that is only for blocks.
     pub fn range_sync_block_only_response(
         &mut self,
@@ -553,7 +553,8 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     }
                 }
             }
-            BatchProcessResult::NonFaultyFailure => {
+            // Only backfill sync fails fatally, range sync batches are retried.
+            BatchProcessResult::NonFaultyFailure | BatchProcessResult::FatalFailure => {
                 batch.processing_completed(BatchProcessingResult::NonFaultyFailure)?;
                 // Simply redownload the batch.
                 self.retry_batch_download(network, batch_id)
@@ -165,6 +165,20 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .set(clap::ArgSettings::Hidden)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("upload-bandwidth-limit")
+                .long("upload-bandwidth-limit")
+                .value_name("BYTES_PER_SEC")
+                .help("Limits the upload bandwidth used by the p2p stack to this many bytes per second. When the limit is approached, forwarding gossip other than blocks and blobs and serving blocks and blobs by range are throttled first so that the publication of our own blocks and attestations keeps priority.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("download-bandwidth-limit")
+                .long("download-bandwidth-limit")
+                .value_name("BYTES_PER_SEC")
+                .help("Limits the download bandwidth used by the p2p stack to this many bytes per second.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("disable-upnp")
                 .long("disable-upnp")
@@ -298,6 +312,33 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Attempts to download blocks all the way back to genesis when checkpoint syncing.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("backfill-archive-url")
+                .long("backfill-archive-url")
+                .value_name("URL")
+                .help("A beacon node HTTP API to download historical blocks from during backfill \
+                       sync, when no synced peer is able to serve them. Blocks are checked to \
+                       form a chain of parent roots before they are imported.")
+                .takes_value(true)
+                .conflicts_with("backfill-era-dir"),
+        )
+        .arg(
+            Arg::with_name("verify-backfill-block-hashes")
+                .long("verify-backfill-block-hashes")
+                .help("Recompute the block hash of the execution payload of each block imported \
+                       by backfill sync and stop backfill sync if one doesn't match. This \
+                       detects corrupted history without an execution engine, at the cost of \
+                       slower backfill sync.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("backfill-era-dir")
+                .long("backfill-era-dir")
+                .value_name("DIR")
+                .help("A directory of era files to read historical blocks from during backfill \
+                       sync, when no synced peer is able to serve them.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("enable-private-discovery")
                 .long("enable-private-discovery")
@@ -344,6 +385,41 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             .takes_value(true)
             .hidden(true)
         )
+        .arg(
+            Arg::with_name("inbound-rate-limiter-trusted-peers")
+            .long("inbound-rate-limiter-trusted-peers")
+            .value_name("QUOTAS")
+            .help(
+                "Inbound rate limit quotas for trusted peers, in the format of \
+                --inbound-rate-limiter. Trusted peers use the same quotas as other peers if \
+                not set."
+            )
+            .takes_value(true)
+            .hidden(true)
+        )
+        .arg(
+            Arg::with_name("inbound-rate-limiter-ip-range")
+            .long("inbound-rate-limiter-ip-range")
+            .value_name("QUOTAS")
+            .help(
+                "Inbound rate limit quotas for untrusted peers connected from one of the \
+                --inbound-rate-limiter-ip-ranges, in the format of --inbound-rate-limiter."
+            )
+            .takes_value(true)
+            .requires("inbound-rate-limiter-ip-ranges")
+            .hidden(true)
+        )
+        .arg(
+            Arg::with_name("inbound-rate-limiter-ip-ranges")
+            .long("inbound-rate-limiter-ip-ranges")
+            .value_name("CIDR_LIST")
+            .help(
+                "Comma-separated list of address ranges in CIDR notation (e.g. 10.0.0.0/8) \
+                whose peers are subject to the --inbound-rate-limiter-ip-range quotas."
+            )
+            .takes_value(true)
+            .hidden(true)
+        )
         .arg(
             Arg::with_name("disable-backfill-rate-limiting")
                 .long("disable-backfill-rate-limiting")
@@ -638,8 +714,10 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("EXECUTION-ENDPOINT")
                 .alias("execution-endpoints")
                 .help("Server endpoint for an execution layer JWT-authenticated HTTP \
-                       JSON-RPC connection. Uses the same endpoint to populate the \
-                       deposit cache.")
+                       JSON-RPC connection. Multiple comma-separated endpoints may be provided, \
+                       in which case forkchoice updates and new payloads are sent to all of them \
+                       and other requests fall back to the next healthy endpoint. The first \
+                       endpoint is used to populate the deposit cache.")
                 .takes_value(true)
         )
         .arg(
@@ -648,7 +726,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("EXECUTION-JWT")
                 .alias("jwt-secrets")
                 .help("File path which contains the hex-encoded JWT secret for the \
-                       execution endpoint provided in the --execution-endpoint flag.")
+                       execution endpoint provided in the --execution-endpoint flag. When \
+                       multiple execution endpoints are provided, either a single file shared \
+                       by all endpoints or one comma-separated file per endpoint.")
                 .requires("execution-endpoint")
                 .takes_value(true)
         )
@@ -685,6 +765,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("execution-jwt")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("execution-new-payload-quorum")
+                .long("execution-new-payload-quorum")
+                .value_name("NUM")
+                .help("The number of execution endpoints which must agree on the validity of a \
+                       payload. Blocks for which no quorum is reached are imported \
+                       optimistically. Defaults to 1.")
+                .requires("execution-endpoint")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("suggested-fee-recipient")
                 .long("suggested-fee-recipient")
@@ -700,7 +790,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("builder")
                 .alias("payload-builder")
                 .alias("payload-builders")
-                .help("The URL of a service compatible with the MEV-boost API.")
+                .help("The URL of a service compatible with the MEV-boost API. Multiple \
+                       comma-separated relays may be provided, in which case validators are \
+                       registered with all relays and the most valuable bid is used.")
                 .requires("execution-endpoint")
                 .takes_value(true)
         )
@@ -712,6 +804,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("1")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("execution-recording-dir")
+                .long("execution-recording-dir")
+                .value_name("DIR")
+                .help("Record every request sent to the execution engines, and its response, to \
+                       rotating files in this directory. Recordings can be replayed with \
+                       `lcli mock-el --replay` to reproduce issues offline. Recordings contain \
+                       full payloads and grow quickly, so only enable this while debugging.")
+                .requires("execution-endpoint")
+                .takes_value(true)
+        )
         /* Deneb settings */
         .arg(
             Arg::with_name("trusted-setup-file-override")
@@ -789,7 +892,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("graffiti")
                 .help(
                     "Specify your custom graffiti to be included in blocks. \
-                    Defaults to the current version and commit, truncated to fit in 32 bytes. "
+                    Defaults to the current version and commit, truncated to fit in 32 bytes, \
+                    prefixed with the client code and commit of the execution client once known. "
                 )
                 .value_name("GRAFFITI")
                 .takes_value(true)
@@ -2,6 +2,7 @@ use beacon_chain::chain_config::{
     DisallowedReOrgOffsets, ReOrgThreshold, DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR,
     DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_THRESHOLD,
 };
+use beacon_chain::graffiti_calculator::GraffitiOrigin;
 use beacon_chain::TrustedSetup;
 use clap::ArgMatches;
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
@@ -26,7 +27,7 @@ use std::num::NonZeroU16;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
-use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes, GRAFFITI_BYTES_LEN};
+use types::{Checkpoint, Epoch, EthSpec, Graffiti, Hash256, PublicKeyBytes, GRAFFITI_BYTES_LEN};
 
 /// Gets the fully-initialized global client.
 ///
@@ -280,25 +281,34 @@ pub fn get_config<E: EthSpec>(
         // stakers. The merge is already complicated enough.
         client_config.sync_eth1_chain = true;
 
-        // Parse a single execution endpoint, logging warnings if multiple endpoints are supplied.
-        let execution_endpoint =
-            parse_only_one_value(endpoints, SensitiveUrl::parse, "--execution-endpoint", log)?;
+        // Parse the execution endpoints, the first of which is the primary.
+        let execution_endpoints =
+            parse_comma_separated_values(endpoints, SensitiveUrl::parse, "--execution-endpoint")?;
+        let execution_endpoint = execution_endpoints[0].clone();
 
         // JWTs are required if `--execution-endpoint` is supplied. They can be either passed via
         // file_path or directly as string.
 
-        let secret_file: PathBuf;
-        // Parse a single JWT secret from a given file_path, logging warnings if multiple are supplied.
-        if let Some(secret_files) = cli_args.value_of("execution-jwt") {
-            secret_file =
-                parse_only_one_value(secret_files, PathBuf::from_str, "--execution-jwt", log)?;
+        let secret_files: Vec<PathBuf>;
+        // Parse either one JWT secret for each execution endpoint or a single shared JWT secret.
+        if let Some(files) = cli_args.value_of("execution-jwt") {
+            secret_files =
+                parse_comma_separated_values(files, PathBuf::from_str, "--execution-jwt")?;
+            if secret_files.len() != 1 && secret_files.len() != execution_endpoints.len() {
+                return Err(format!(
+                    "--execution-jwt must contain either one secret for each execution endpoint or \
+                     a single secret, {} secrets were provided for {} endpoints",
+                    secret_files.len(),
+                    execution_endpoints.len()
+                ));
+            }
 
         // Check if the JWT secret key is passed directly via cli flag and persist it to the default
         // file location.
         } else if let Some(jwt_secret_key) = cli_args.value_of("execution-jwt-secret-key") {
             use std::fs::File;
             use std::io::Write;
-            secret_file = client_config.data_dir().join(DEFAULT_JWT_FILE);
+            let secret_file = client_config.data_dir().join(DEFAULT_JWT_FILE);
             let mut jwt_secret_key_file = File::create(secret_file.clone())
                 .map_err(|e| format!("Error while creating jwt_secret_key file: {:?}", e))?;
             jwt_secret_key_file
@@ -309,15 +319,15 @@ pub fn get_config<E: EthSpec>(
                         e
                     )
                 })?;
+            secret_files = vec![secret_file];
         } else {
             return Err("Error! Please set either --execution-jwt file_path or --execution-jwt-secret-key directly via cli when using --execution-endpoint".to_string());
         }
 
-        // Parse and set the payload builder, if any.
-        if let Some(endpoint) = cli_args.value_of("builder") {
-            let payload_builder =
-                parse_only_one_value(endpoint, SensitiveUrl::parse, "--builder", log)?;
-            el_config.builder_url = Some(payload_builder);
+        // Parse and set the payload builders, if any.
+        if let Some(endpoints) = cli_args.value_of("builder") {
+            el_config.builder_urls =
+                parse_comma_separated_values(endpoints, SensitiveUrl::parse, "--builder")?;
 
             el_config.builder_user_agent =
                 clap_utils::parse_optional(cli_args, "builder-user-agent")?;
@@ -339,8 +349,12 @@ pub fn get_config<E: EthSpec>(
         }
 
         // Set config values from parse values.
-        el_config.secret_files = vec![secret_file.clone()];
-        el_config.execution_endpoints = vec![execution_endpoint.clone()];
+        // The deposit cache is populated from the primary execution endpoint.
+        let secret_file = secret_files[0].clone();
+        el_config.secret_files = secret_files;
+        el_config.execution_endpoints = execution_endpoints;
+        el_config.new_payload_quorum =
+            clap_utils::parse_optional(cli_args, "execution-new-payload-quorum")?;
         el_config.suggested_fee_recipient =
             clap_utils::parse_optional(cli_args, "suggested-fee-recipient")?;
         el_config.jwt_id = clap_utils::parse_optional(cli_args, "execution-jwt-id")?;
@@ -351,6 +365,8 @@ pub fn get_config<E: EthSpec>(
         let execution_timeout_multiplier =
             clap_utils::parse_required(cli_args, "execution-timeout-multiplier")?;
         el_config.execution_timeout_multiplier = Some(execution_timeout_multiplier);
+        el_config.engine_recording_dir =
+            clap_utils::parse_optional(cli_args, "execution-recording-dir")?;
 
         client_config.eth1.endpoint = Eth1Endpoint::Auth {
             endpoint: execution_endpoint,
@@ -566,7 +582,13 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.genesis_backfill = true;
     }
 
-    let raw_graffiti = if let Some(graffiti) = cli_args.value_of("graffiti") {
+    if cli_args.is_present("verify-backfill-block-hashes") {
+        client_config.chain.verify_backfill_block_hashes = true;
+    }
+
+    // The default graffiti identifies the execution client once it is known, unless the user
+    // specified graffiti or asked for privacy.
+    let (raw_graffiti, user_specified) = if let Some(graffiti) = cli_args.value_of("graffiti") {
         if graffiti.len() > GRAFFITI_BYTES_LEN {
             return Err(format!(
                 "Your graffiti is too long! {} bytes maximum!",
@@ -574,16 +596,21 @@ pub fn get_config<E: EthSpec>(
             ));
         }
 
-        graffiti.as_bytes()
+        (graffiti.as_bytes(), true)
     } else if cli_args.is_present("private") {
-        b""
+        (&b""[..], true)
     } else {
-        lighthouse_version::VERSION.as_bytes()
+        (lighthouse_version::VERSION.as_bytes(), false)
     };
 
+    let mut graffiti = Graffiti::default();
     let trimmed_graffiti_len = cmp::min(raw_graffiti.len(), GRAFFITI_BYTES_LEN);
-    client_config.graffiti.0[..trimmed_graffiti_len]
-        .copy_from_slice(&raw_graffiti[..trimmed_graffiti_len]);
+    graffiti.0[..trimmed_graffiti_len].copy_from_slice(&raw_graffiti[..trimmed_graffiti_len]);
+    client_config.graffiti = if user_specified {
+        GraffitiOrigin::UserSpecified(graffiti)
+    } else {
+        GraffitiOrigin::Calculated(graffiti)
+    };
 
     if let Some(wss_checkpoint) = cli_args.value_of("wss-checkpoint") {
         let mut split = wss_checkpoint.split(':');
@@ -832,6 +859,11 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.genesis_backfill = true;
     }
 
+    client_config.network.backfill_archive_url =
+        clap_utils::parse_optional(cli_args, "backfill-archive-url")?;
+    client_config.network.backfill_era_dir =
+        clap_utils::parse_optional(cli_args, "backfill-era-dir")?;
+
     // Backfill sync rate-limiting
     client_config.beacon_processor.enable_backfill_rate_limiting =
         !cli_args.is_present("disable-backfill-rate-limiting");
@@ -1139,6 +1171,10 @@ pub fn set_network_config(
         config.network_load = network_load;
     }
 
+    config.upload_bandwidth_limit = clap_utils::parse_optional(cli_args, "upload-bandwidth-limit")?;
+    config.download_bandwidth_limit =
+        clap_utils::parse_optional(cli_args, "download-bandwidth-limit")?;
+
     if let Some(boot_enr_str) = cli_args.value_of("boot-nodes") {
         let mut enrs: Vec<Enr> = vec![];
         let mut multiaddrs: Vec<Multiaddr> = vec![];
@@ -1431,6 +1467,18 @@ pub fn set_network_config(
             Some(config_str.parse()?)
         }
     };
+    if let Some(inbound_config) = config.inbound_rate_limiter_config.as_mut() {
+        inbound_config.trusted =
+            clap_utils::parse_optional(cli_args, "inbound-rate-limiter-trusted-peers")?;
+        inbound_config.ip_range =
+            clap_utils::parse_optional(cli_args, "inbound-rate-limiter-ip-range")?;
+        if let Some(ip_ranges) = cli_args.value_of("inbound-rate-limiter-ip-ranges") {
+            inbound_config.ip_ranges = ip_ranges
+                .split(',')
+                .map(|range| range.trim().parse())
+                .collect::<Result<_, _>>()?;
+        }
+    }
     Ok(())
 }
 
@@ -1475,13 +1523,12 @@ pub fn get_slots_per_restore_point<E: EthSpec>(
 
 /// Parses the `cli_value` as a comma-separated string of values to be parsed with `parser`.
 ///
-/// If there is more than one value, log a warning. If there are no values, return an error.
-pub fn parse_only_one_value<F, T, E>(
+/// If there are no values, return an error.
+pub fn parse_comma_separated_values<F, T, E>(
     cli_value: &str,
     parser: F,
     flag_name: &str,
-    log: &Logger,
-) -> Result<T, String>
+) -> Result<Vec<T>, String>
 where
     F: Fn(&str) -> Result<T, E>,
     E: Debug,
@@ -1492,18 +1539,8 @@ where
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| format!("{} contains an invalid value {:?}", flag_name, e))?;
 
-    if values.len() > 1 {
-        warn!(
-            log,
-            "Multiple values provided";
-            "info" => "multiple values are deprecated, only the first value will be used",
-            "count" => values.len(),
-            "flag" => flag_name
-        );
+    if values.is_empty() {
+        return Err(format!("Must provide at least one value to {}", flag_name));
     }
-
-    values
-        .into_iter()
-        .next()
-        .ok_or(format!("Must provide at least one value to {}", flag_name))
+    Ok(values)
 }
@@ -265,6 +265,9 @@ pub enum DBColumn {
     OptimisticTransitionBlock,
     #[strum(serialize = "bhs")]
     BeaconHistoricalSummaries,
+    /// For the audit trail of the payloads of blocks produced by this node.
+    #[strum(serialize = "bpr")]
+    ProposalRecord,
     #[strum(serialize = "olc")]
     OverflowLRUCache,
 }
@@ -304,7 +307,8 @@ impl DBColumn {
             | Self::PubkeyCache
             | Self::BeaconRestorePoint
             | Self::DhtEnrs
-            | Self::OptimisticTransitionBlock => 32,
+            | Self::OptimisticTransitionBlock
+            | Self::ProposalRecord => 32,
             Self::BeaconBlockRoots
             | Self::BeaconStateRoots
             | Self::BeaconHistoricalRoots
@@ -15,7 +15,10 @@ use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io;
 use std::path::{Path, PathBuf};
-use types::{graffiti::GraffitiString, Address, PublicKey};
+use types::{
+    graffiti::{GraffitiString, GraffitiTemplate},
+    Address, PublicKey,
+};
 use validator_dir::VOTING_KEYSTORE_FILE;
 
 /// The file name for the serialized `ValidatorDefinitions` struct.
@@ -82,6 +85,33 @@ pub struct Web3SignerDefinition {
     pub client_identity_password: Option<String>,
 }
 
+/// A validator client which holds another share of a threshold validator.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdPeerDefinition {
+    /// The index of the share held by the peer.
+    pub share_index: u64,
+    /// The public key of the share held by the peer, used to verify its partial signatures.
+    pub share_public_key: PublicKey,
+    /// The URL of the HTTP API of the peer.
+    pub url: String,
+    /// Path to a file containing the threshold token of the peer (`threshold-api-token.txt`),
+    /// which only authorises requests for partial signatures.
+    pub api_token_path: PathBuf,
+}
+
+/// Defines how the share of a threshold validator is combined with the shares of its peers.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdDefinition {
+    /// The index of the share held in the local keystore.
+    pub share_index: u64,
+    /// The number of shares required to produce a signature.
+    pub threshold: usize,
+    pub peers: Vec<ThresholdPeerDefinition>,
+    /// Specifies a request timeout for partial signatures from peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_ms: Option<u64>,
+}
+
 /// Defines how the validator client should attempt to sign messages for this validator.
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -100,6 +130,17 @@ pub enum SigningDefinition {
     /// https://github.com/ConsenSys/web3signer
     #[serde(rename = "web3signer")]
     Web3Signer(Web3SignerDefinition),
+    /// A validator whose key is split between several validator clients, defined by an EIP-2335
+    /// keystore holding the local share of the key.
+    #[serde(rename = "threshold")]
+    Threshold {
+        voting_keystore_path: PathBuf,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        voting_keystore_password_path: Option<PathBuf>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        voting_keystore_password: Option<ZeroizeString>,
+        threshold: ThresholdDefinition,
+    },
 }
 
 impl SigningDefinition {
@@ -112,15 +153,25 @@ impl SigningDefinition {
             SigningDefinition::LocalKeystore {
                 voting_keystore_password: Some(password),
                 ..
+            }
+            | SigningDefinition::Threshold {
+                voting_keystore_password: Some(password),
+                ..
             } => Ok(Some(password.clone())),
             SigningDefinition::LocalKeystore {
                 voting_keystore_password_path: Some(path),
                 ..
+            }
+            | SigningDefinition::Threshold {
+                voting_keystore_password_path: Some(path),
+                ..
             } => read_password_string(path)
                 .map(Into::into)
                 .map(Option::Some)
                 .map_err(Error::UnableToReadKeystorePassword),
-            SigningDefinition::LocalKeystore { .. } => Err(Error::KeystoreWithoutPassword),
+            SigningDefinition::LocalKeystore { .. } | SigningDefinition::Threshold { .. } => {
+                Err(Error::KeystoreWithoutPassword)
+            }
             SigningDefinition::Web3Signer(_) => Ok(None),
         }
     }
@@ -130,6 +181,10 @@ impl SigningDefinition {
             SigningDefinition::LocalKeystore {
                 voting_keystore_password_path: Some(path),
                 ..
+            }
+            | SigningDefinition::Threshold {
+                voting_keystore_password_path: Some(path),
+                ..
             } => Some(path),
             _ => None,
         }
@@ -146,7 +201,7 @@ pub struct ValidatorDefinition {
     pub voting_public_key: PublicKey,
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub graffiti: Option<GraffitiString>,
+    pub graffiti: Option<GraffitiTemplate>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suggested_fee_recipient: Option<Address>,
@@ -201,7 +256,7 @@ impl ValidatorDefinition {
             enabled: true,
             voting_public_key,
             description: keystore.description().unwrap_or("").to_string(),
-            graffiti,
+            graffiti: graffiti.map(Into::into),
             suggested_fee_recipient,
             gas_limit,
             builder_proposals,
@@ -282,6 +337,10 @@ impl ValidatorDefinitions {
                 SigningDefinition::LocalKeystore {
                     voting_keystore_path,
                     ..
+                }
+                | SigningDefinition::Threshold {
+                    voting_keystore_path,
+                    ..
                 } => Some(voting_keystore_path),
                 // A Web3Signer validator does not use a local keystore file.
                 SigningDefinition::Web3Signer { .. } => None,
@@ -563,8 +622,53 @@ mod tests {
         let def: ValidatorDefinition = serde_yaml::from_str(valid_graffiti).unwrap();
         assert_eq!(
             def.graffiti,
-            Some(GraffitiString::from_str("mrfwashere").unwrap())
+            Some(GraffitiTemplate::from_str("mrfwashere").unwrap())
+        );
+
+        let graffiti_template = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        graffiti: "{client}{version}-{el_client}-{validator_index}"
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+
+        let def: ValidatorDefinition = serde_yaml::from_str(graffiti_template).unwrap();
+        assert!(def.graffiti.unwrap().has_fields());
+    }
+
+    #[test]
+    fn open_definitions_with_braces_in_graffiti() {
+        // Graffiti written before templates were supported may contain braces.
+        let definitions = r#"---
+- description: ""
+  enabled: true
+  type: local_keystore
+  graffiti: "{mrf} was here :-}"
+  voting_keystore_path: ""
+  voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+- description: ""
+  enabled: true
+  type: local_keystore
+  graffiti: "{client}{{validator_index}}"
+  voting_keystore_path: ""
+  voting_public_key: "0xa5566f9ec3c6e1fdf362634ebec9ef7aceb0e460e5079714808388e5d48f4ae1e12897fed1bea951c17fa389d511e477"
+"#;
+        let validators_dir = tempfile::tempdir().unwrap();
+        std::fs::write(validators_dir.path().join(CONFIG_FILENAME), definitions).unwrap();
+
+        let definitions = ValidatorDefinitions::open(validators_dir.path()).unwrap();
+        let graffiti = definitions
+            .as_slice()
+            .iter()
+            .map(|def| def.graffiti.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            graffiti[0].as_graffiti_string(),
+            Some(GraffitiString::from_str("{mrf} was here :-}").unwrap())
         );
+        assert!(graffiti[1].has_fields());
     }
 
     #[test]
@@ -4,16 +4,18 @@ mod attestation_performance;
 pub mod attestation_rewards;
 mod block_packing_efficiency;
 mod block_rewards;
+mod proposals;
 mod standard_block_rewards;
 mod sync_committee_rewards;
 
 use crate::{
     types::{
-        DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
+        DepositTreeSnapshot, Epoch, EthSpec, ExecutionClientVersion, FinalizedExecutionBlock,
+        GenericResponse, PublicKeyBytes, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
 };
-use proto_array::core::ProtoArray;
+use proto_array::{core::ProtoArray, ProtoArrayGraph};
 use serde::{Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
@@ -27,7 +29,9 @@ pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+use lighthouse_network::rpc::config::{InboundRateLimiterConfig, RateLimiterConfig};
+pub use lighthouse_network::{types::SyncState, BandwidthCount, BandwidthSummary, PeerInfo};
+pub use proposals::{BuilderFallbackReason, PayloadSource, ProposalRecord, ProposalsQuery};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
 
@@ -47,6 +51,62 @@ pub struct Peer<T: EthSpec> {
     pub peer_info: PeerInfo<T>,
 }
 
+/// The quotas of the inbound RPC rate limiter for each class of peer.
+///
+/// Quotas use the format of the `--inbound-rate-limiter` flag, e.g. `ping:2/10;status:5/15`.
+/// Protocols without a quota use the default quotas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InboundRateLimits {
+    /// Quotas for peers that do not belong to any other class.
+    pub default: String,
+    /// Quotas for trusted peers. Trusted peers use the default quotas if absent.
+    #[serde(default)]
+    pub trusted: Option<String>,
+    /// Quotas for peers connected from one of `ip_ranges`. These peers use the default quotas
+    /// if absent.
+    #[serde(default)]
+    pub ip_range: Option<String>,
+    /// Address ranges in CIDR notation, e.g. `10.0.0.0/8`.
+    #[serde(default)]
+    pub ip_ranges: Vec<String>,
+}
+
+impl From<&InboundRateLimiterConfig> for InboundRateLimits {
+    fn from(config: &InboundRateLimiterConfig) -> Self {
+        InboundRateLimits {
+            default: config.default.to_string(),
+            trusted: config.trusted.as_ref().map(ToString::to_string),
+            ip_range: config.ip_range.as_ref().map(ToString::to_string),
+            ip_ranges: config.ip_ranges.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl InboundRateLimits {
+    /// Parses the quotas into a rate limiter configuration.
+    pub fn to_config(&self) -> Result<InboundRateLimiterConfig, String> {
+        let parse_quotas = |quotas: &str| {
+            quotas
+                .parse::<RateLimiterConfig>()
+                .map_err(|e| format!("Invalid quotas {quotas:?}: {e}"))
+        };
+        Ok(InboundRateLimiterConfig {
+            default: parse_quotas(&self.default)?,
+            trusted: self.trusted.as_deref().map(parse_quotas).transpose()?,
+            ip_range: self.ip_range.as_deref().map(parse_quotas).transpose()?,
+            ip_ranges: self
+                .ip_ranges
+                .iter()
+                .map(|range| {
+                    range
+                        .parse()
+                        .map_err(|e| format!("Invalid IP range {range:?}: {e}"))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -90,6 +150,22 @@ pub struct ValidatorInclusionData {
     pub is_previous_epoch_head_attester: bool,
 }
 
+/// The representation of the fork choice block tree returned by `lighthouse/proto_array/graph`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProtoArrayGraphFormat {
+    /// A `ProtoArrayGraph`, as JSON.
+    #[default]
+    Json,
+    /// A Graphviz DOT digraph, as plain text.
+    Dot,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProtoArrayGraphQuery {
+    pub format: Option<ProtoArrayGraphFormat>,
+}
+
 #[cfg(target_os = "linux")]
 use {
     psutil::cpu::os::linux::CpuTimesExt, psutil::memory::os::linux::VirtualMemoryExt,
@@ -103,6 +179,9 @@ pub struct Health {
     pub system: SystemHealth,
     #[serde(flatten)]
     pub process: ProcessHealth,
+    /// The execution client driven by the beacon node, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_client: Option<ExecutionClientVersion>,
 }
 
 /// System related health.
@@ -295,6 +374,7 @@ impl Health {
         Ok(Self {
             process: ProcessHealth::observe()?,
             system: SystemHealth::observe()?,
+            execution_client: None,
         })
     }
 }
@@ -321,6 +401,75 @@ pub struct DepositLog {
     pub index: u64,
     /// True if the signature is valid.
     pub signature_is_valid: bool,
+    /// The hash of the transaction which emitted the log.
+    pub transaction_hash: Hash256,
+    /// The index of the log in its block.
+    pub log_index: u64,
+}
+
+/// A deposit as recorded in the deposit index of the beacon node.
+///
+/// Unlike `DepositLog`, this is kept once the deposit is finalized.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct IndexedDeposit {
+    /// The index of the deposit in the deposit contract.
+    pub index: u64,
+    pub pubkey: PublicKeyBytes,
+    pub withdrawal_credentials: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub amount: u64,
+    /// True if the signature is valid.
+    pub signature_is_valid: bool,
+    /// The block number of the log of the deposit.
+    pub block_number: u64,
+    /// The hash of the transaction which emitted the log.
+    pub transaction_hash: Hash256,
+    /// The index of the log in its block.
+    pub log_index: u64,
+}
+
+impl From<&DepositLog> for IndexedDeposit {
+    fn from(log: &DepositLog) -> Self {
+        Self {
+            index: log.index,
+            pubkey: log.deposit_data.pubkey,
+            withdrawal_credentials: log.deposit_data.withdrawal_credentials,
+            amount: log.deposit_data.amount,
+            signature_is_valid: log.signature_is_valid,
+            block_number: log.block_number,
+            transaction_hash: log.transaction_hash,
+            log_index: log.log_index,
+        }
+    }
+}
+
+/// A deposit and its inclusion in the beacon chain.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorDeposit {
+    #[serde(flatten)]
+    pub deposit: IndexedDeposit,
+    /// True if the deposit has been processed by the head state.
+    pub processed: bool,
+    /// The slot of the canonical block which included the deposit, if it is known.
+    pub inclusion_slot: Option<Slot>,
+}
+
+/// The deposits to a validator public key.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorDeposits {
+    pub pubkey: PublicKeyBytes,
+    /// The index of the validator in the head state, if it exists.
+    pub validator_index: Option<u64>,
+    /// The deposits in the deposit index, in index order.
+    pub deposits: Vec<ValidatorDeposit>,
+}
+
+/// The deposits of an execution layer transaction.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TransactionDeposits {
+    pub transaction_hash: Hash256,
+    /// The deposits in the deposit index, in index order.
+    pub deposits: Vec<ValidatorDeposit>,
 }
 
 /// A block of the eth1 chain.
@@ -392,6 +541,52 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/network/bandwidth`
+    pub async fn get_lighthouse_network_bandwidth(
+        &self,
+    ) -> Result<GenericResponse<BandwidthSummary>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("bandwidth");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/inbound_rate_limits`
+    pub async fn get_lighthouse_network_inbound_rate_limits(
+        &self,
+    ) -> Result<GenericResponse<InboundRateLimits>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("inbound_rate_limits");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/network/inbound_rate_limits`
+    pub async fn post_lighthouse_network_inbound_rate_limits(
+        &self,
+        limits: &InboundRateLimits,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("inbound_rate_limits");
+
+        self.post(path, limits).await
+    }
+
     /*
      * Note:
      *
@@ -412,6 +607,40 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/proto_array/graph?format=json`
+    pub async fn get_lighthouse_proto_array_graph(
+        &self,
+    ) -> Result<GenericResponse<ProtoArrayGraph>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proto_array")
+            .push("graph");
+
+        path.query_pairs_mut().append_pair("format", "json");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/proto_array/graph?format=dot`
+    ///
+    /// Returns the fork choice block tree as a Graphviz DOT digraph.
+    pub async fn get_lighthouse_proto_array_dot(&self) -> Result<String, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proto_array")
+            .push("graph");
+
+        path.query_pairs_mut().append_pair("format", "dot");
+
+        Ok(self.get_response(path, |b| b).await?.text().await?)
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -492,6 +721,41 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/eth1/deposits/{pubkey}`
+    pub async fn get_lighthouse_eth1_deposits(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<GenericResponse<ValidatorDeposits>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("deposits")
+            .push(&pubkey.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/eth1/transactions/{transaction_hash}/deposits`
+    pub async fn get_lighthouse_eth1_transaction_deposits(
+        &self,
+        transaction_hash: Hash256,
+    ) -> Result<GenericResponse<TransactionDeposits>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("transactions")
+            .push(&format!("{:?}", transaction_hash))
+            .push("deposits");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/staking`
     pub async fn get_lighthouse_staking(&self) -> Result<bool, Error> {
         let mut path = self.server.full.clone();
@@ -555,6 +819,31 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/proposals?start_slot,end_slot`
+    pub async fn get_lighthouse_proposals(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Result<GenericResponse<Vec<ProposalRecord>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proposals");
+
+        if let Some(start_slot) = start_slot {
+            path.query_pairs_mut()
+                .append_pair("start_slot", &start_slot.to_string());
+        }
+        if let Some(end_slot) = end_slot {
+            path.query_pairs_mut()
+                .append_pair("end_slot", &end_slot.to_string());
+        }
+
+        self.get(path).await
+    }
+
     /// `GET` lighthouse/analysis/block_packing?start_epoch,end_epoch
     pub async fn get_lighthouse_analysis_block_packing(
         &self,
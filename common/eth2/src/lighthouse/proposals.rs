@@ -0,0 +1,134 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ssz::four_byte_option_impl;
+use ssz_derive::{Decode, Encode};
+use types::{Address, Hash256, Slot, Uint256};
+
+four_byte_option_impl!(four_byte_option_u256, Uint256);
+four_byte_option_impl!(four_byte_option_fallback_reason, BuilderFallbackReason);
+
+/// Where the execution payload of a produced block came from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "snake_case")]
+#[ssz(enum_behaviour = "tag")]
+pub enum PayloadSource {
+    /// The local execution engine.
+    Local,
+    /// A builder, via a relay.
+    Builder,
+}
+
+/// Why a local payload was used even though a builder is configured.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "snake_case")]
+#[ssz(enum_behaviour = "tag")]
+pub enum BuilderFallbackReason {
+    /// Too many slots were skipped recently, see `--builder-fallback-skips`.
+    Skips,
+    /// Too many slots were skipped in the last epoch, see `--builder-fallback-skips-per-epoch`.
+    SkipsPerEpoch,
+    /// Finality is delayed, see `--builder-fallback-epochs-since-finalization`.
+    EpochsSinceFinalization,
+    /// The head is optimistic, so a builder cannot be used safely.
+    Optimistic,
+    /// All relays failed to respond.
+    BuilderError,
+    /// No relay returned a valid bid.
+    NoBid,
+    /// The local payload was at least as valuable as the builder bid.
+    LocalMoreValuable,
+    /// The execution engine suggested that the builder bid be ignored.
+    ExecutionEngineOverride,
+}
+
+/// The payload of a block produced by the beacon node, and how it was chosen.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct ProposalRecord {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub proposer_index: u64,
+    pub payload_source: PayloadSource,
+    pub fee_recipient: Address,
+    /// The value of the local payload in wei, if one was produced.
+    #[serde(with = "option_u256_dec")]
+    #[ssz(with = "four_byte_option_u256")]
+    pub local_value: Option<Uint256>,
+    /// The value of the builder bid in wei, if a valid bid was received.
+    #[serde(with = "option_u256_dec")]
+    #[ssz(with = "four_byte_option_u256")]
+    pub builder_value: Option<Uint256>,
+    /// Why a local payload was used, if a builder is configured.
+    #[ssz(with = "four_byte_option_fallback_reason")]
+    pub builder_fallback_reason: Option<BuilderFallbackReason>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ProposalsQuery {
+    pub start_slot: Option<Slot>,
+    pub end_slot: Option<Slot>,
+}
+
+/// Serializes an `Option<Uint256>` as an optional decimal string, like `serde_utils::u256_dec`.
+mod option_u256_dec {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Uint256>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_some(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Uint256>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| {
+                Uint256::from_dec_str(&value)
+                    .map_err(|e| serde::de::Error::custom(format!("invalid u256: {:?}", e)))
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> ProposalRecord {
+        ProposalRecord {
+            slot: Slot::new(1),
+            block_root: Hash256::repeat_byte(1),
+            proposer_index: 2,
+            payload_source: PayloadSource::Local,
+            fee_recipient: Address::repeat_byte(3),
+            local_value: Some(Uint256::from(20_000_000_000_000_000u128)),
+            builder_value: None,
+            builder_fallback_reason: Some(BuilderFallbackReason::NoBid),
+        }
+    }
+
+    #[test]
+    fn proposal_record_json_round_trip() {
+        let record = record();
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["local_value"], "20000000000000000");
+        assert_eq!(json["builder_value"], serde_json::Value::Null);
+        assert_eq!(json["builder_fallback_reason"], "no_bid");
+        assert_eq!(
+            serde_json::from_value::<ProposalRecord>(json).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn proposal_record_ssz_round_trip() {
+        use ssz::{Decode, Encode};
+
+        let record = record();
+        let bytes = record.as_ssz_bytes();
+        assert_eq!(ProposalRecord::from_ssz_bytes(&bytes).unwrap(), record);
+    }
+}
@@ -16,7 +16,7 @@ use std::path::Path;
 
 pub use reqwest;
 pub use reqwest::{Response, StatusCode, Url};
-use types::graffiti::GraffitiString;
+use types::graffiti::{GraffitiString, GraffitiTemplate};
 
 /// A wrapper around `reqwest::Client` which provides convenience methods for interfacing with a
 /// Lighthouse Validator Client HTTP server (`validator_client/src/http_api`).
@@ -375,6 +375,21 @@ impl ValidatorClientHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/beacon/health`
+    pub async fn get_lighthouse_beacon_health(
+        &self,
+    ) -> Result<GenericResponse<Vec<BeaconNodeHealthData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("health");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/spec`
     pub async fn get_lighthouse_spec<T: Serialize + DeserializeOwned>(
         &self,
@@ -419,6 +434,34 @@ impl ValidatorClientHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET lighthouse/validators/{validator_pubkey}/history`
+    pub async fn get_lighthouse_validators_pubkey_history(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        from_epoch: Option<Epoch>,
+        to_epoch: Option<Epoch>,
+    ) -> Result<GenericResponse<Vec<DutyRecord>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&validator_pubkey.to_string())
+            .push("history");
+
+        if let Some(from_epoch) = from_epoch {
+            path.query_pairs_mut()
+                .append_pair("from_epoch", &from_epoch.to_string());
+        }
+        if let Some(to_epoch) = to_epoch {
+            path.query_pairs_mut()
+                .append_pair("to_epoch", &to_epoch.to_string());
+        }
+
+        self.get(path).await
+    }
+
     /// `POST lighthouse/validators`
     pub async fn post_lighthouse_validators(
         &self,
@@ -482,6 +525,24 @@ impl ValidatorClientHttpClient {
         self.post(path, &request).await
     }
 
+    /// `POST lighthouse/validators/{validator_pubkey}/threshold_signature`
+    pub async fn post_lighthouse_validators_threshold_signature<E: EthSpec>(
+        &self,
+        voting_pubkey: &PublicKeyBytes,
+        request: &ThresholdSignatureRequest<E>,
+    ) -> Result<GenericResponse<ThresholdSignatureResponse>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&voting_pubkey.to_string())
+            .push("threshold_signature");
+
+        self.post(path, &request).await
+    }
+
     /// `PATCH lighthouse/validators/{validator_pubkey}`
     #[allow(clippy::too_many_arguments)]
     pub async fn patch_lighthouse_validators(
@@ -739,6 +800,19 @@ impl ValidatorClientHttpClient {
         &self,
         pubkey: &PublicKeyBytes,
         graffiti: GraffitiString,
+    ) -> Result<(), Error> {
+        let url = self.make_graffiti_url(pubkey)?;
+        let set_graffiti_request = SetGraffitiRequest {
+            graffiti: graffiti.into(),
+        };
+        self.post(url, &set_graffiti_request).await
+    }
+
+    /// `POST /eth/v1/validator/{pubkey}/graffiti`, with a graffiti template.
+    pub async fn set_graffiti_template(
+        &self,
+        pubkey: &PublicKeyBytes,
+        graffiti: GraffitiTemplate,
     ) -> Result<(), Error> {
         let url = self.make_graffiti_url(pubkey)?;
         let set_graffiti_request = SetGraffitiRequest { graffiti };
@@ -1,6 +1,6 @@
 use account_utils::ZeroizeString;
 use eth2_keystore::Keystore;
-use graffiti::GraffitiString;
+use graffiti::{GraffitiString, GraffitiTemplate};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -195,5 +195,128 @@ pub struct SingleExportKeystoresResponse {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SetGraffitiRequest {
-    pub graffiti: GraffitiString,
+    /// A graffiti, or a template with fields resolved when a block is proposed.
+    pub graffiti: GraffitiTemplate,
+}
+
+/// A message whose signing root is requested from a threshold peer.
+///
+/// The peer rebuilds the signing root of the message with its own domain, so that it never signs
+/// a root it does not know the message of. A block is sent as its header, which has the same
+/// signing root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", bound = "E: EthSpec")]
+pub enum ThresholdSignableMessage<E: EthSpec> {
+    RandaoReveal {
+        epoch: Epoch,
+    },
+    BlockHeader(BeaconBlockHeader),
+    AttestationData(AttestationData),
+    AggregateAndProof(AggregateAndProof<E>),
+    SelectionProof {
+        slot: Slot,
+    },
+    SyncSelectionProof(SyncAggregatorSelectionData),
+    SyncCommitteeMessage {
+        beacon_block_root: Hash256,
+        slot: Slot,
+    },
+    ContributionAndProof(ContributionAndProof<E>),
+    ValidatorRegistration(ValidatorRegistrationData),
+    VoluntaryExit(VoluntaryExit),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct ThresholdSignatureRequest<E: EthSpec> {
+    pub signing_root: Hash256,
+    /// The message to be signed. The peer checks that it has `signing_root`, and checks slashable
+    /// messages against its slashing protection database before returning a partial signature.
+    pub message: ThresholdSignableMessage<E>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdSignatureResponse {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub share_index: u64,
+    pub signature: Signature,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DutyKind {
+    Proposal,
+    Attestation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DutyStatus {
+    /// The duty is known but has not been performed yet.
+    Scheduled,
+    /// The message has been signed but not published yet.
+    Signed,
+    /// The message has been published to at least one beacon node.
+    Published,
+    /// The message could not be signed or published.
+    Failed,
+}
+
+/// A duty performed, or due to be performed, by a validator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DutyRecord {
+    pub kind: DutyKind,
+    pub slot: Slot,
+    pub status: DutyStatus,
+    /// The beacon nodes which accepted the message.
+    pub beacon_nodes: Vec<String>,
+    /// The time from the start of the slot until the message was signed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_delay_ms: Option<u64>,
+    /// The time from the start of the slot until the message was published.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_delay_ms: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DutyHistoryQuery {
+    pub from_epoch: Option<Epoch>,
+    pub to_epoch: Option<Epoch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeaconNodeStatus {
+    Synced,
+    NotSynced,
+    Offline,
+    Incompatible,
+    Uninitialized,
+}
+
+/// The health of a beacon node, as measured by the validator client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeHealthData {
+    /// The position of the beacon node in the `--beacon-nodes` flag.
+    pub index: usize,
+    pub endpoint: String,
+    pub status: BeaconNodeStatus,
+    /// From 0 to 100, healthier beacon nodes have a higher score.
+    pub score: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_distance: Option<Slot>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub el_offline: Option<bool>,
+    /// The recent proportion of failed requests, from 0 to 1.
+    pub error_rate: f64,
 }
@@ -594,6 +594,20 @@ pub struct MetaData {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VersionData {
     pub version: String,
+    /// The execution client driven by the beacon node, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_client: Option<ExecutionClientVersion>,
+}
+
+/// The identity of an execution client, as returned by `engine_getClientVersionV1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionClientVersion {
+    /// The two-letter client code, e.g. `GE` for Geth.
+    pub code: String,
+    pub name: String,
+    pub version: String,
+    /// The first four bytes of the commit hash, e.g. `0xfa4ff922`.
+    pub commit: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -21,6 +21,34 @@ pub const VERSION: &str = git_version!(
     fallback = "Lighthouse/v5.1.3"
 );
 
+/// Returns the git commit of this build, abbreviated to at least eight characters.
+///
+/// Unlike `VERSION`, no indication is given if the tree is dirty. This is the commit reported to
+/// the execution engine in the engine API `ClientVersionV1`.
+pub const COMMIT_PREFIX: &str = git_version!(
+    args = [
+        "--always",
+        "--abbrev=8",
+        // NOTE: using --match instead of --exclude for compatibility with old Git
+        "--match=thiswillnevermatchlol"
+    ],
+    prefix = "",
+    fallback = "00000000"
+);
+
+/// The two-letter code of Lighthouse in the engine API `ClientVersionV1`.
+pub const CLIENT_CODE: &str = "LH";
+
+/// Returns the version number of `VERSION`, without the client name or commit.
+///
+/// ## Example
+///
+/// `v1.5.1`
+pub fn version_number() -> &'static str {
+    let version = VERSION.strip_prefix("Lighthouse/").unwrap_or(VERSION);
+    version.split('-').next().unwrap_or(version)
+}
+
 /// Returns `VERSION`, but with platform information appended to the end.
 ///
 /// ## Example
@@ -35,6 +63,16 @@ mod test {
     use super::*;
     use regex::Regex;
 
+    #[test]
+    fn commit_prefix_formatting() {
+        let re = Regex::new(r"^[[:xdigit:]]{8,}$").unwrap();
+        assert!(
+            re.is_match(COMMIT_PREFIX),
+            "commit prefix doesn't match regex: {}",
+            COMMIT_PREFIX
+        );
+    }
+
     #[test]
     fn version_formatting() {
         let re =
@@ -46,4 +84,14 @@ mod test {
             VERSION
         );
     }
+
+    #[test]
+    fn version_number_formatting() {
+        let re = Regex::new(r"^v[0-9]+\.[0-9]+\.[0-9]+$").unwrap();
+        assert!(
+            re.is_match(version_number()),
+            "version number doesn't match regex: {}",
+            version_number()
+        );
+    }
 }
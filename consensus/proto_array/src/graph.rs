@@ -0,0 +1,253 @@
+//! Compact views of the `ProtoArray` DAG, intended for debugging fork choice and re-orgs.
+
+use crate::proto_array::{ProposerBoost, ProtoArray};
+use crate::ExecutionStatus;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use types::{Checkpoint, Epoch, Hash256, Slot};
+
+/// A `ProtoArray` reduced to the fields which are relevant to the shape of the block tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtoArrayGraph {
+    pub justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+    pub proposer_boost: ProposerBoost,
+    /// All nodes, ordered such that each parent precedes its children.
+    pub nodes: Vec<ProtoArrayGraphNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtoArrayGraphNode {
+    pub slot: Slot,
+    pub root: Hash256,
+    /// `None` for the root of the tree, or if the parent has been pruned.
+    pub parent_root: Option<Hash256>,
+    pub children: Vec<Hash256>,
+    pub weight: u64,
+    pub justified_epoch: Epoch,
+    pub finalized_epoch: Epoch,
+    pub unrealized_justified_epoch: Option<Epoch>,
+    pub unrealized_finalized_epoch: Option<Epoch>,
+    pub execution_status: ExecutionStatus,
+    pub best_child: Option<Hash256>,
+    pub best_descendant: Option<Hash256>,
+}
+
+impl From<&ProtoArray> for ProtoArrayGraph {
+    fn from(proto_array: &ProtoArray) -> Self {
+        let root_at = |index: Option<usize>| {
+            index
+                .and_then(|index| proto_array.nodes.get(index))
+                .map(|node| node.root)
+        };
+
+        let mut nodes = proto_array
+            .nodes
+            .iter()
+            .map(|node| ProtoArrayGraphNode {
+                slot: node.slot,
+                root: node.root,
+                parent_root: root_at(node.parent),
+                children: vec![],
+                weight: node.weight,
+                justified_epoch: node.justified_checkpoint.epoch,
+                finalized_epoch: node.finalized_checkpoint.epoch,
+                unrealized_justified_epoch: node
+                    .unrealized_justified_checkpoint
+                    .map(|checkpoint| checkpoint.epoch),
+                unrealized_finalized_epoch: node
+                    .unrealized_finalized_checkpoint
+                    .map(|checkpoint| checkpoint.epoch),
+                execution_status: node.execution_status,
+                best_child: root_at(node.best_child),
+                best_descendant: root_at(node.best_descendant),
+            })
+            .collect::<Vec<_>>();
+
+        for node in &proto_array.nodes {
+            if let Some(parent) = node.parent.and_then(|parent| nodes.get_mut(parent)) {
+                parent.children.push(node.root);
+            }
+        }
+
+        Self {
+            justified_checkpoint: proto_array.justified_checkpoint,
+            finalized_checkpoint: proto_array.finalized_checkpoint,
+            proposer_boost: proto_array.previous_proposer_boost,
+            nodes,
+        }
+    }
+}
+
+impl ProtoArrayGraph {
+    /// Render the graph in the Graphviz DOT language, e.g. for `dot -Tsvg`.
+    ///
+    /// Nodes are coloured by execution status, the justified and finalized blocks are drawn with
+    /// two and three borders respectively and edges to best children are drawn in bold.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // Writing to a `String` is infallible.
+        let _ = self.write_dot(&mut dot);
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String) -> std::fmt::Result {
+        writeln!(dot, "digraph proto_array {{")?;
+        writeln!(dot, "  rankdir=LR;")?;
+        writeln!(
+            dot,
+            "  node [shape=box, style=filled, fontname=\"monospace\"];"
+        )?;
+
+        for node in &self.nodes {
+            let mut label = format!(
+                "{}\\nslot {}\\nweight {}\\nJ {} / F {}",
+                short_root(node.root),
+                node.slot,
+                node.weight,
+                node.justified_epoch,
+                node.finalized_epoch,
+            );
+            if let (Some(justified), Some(finalized)) = (
+                node.unrealized_justified_epoch,
+                node.unrealized_finalized_epoch,
+            ) {
+                write!(label, "\\nUJ {} / UF {}", justified, finalized)?;
+            }
+            write!(
+                label,
+                "\\n{}",
+                execution_status_name(&node.execution_status)
+            )?;
+
+            let mut attributes = format!(
+                "label=\"{}\", fillcolor=\"{}\"",
+                label,
+                execution_status_colour(&node.execution_status)
+            );
+            if node.root == self.finalized_checkpoint.root {
+                attributes.push_str(", peripheries=3");
+            } else if node.root == self.justified_checkpoint.root {
+                attributes.push_str(", peripheries=2");
+            }
+            if node.root == self.proposer_boost.root && self.proposer_boost.score > 0 {
+                write!(
+                    attributes,
+                    ", color=\"blue\", xlabel=\"boost {}\"",
+                    self.proposer_boost.score
+                )?;
+            }
+
+            writeln!(dot, "  \"{:?}\" [{}];", node.root, attributes)?;
+        }
+
+        for node in &self.nodes {
+            for child in &node.children {
+                let style = if node.best_child == Some(*child) {
+                    " [penwidth=3]"
+                } else {
+                    ""
+                };
+                writeln!(dot, "  \"{:?}\" -> \"{:?}\"{};", node.root, child, style)?;
+            }
+        }
+
+        writeln!(dot, "}}")
+    }
+}
+
+/// The first four bytes of `root`, which is enough to tell blocks apart in a graph.
+fn short_root(root: Hash256) -> String {
+    let mut short = format!("{:?}", root);
+    short.truncate(10);
+    short
+}
+
+fn execution_status_name(status: &ExecutionStatus) -> &'static str {
+    match status {
+        ExecutionStatus::Valid(_) => "valid",
+        ExecutionStatus::Invalid(_) => "invalid",
+        ExecutionStatus::Optimistic(_) => "optimistic",
+        ExecutionStatus::Irrelevant(_) => "pre-merge",
+    }
+}
+
+fn execution_status_colour(status: &ExecutionStatus) -> &'static str {
+    match status {
+        ExecutionStatus::Valid(_) => "palegreen",
+        ExecutionStatus::Invalid(_) => "salmon",
+        ExecutionStatus::Optimistic(_) => "khaki",
+        ExecutionStatus::Irrelevant(_) => "lightgrey",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Block, ProtoArrayForkChoice};
+    use types::{AttestationShufflingId, MainnetEthSpec};
+
+    #[test]
+    fn graph_of_fork() {
+        let genesis_slot = Slot::new(0);
+        let genesis_root = Hash256::from_low_u64_be(1);
+        let shuffling_id = AttestationShufflingId::from_components(Epoch::new(0), Hash256::zero());
+        let genesis_checkpoint = Checkpoint {
+            epoch: Epoch::new(0),
+            root: genesis_root,
+        };
+
+        let mut fc = ProtoArrayForkChoice::new::<MainnetEthSpec>(
+            genesis_slot,
+            genesis_slot,
+            Hash256::zero(),
+            genesis_checkpoint,
+            genesis_checkpoint,
+            shuffling_id.clone(),
+            shuffling_id.clone(),
+            ExecutionStatus::irrelevant(),
+        )
+        .unwrap();
+
+        let children = [Hash256::from_low_u64_be(2), Hash256::from_low_u64_be(3)];
+        for root in children {
+            fc.core_proto_array_mut()
+                .on_block::<MainnetEthSpec>(
+                    Block {
+                        slot: genesis_slot + 1,
+                        root,
+                        parent_root: Some(genesis_root),
+                        state_root: Hash256::zero(),
+                        target_root: genesis_root,
+                        current_epoch_shuffling_id: shuffling_id.clone(),
+                        next_epoch_shuffling_id: shuffling_id.clone(),
+                        justified_checkpoint: genesis_checkpoint,
+                        finalized_checkpoint: genesis_checkpoint,
+                        execution_status: ExecutionStatus::irrelevant(),
+                        unrealized_justified_checkpoint: Some(genesis_checkpoint),
+                        unrealized_finalized_checkpoint: Some(genesis_checkpoint),
+                    },
+                    genesis_slot + 1,
+                )
+                .unwrap();
+        }
+
+        let graph = ProtoArrayGraph::from(fc.core_proto_array());
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.nodes[0].root, genesis_root);
+        assert_eq!(graph.nodes[0].parent_root, None);
+        assert_eq!(graph.nodes[0].children, children);
+        for (node, root) in graph.nodes[1..].iter().zip(children) {
+            assert_eq!(node.root, root);
+            assert_eq!(node.parent_root, Some(genesis_root));
+            assert!(node.children.is_empty());
+        }
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph proto_array {"));
+        for child in children {
+            assert!(dot.contains(&format!("\"{:?}\" -> \"{:?}\"", genesis_root, child)));
+        }
+        assert!(dot.contains("peripheries=3"));
+    }
+}
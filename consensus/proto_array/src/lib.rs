@@ -1,10 +1,12 @@
 mod error;
 pub mod fork_choice_test_definition;
+mod graph;
 mod justified_balances;
 mod proto_array;
 mod proto_array_fork_choice;
 mod ssz_container;
 
+pub use crate::graph::{ProtoArrayGraph, ProtoArrayGraphNode};
 pub use crate::justified_balances::JustifiedBalances;
 pub use crate::proto_array::{calculate_committee_fraction, InvalidationOperation};
 pub use crate::proto_array_fork_choice::{
@@ -89,6 +89,164 @@ impl Into<Graffiti> for GraffitiString {
     }
 }
 
+/// The maximum length of a `GraffitiTemplate`, before its fields are resolved.
+pub const GRAFFITI_TEMPLATE_MAX_LEN: usize = 128;
+
+/// The fields which may be used in a `GraffitiTemplate`, as `{field}`.
+pub const GRAFFITI_TEMPLATE_FIELDS: &[&str] = &[
+    "client",
+    "version",
+    "el_client",
+    "el_version",
+    "validator_index",
+];
+
+/// The values of the fields of a `GraffitiTemplate`, unknown values resolve to an empty string.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraffitiTemplateFields {
+    pub client: String,
+    pub version: String,
+    pub el_client: Option<String>,
+    pub el_version: Option<String>,
+    pub validator_index: Option<u64>,
+}
+
+impl GraffitiTemplateFields {
+    fn get(&self, field: &str) -> Option<String> {
+        match field {
+            "client" => Some(self.client.clone()),
+            "version" => Some(self.version.clone()),
+            "el_client" => self.el_client.clone(),
+            "el_version" => self.el_version.clone(),
+            "validator_index" => self.validator_index.map(|index| index.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// A graffiti which may contain fields, e.g. `{client}{version}-{el_client}-{validator_index}`,
+/// resolved when a block is proposed.
+///
+/// Braces which do not enclose a known field are kept as literal text, so that graffiti written
+/// before templates were supported keep their meaning.
+///
+/// A template without any fields is a plain graffiti, so it is limited to `GRAFFITI_BYTES_LEN`
+/// bytes. A resolved template is truncated to `GRAFFITI_BYTES_LEN` bytes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct GraffitiTemplate(String);
+
+/// A part of a `GraffitiTemplate`.
+#[derive(Debug, PartialEq)]
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Field(&'a str),
+}
+
+impl GraffitiTemplate {
+    /// Splits the template into literal text and known fields.
+    fn parts(&self) -> Vec<TemplatePart<'_>> {
+        let template = self.0.as_str();
+        let mut parts = vec![];
+        let mut text_start = 0;
+        let mut search_start = 0;
+        while let Some(offset) = template[search_start..].find('{') {
+            let start = search_start + offset;
+            let field = template[start + 1..]
+                .find('}')
+                .map(|len| &template[start + 1..start + 1 + len])
+                .filter(|field| GRAFFITI_TEMPLATE_FIELDS.contains(field));
+            if let Some(field) = field {
+                if text_start < start {
+                    parts.push(TemplatePart::Text(&template[text_start..start]));
+                }
+                parts.push(TemplatePart::Field(field));
+                text_start = start + field.len() + 2;
+                search_start = text_start;
+            } else {
+                // Not a field, the brace is literal text.
+                search_start = start + 1;
+            }
+        }
+        if text_start < template.len() {
+            parts.push(TemplatePart::Text(&template[text_start..]));
+        }
+        parts
+    }
+
+    /// Does the template contain any fields?
+    pub fn has_fields(&self) -> bool {
+        self.parts()
+            .iter()
+            .any(|part| matches!(part, TemplatePart::Field(_)))
+    }
+
+    /// Returns the template as a `GraffitiString` if it does not contain any fields.
+    pub fn as_graffiti_string(&self) -> Option<GraffitiString> {
+        if self.has_fields() {
+            None
+        } else {
+            GraffitiString::from_str(&self.0).ok()
+        }
+    }
+
+    /// Resolve the fields of the template.
+    pub fn render(&self, fields: &GraffitiTemplateFields) -> Graffiti {
+        let mut rendered = String::new();
+        for part in self.parts() {
+            match part {
+                TemplatePart::Text(text) => rendered.push_str(text),
+                TemplatePart::Field(field) => {
+                    rendered.push_str(&fields.get(field).unwrap_or_default())
+                }
+            }
+        }
+
+        // Truncate at a character boundary.
+        let mut len = std::cmp::min(rendered.len(), GRAFFITI_BYTES_LEN);
+        while !rendered.is_char_boundary(len) {
+            len -= 1;
+        }
+        rendered.truncate(len);
+        GraffitiString(rendered).into()
+    }
+}
+
+impl FromStr for GraffitiTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > GRAFFITI_TEMPLATE_MAX_LEN {
+            return Err(format!(
+                "Graffiti template exceeds max length {}",
+                GRAFFITI_TEMPLATE_MAX_LEN
+            ));
+        }
+
+        let template = Self(s.to_string());
+        if !template.has_fields() {
+            GraffitiString::from_str(s)?;
+        }
+        Ok(template)
+    }
+}
+
+impl<'de> Deserialize<'de> for GraffitiTemplate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: String = serde::Deserialize::deserialize(deserializer)?;
+        GraffitiTemplate::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<GraffitiString> for GraffitiTemplate {
+    fn from(graffiti: GraffitiString) -> Self {
+        Self(graffiti.0)
+    }
+}
+
 pub mod serde_graffiti {
     use super::*;
 
@@ -177,3 +335,69 @@ impl TestRandom for Graffiti {
         Self::from(Hash256::random_for_test(rng).to_fixed_bytes())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn graffiti(s: &str) -> Graffiti {
+        GraffitiString::from_str(s).unwrap().into()
+    }
+
+    #[test]
+    fn graffiti_template_parsing() {
+        assert!(
+            GraffitiTemplate::from_str("{client}{version}-{el_client}-{validator_index}").is_ok()
+        );
+        // Braces which do not enclose a known field are plain graffiti.
+        for plain in ["{unknown}", "{client", "client}", "}{", "{}", ":-{"] {
+            let template = GraffitiTemplate::from_str(plain).unwrap();
+            assert!(!template.has_fields());
+            assert_eq!(
+                template.as_graffiti_string(),
+                Some(GraffitiString::from_str(plain).unwrap())
+            );
+        }
+        assert!(GraffitiTemplate::from_str(&format!("{{{}}}", "a".repeat(31))).is_err());
+        // A template without fields is a plain graffiti.
+        assert!(GraffitiTemplate::from_str(&"a".repeat(GRAFFITI_BYTES_LEN)).is_ok());
+        assert!(GraffitiTemplate::from_str(&"a".repeat(GRAFFITI_BYTES_LEN + 1)).is_err());
+        assert_eq!(
+            GraffitiTemplate::from_str("plain")
+                .unwrap()
+                .as_graffiti_string(),
+            Some(GraffitiString::from_str("plain").unwrap())
+        );
+    }
+
+    #[test]
+    fn graffiti_template_rendering() {
+        let template =
+            GraffitiTemplate::from_str("{client}{version}-{el_client}-{validator_index}").unwrap();
+        let mut fields = GraffitiTemplateFields {
+            client: "LH".to_string(),
+            version: "v5.1.3".to_string(),
+            el_client: Some("GE".to_string()),
+            el_version: Some("v1.13.14".to_string()),
+            validator_index: Some(42),
+        };
+        assert_eq!(template.render(&fields), graffiti("LHv5.1.3-GE-42"));
+
+        fields.el_client = None;
+        assert_eq!(template.render(&fields), graffiti("LHv5.1.3--42"));
+
+        // Braces around anything other than a known field are kept.
+        let template = GraffitiTemplate::from_str("{hi} {{client}} }{validator_index{").unwrap();
+        assert_eq!(
+            template.render(&fields),
+            graffiti("{hi} {LH} }{validator_index{")
+        );
+
+        // The rendered graffiti is truncated at a character boundary.
+        let template = GraffitiTemplate::from_str("{validator_index}éééééééééééééééé").unwrap();
+        assert_eq!(
+            template.render(&fields),
+            graffiti(&format!("42{}", "é".repeat(15)))
+        );
+    }
+}
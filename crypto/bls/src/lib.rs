@@ -32,6 +32,8 @@ mod get_withdrawal_credentials;
 mod zeroize_hash;
 
 pub mod impls;
+#[cfg(feature = "supranational")]
+pub mod threshold;
 
 pub use generic_public_key::{INFINITY_PUBLIC_KEY, PUBLIC_KEY_BYTES_LEN};
 pub use generic_secret_key::SECRET_KEY_BYTES_LEN;
@@ -57,6 +59,10 @@ pub enum Error {
     InvalidInfinityPublicKey,
     /// The secret key is all zero bytes, which is invalid.
     InvalidZeroSecretKey,
+    /// The threshold is zero or greater than the number of shares.
+    InvalidThreshold { threshold: usize, shares: usize },
+    /// The share index is zero or appears more than once.
+    InvalidShareIndex(u64),
 }
 
 #[cfg(feature = "supranational")]
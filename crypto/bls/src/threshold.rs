@@ -0,0 +1,211 @@
+//! Provides threshold BLS signatures, using Shamir's secret sharing.
+//!
+//! A secret key is split into `n` shares such that the signatures of any `threshold` of those
+//! shares can be combined into the signature of the original secret key. Share `i` is the
+//! evaluation at `x = i` of a random polynomial of degree `threshold - 1` whose constant term is
+//! the secret key. Signatures are combined by Lagrange interpolation at `x = 0`.
+//!
+//! Since BLS signatures are deterministic, a combined signature is identical to a signature
+//! produced by the original secret key.
+
+use crate::{Error, SecretKey, Signature, SECRET_KEY_BYTES_LEN, SIGNATURE_BYTES_LEN};
+use blst::{blst_fr, blst_p2, blst_p2_affine, blst_scalar, BLST_ERROR};
+use std::collections::HashSet;
+use zeroize::Zeroize;
+
+/// The number of bits of a scalar of the BLS12-381 curve.
+const SCALAR_BITS: usize = 255;
+
+/// Splits `secret_key` into `num_shares` shares, any `threshold` of which are able to produce
+/// signatures for `secret_key`.
+///
+/// Returns `(index, share)` pairs, where the indices are `1..=num_shares`.
+pub fn split_secret_key(
+    secret_key: &SecretKey,
+    threshold: usize,
+    num_shares: usize,
+) -> Result<Vec<(u64, SecretKey)>, Error> {
+    if threshold == 0 || threshold > num_shares {
+        return Err(Error::InvalidThreshold {
+            threshold,
+            shares: num_shares,
+        });
+    }
+
+    // The constant term is the secret key, all other coefficients are random.
+    let mut coefficients = Vec::with_capacity(threshold);
+    coefficients.push(fr_from_secret_key(secret_key));
+    for _ in 1..threshold {
+        coefficients.push(fr_from_secret_key(&SecretKey::random()));
+    }
+
+    let shares = (1..=num_shares as u64)
+        .map(|index| {
+            let x = fr_from_u64(index);
+            // Evaluate the polynomial at `x` using Horner's method.
+            let mut share = coefficients[threshold - 1];
+            for coefficient in coefficients[..threshold - 1].iter().rev() {
+                let product = fr_mul(&share, &x);
+                share = fr_add(&product, coefficient);
+            }
+            secret_key_from_fr(&share).map(|share| (index, share))
+        })
+        .collect();
+
+    for coefficient in &mut coefficients {
+        zeroize_fr(coefficient);
+    }
+    shares
+}
+
+/// Combines the signatures of at least `threshold` distinct shares into the signature of the
+/// secret key the shares were split from.
+///
+/// The signatures must be of the same message and must have been verified against the public keys
+/// of their shares. Combining fewer than `threshold` signatures yields an invalid signature.
+pub fn combine_signatures(signatures: &[(u64, Signature)]) -> Result<Signature, Error> {
+    if signatures.is_empty() {
+        return Err(Error::InvalidThreshold {
+            threshold: 1,
+            shares: 0,
+        });
+    }
+
+    let mut seen = HashSet::with_capacity(signatures.len());
+    for (index, _) in signatures {
+        if *index == 0 || !seen.insert(*index) {
+            return Err(Error::InvalidShareIndex(*index));
+        }
+    }
+
+    let indices = signatures
+        .iter()
+        .map(|(index, _)| *index)
+        .collect::<Vec<_>>();
+
+    // The default point has `z = 0`, which is the point at infinity.
+    let mut combined = blst_p2::default();
+    for (index, signature) in signatures {
+        let point = p2_from_signature(signature)?;
+        let term = p2_mult(&point, &lagrange_coefficient(*index, &indices));
+        let sum = combined;
+        unsafe {
+            blst::blst_p2_add_or_double(&mut combined, &sum, &term);
+        }
+    }
+
+    signature_from_p2(&combined)
+}
+
+/// Returns the Lagrange coefficient of `index` for interpolation at `x = 0` over `indices`.
+fn lagrange_coefficient(index: u64, indices: &[u64]) -> blst_fr {
+    let x_i = fr_from_u64(index);
+    let mut numerator = fr_from_u64(1);
+    let mut denominator = fr_from_u64(1);
+    for &other in indices.iter().filter(|&&other| other != index) {
+        let x_j = fr_from_u64(other);
+        numerator = fr_mul(&numerator, &x_j);
+        denominator = fr_mul(&denominator, &fr_sub(&x_j, &x_i));
+    }
+
+    let mut inverse = blst_fr::default();
+    unsafe {
+        blst::blst_fr_inverse(&mut inverse, &denominator);
+    }
+    fr_mul(&numerator, &inverse)
+}
+
+fn fr_from_u64(value: u64) -> blst_fr {
+    let limbs = [value, 0, 0, 0];
+    let mut out = blst_fr::default();
+    unsafe {
+        blst::blst_fr_from_uint64(&mut out, limbs.as_ptr());
+    }
+    out
+}
+
+fn fr_add(a: &blst_fr, b: &blst_fr) -> blst_fr {
+    let mut out = blst_fr::default();
+    unsafe {
+        blst::blst_fr_add(&mut out, a, b);
+    }
+    out
+}
+
+fn fr_sub(a: &blst_fr, b: &blst_fr) -> blst_fr {
+    let mut out = blst_fr::default();
+    unsafe {
+        blst::blst_fr_sub(&mut out, a, b);
+    }
+    out
+}
+
+fn fr_mul(a: &blst_fr, b: &blst_fr) -> blst_fr {
+    let mut out = blst_fr::default();
+    unsafe {
+        blst::blst_fr_mul(&mut out, a, b);
+    }
+    out
+}
+
+fn zeroize_fr(fr: &mut blst_fr) {
+    fr.l.zeroize();
+}
+
+fn fr_from_secret_key(secret_key: &SecretKey) -> blst_fr {
+    let bytes = secret_key.serialize();
+    let mut scalar = blst_scalar::default();
+    let mut out = blst_fr::default();
+    unsafe {
+        blst::blst_scalar_from_bendian(&mut scalar, bytes.as_bytes().as_ptr());
+        blst::blst_fr_from_scalar(&mut out, &scalar);
+    }
+    scalar.b.zeroize();
+    out
+}
+
+fn secret_key_from_fr(fr: &blst_fr) -> Result<SecretKey, Error> {
+    let mut scalar = blst_scalar::default();
+    let mut bytes = [0; SECRET_KEY_BYTES_LEN];
+    unsafe {
+        blst::blst_scalar_from_fr(&mut scalar, fr);
+        blst::blst_bendian_from_scalar(bytes.as_mut_ptr(), &scalar);
+    }
+    let secret_key = SecretKey::deserialize(&bytes);
+    scalar.b.zeroize();
+    bytes.zeroize();
+    secret_key
+}
+
+fn p2_from_signature(signature: &Signature) -> Result<blst_p2, Error> {
+    let bytes = signature.serialize();
+    let mut affine = blst_p2_affine::default();
+    let result = unsafe { blst::blst_p2_uncompress(&mut affine, bytes.as_ptr()) };
+    if result != BLST_ERROR::BLST_SUCCESS {
+        return Err(Error::BlstError(result));
+    }
+
+    let mut point = blst_p2::default();
+    unsafe {
+        blst::blst_p2_from_affine(&mut point, &affine);
+    }
+    Ok(point)
+}
+
+fn signature_from_p2(point: &blst_p2) -> Result<Signature, Error> {
+    let mut bytes = [0; SIGNATURE_BYTES_LEN];
+    unsafe {
+        blst::blst_p2_compress(bytes.as_mut_ptr(), point);
+    }
+    Signature::deserialize(&bytes)
+}
+
+fn p2_mult(point: &blst_p2, fr: &blst_fr) -> blst_p2 {
+    let mut scalar = blst_scalar::default();
+    let mut out = blst_p2::default();
+    unsafe {
+        blst::blst_scalar_from_fr(&mut scalar, fr);
+        blst::blst_p2_mult(&mut out, point, scalar.b.as_ptr(), SCALAR_BITS);
+    }
+    out
+}
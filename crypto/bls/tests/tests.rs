@@ -509,3 +509,75 @@ macro_rules! test_suite {
 mod blst {
     test_suite!(blst_implementations);
 }
+
+mod threshold {
+    use bls::threshold::{combine_signatures, split_secret_key};
+    use bls::{Error, Hash256, SecretKey};
+
+    #[test]
+    fn any_threshold_of_shares_signs_for_the_secret_key() {
+        let secret_key = SecretKey::random();
+        let msg = Hash256::from_low_u64_be(42);
+        let expected = secret_key.sign(msg);
+
+        let shares = split_secret_key(&secret_key, 2, 3).unwrap();
+        assert_eq!(shares.len(), 3);
+        let signatures = shares
+            .iter()
+            .map(|(index, share)| (*index, share.sign(msg)))
+            .collect::<Vec<_>>();
+
+        for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+            let combined =
+                combine_signatures(&[signatures[a].clone(), signatures[b].clone()]).unwrap();
+            assert_eq!(combined, expected);
+            assert!(combined.verify(&secret_key.public_key(), msg));
+        }
+        assert_eq!(combine_signatures(&signatures).unwrap(), expected);
+    }
+
+    #[test]
+    fn fewer_than_threshold_shares_do_not_sign() {
+        let secret_key = SecretKey::random();
+        let msg = Hash256::from_low_u64_be(42);
+
+        let shares = split_secret_key(&secret_key, 3, 5).unwrap();
+        let signatures = shares
+            .iter()
+            .take(2)
+            .map(|(index, share)| (*index, share.sign(msg)))
+            .collect::<Vec<_>>();
+
+        let combined = combine_signatures(&signatures).unwrap();
+        assert!(!combined.verify(&secret_key.public_key(), msg));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let secret_key = SecretKey::random();
+        assert_eq!(
+            split_secret_key(&secret_key, 0, 3).unwrap_err(),
+            Error::InvalidThreshold {
+                threshold: 0,
+                shares: 3
+            }
+        );
+        assert_eq!(
+            split_secret_key(&secret_key, 4, 3).unwrap_err(),
+            Error::InvalidThreshold {
+                threshold: 4,
+                shares: 3
+            }
+        );
+
+        let signature = secret_key.sign(Hash256::zero());
+        assert_eq!(
+            combine_signatures(&[(0, signature.clone())]).unwrap_err(),
+            Error::InvalidShareIndex(0)
+        );
+        assert_eq!(
+            combine_signatures(&[(1, signature.clone()), (1, signature)]).unwrap_err(),
+            Error::InvalidShareIndex(1)
+        );
+    }
+}
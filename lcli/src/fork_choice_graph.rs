@@ -0,0 +1,67 @@
+//! # Fork Choice Graph
+//!
+//! Use this tool to render the fork choice block tree which a beacon node persisted to its
+//! database, for investigating re-orgs after the fact.
+//!
+//! The beacon node must not be running, since it holds a lock on the database.
+//!
+//! ## Examples
+//!
+//! Render the block tree as an SVG using Graphviz.
+//!
+//! ```ignore
+//! lcli fork-choice-graph \
+//!     --db-path ~/.lighthouse/mainnet/beacon/chain_db \
+//!     --output-path /tmp/fork_choice.dot
+//! dot -Tsvg /tmp/fork_choice.dot > /tmp/fork_choice.svg
+//! ```
+//!
+//! Print the block tree as JSON.
+//!
+//! ```ignore
+//! lcli fork-choice-graph \
+//!     --db-path ~/.lighthouse/mainnet/beacon/chain_db \
+//!     --format json
+//! ```
+use beacon_chain::{PersistedForkChoice, FORK_CHOICE_DB_KEY};
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use proto_array::{ProtoArrayForkChoice, ProtoArrayGraph};
+use std::fs;
+use std::path::PathBuf;
+use store::{ItemStore, LevelDB};
+use types::EthSpec;
+
+pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let db_path: PathBuf = parse_required(matches, "db-path")?;
+    let format: String = parse_required(matches, "format")?;
+    let output_path: Option<PathBuf> = parse_optional(matches, "output-path")?;
+
+    let db = LevelDB::<T>::open(&db_path)
+        .map_err(|e| format!("Unable to open database at {}: {:?}", db_path.display(), e))?;
+    let persisted_fork_choice = db
+        .get::<PersistedForkChoice>(&FORK_CHOICE_DB_KEY)
+        .map_err(|e| format!("Unable to read fork choice: {:?}", e))?
+        .ok_or("No fork choice found in the database")?;
+    let fork_choice =
+        ProtoArrayForkChoice::from_bytes(&persisted_fork_choice.fork_choice.proto_array_bytes)?;
+
+    let graph = ProtoArrayGraph::from(fork_choice.core_proto_array());
+    info!("Loaded fork choice with {} blocks", graph.nodes.len());
+
+    let output = match format.as_str() {
+        "dot" => graph.to_dot(),
+        "json" => serde_json::to_string_pretty(&graph)
+            .map_err(|e| format!("Unable to serialize graph: {:?}", e))?,
+        other => return Err(format!("Unknown format: {}", other)),
+    };
+
+    if let Some(output_path) = output_path {
+        fs::write(&output_path, output)
+            .map_err(|e| format!("Unable to write to {}: {:?}", output_path.display(), e))?;
+    } else {
+        println!("{}", output);
+    }
+
+    Ok(())
+}
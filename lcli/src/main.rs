@@ -6,15 +6,18 @@ mod check_deposit_data;
 mod create_payload_header;
 mod deploy_deposit_contract;
 mod eth1_genesis;
+mod fork_choice_graph;
 mod generate_bootnode_enr;
 mod indexed_attestations;
 mod insecure_validators;
 mod interop_genesis;
 mod mnemonic_validators;
+mod mock_builder;
 mod mock_el;
 mod new_testnet;
 mod parse_ssz;
 mod replace_state_pubkeys;
+mod replay_fork_choice;
 mod skip_slots;
 mod state_root;
 mod transition_blocks;
@@ -939,6 +942,83 @@ fn main() {
                         .help("Number of repeat runs, useful for benchmarking."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("fork-choice-graph")
+                .about("Renders the fork choice block tree persisted in a beacon node database.")
+                .arg(
+                    Arg::with_name("db-path")
+                        .long("db-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the hot database of a beacon node which is not running, \
+                            e.g. ~/.lighthouse/mainnet/beacon/chain_db."),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .default_value("dot")
+                        .possible_values(&["dot", "json"])
+                        .help("Output format: a Graphviz DOT digraph, or a JSON list of nodes."),
+                )
+                .arg(
+                    Arg::with_name("output-path")
+                        .long("output-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Path to write the output to. Defaults to stdout."),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("replay-fork-choice")
+                .about("Replays blocks from a beacon node database through fork choice and \
+                    reports every change of head.")
+                .arg(
+                    Arg::with_name("datadir")
+                        .long("datadir")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Data directory of a beacon node which is not running, \
+                            e.g. ~/.lighthouse/mainnet. Blocks are loaded from its database."),
+                )
+                .arg(
+                    Arg::with_name("fork-choice-db-path")
+                        .long("fork-choice-db-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Path to the hot database of an earlier copy of the beacon node \
+                            database to load the initial fork choice from. Defaults to the fork \
+                            choice in --datadir."),
+                )
+                .arg(
+                    Arg::with_name("end-slot")
+                        .long("end-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .help("Last slot to replay. Defaults to the slot of the latest block."),
+                )
+                .arg(
+                    Arg::with_name("block-delay-ms")
+                        .long("block-delay-ms")
+                        .value_name("MILLIS")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Delay after the start of its slot at which each block is treated \
+                            as arriving. Blocks arriving later than a third of a slot don't \
+                            receive proposer boost."),
+                )
+                .arg(
+                    Arg::with_name("slots-per-restore-point")
+                        .long("slots-per-restore-point")
+                        .value_name("SLOT_COUNT")
+                        .takes_value(true)
+                        .help("The --slots-per-restore-point the beacon node was run with, if \
+                            not the default."),
+                )
+        )
         .subcommand(
             SubCommand::with_name("mock-el")
                 .about("Creates a mock execution layer server. This is NOT SAFE and should only \
@@ -993,6 +1073,98 @@ fn main() {
                         .help("The payload timestamp that enables Cancun. No default is provided \
                                 until Cancun is triggered on mainnet.")
                 )
+                .arg(
+                    Arg::with_name("replay")
+                        .long("replay")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Answer all requests from a recording made with the beacon node's \
+                            --execution-recording-dir flag. Either a single recording file or \
+                            the recording directory may be provided.")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("mock-builder")
+                .about("Creates a mock builder server which builds payloads with an execution \
+                engine and serves them over the builder API. Its bids can be made slow, invalid \
+                or withheld to test the builder fallback logic of the beacon node. This is NOT \
+                SAFE and should only be used for testing and development on local devnets.")
+                .arg(
+                    Arg::with_name("execution-endpoint")
+                        .long("execution-endpoint")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("The engine API endpoint of the execution engine or mock EL to \
+                            build payloads with.")
+                        .default_value("http://localhost:8551")
+                )
+                .arg(
+                    Arg::with_name("jwt-secret")
+                        .long("jwt-secret")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the JWT secret of the execution endpoint."),
+                )
+                .arg(
+                    Arg::with_name("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("The HTTP API of the beacon node whose head is built upon.")
+                        .default_value("http://localhost:5052")
+                )
+                .arg(
+                    Arg::with_name("listen-address")
+                        .long("listen-address")
+                        .value_name("IP_ADDRESS")
+                        .takes_value(true)
+                        .help("The server will listen on this address.")
+                        .default_value("127.0.0.1")
+                )
+                .arg(
+                    Arg::with_name("listen-port")
+                        .long("listen-port")
+                        .value_name("PORT")
+                        .takes_value(true)
+                        .help("The server will listen on this port.")
+                        .default_value("18550")
+                )
+                .arg(
+                    Arg::with_name("bid-value")
+                        .long("bid-value")
+                        .value_name("WEI")
+                        .takes_value(true)
+                        .help("The value of every bid, in wei. Defaults to 0.02 ETH.")
+                )
+                .arg(
+                    Arg::with_name("header-delay-ms")
+                        .long("header-delay-ms")
+                        .value_name("MILLISECONDS")
+                        .takes_value(true)
+                        .help("Delay responses to requests for bids by this many milliseconds.")
+                        .default_value("0")
+                )
+                .arg(
+                    Arg::with_name("invalid-bids")
+                        .long("invalid-bids")
+                        .takes_value(false)
+                        .help("Sign bids with an invalid signature, so that the beacon node \
+                            rejects them.")
+                )
+                .arg(
+                    Arg::with_name("withhold-bids")
+                        .long("withhold-bids")
+                        .takes_value(false)
+                        .help("Respond to all requests for bids with no bid.")
+                )
+                .arg(
+                    Arg::with_name("withhold-payloads")
+                        .long("withhold-payloads")
+                        .takes_value(false)
+                        .help("Refuse to reveal the payloads of signed blinded blocks, so that \
+                            the proposals are missed.")
+                )
         )
         .get_matches();
 
@@ -1135,8 +1307,17 @@ fn run<T: EthSpec>(
             state_root::run::<T>(env, network_config, matches)
                 .map_err(|e| format!("Failed to run state-root command: {}", e))
         }
+        ("fork-choice-graph", Some(matches)) => fork_choice_graph::run::<T>(matches)
+            .map_err(|e| format!("Failed to run fork-choice-graph command: {}", e)),
+        ("replay-fork-choice", Some(matches)) => {
+            let network_config = get_network_config()?;
+            replay_fork_choice::run::<T>(env, network_config, matches)
+                .map_err(|e| format!("Failed to run replay-fork-choice command: {}", e))
+        }
         ("mock-el", Some(matches)) => mock_el::run::<T>(env, matches)
             .map_err(|e| format!("Failed to run mock-el command: {}", e)),
+        ("mock-builder", Some(matches)) => mock_builder::run::<T>(env, matches)
+            .map_err(|e| format!("Failed to run mock-builder command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
     }
 }
@@ -0,0 +1,65 @@
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use environment::Environment;
+use execution_layer::test_utils::{serve_mock_builder, MockBuilder};
+use sensitive_url::SensitiveUrl;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::time::Duration;
+use types::*;
+
+pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let execution_endpoint: SensitiveUrl = parse_required(matches, "execution-endpoint")?;
+    let jwt_secret_path: PathBuf = parse_required(matches, "jwt-secret")?;
+    let beacon_url: SensitiveUrl = parse_required(matches, "beacon-url")?;
+    let listen_addr: Ipv4Addr = parse_required(matches, "listen-address")?;
+    let listen_port: u16 = parse_required(matches, "listen-port")?;
+    let bid_value = matches
+        .value_of("bid-value")
+        .map(Uint256::from_dec_str)
+        .transpose()
+        .map_err(|e| format!("Invalid --bid-value: {:?}", e))?;
+    let header_delay_ms: u64 = parse_required(matches, "header-delay-ms")?;
+    let invalid_bids = matches.is_present("invalid-bids");
+    let withhold_bids = matches.is_present("withhold-bids");
+    let withhold_payloads = matches.is_present("withhold-payloads");
+
+    let context = env.core_context();
+    let spec = context.eth2_config.spec.clone();
+    let executor = context.executor;
+
+    let builder = MockBuilder::<T>::from_endpoints(
+        execution_endpoint,
+        jwt_secret_path,
+        beacon_url,
+        spec,
+        executor.clone(),
+    )
+    .map_err(|e| format!("Unable to connect to the execution endpoint: {:?}", e))?;
+
+    if let Some(bid_value) = bid_value {
+        builder.set_bid_value(bid_value);
+    }
+    builder.set_header_delay(Duration::from_millis(header_delay_ms));
+    if invalid_bids {
+        builder.invalid_signatures();
+    }
+    builder.set_withhold_bids(withhold_bids);
+    builder.set_withhold_payloads(withhold_payloads);
+
+    let (listen_socket, server) = serve_mock_builder(listen_addr, listen_port, builder)
+        .map_err(|e| format!("Unable to start the mock builder: {:?}", e))?;
+    executor.spawn(server, "mock_builder");
+
+    eprintln!(
+        "This tool is for TESTING PURPOSES ONLY. Do not use in production or on mainnet. \
+        Proposers using it may miss blocks."
+    );
+    eprintln!("Server listening on {}", listen_socket);
+
+    let shutdown_reason = env.block_until_shutdown_requested()?;
+
+    eprintln!("Shutting down: {:?}", shutdown_reason);
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use environment::Environment;
 use execution_layer::{
     auth::JwtKey,
     test_utils::{
-        Config, MockExecutionConfig, MockServer, DEFAULT_JWT_SECRET, DEFAULT_TERMINAL_BLOCK,
+        Config, MockExecutionConfig, MockServer, Replay, DEFAULT_JWT_SECRET, DEFAULT_TERMINAL_BLOCK,
     },
 };
 use std::net::Ipv4Addr;
@@ -18,6 +18,7 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<
     let all_payloads_valid: bool = parse_required(matches, "all-payloads-valid")?;
     let shanghai_time = parse_required(matches, "shanghai-time")?;
     let cancun_time = parse_optional(matches, "cancun-time")?;
+    let replay_path: Option<PathBuf> = parse_optional(matches, "replay")?;
 
     let handle = env.core_context().executor.handle().unwrap();
     let spec = &T::default_spec();
@@ -48,6 +49,16 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<
         server.all_payloads_valid();
     }
 
+    if let Some(replay_path) = replay_path {
+        let replay = Replay::from_path(&replay_path)?;
+        eprintln!(
+            "Replaying {} recorded requests from {}",
+            replay.len(),
+            replay_path.display()
+        );
+        server.set_replay(replay);
+    }
+
     eprintln!(
         "This tool is for TESTING PURPOSES ONLY. Do not use in production or on mainnet. \
         It cannot perform validator duties. It may cause nodes to follow an invalid chain."
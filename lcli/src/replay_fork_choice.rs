@@ -0,0 +1,394 @@
+//! # Replay Fork Choice
+//!
+//! Use this tool to reproduce head selection by replaying blocks from a beacon node database
+//! through fork choice, reporting every change of head and the weights which caused it.
+//!
+//! Fork choice starts from a `PersistedForkChoice`. By default this is the fork choice persisted
+//! in `--datadir`, in which case only blocks imported after it was persisted are replayed (i.e.
+//! none, unless the node crashed). To replay an incident, take a copy of the database before the
+//! incident and pass its hot database as `--fork-choice-db-path`; the blocks which follow it are
+//! then loaded from `--datadir`.
+//!
+//! Every block with a slot after the fork choice's current slot is replayed in slot order,
+//! including blocks on forks which haven't been pruned. The attestations included in each block
+//! are applied after it. Attestations which were only seen on gossip are not persisted, so they
+//! can't be replayed.
+//!
+//! Both beacon nodes must not be running, since they hold a lock on their databases.
+//!
+//! ## Examples
+//!
+//! ```ignore
+//! lcli replay-fork-choice \
+//!     --datadir ~/.lighthouse/mainnet \
+//!     --fork-choice-db-path /backups/mainnet/beacon/chain_db \
+//!     --end-slot 8000000
+//! ```
+use beacon_chain::{BeaconForkChoiceStore, PersistedForkChoice, FORK_CHOICE_DB_KEY};
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2_network_config::Eth2NetworkConfig;
+use fork_choice::{
+    AttestationFromBlock, ForkChoice, ForkChoiceStore, PayloadVerificationStatus,
+    ResetPayloadStatuses,
+};
+use slog::Logger;
+use ssz::Decode;
+use state_processing::common::get_indexed_attestation;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use store::{DBColumn, HotColdDB, ItemStore, KeyValueStore, LevelDB, StoreConfig};
+use types::{
+    BeaconState, ChainSpec, EthSpec, Hash256, ProgressiveBalancesMode, RelativeEpoch,
+    SignatureBytes, SignedBlindedBeaconBlock, Slot,
+};
+
+type Store<E> = HotColdDB<E, LevelDB<E>, LevelDB<E>>;
+type ReplayForkChoice<E> = ForkChoice<BeaconForkChoiceStore<E, LevelDB<E>, LevelDB<E>>, E>;
+
+pub fn run<T: EthSpec>(
+    env: Environment<T>,
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let spec = &network_config.chain_spec::<T>()?;
+    let log = env.core_context().log().clone();
+
+    let datadir: PathBuf = parse_required(matches, "datadir")?;
+    let fork_choice_db_path: Option<PathBuf> = parse_optional(matches, "fork-choice-db-path")?;
+    let end_slot: Option<Slot> = parse_optional(matches, "end-slot")?;
+    let block_delay = Duration::from_millis(parse_required(matches, "block-delay-ms")?);
+    let slots_per_restore_point: Option<u64> = parse_optional(matches, "slots-per-restore-point")?;
+
+    /*
+     * Load the persisted fork choice, then open the database to replay blocks from.
+     */
+
+    // The snapshot is opened and closed before the main database, in case they're the same.
+    let snapshot_fork_choice = fork_choice_db_path
+        .map(|path| {
+            info!("Loading fork choice from {}", path.display());
+            let db = LevelDB::<T>::open(&path)
+                .map_err(|e| format!("Unable to open database at {}: {:?}", path.display(), e))?;
+            load_persisted_fork_choice(&db)
+        })
+        .transpose()?;
+
+    let beacon_dir = datadir.join(directory::DEFAULT_BEACON_NODE_DIR);
+    let hot_path = beacon_dir.join("chain_db");
+    if !hot_path.exists() {
+        return Err(format!("No database found at {}", hot_path.display()));
+    }
+
+    let mut store_config = StoreConfig::default();
+    if let Some(slots_per_restore_point) = slots_per_restore_point {
+        store_config.slots_per_restore_point = slots_per_restore_point;
+        store_config.slots_per_restore_point_set_explicitly = true;
+    }
+
+    info!("Opening database at {}", beacon_dir.display());
+    let store: Arc<Store<T>> = HotColdDB::open(
+        &hot_path,
+        &beacon_dir.join("freezer_db"),
+        &beacon_dir.join("blobs_db"),
+        |_, from, to| {
+            if from == to {
+                Ok(())
+            } else {
+                Err(store::Error::SchemaMigrationError(format!(
+                    "database schema v{} is not v{}, run the beacon node to migrate it",
+                    from.as_u64(),
+                    to.as_u64()
+                )))
+            }
+        },
+        store_config,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+    let persisted_fork_choice = match snapshot_fork_choice {
+        Some(persisted_fork_choice) => persisted_fork_choice,
+        None => load_persisted_fork_choice(&store.hot_db)?,
+    };
+
+    let fc_store = BeaconForkChoiceStore::from_persisted(
+        persisted_fork_choice.fork_choice_store,
+        store.clone(),
+    )
+    .map_err(|e| format!("Unable to load fork choice store: {:?}", e))?;
+    let mut fork_choice: ReplayForkChoice<T> = ForkChoice::from_persisted(
+        persisted_fork_choice.fork_choice,
+        ResetPayloadStatuses::OnlyWithInvalidPayload,
+        fc_store,
+        spec,
+        &log,
+    )
+    .map_err(|e| format!("Unable to load fork choice: {:?}", e))?;
+
+    let start_slot = fork_choice.fc_store().get_current_slot();
+    let mut head = fork_choice
+        .get_head(start_slot, spec)
+        .map_err(|e| format!("Unable to find initial head: {:?}", e))?;
+    info!(
+        "Loaded fork choice at slot {} with head {:?}, justified epoch {} and finalized epoch {}",
+        start_slot,
+        head,
+        fork_choice.justified_checkpoint().epoch,
+        fork_choice.finalized_checkpoint().epoch
+    );
+
+    /*
+     * Load the blocks to replay.
+     */
+
+    let blocks = load_blocks(&store, start_slot, end_slot, spec)?;
+    let Some(last_block_slot) = blocks.last().map(|(_, block)| block.slot()) else {
+        info!("No blocks after slot {} to replay", start_slot);
+        return Ok(());
+    };
+    let end_slot = end_slot.unwrap_or(last_block_slot);
+    info!(
+        "Replaying {} blocks from slot {} to {}",
+        blocks.len(),
+        start_slot + 1,
+        end_slot
+    );
+
+    /*
+     * Replay the blocks slot by slot, reporting changes of head.
+     */
+
+    let mut blocks = blocks.into_iter().peekable();
+    for slot in (start_slot.as_u64() + 1..=end_slot.as_u64()).map(Slot::new) {
+        fork_choice
+            .update_time(slot)
+            .map_err(|e| format!("Unable to update time to slot {}: {:?}", slot, e))?;
+
+        while let Some((block_root, block)) = blocks.next_if(|(_, block)| block.slot() == slot) {
+            if let Err(e) = apply_block(
+                &mut fork_choice,
+                &store,
+                block_root,
+                &block,
+                block_delay,
+                spec,
+                &log,
+            ) {
+                warn!("Skipping block {:?} at slot {}: {}", block_root, slot, e);
+            }
+        }
+
+        let new_head = fork_choice
+            .get_head(slot, spec)
+            .map_err(|e| format!("Unable to find head at slot {}: {:?}", slot, e))?;
+        if new_head != head {
+            report_head_change(&fork_choice, slot, head, new_head);
+            head = new_head;
+        }
+    }
+
+    info!("Replay finished with head {:?}", head);
+
+    Ok(())
+}
+
+fn load_persisted_fork_choice<E: EthSpec>(
+    db: &impl ItemStore<E>,
+) -> Result<PersistedForkChoice, String> {
+    db.get::<PersistedForkChoice>(&FORK_CHOICE_DB_KEY)
+        .map_err(|e| format!("Unable to read fork choice: {:?}", e))?
+        .ok_or_else(|| "No fork choice found in the database".to_string())
+}
+
+/// Load all blocks in `store` with slots in `(start_slot, end_slot]`, ordered by slot.
+fn load_blocks<E: EthSpec>(
+    store: &Store<E>,
+    start_slot: Slot,
+    end_slot: Option<Slot>,
+    spec: &ChainSpec,
+) -> Result<Vec<(Hash256, SignedBlindedBeaconBlock<E>)>, String> {
+    let mut blocks = vec![];
+    for result in store.hot_db.iter_column::<Hash256>(DBColumn::BeaconBlock) {
+        let (block_root, bytes) =
+            result.map_err(|e| format!("Unable to iterate blocks: {:?}", e))?;
+
+        // Avoid decoding the full block unless it's in range, since there may be many blocks.
+        let slot = block_slot_from_ssz(&bytes)
+            .map_err(|e| format!("Unable to decode block {:?}: {:?}", block_root, e))?;
+        if slot <= start_slot || end_slot.map_or(false, |end_slot| slot > end_slot) {
+            continue;
+        }
+
+        let block = SignedBlindedBeaconBlock::from_ssz_bytes(&bytes, spec)
+            .map_err(|e| format!("Unable to decode block {:?}: {:?}", block_root, e))?;
+        blocks.push((block_root, block));
+    }
+    blocks.sort_by_key(|(block_root, block)| (block.slot(), *block_root));
+    Ok(blocks)
+}
+
+/// Read the slot of an SSZ-encoded `SignedBeaconBlock` without decoding the rest of the block.
+fn block_slot_from_ssz(bytes: &[u8]) -> Result<Slot, ssz::DecodeError> {
+    let mut builder = ssz::SszDecoderBuilder::new(bytes);
+    builder.register_anonymous_variable_length_item()?;
+    builder.register_type::<SignatureBytes>()?;
+    let mut decoder = builder.build()?;
+
+    // The slot is the first field of the block.
+    decoder.decode_next_with(|message| {
+        let slot_len = <Slot as Decode>::ssz_fixed_len();
+        let slot_bytes = message
+            .get(0..slot_len)
+            .ok_or(ssz::DecodeError::InvalidByteLength {
+                len: message.len(),
+                expected: slot_len,
+            })?;
+        Slot::from_ssz_bytes(slot_bytes)
+    })
+}
+
+/// Apply `block` and the attestations it contains to `fork_choice`.
+fn apply_block<E: EthSpec>(
+    fork_choice: &mut ReplayForkChoice<E>,
+    store: &Store<E>,
+    block_root: Hash256,
+    block: &SignedBlindedBeaconBlock<E>,
+    block_delay: Duration,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> Result<(), String> {
+    let mut state: BeaconState<E> = store
+        .get_state(&block.state_root(), Some(block.slot()))
+        .map_err(|e| format!("unable to load state: {:?}", e))?
+        .ok_or("post-state is not in the database")?;
+    state
+        .build_committee_cache(RelativeEpoch::Previous, spec)
+        .and_then(|_| state.build_committee_cache(RelativeEpoch::Current, spec))
+        .map_err(|e| format!("unable to build committee caches: {:?}", e))?;
+
+    // Blocks in the database have already been imported, so their payloads are treated as valid.
+    fork_choice
+        .on_block(
+            block.slot(),
+            block.message(),
+            block_root,
+            block_delay,
+            &state,
+            PayloadVerificationStatus::Verified,
+            ProgressiveBalancesMode::Disabled,
+            spec,
+            log,
+        )
+        .map_err(|e| format!("rejected by fork choice: {:?}", e))?;
+
+    for attestation in block.message().body().attestations() {
+        let result = state
+            .get_beacon_committee(attestation.data.slot, attestation.data.index)
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|committee| {
+                get_indexed_attestation(committee.committee, attestation)
+                    .map_err(|e| format!("{:?}", e))
+            })
+            .and_then(|indexed_attestation| {
+                fork_choice
+                    .on_attestation(
+                        block.slot(),
+                        &indexed_attestation,
+                        AttestationFromBlock::True,
+                    )
+                    .map_err(|e| format!("{:?}", e))
+            });
+        if let Err(e) = result {
+            debug!(
+                "Ignoring attestation in block {:?} for slot {}: {}",
+                block_root, attestation.data.slot, e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a change of head from `old_head` to `new_head`.
+///
+/// If the new head doesn't descend from the old head, the weights of the first blocks of the two
+/// branches are printed too, since these determined the re-org.
+fn report_head_change<E: EthSpec>(
+    fork_choice: &ReplayForkChoice<E>,
+    slot: Slot,
+    old_head: Hash256,
+    new_head: Hash256,
+) {
+    let proto_array = fork_choice.proto_array();
+    let describe = |root: Hash256| match proto_array.get_block(&root) {
+        Some(block) => format!(
+            "{:?} (slot {}, weight {})",
+            root,
+            block.slot,
+            proto_array.get_weight(&root).unwrap_or(0)
+        ),
+        None => format!("{:?} (pruned)", root),
+    };
+
+    let proposer_boost_root = fork_choice.proposer_boost_root();
+    println!(
+        "slot {}: head changed from {} to {}",
+        slot,
+        describe(old_head),
+        describe(new_head)
+    );
+    println!(
+        "  justified epoch {}, finalized epoch {}, proposer boost {}",
+        fork_choice.justified_checkpoint().epoch,
+        fork_choice.finalized_checkpoint().epoch,
+        if proposer_boost_root.is_zero() {
+            "none".to_string()
+        } else {
+            format!("{:?}", proposer_boost_root)
+        }
+    );
+
+    // Find the block at which the branches of the old and new heads diverge, along with the
+    // first block of each branch.
+    let core_proto_array = proto_array.core_proto_array();
+    let old_ancestors = core_proto_array
+        .iter_block_roots(&old_head)
+        .map(|(root, _)| root)
+        .collect::<HashSet<_>>();
+    let mut new_branch = new_head;
+    let Some(fork_root) = core_proto_array
+        .iter_block_roots(&new_head)
+        .map(|(root, _)| root)
+        .find(|root| {
+            let is_common = old_ancestors.contains(root);
+            if !is_common {
+                new_branch = *root;
+            }
+            is_common
+        })
+    else {
+        return;
+    };
+    // Nothing competed with the new head if it extends or is an ancestor of the old head.
+    if fork_root == old_head || fork_root == new_head {
+        return;
+    }
+    let old_branch = core_proto_array
+        .iter_block_roots(&old_head)
+        .map(|(root, _)| root)
+        .take_while(|root| *root != fork_root)
+        .last()
+        .unwrap_or(old_head);
+
+    println!(
+        "  re-org at {}: old branch {} vs new branch {}",
+        describe(fork_root),
+        describe(old_branch),
+        describe(new_branch)
+    );
+}
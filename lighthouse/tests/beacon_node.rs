@@ -5,6 +5,7 @@ use beacon_node::beacon_chain::chain_config::{
     DisallowedReOrgOffsets, DEFAULT_RE_ORG_CUTOFF_DENOMINATOR,
     DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_THRESHOLD,
 };
+use beacon_node::beacon_chain::graffiti_calculator::GraffitiOrigin;
 use beacon_processor::BeaconProcessorConfig;
 use eth1::Eth1Endpoint;
 use lighthouse_network::PeerId;
@@ -20,7 +21,7 @@ use std::time::Duration;
 use tempfile::TempDir;
 use types::non_zero_usize::new_non_zero_usize;
 use types::{
-    Address, Checkpoint, Epoch, ExecutionBlockHash, ForkName, Hash256, MainnetEthSpec,
+    Address, Checkpoint, Epoch, ExecutionBlockHash, ForkName, Graffiti, Hash256, MainnetEthSpec,
     ProgressiveBalancesMode,
 };
 use unused_port::{unused_tcp4_port, unused_tcp6_port, unused_udp4_port, unused_udp6_port};
@@ -319,13 +320,40 @@ fn graffiti_flag() {
         .flag("graffiti", Some("nice-graffiti"))
         .run_with_zero_port()
         .with_config(|config| {
+            assert!(matches!(config.graffiti, GraffitiOrigin::UserSpecified(_)));
             assert_eq!(
-                config.graffiti.to_string(),
+                config.graffiti.graffiti().to_string(),
                 "0x6e6963652d677261666669746900000000000000000000000000000000000000"
             );
         });
 }
 
+#[test]
+fn graffiti_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(matches!(config.graffiti, GraffitiOrigin::Calculated(_)));
+            assert_eq!(
+                config.graffiti.graffiti().as_utf8_lossy(),
+                lighthouse_version::VERSION
+            );
+        });
+}
+
+#[test]
+fn graffiti_private_flag() {
+    CommandLineTest::new()
+        .flag("private", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.graffiti,
+                GraffitiOrigin::UserSpecified(Graffiti::default())
+            )
+        });
+}
+
 #[test]
 fn trusted_peers_flag() {
     let peers = vec![PeerId::random(), PeerId::random()];
@@ -355,6 +383,21 @@ fn genesis_backfill_flag() {
         .with_config(|config| assert_eq!(config.chain.genesis_backfill, true));
 }
 
+#[test]
+fn verify_backfill_block_hashes_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.verify_backfill_block_hashes));
+}
+
+#[test]
+fn verify_backfill_block_hashes_flag() {
+    CommandLineTest::new()
+        .flag("verify-backfill-block-hashes", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.verify_backfill_block_hashes));
+}
+
 /// The genesis backfill flag should be enabled if historic states flag is set.
 #[test]
 fn genesis_backfill_with_historic_flag() {
@@ -364,6 +407,38 @@ fn genesis_backfill_with_historic_flag() {
         .with_config(|config| assert_eq!(config.chain.genesis_backfill, true));
 }
 
+#[test]
+fn backfill_archive_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.backfill_archive_url, None);
+            assert_eq!(config.network.backfill_era_dir, None);
+        });
+}
+
+#[test]
+fn backfill_archive_url_flag() {
+    let url = "http://archive.example.com:5052";
+    CommandLineTest::new()
+        .flag("backfill-archive-url", Some(url))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.backfill_archive_url, Some(url.to_string()))
+        });
+}
+
+#[test]
+fn backfill_era_dir_flag() {
+    let dir = "/var/lib/era";
+    CommandLineTest::new()
+        .flag("backfill-era-dir", Some(dir))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.backfill_era_dir, Some(PathBuf::from(dir)))
+        });
+}
+
 // Tests for Eth1 flags.
 #[test]
 fn dummy_eth1_flag() {
@@ -417,8 +492,6 @@ fn eth1_cache_follow_distance_manual() {
 fn run_merge_execution_endpoints_flag_test(flag: &str) {
     use sensitive_url::SensitiveUrl;
     let urls = vec!["http://sigp.io/no-way:1337", "http://infura.not_real:4242"];
-    // we don't support redundancy for execution-endpoints
-    // only the first provided endpoint is parsed.
 
     let mut endpoint_arg = urls[0].to_string();
     for url in urls.iter().skip(1) {
@@ -448,13 +521,74 @@ fn run_merge_execution_endpoints_flag_test(flag: &str) {
         .run_with_zero_port()
         .with_config(|config| {
             let config = config.execution_layer.as_ref().unwrap();
-            assert_eq!(config.execution_endpoints.len(), 1);
             assert_eq!(
-                config.execution_endpoints[0],
-                SensitiveUrl::parse(&urls[0]).unwrap()
+                config.execution_endpoints,
+                urls.iter()
+                    .map(|url| SensitiveUrl::parse(url).unwrap())
+                    .collect::<Vec<_>>()
             );
-            // Only the first secret file should be used.
-            assert_eq!(config.secret_files, vec![jwts[0].clone()]);
+            assert_eq!(config.secret_files, jwts);
+            assert_eq!(config.new_payload_quorum, None);
+        });
+}
+#[test]
+fn execution_endpoints_shared_jwt_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let jwt = dir.path().join("jwt-file");
+    CommandLineTest::new()
+        .flag(
+            "execution-endpoint",
+            Some("http://localhost:8551/,http://localhost:8552/"),
+        )
+        .flag("execution-jwt", jwt.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let el_config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(el_config.execution_endpoints.len(), 2);
+            assert_eq!(el_config.secret_files, vec![jwt.clone()]);
+            // The deposit cache follows the primary endpoint.
+            assert!(matches!(
+                &config.eth1.endpoint,
+                Eth1Endpoint::Auth { endpoint, .. }
+                    if endpoint.full.to_string() == "http://localhost:8551/"
+            ));
+        });
+}
+#[test]
+#[should_panic]
+fn execution_endpoints_mismatched_jwts_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let jwts = format!(
+        "{},{},{}",
+        dir.path().join("jwt-0").display(),
+        dir.path().join("jwt-1").display(),
+        dir.path().join("jwt-2").display()
+    );
+    CommandLineTest::new()
+        .flag(
+            "execution-endpoint",
+            Some("http://localhost:8551/,http://localhost:8552/"),
+        )
+        .flag("execution-jwt", Some(&jwts))
+        .run_with_zero_port();
+}
+#[test]
+fn execution_new_payload_quorum_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag(
+            "execution-endpoint",
+            Some("http://localhost:8551/,http://localhost:8552/"),
+        )
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag("execution-new-payload-quorum", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.new_payload_quorum, Some(2));
         });
 }
 #[test]
@@ -495,6 +629,38 @@ fn execution_timeout_multiplier_flag() {
         });
 }
 #[test]
+fn execution_recording_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let recording_dir = dir.path().join("recordings");
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://meow.cats"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag("execution-recording-dir", recording_dir.to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.engine_recording_dir, Some(recording_dir.clone()));
+        });
+}
+#[test]
+fn execution_recording_dir_default() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://meow.cats"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.engine_recording_dir, None);
+        });
+}
+#[test]
 fn merge_execution_endpoints_flag() {
     run_merge_execution_endpoints_flag_test("execution-endpoints")
 }
@@ -555,9 +721,7 @@ fn run_payload_builder_flag_test(flag: &str, builders: &str) {
         .collect();
     run_payload_builder_flag_test_with_config(flag, builders, None, None, |config| {
         let config = config.execution_layer.as_ref().unwrap();
-        // Only first provided endpoint is parsed as we don't support
-        // redundancy.
-        assert_eq!(config.builder_url, all_builders.get(0).cloned());
+        assert_eq!(config.builder_urls, all_builders);
     })
 }
 fn run_payload_builder_flag_test_with_config<F: Fn(&Config)>(
@@ -1238,6 +1402,26 @@ fn network_load_flag() {
             assert_eq!(config.network.network_load, 4);
         });
 }
+#[test]
+fn bandwidth_limit_flags() {
+    CommandLineTest::new()
+        .flag("upload-bandwidth-limit", Some("1000000"))
+        .flag("download-bandwidth-limit", Some("2000000"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.upload_bandwidth_limit, Some(1_000_000));
+            assert_eq!(config.network.download_bandwidth_limit, Some(2_000_000));
+        });
+}
+#[test]
+fn bandwidth_limit_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.upload_bandwidth_limit, None);
+            assert_eq!(config.network.download_bandwidth_limit, None);
+        });
+}
 
 // Tests for ENR flags.
 #[test]
@@ -1564,6 +1748,33 @@ fn disable_inbound_rate_limiter_flag() {
         .run_with_zero_port()
         .with_config(|config| assert_eq!(config.network.inbound_rate_limiter_config, None));
 }
+#[test]
+fn inbound_rate_limiter_peer_class_flags() {
+    CommandLineTest::new()
+        .flag("inbound-rate-limiter-trusted-peers", Some("ping:10/1"))
+        .flag("inbound-rate-limiter-ip-range", Some("ping:2/1"))
+        .flag(
+            "inbound-rate-limiter-ip-ranges",
+            Some("10.0.0.0/8, 192.168.1.7"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let inbound_config = config
+                .network
+                .inbound_rate_limiter_config
+                .as_ref()
+                .expect("inbound rate limiter is enabled");
+            assert_eq!(inbound_config.trusted, Some("ping:10/1".parse().unwrap()));
+            assert_eq!(inbound_config.ip_range, Some("ping:2/1".parse().unwrap()));
+            assert_eq!(
+                inbound_config.ip_ranges,
+                vec![
+                    "10.0.0.0/8".parse().unwrap(),
+                    "192.168.1.7/32".parse().unwrap()
+                ]
+            );
+        });
+}
 
 #[test]
 fn http_allow_origin_flag() {
@@ -1,4 +1,4 @@
-use validator_client::{ApiTopic, Config};
+use validator_client::{duty_history, exit_vault, replication, ApiTopic, Config};
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
@@ -542,6 +542,23 @@ fn no_broadcast_flag() {
     });
 }
 
+#[test]
+fn beacon_node_ranking_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.beacon_node_ranking);
+    });
+}
+
+#[test]
+fn enable_beacon_node_ranking_flag() {
+    CommandLineTest::new()
+        .flag("enable-beacon-node-ranking", None)
+        .run()
+        .with_config(|config| {
+            assert!(config.beacon_node_ranking);
+        });
+}
+
 #[test]
 fn broadcast_flag() {
     // "none" variant
@@ -653,3 +670,106 @@ fn validator_disable_web3_signer_slashing_protection() {
             assert!(!config.enable_web3signer_slashing_protection);
         });
 }
+
+#[test]
+fn exit_vault_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.exit_vault, None);
+    });
+}
+
+#[test]
+fn exit_vault_flags() {
+    CommandLineTest::new()
+        .flag("exit-vault-dir", Some("./exits"))
+        .flag("exit-vault-password-file", Some("./exits-password.txt"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.exit_vault,
+                Some(exit_vault::Config {
+                    dir: PathBuf::from("./exits"),
+                    password_file: PathBuf::from("./exits-password.txt"),
+                })
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn exit_vault_dir_without_password_file() {
+    CommandLineTest::new()
+        .flag("exit-vault-dir", Some("./exits"))
+        .run();
+}
+
+#[test]
+fn slashing_protection_replication_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.slashing_protection_replication, None);
+    });
+}
+
+#[test]
+fn slashing_protection_replication_active() {
+    CommandLineTest::new()
+        .flag("slashing-protection-journal", Some("./journal"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.slashing_protection_replication,
+                Some(replication::Config {
+                    journal_path: PathBuf::from("./journal"),
+                    standby: false,
+                    failover_epochs: replication::DEFAULT_FAILOVER_EPOCHS,
+                })
+            );
+        });
+}
+
+#[test]
+fn slashing_protection_replication_standby() {
+    CommandLineTest::new()
+        .flag("slashing-protection-journal", Some("./journal"))
+        .flag("slashing-protection-standby", None)
+        .flag("slashing-protection-failover-epochs", Some("5"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.slashing_protection_replication,
+                Some(replication::Config {
+                    journal_path: PathBuf::from("./journal"),
+                    standby: true,
+                    failover_epochs: 5,
+                })
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn slashing_protection_standby_without_journal() {
+    CommandLineTest::new()
+        .flag("slashing-protection-standby", None)
+        .run();
+}
+
+#[test]
+fn duty_history_epochs_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.duty_history_epochs,
+            duty_history::DEFAULT_DUTY_HISTORY_EPOCHS
+        );
+    });
+}
+
+#[test]
+fn duty_history_epochs_flag() {
+    CommandLineTest::new()
+        .flag("duty-history-epochs", Some("8"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.duty_history_epochs, 8);
+        });
+}
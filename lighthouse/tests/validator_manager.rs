@@ -8,9 +8,14 @@ use std::str::FromStr;
 use tempfile::{tempdir, TempDir};
 use types::*;
 use validator_manager::{
+    bls_change::BlsChangeConfig,
     create_validators::CreateConfig,
+    delete_validators::DeleteConfig,
+    exit_validators::ExitConfig,
     import_validators::ImportConfig,
+    list_validators::ListConfig,
     move_validators::{MoveConfig, PasswordSource, Validators},
+    split_key::SplitKeyConfig,
 };
 
 const EXAMPLE_ETH1_ADDRESS: &str = "0x00000000219ab540356cBB839Cbe05303d7705Fa";
@@ -100,6 +105,36 @@ impl CommandLineTest<MoveConfig> {
     }
 }
 
+impl CommandLineTest<ExitConfig> {
+    fn validators_exit() -> Self {
+        Self::default().flag("exit", None)
+    }
+}
+
+impl CommandLineTest<ListConfig> {
+    fn validators_list() -> Self {
+        Self::default().flag("list", None)
+    }
+}
+
+impl CommandLineTest<DeleteConfig> {
+    fn validators_delete() -> Self {
+        Self::default().flag("delete", None)
+    }
+}
+
+impl CommandLineTest<SplitKeyConfig> {
+    fn validators_split_key() -> Self {
+        Self::default().flag("split-key", None)
+    }
+}
+
+impl CommandLineTest<BlsChangeConfig> {
+    fn validators_bls_change() -> Self {
+        Self::default().flag("bls-change", None)
+    }
+}
+
 #[test]
 pub fn validator_create_without_output_path() {
     CommandLineTest::validators_create().assert_failed();
@@ -389,3 +424,261 @@ pub fn validator_move_count() {
             assert_eq!(expected, config);
         });
 }
+
+#[test]
+pub fn validator_bls_change_defaults() {
+    CommandLineTest::validators_bls_change()
+        .flag("--validator-indices", Some("1,42"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag("--beacon-node", Some("http://localhost:1"))
+        .flag("--output-path", Some("./changes.json"))
+        .assert_success(|config| {
+            let expected = BlsChangeConfig {
+                validator_indices: vec![1, 42],
+                execution_address: Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap(),
+                first_index: 0,
+                count: 1024,
+                mnemonic_path: None,
+                stdin_inputs: cfg!(windows) || false,
+                bn_url: Some(SensitiveUrl::parse("http://localhost:1").unwrap()),
+                state_path: None,
+                output_path: Some(PathBuf::from("./changes.json")),
+                submit: false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_bls_change_offline_state() {
+    CommandLineTest::validators_bls_change()
+        .flag("--validator-indices", Some("7"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag("--state-path", Some("./state.ssz"))
+        .flag("--output-path", Some("./changes.json"))
+        .flag("--first-index", Some("10"))
+        .flag("--count", Some("20"))
+        .assert_success(|config| {
+            let expected = BlsChangeConfig {
+                validator_indices: vec![7],
+                execution_address: Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap(),
+                first_index: 10,
+                count: 20,
+                mnemonic_path: None,
+                stdin_inputs: cfg!(windows) || false,
+                bn_url: None,
+                state_path: Some(PathBuf::from("./state.ssz")),
+                output_path: Some(PathBuf::from("./changes.json")),
+                submit: false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_bls_change_submit_without_beacon_node() {
+    CommandLineTest::validators_bls_change()
+        .flag("--validator-indices", Some("7"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag("--state-path", Some("./state.ssz"))
+        .flag("--submit", None)
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_exit_defaults() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .flag("--output-path", Some("./exits.json"))
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: Some(PathBuf::from("./token.json")),
+                validators: Some(Validators::All),
+                exit_epoch: None,
+                exits_file_path: None,
+                output_path: Some(PathBuf::from("./exits.json")),
+                bn_url: None,
+                broadcast_interval_millis: 1000,
+                no_confirmation: false,
+                stdin_inputs: cfg!(windows) || false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_misc_flags() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag(
+            "--validators",
+            Some(&format!("{},{}", EXAMPLE_PUBKEY_0, EXAMPLE_PUBKEY_1)),
+        )
+        .flag("--exit-epoch", Some("42"))
+        .flag("--beacon-node", Some("http://localhost:2"))
+        .flag("--broadcast-interval", Some("12000"))
+        .flag("--no-confirmation", None)
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                vc_token_path: Some(PathBuf::from("./token.json")),
+                validators: Some(Validators::Specific(vec![
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap(),
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_1).unwrap(),
+                ])),
+                exit_epoch: Some(Epoch::new(42)),
+                exits_file_path: None,
+                output_path: None,
+                bn_url: Some(SensitiveUrl::parse("http://localhost:2").unwrap()),
+                broadcast_interval_millis: 12000,
+                no_confirmation: true,
+                stdin_inputs: cfg!(windows) || false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_from_file() {
+    CommandLineTest::validators_exit()
+        .flag("--exits-file", Some("./exits.json"))
+        .flag("--beacon-node", Some("http://localhost:2"))
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: None,
+                validators: None,
+                exit_epoch: None,
+                exits_file_path: Some(PathBuf::from("./exits.json")),
+                output_path: None,
+                bn_url: Some(SensitiveUrl::parse("http://localhost:2").unwrap()),
+                broadcast_interval_millis: 1000,
+                no_confirmation: false,
+                stdin_inputs: cfg!(windows) || false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_without_output() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_list_defaults() {
+    CommandLineTest::validators_list()
+        .flag("--vc-token", Some("./token.json"))
+        .assert_success(|config| {
+            let expected = ListConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                bn_url: None,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_list_beacon_node() {
+    CommandLineTest::validators_list()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--beacon-node", Some("http://localhost:2"))
+        .assert_success(|config| {
+            let expected = ListConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                bn_url: Some(SensitiveUrl::parse("http://localhost:2").unwrap()),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_delete_defaults() {
+    CommandLineTest::validators_delete()
+        .flag("--vc-token", Some("./token.json"))
+        .flag(
+            "--validators",
+            Some(&format!("{},{}", EXAMPLE_PUBKEY_0, EXAMPLE_PUBKEY_1)),
+        )
+        .flag("--slashing-protection-path", Some("./interchange.json"))
+        .assert_success(|config| {
+            let expected = DeleteConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: vec![
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap(),
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_1).unwrap(),
+                ],
+                slashing_protection_path: PathBuf::from("./interchange.json"),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_delete_without_slashing_protection_path() {
+    CommandLineTest::validators_delete()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some(EXAMPLE_PUBKEY_0))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_split_key_defaults() {
+    CommandLineTest::validators_split_key()
+        .flag("--keystore", Some("./keystore.json"))
+        .flag("--threshold", Some("2"))
+        .flag("--shares", Some("3"))
+        .flag("--output-dir", Some("./shares"))
+        .assert_success(|config| {
+            let expected = SplitKeyConfig {
+                keystore_path: PathBuf::from("./keystore.json"),
+                password_path: None,
+                threshold: 2,
+                shares: 3,
+                output_dir: PathBuf::from("./shares"),
+                stdin_inputs: cfg!(windows) || false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_split_key_password_file() {
+    CommandLineTest::validators_split_key()
+        .flag("--keystore", Some("./keystore.json"))
+        .flag("--password-file", Some("./password.txt"))
+        .flag("--threshold", Some("3"))
+        .flag("--shares", Some("5"))
+        .flag("--output-dir", Some("./shares"))
+        .flag("--stdin-inputs", None)
+        .assert_success(|config| {
+            let expected = SplitKeyConfig {
+                keystore_path: PathBuf::from("./keystore.json"),
+                password_path: Some(PathBuf::from("./password.txt")),
+                threshold: 3,
+                shares: 5,
+                output_dir: PathBuf::from("./shares"),
+                stdin_inputs: true,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_split_key_without_threshold() {
+    CommandLineTest::validators_split_key()
+        .flag("--keystore", Some("./keystore.json"))
+        .flag("--shares", Some("3"))
+        .flag("--output-dir", Some("./shares"))
+        .assert_failed();
+}
@@ -1,8 +1,13 @@
 #![cfg(test)]
 
+use crate::interchange::{
+    Interchange, InterchangeConflict, InterchangeData, InterchangeMetadata,
+    SignedAttestation as InterchangeAttestation, SignedBlock as InterchangeBlock,
+};
 use crate::test_utils::pubkey;
 use crate::*;
 use tempfile::tempdir;
+use types::{Epoch, Slot};
 
 #[test]
 fn export_non_existent_key() {
@@ -73,3 +78,142 @@ fn export_same_key_twice() {
         export_double.minify().unwrap()
     );
 }
+
+fn block(slot: u64, signing_root: Option<u8>) -> InterchangeBlock {
+    InterchangeBlock {
+        slot: Slot::new(slot),
+        signing_root: signing_root.map(Hash256::repeat_byte),
+    }
+}
+
+fn attestation(
+    source_epoch: u64,
+    target_epoch: u64,
+    signing_root: Option<u8>,
+) -> InterchangeAttestation {
+    InterchangeAttestation {
+        source_epoch: Epoch::new(source_epoch),
+        target_epoch: Epoch::new(target_epoch),
+        signing_root: signing_root.map(Hash256::repeat_byte),
+    }
+}
+
+fn interchange_with(genesis_validators_root: Hash256, data: Vec<InterchangeData>) -> Interchange {
+    Interchange {
+        metadata: InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root,
+        },
+        data,
+    }
+}
+
+#[test]
+fn merge_interchanges() {
+    let gvr = Hash256::repeat_byte(0xaa);
+    let first = interchange_with(
+        gvr,
+        vec![InterchangeData {
+            pubkey: pubkey(1),
+            signed_blocks: vec![block(1, Some(1))],
+            signed_attestations: vec![attestation(0, 1, Some(2))],
+        }],
+    );
+    let second = interchange_with(
+        gvr,
+        vec![
+            InterchangeData {
+                pubkey: pubkey(2),
+                signed_blocks: vec![block(2, None)],
+                signed_attestations: vec![],
+            },
+            InterchangeData {
+                pubkey: pubkey(1),
+                signed_blocks: vec![block(1, Some(1)), block(3, Some(3))],
+                signed_attestations: vec![attestation(1, 2, Some(4))],
+            },
+        ],
+    );
+
+    let merged = Interchange::merge(gvr, vec![first.clone(), second.clone()]).unwrap();
+    assert_eq!(
+        merged.data,
+        vec![
+            InterchangeData {
+                pubkey: pubkey(1),
+                signed_blocks: vec![block(1, Some(1)), block(3, Some(3))],
+                signed_attestations: vec![attestation(0, 1, Some(2)), attestation(1, 2, Some(4))],
+            },
+            InterchangeData {
+                pubkey: pubkey(2),
+                signed_blocks: vec![block(2, None)],
+                signed_attestations: vec![],
+            },
+        ]
+    );
+    assert!(merged.conflicts().is_empty());
+
+    // All interchanges must be for the same network.
+    let other_network = interchange_with(Hash256::repeat_byte(0xbb), second.data);
+    assert!(matches!(
+        Interchange::merge(gvr, vec![first, other_network]),
+        Err(InterchangeError::GenesisValidatorsMismatch { .. })
+    ));
+}
+
+#[test]
+fn interchange_conflicts() {
+    let interchange = interchange_with(
+        Hash256::zero(),
+        vec![
+            InterchangeData {
+                pubkey: pubkey(1),
+                signed_blocks: vec![block(1, Some(1)), block(1, None), block(2, None)],
+                signed_attestations: vec![attestation(0, 1, Some(1)), attestation(1, 2, None)],
+            },
+            InterchangeData {
+                pubkey: pubkey(1),
+                signed_blocks: vec![block(1, Some(2)), block(2, None)],
+                signed_attestations: vec![
+                    attestation(0, 1, None),
+                    attestation(0, 2, None),
+                    attestation(1, 2, None),
+                    attestation(0, 5, Some(3)),
+                    attestation(2, 4, Some(4)),
+                ],
+            },
+            // The same records for another validator are not a conflict.
+            InterchangeData {
+                pubkey: pubkey(2),
+                signed_blocks: vec![block(1, Some(2))],
+                signed_attestations: vec![attestation(2, 4, Some(5))],
+            },
+        ],
+    );
+
+    assert_eq!(
+        interchange.conflicts(),
+        vec![
+            InterchangeConflict::DoubleBlockProposal {
+                pubkey: pubkey(1),
+                first: block(1, Some(1)),
+                second: block(1, Some(2)),
+            },
+            InterchangeConflict::DoubleVote {
+                pubkey: pubkey(1),
+                first: attestation(1, 2, None),
+                second: attestation(0, 2, None),
+            },
+            InterchangeConflict::SurroundVote {
+                pubkey: pubkey(1),
+                surrounding: attestation(0, 5, Some(3)),
+                surrounded: attestation(1, 2, None),
+            },
+            InterchangeConflict::SurroundVote {
+                pubkey: pubkey(1),
+                surrounding: attestation(0, 5, Some(3)),
+                surrounded: attestation(2, 4, Some(4)),
+            },
+        ]
+    );
+}
@@ -1,4 +1,4 @@
-use crate::InterchangeError;
+use crate::{InterchangeError, SUPPORTED_INTERCHANGE_FORMAT_VERSION};
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
@@ -45,6 +45,39 @@ pub struct SignedAttestation {
     pub signing_root: Option<Hash256>,
 }
 
+/// A pair of records for the same validator which are slashable if both messages were signed.
+///
+/// A record without a signing root may be for the same message as another record at the same slot
+/// or target epoch, so it is only reported as a double vote if its source epoch differs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterchangeConflict {
+    DoubleBlockProposal {
+        pubkey: PublicKeyBytes,
+        first: SignedBlock,
+        second: SignedBlock,
+    },
+    DoubleVote {
+        pubkey: PublicKeyBytes,
+        first: SignedAttestation,
+        second: SignedAttestation,
+    },
+    SurroundVote {
+        pubkey: PublicKeyBytes,
+        surrounding: SignedAttestation,
+        surrounded: SignedAttestation,
+    },
+}
+
+impl InterchangeConflict {
+    pub fn pubkey(&self) -> &PublicKeyBytes {
+        match self {
+            InterchangeConflict::DoubleBlockProposal { pubkey, .. }
+            | InterchangeConflict::DoubleVote { pubkey, .. }
+            | InterchangeConflict::SurroundVote { pubkey, .. } => pubkey,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Interchange {
@@ -156,4 +189,207 @@ impl Interchange {
             data,
         })
     }
+
+    /// Check that the interchange is for the network with `genesis_validators_root`, in a format
+    /// version which is supported.
+    pub fn check_metadata(&self, genesis_validators_root: Hash256) -> Result<(), InterchangeError> {
+        let version = self.metadata.interchange_format_version;
+        if version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
+            return Err(InterchangeError::UnsupportedVersion(version));
+        }
+
+        if genesis_validators_root != self.metadata.genesis_validators_root {
+            return Err(InterchangeError::GenesisValidatorsMismatch {
+                client: genesis_validators_root,
+                interchange_file: self.metadata.genesis_validators_root,
+            });
+        }
+        Ok(())
+    }
+
+    /// Merge several interchanges for the network with `genesis_validators_root` into one.
+    ///
+    /// The result contains a single entry per validator, holding every distinct block and
+    /// attestation of that validator across all of the `interchanges`. No record is dropped, even
+    /// if it conflicts with another record (see `Self::conflicts`).
+    pub fn merge(
+        genesis_validators_root: Hash256,
+        interchanges: impl IntoIterator<Item = Self>,
+    ) -> Result<Self, InterchangeError> {
+        let mut data = Vec::<InterchangeData>::new();
+        // Index of each validator in `data`.
+        let mut validator_indices = HashMap::<PublicKeyBytes, usize>::new();
+        let mut seen_blocks = HashSet::new();
+        let mut seen_attestations = HashSet::new();
+
+        for interchange in interchanges {
+            interchange.check_metadata(genesis_validators_root)?;
+
+            for record in interchange.data {
+                let pubkey = record.pubkey;
+                let index = *validator_indices.entry(pubkey).or_insert_with(|| {
+                    data.push(InterchangeData {
+                        pubkey,
+                        signed_blocks: vec![],
+                        signed_attestations: vec![],
+                    });
+                    data.len() - 1
+                });
+                let merged = &mut data[index];
+
+                for block in record.signed_blocks {
+                    if seen_blocks.insert((pubkey, block.clone())) {
+                        merged.signed_blocks.push(block);
+                    }
+                }
+                for attestation in record.signed_attestations {
+                    if seen_attestations.insert((pubkey, attestation.clone())) {
+                        merged.signed_attestations.push(attestation);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            metadata: InterchangeMetadata {
+                interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root,
+            },
+            data,
+        })
+    }
+
+    /// Find all pairs of records in the interchange which would be slashable if both were signed.
+    ///
+    /// Multiple entries for the same validator are checked against each other, so merging several
+    /// interchanges first cross-checks them.
+    pub fn conflicts(&self) -> Vec<InterchangeConflict> {
+        let mut validator_data =
+            HashMap::<PublicKeyBytes, (Vec<&SignedBlock>, Vec<&SignedAttestation>)>::new();
+        let mut pubkeys = vec![];
+        // Records which appear more than once are only checked once.
+        let mut seen_blocks = HashSet::new();
+        let mut seen_attestations = HashSet::new();
+        for data in self.data.iter() {
+            let pubkey = data.pubkey;
+            let (blocks, attestations) = validator_data.entry(pubkey).or_insert_with(|| {
+                pubkeys.push(pubkey);
+                Default::default()
+            });
+            blocks.extend(
+                data.signed_blocks
+                    .iter()
+                    .filter(|block| seen_blocks.insert((pubkey, *block))),
+            );
+            attestations.extend(
+                data.signed_attestations
+                    .iter()
+                    .filter(|att| seen_attestations.insert((pubkey, *att))),
+            );
+        }
+
+        let mut conflicts = vec![];
+        for pubkey in pubkeys {
+            let Some((mut blocks, mut attestations)) = validator_data.remove(&pubkey) else {
+                continue;
+            };
+
+            // Blocks at the same slot as a block with a different signing root.
+            blocks.sort_by_key(|block| block.slot);
+            let mut known_root_block: Option<&SignedBlock> = None;
+            for block in blocks {
+                if known_root_block.map_or(false, |known| known.slot != block.slot) {
+                    known_root_block = None;
+                }
+                if block.signing_root.is_none() {
+                    continue;
+                }
+                match known_root_block {
+                    Some(known) if known.signing_root != block.signing_root => {
+                        conflicts.push(InterchangeConflict::DoubleBlockProposal {
+                            pubkey,
+                            first: known.clone(),
+                            second: block.clone(),
+                        });
+                    }
+                    Some(_) => (),
+                    None => known_root_block = Some(block),
+                }
+            }
+
+            // Attestations with the same target as an attestation with a different source or
+            // signing root.
+            attestations.sort_by_key(|att| att.target_epoch);
+            let mut first_attestation: Option<&SignedAttestation> = None;
+            let mut known_root_attestation: Option<&SignedAttestation> = None;
+            for attestation in attestations.iter().copied() {
+                match first_attestation {
+                    Some(first) if first.target_epoch == attestation.target_epoch => {
+                        if first.source_epoch != attestation.source_epoch {
+                            conflicts.push(InterchangeConflict::DoubleVote {
+                                pubkey,
+                                first: first.clone(),
+                                second: attestation.clone(),
+                            });
+                            continue;
+                        }
+                    }
+                    _ => {
+                        first_attestation = Some(attestation);
+                        known_root_attestation = None;
+                    }
+                }
+                if attestation.signing_root.is_none() {
+                    continue;
+                }
+                match known_root_attestation {
+                    Some(known) if known.signing_root != attestation.signing_root => {
+                        conflicts.push(InterchangeConflict::DoubleVote {
+                            pubkey,
+                            first: known.clone(),
+                            second: attestation.clone(),
+                        });
+                    }
+                    Some(_) => (),
+                    None => known_root_attestation = Some(attestation),
+                }
+            }
+
+            // Attestations surrounded by an attestation with a lower source. Sweep in order of
+            // source epoch, tracking the attestation with the highest target amongst those with a
+            // strictly lower source.
+            attestations.sort_by_key(|att| att.source_epoch);
+            let mut max_target_attestation: Option<&SignedAttestation> = None;
+            let mut group_start = 0;
+            while group_start < attestations.len() {
+                let source_epoch = attestations[group_start].source_epoch;
+                let group_end = attestations[group_start..]
+                    .iter()
+                    .position(|att| att.source_epoch != source_epoch)
+                    .map_or(attestations.len(), |len| group_start + len);
+                let group = &attestations[group_start..group_end];
+
+                for attestation in group {
+                    if let Some(surrounding) = max_target_attestation
+                        .filter(|surrounding| surrounding.target_epoch > attestation.target_epoch)
+                    {
+                        conflicts.push(InterchangeConflict::SurroundVote {
+                            pubkey,
+                            surrounding: surrounding.clone(),
+                            surrounded: (*attestation).clone(),
+                        });
+                    }
+                }
+                for attestation in group {
+                    if max_target_attestation.map_or(true, |max_att| {
+                        attestation.target_epoch > max_att.target_epoch
+                    }) {
+                        max_target_attestation = Some(attestation);
+                    }
+                }
+                group_start = group_end;
+            }
+        }
+        conflicts
+    }
 }
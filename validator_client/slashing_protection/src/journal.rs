@@ -0,0 +1,482 @@
+//! An append-only journal of the blocks and attestations signed by a validator client.
+//!
+//! The journal is used to replicate slashing protection data from an active validator client to
+//! standby validator clients, via a file on a filesystem shared between them. The first line of
+//! the journal holds the `JournalMetadata`, each following line holds a `JournalEntry`.
+//!
+//! Entries are written (and synced to disk) *before* the corresponding signature is produced, so a
+//! standby which has imported every complete entry knows about every message signed by the
+//! active validator client.
+//!
+//! The journal is compacted by replacing it with a summary of its entries, under the next
+//! generation. Readers which notice a new generation start again from its first entry.
+
+use crate::interchange::{InterchangeData, SignedAttestation, SignedBlock};
+use crate::SUPPORTED_INTERCHANGE_FORMAT_VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use types::{Epoch, Hash256, PublicKeyBytes, Slot};
+
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    SerdeJson(serde_json::Error),
+    /// The first line of the journal is not valid metadata.
+    MissingMetadata,
+    UnsupportedVersion(u64),
+    GenesisValidatorsMismatch {
+        journal: Hash256,
+        client: Hash256,
+    },
+}
+
+impl From<io::Error> for JournalError {
+    fn from(e: io::Error) -> Self {
+        JournalError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for JournalError {
+    fn from(e: serde_json::Error) -> Self {
+        JournalError::SerdeJson(e)
+    }
+}
+
+/// A single record of the journal.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct JournalEntry {
+    /// Identifies the validator client which wrote the entry.
+    #[serde(with = "serde_utils::quoted_u64::require_quotes")]
+    pub writer_id: u64,
+    pub data: InterchangeData,
+}
+
+impl JournalEntry {
+    pub fn block(
+        writer_id: u64,
+        pubkey: PublicKeyBytes,
+        slot: Slot,
+        signing_root: Hash256,
+    ) -> Self {
+        Self {
+            writer_id,
+            data: InterchangeData {
+                pubkey,
+                signed_blocks: vec![SignedBlock {
+                    slot,
+                    signing_root: Some(signing_root),
+                }],
+                signed_attestations: vec![],
+            },
+        }
+    }
+
+    pub fn attestation(
+        writer_id: u64,
+        pubkey: PublicKeyBytes,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: Hash256,
+    ) -> Self {
+        Self {
+            writer_id,
+            data: InterchangeData {
+                pubkey,
+                signed_blocks: vec![],
+                signed_attestations: vec![SignedAttestation {
+                    source_epoch,
+                    target_epoch,
+                    signing_root: Some(signing_root),
+                }],
+            },
+        }
+    }
+}
+
+/// The first line of the journal.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct JournalMetadata {
+    #[serde(with = "serde_utils::quoted_u64::require_quotes")]
+    pub interchange_format_version: u64,
+    pub genesis_validators_root: Hash256,
+    /// Incremented each time the journal is compacted.
+    #[serde(default, with = "serde_utils::quoted_u64::require_quotes")]
+    pub generation: u64,
+}
+
+fn check_metadata(
+    metadata: &JournalMetadata,
+    genesis_validators_root: Hash256,
+) -> Result<(), JournalError> {
+    if metadata.interchange_format_version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
+        return Err(JournalError::UnsupportedVersion(
+            metadata.interchange_format_version,
+        ));
+    }
+    if metadata.genesis_validators_root != genesis_validators_root {
+        return Err(JournalError::GenesisValidatorsMismatch {
+            journal: metadata.genesis_validators_root,
+            client: genesis_validators_root,
+        });
+    }
+    Ok(())
+}
+
+/// Reads and checks the metadata line at the start of `file`, returning it along with the offset
+/// of the first entry.
+fn read_metadata(
+    file: &mut File,
+    genesis_validators_root: Hash256,
+) -> Result<(JournalMetadata, u64), JournalError> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut line = String::new();
+    BufReader::new(file).read_line(&mut line)?;
+    if !line.ends_with('\n') {
+        return Err(JournalError::MissingMetadata);
+    }
+    let metadata: JournalMetadata =
+        serde_json::from_str(&line).map_err(|_| JournalError::MissingMetadata)?;
+    check_metadata(&metadata, genesis_validators_root)?;
+    Ok((metadata, line.len() as u64))
+}
+
+/// Serializes `item` as a line of the journal.
+fn to_line<T: Serialize>(item: &T) -> Result<Vec<u8>, JournalError> {
+    let mut line = serde_json::to_vec(item)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Appends entries to the journal.
+pub struct JournalWriter {
+    path: PathBuf,
+    metadata: JournalMetadata,
+    file: File,
+}
+
+impl JournalWriter {
+    /// Open the journal at `path` for appending, creating it if it does not exist.
+    pub fn open(path: &Path, genesis_validators_root: Hash256) -> Result<Self, JournalError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let metadata = if file.metadata()?.len() == 0 {
+            let metadata = JournalMetadata {
+                interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root,
+                generation: 0,
+            };
+            file.write_all(&to_line(&metadata)?)?;
+            file.sync_data()?;
+            metadata
+        } else {
+            read_metadata(&mut file, genesis_validators_root)?.0
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            metadata,
+            file,
+        })
+    }
+
+    /// Append `entry` to the journal, returning once it is durably stored.
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<(), JournalError> {
+        self.append_all(std::iter::once(entry))
+    }
+
+    /// Append `entries` to the journal with a single write and sync, returning once they are
+    /// durably stored.
+    pub fn append_all<'a>(
+        &mut self,
+        entries: impl IntoIterator<Item = &'a JournalEntry>,
+    ) -> Result<(), JournalError> {
+        let mut lines = vec![];
+        for entry in entries {
+            lines.extend(to_line(entry)?);
+        }
+
+        // Another validator client may have compacted the journal, which replaces the file.
+        let mut file = File::open(&self.path)?;
+        let (metadata, _) = read_metadata(&mut file, self.metadata.genesis_validators_root)?;
+        if metadata.generation != self.metadata.generation {
+            self.file = OpenOptions::new()
+                .read(true)
+                .append(true)
+                .open(&self.path)?;
+            self.metadata = metadata;
+        }
+
+        // A single write in append mode, so that a concurrent reader never observes a complete
+        // line which is later extended.
+        self.file.write_all(&lines)?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// The size of the journal in bytes.
+    pub fn size(&self) -> Result<u64, JournalError> {
+        Ok(self.file.metadata()?.len())
+    }
+
+    /// Replace the journal with a single entry per validator, written by `writer_id`, which
+    /// summarises all entries of the validator. Returns the number of entries replaced.
+    ///
+    /// Entries are imported like minimal interchange records, so the summary protects against the
+    /// same messages as the entries it replaces. The compacted journal is written to a temporary
+    /// file which is renamed over the journal, so that a crash leaves either journal intact.
+    pub fn compact(&mut self, writer_id: u64) -> Result<usize, JournalError> {
+        let mut reader =
+            JournalReader::new(self.path.clone(), self.metadata.genesis_validators_root);
+        let entries = reader.read_new_entries()?;
+        let num_entries = entries.len();
+
+        let mut summaries: Vec<InterchangeData> = vec![];
+        let mut indices = HashMap::new();
+        for entry in entries {
+            let index = *indices.entry(entry.data.pubkey).or_insert_with(|| {
+                summaries.push(InterchangeData {
+                    pubkey: entry.data.pubkey,
+                    signed_blocks: vec![],
+                    signed_attestations: vec![],
+                });
+                summaries.len() - 1
+            });
+            let summary = &mut summaries[index];
+            summary.signed_blocks.extend(entry.data.signed_blocks);
+            summary
+                .signed_attestations
+                .extend(entry.data.signed_attestations);
+        }
+        for summary in &mut summaries {
+            // Only the maximum slot, source and target are imported from each record.
+            let max_block = summary
+                .signed_blocks
+                .iter()
+                .max_by_key(|block| block.slot)
+                .cloned();
+            summary.signed_blocks = max_block.into_iter().collect();
+
+            let attestations = &summary.signed_attestations;
+            let max_source = attestations.iter().max_by_key(|att| att.source_epoch);
+            let max_target = attestations.iter().max_by_key(|att| att.target_epoch);
+            let mut max_attestations = max_source.into_iter().cloned().collect::<Vec<_>>();
+            if let Some(max_target) = max_target {
+                if max_source != Some(max_target) {
+                    max_attestations.push(max_target.clone());
+                }
+            }
+            summary.signed_attestations = max_attestations;
+        }
+
+        let metadata = JournalMetadata {
+            generation: self.metadata.generation + 1,
+            ..self.metadata.clone()
+        };
+        let mut bytes = to_line(&metadata)?;
+        for data in summaries {
+            bytes.extend(to_line(&JournalEntry { writer_id, data })?);
+        }
+
+        let mut compacted_path = self.path.clone().into_os_string();
+        compacted_path.push(".compacted");
+        let mut compacted = File::create(&compacted_path)?;
+        compacted.write_all(&bytes)?;
+        compacted.sync_all()?;
+        fs::rename(&compacted_path, &self.path)?;
+
+        self.file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        self.metadata = metadata;
+        Ok(num_entries)
+    }
+}
+
+/// Reads the entries appended to the journal since the previous read.
+pub struct JournalReader {
+    path: PathBuf,
+    genesis_validators_root: Hash256,
+    /// The generation of the journal which is being read.
+    generation: Option<u64>,
+    /// The offset of the first byte which has not been read yet.
+    offset: u64,
+}
+
+impl JournalReader {
+    pub fn new(path: PathBuf, genesis_validators_root: Hash256) -> Self {
+        Self {
+            path,
+            genesis_validators_root,
+            generation: None,
+            offset: 0,
+        }
+    }
+
+    /// Returns all complete entries written since the previous call.
+    ///
+    /// A journal which does not exist yet is treated as empty. If the journal has been truncated
+    /// or compacted it is read again from the start, importing an entry twice is harmless.
+    pub fn read_new_entries(&mut self) -> Result<Vec<JournalEntry>, JournalError> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+
+        let len = file.metadata()?.len();
+        if len == 0 {
+            return Ok(vec![]);
+        }
+        let (metadata, entries_offset) = read_metadata(&mut file, self.genesis_validators_root)?;
+        if self.generation != Some(metadata.generation) || len < self.offset {
+            self.generation = Some(metadata.generation);
+            self.offset = entries_offset;
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        // Ignore a trailing partial line, it is read once it has been completely written.
+        let complete_len = bytes
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |position| position + 1);
+
+        let entries = bytes[..complete_len]
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(serde_json::from_slice)
+            .collect::<Result<Vec<JournalEntry>, _>>()?;
+
+        self.offset += complete_len as u64;
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::pubkey;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reader_sees_appended_entries() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("journal");
+        let gvr = Hash256::repeat_byte(1);
+
+        let mut reader = JournalReader::new(path.clone(), gvr);
+        assert!(reader.read_new_entries().unwrap().is_empty());
+
+        let mut writer = JournalWriter::open(&path, gvr).unwrap();
+        let block = JournalEntry::block(1, pubkey(0), Slot::new(5), Hash256::repeat_byte(2));
+        writer.append(&block).unwrap();
+        assert_eq!(reader.read_new_entries().unwrap(), vec![block]);
+        assert!(reader.read_new_entries().unwrap().is_empty());
+
+        let attestation = JournalEntry::attestation(
+            1,
+            pubkey(1),
+            Epoch::new(1),
+            Epoch::new(2),
+            Hash256::repeat_byte(3),
+        );
+        writer.append(&attestation).unwrap();
+
+        // A partially written line is not read.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"writer_id\":").unwrap();
+        assert_eq!(reader.read_new_entries().unwrap(), vec![attestation]);
+    }
+
+    #[test]
+    fn compaction_summarises_entries() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("journal");
+        let gvr = Hash256::repeat_byte(1);
+
+        let mut writer = JournalWriter::open(&path, gvr).unwrap();
+        let mut reader = JournalReader::new(path.clone(), gvr);
+        let entries = vec![
+            JournalEntry::block(1, pubkey(0), Slot::new(5), Hash256::repeat_byte(2)),
+            JournalEntry::block(1, pubkey(0), Slot::new(9), Hash256::repeat_byte(3)),
+            JournalEntry::attestation(
+                1,
+                pubkey(0),
+                Epoch::new(1),
+                Epoch::new(2),
+                Hash256::repeat_byte(4),
+            ),
+            JournalEntry::attestation(
+                1,
+                pubkey(1),
+                Epoch::new(2),
+                Epoch::new(3),
+                Hash256::repeat_byte(5),
+            ),
+        ];
+        writer.append_all(&entries).unwrap();
+        assert_eq!(reader.read_new_entries().unwrap(), entries);
+
+        let size = writer.size().unwrap();
+        assert_eq!(writer.compact(2).unwrap(), entries.len());
+        assert!(writer.size().unwrap() < size);
+
+        // The reader starts again from the summary of each validator.
+        let summary = |index, signed_blocks, signed_attestations| JournalEntry {
+            writer_id: 2,
+            data: InterchangeData {
+                pubkey: pubkey(index),
+                signed_blocks,
+                signed_attestations,
+            },
+        };
+        assert_eq!(
+            reader.read_new_entries().unwrap(),
+            vec![
+                summary(
+                    0,
+                    entries[1].data.signed_blocks.clone(),
+                    entries[2].data.signed_attestations.clone()
+                ),
+                summary(1, vec![], entries[3].data.signed_attestations.clone()),
+            ]
+        );
+
+        // Entries appended after the compaction are read as usual, also by a new writer.
+        let block = JournalEntry::block(2, pubkey(1), Slot::new(10), Hash256::repeat_byte(6));
+        writer.append(&block).unwrap();
+        assert_eq!(reader.read_new_entries().unwrap(), vec![block]);
+
+        let mut other_writer = JournalWriter::open(&path, gvr).unwrap();
+        writer.compact(2).unwrap();
+        let block = JournalEntry::block(3, pubkey(0), Slot::new(11), Hash256::repeat_byte(7));
+        other_writer.append(&block).unwrap();
+        let entries = reader.read_new_entries().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries.last(), Some(&block));
+    }
+
+    #[test]
+    fn reopen_checks_genesis_validators_root() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("journal");
+        JournalWriter::open(&path, Hash256::repeat_byte(1)).unwrap();
+        JournalWriter::open(&path, Hash256::repeat_byte(1)).unwrap();
+        assert!(matches!(
+            JournalWriter::open(&path, Hash256::repeat_byte(2)),
+            Err(JournalError::GenesisValidatorsMismatch { .. })
+        ));
+    }
+}
@@ -3,6 +3,7 @@ mod block_tests;
 mod extra_interchange_tests;
 pub mod interchange;
 pub mod interchange_test;
+pub mod journal;
 mod parallel_tests;
 mod registration_tests;
 mod signed_attestation;
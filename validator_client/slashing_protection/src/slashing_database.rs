@@ -2,6 +2,7 @@ use crate::interchange::{
     Interchange, InterchangeData, InterchangeMetadata, SignedAttestation as InterchangeAttestation,
     SignedBlock as InterchangeBlock,
 };
+use crate::journal::JournalEntry;
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
 use crate::{signing_root_from_row, NotSafe, Safe, SignedAttestation, SignedBlock, SigningRoot};
@@ -679,17 +680,7 @@ impl SlashingDatabase {
         interchange: Interchange,
         genesis_validators_root: Hash256,
     ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
-        let version = interchange.metadata.interchange_format_version;
-        if version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
-            return Err(InterchangeError::UnsupportedVersion(version));
-        }
-
-        if genesis_validators_root != interchange.metadata.genesis_validators_root {
-            return Err(InterchangeError::GenesisValidatorsMismatch {
-                client: genesis_validators_root,
-                interchange_file: interchange.metadata.genesis_validators_root,
-            });
-        }
+        interchange.check_metadata(genesis_validators_root)?;
 
         // Create a single transaction for the entire batch, which will only be committed if
         // all records are imported successfully.
@@ -792,6 +783,19 @@ impl SlashingDatabase {
         }
     }
 
+    /// Import the records of a replication journal, atomically.
+    ///
+    /// Each record is imported like a minimal interchange record, so importing a record more than
+    /// once is harmless.
+    pub fn import_journal_entries(&self, entries: Vec<JournalEntry>) -> Result<(), NotSafe> {
+        self.with_transaction(|txn| {
+            for entry in entries {
+                self.import_interchange_record(entry.data, txn)?;
+            }
+            Ok(())
+        })
+    }
+
     pub fn export_all_interchange_info(
         &self,
         genesis_validators_root: Hash256,
@@ -1,12 +1,15 @@
 use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::{
     duties_service::{DutiesService, DutyAndProof},
+    duty_history::delay_since_slot_start,
     http_metrics::metrics,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::DutyKind;
 use futures::future::join_all;
+use parking_lot::Mutex;
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
@@ -394,7 +397,15 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 )
                 .await
             {
-                Ok(()) => Some((attestation, duty.validator_index)),
+                Ok(()) => {
+                    self.validator_store.duty_history().signed(
+                        duty.pubkey,
+                        DutyKind::Attestation,
+                        slot,
+                        delay_since_slot_start(&self.slot_clock, slot),
+                    );
+                    Some((attestation, duty.validator_index, duty.pubkey))
+                }
                 Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                     // A pubkey can be missing when a validator was recently
                     // removed via the API.
@@ -410,6 +421,12 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     None
                 }
                 Err(e) => {
+                    self.validator_store.duty_history().failed(
+                        duty.pubkey,
+                        DutyKind::Attestation,
+                        slot,
+                        format!("Failed to sign attestation: {:?}", e),
+                    );
                     crit!(
                         log,
                         "Failed to sign attestation";
@@ -424,18 +441,25 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
-        let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = join_all(signing_futures)
-            .await
-            .into_iter()
-            .flatten()
-            .unzip();
+        let mut attestations = vec![];
+        let mut validator_indices = vec![];
+        let mut pubkeys = vec![];
+        for (attestation, validator_index, pubkey) in
+            join_all(signing_futures).await.into_iter().flatten()
+        {
+            attestations.push(attestation);
+            validator_indices.push(validator_index);
+            pubkeys.push(pubkey);
+        }
+        let attestations = &attestations;
 
         if attestations.is_empty() {
             warn!(log, "No attestations were published");
             return Ok(None);
         }
 
-        // Post the attestations to the BN.
+        // Post the attestations to the BN, noting the beacon nodes which accept them.
+        let published_to = &Mutex::new(vec![]);
         match self
             .beacon_nodes
             .request(
@@ -447,31 +471,58 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         &metrics::ATTESTATION_SERVICE_TIMES,
                         &[metrics::ATTESTATIONS_HTTP_POST],
                     );
-                    beacon_node
+                    let result = beacon_node
                         .post_beacon_pool_attestations(attestations)
-                        .await
+                        .await;
+                    if result.is_ok() {
+                        published_to.lock().push(beacon_node.to_string());
+                    }
+                    result
                 },
             )
             .await
         {
-            Ok(()) => info!(
-                log,
-                "Successfully published attestations";
-                "count" => attestations.len(),
-                "validator_indices" => ?validator_indices,
-                "head_block" => ?attestation_data.beacon_block_root,
-                "committee_index" => attestation_data.index,
-                "slot" => attestation_data.slot.as_u64(),
-                "type" => "unaggregated",
-            ),
-            Err(e) => error!(
-                log,
-                "Unable to publish attestations";
-                "error" => %e,
-                "committee_index" => attestation_data.index,
-                "slot" => slot.as_u64(),
-                "type" => "unaggregated",
-            ),
+            Ok(()) => {
+                let delay = delay_since_slot_start(&self.slot_clock, slot);
+                let beacon_nodes = published_to.lock().clone();
+                for pubkey in &pubkeys {
+                    self.validator_store.duty_history().published(
+                        *pubkey,
+                        DutyKind::Attestation,
+                        slot,
+                        beacon_nodes.clone(),
+                        delay,
+                    );
+                }
+                info!(
+                    log,
+                    "Successfully published attestations";
+                    "count" => attestations.len(),
+                    "validator_indices" => ?validator_indices,
+                    "head_block" => ?attestation_data.beacon_block_root,
+                    "committee_index" => attestation_data.index,
+                    "slot" => attestation_data.slot.as_u64(),
+                    "type" => "unaggregated",
+                )
+            }
+            Err(e) => {
+                for pubkey in &pubkeys {
+                    self.validator_store.duty_history().failed(
+                        *pubkey,
+                        DutyKind::Attestation,
+                        slot,
+                        format!("Unable to publish attestation: {}", e),
+                    );
+                }
+                error!(
+                    log,
+                    "Unable to publish attestations";
+                    "error" => %e,
+                    "committee_index" => attestation_data.index,
+                    "slot" => slot.as_u64(),
+                    "type" => "unaggregated",
+                )
+            }
         }
 
         Ok(Some(attestation_data))
@@ -2,14 +2,20 @@
 //! "fallback" behaviour; it will try a request on all of the nodes until one or none of them
 //! succeed.
 
+use crate::beacon_node_health::BeaconNodeHealth;
 use crate::check_synced::check_synced;
-use crate::http_metrics::metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
+use crate::http_metrics::metrics::{
+    inc_counter_vec, set_int_gauge, ENDPOINT_ERRORS, ENDPOINT_REQUESTS, VC_BEACON_NODE_HEALTH_SCORE,
+};
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{BeaconNodeHealthData, BeaconNodeStatus};
 use eth2::BeaconNodeHttpClient;
 use futures::future;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
+use std::cmp::Reverse;
 use std::fmt;
 use std::fmt::Debug;
 use std::future::Future;
@@ -143,11 +149,24 @@ pub enum CandidateError {
     NotSynced,
 }
 
+impl From<Result<(), CandidateError>> for BeaconNodeStatus {
+    fn from(status: Result<(), CandidateError>) -> Self {
+        match status {
+            Ok(()) => BeaconNodeStatus::Synced,
+            Err(CandidateError::Uninitialized) => BeaconNodeStatus::Uninitialized,
+            Err(CandidateError::Offline) => BeaconNodeStatus::Offline,
+            Err(CandidateError::Incompatible) => BeaconNodeStatus::Incompatible,
+            Err(CandidateError::NotSynced) => BeaconNodeStatus::NotSynced,
+        }
+    }
+}
+
 /// Represents a `BeaconNodeHttpClient` inside a `BeaconNodeFallback` that may or may not be used
 /// for a query.
 pub struct CandidateBeaconNode<E> {
     beacon_node: BeaconNodeHttpClient,
     status: RwLock<Result<(), CandidateError>>,
+    health: Mutex<BeaconNodeHealth>,
     _phantom: PhantomData<E>,
 }
 
@@ -157,6 +176,7 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Self {
             beacon_node,
             status: RwLock::new(Err(CandidateError::Uninitialized)),
+            health: Mutex::new(BeaconNodeHealth::default()),
             _phantom: PhantomData,
         }
     }
@@ -176,6 +196,16 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         *self.status.write().await = Err(CandidateError::Offline)
     }
 
+    /// Returns the latest health measurements of `self`.
+    pub fn health(&self) -> BeaconNodeHealth {
+        self.health.lock().clone()
+    }
+
+    /// Record the outcome of a request to `self`.
+    fn record_request(&self, success: bool) {
+        self.health.lock().record_request(success);
+    }
+
     /// Perform some queries against the node to determine if it is a good candidate, updating
     /// `self.status` and returning that result.
     pub async fn refresh_status<T: SlotClock>(
@@ -208,12 +238,23 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 
     /// Checks if the node is reachable.
     async fn is_online(&self, was_offline: bool, log: &Logger) -> Result<(), CandidateError> {
+        let request_instant = Instant::now();
         let result = self
             .beacon_node
             .get_node_version()
             .await
             .map(|body| body.data.version);
 
+        // The status of the node is refreshed every slot, which allows the error rate of a node
+        // to recover even when it receives no other requests.
+        {
+            let mut health = self.health.lock();
+            health.record_request(result.is_ok());
+            if result.is_ok() {
+                health.record_latency(request_instant.elapsed());
+            }
+        }
+
         match result {
             Ok(version) => {
                 if was_offline {
@@ -318,7 +359,13 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         log: &Logger,
     ) -> Result<(), CandidateError> {
         if let Some(slot_clock) = slot_clock {
-            check_synced(&self.beacon_node, slot_clock, Some(log)).await
+            let (result, syncing) = check_synced(&self.beacon_node, slot_clock, Some(log)).await;
+            if let Some(syncing) = syncing {
+                self.health
+                    .lock()
+                    .record_sync_status(syncing.sync_distance, syncing.el_offline);
+            }
+            result
         } else {
             // Skip this check if we don't supply a slot clock.
             Ok(())
@@ -333,6 +380,8 @@ pub struct BeaconNodeFallback<T, E> {
     candidates: Vec<CandidateBeaconNode<E>>,
     slot_clock: Option<T>,
     broadcast_topics: Vec<ApiTopic>,
+    /// Try the candidates in order of their health score rather than in the configured order.
+    rank_by_health: bool,
     spec: ChainSpec,
     log: Logger,
 }
@@ -341,6 +390,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     pub fn new(
         candidates: Vec<CandidateBeaconNode<E>>,
         broadcast_topics: Vec<ApiTopic>,
+        rank_by_health: bool,
         spec: ChainSpec,
         log: Logger,
     ) -> Self {
@@ -348,6 +398,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
             candidates,
             slot_clock: None,
             broadcast_topics,
+            rank_by_health,
             spec,
             log,
         }
@@ -416,6 +467,48 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
 
         // run all updates concurrently and ignore errors
         let _ = future::join_all(futures).await;
+
+        for candidate in &self.candidates {
+            set_int_gauge(
+                &VC_BEACON_NODE_HEALTH_SCORE,
+                &[candidate.beacon_node.as_ref()],
+                candidate.health().score() as i64,
+            );
+        }
+    }
+
+    /// Returns the candidates in the order in which they should be tried.
+    ///
+    /// If ranking is enabled the healthiest candidates come first, candidates with the same score
+    /// keep their configured order.
+    fn ranked_candidates(&self) -> Vec<&CandidateBeaconNode<E>> {
+        let mut candidates = self.candidates.iter().collect::<Vec<_>>();
+        if self.rank_by_health {
+            candidates.sort_by_cached_key(|candidate| Reverse(candidate.health().score()));
+        }
+        candidates
+    }
+
+    /// Returns the health of each candidate, in the order in which they are tried.
+    pub async fn health(&self) -> Vec<BeaconNodeHealthData> {
+        let mut ranking = Vec::with_capacity(self.candidates.len());
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            let health = candidate.health();
+            ranking.push(BeaconNodeHealthData {
+                index,
+                endpoint: candidate.beacon_node.to_string(),
+                status: candidate.status(RequireSynced::Yes).await.into(),
+                score: health.score(),
+                latency_ms: health.latency.map(|latency| latency.as_millis() as u64),
+                sync_distance: health.sync_distance,
+                el_offline: health.el_offline,
+                error_rate: health.error_rate,
+            });
+        }
+        if self.rank_by_health {
+            ranking.sort_by_key(|health| Reverse(health.score));
+        }
+        ranking
     }
 
     /// Concurrently send a request to all candidates (regardless of
@@ -439,7 +532,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                     .await
                     .ok()
                     .map(|_| Instant::now());
-                (beacon_node_id, response_instant)
+                (candidate, beacon_node_id, response_instant)
             })
             .collect();
 
@@ -451,10 +544,16 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         future::join_all(futures)
             .await
             .into_iter()
-            .map(|(beacon_node_id, response_instant)| LatencyMeasurement {
-                beacon_node_id,
-                latency: response_instant
-                    .and_then(|response| response.checked_duration_since(request_instant)),
+            .map(|(candidate, beacon_node_id, response_instant)| {
+                let latency = response_instant
+                    .and_then(|response| response.checked_duration_since(request_instant));
+                if let Some(latency) = latency {
+                    candidate.health.lock().record_latency(latency);
+                }
+                LatencyMeasurement {
+                    beacon_node_id,
+                    latency,
+                }
             })
             .collect()
     }
@@ -492,8 +591,12 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                 // There exists a race condition where `func` may be called when the candidate is
                 // actually not ready. We deem this an acceptable inefficiency.
                 match func(&$candidate.beacon_node).await {
-                    Ok(val) => return Ok(val),
+                    Ok(val) => {
+                        $candidate.record_request(true);
+                        return Ok(val);
+                    }
                     Err(e) => {
+                        $candidate.record_request(false);
                         debug!(
                             log,
                             "Request to beacon node failed";
@@ -518,7 +621,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // First pass: try `func` on all synced and ready candidates.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.ranked_candidates() {
             match candidate.status(RequireSynced::Yes).await {
                 Err(e @ CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
@@ -606,8 +709,12 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                 // There exists a race condition where `func` may be called when the candidate is
                 // actually not ready. We deem this an acceptable inefficiency.
                 match func(&$candidate.beacon_node).await {
-                    Ok(val) => results.push(Ok(val)),
+                    Ok(val) => {
+                        $candidate.record_request(true);
+                        results.push(Ok(val));
+                    }
                     Err(e) => {
+                        $candidate.record_request(false);
                         // If we have an error on this function, make the client as not-ready.
                         //
                         // There exists a race condition where the candidate may have been marked
@@ -629,7 +736,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // First pass: try `func` on all synced and ready candidates.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.ranked_candidates() {
             match candidate.status(RequireSynced::Yes).await {
                 Err(CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
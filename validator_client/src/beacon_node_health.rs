@@ -0,0 +1,143 @@
+//! Scores the health of each beacon node, so that `BeaconNodeFallback` is able to try the
+//! healthiest beacon nodes first.
+//!
+//! The score starts at `MAX_SCORE` and is reduced by penalties for latency, distance from the head
+//! of the chain, an offline execution layer and recent errors.
+
+use std::time::Duration;
+use types::Slot;
+
+/// The score of a beacon node without any penalty.
+pub const MAX_SCORE: u64 = 100;
+
+/// One point is deducted for each `LATENCY_MS_PER_POINT` of latency.
+const LATENCY_MS_PER_POINT: u64 = 20;
+const MAX_LATENCY_PENALTY: u64 = 30;
+/// The penalty of a beacon node whose latency has not been measured yet.
+const UNKNOWN_LATENCY_PENALTY: u64 = MAX_LATENCY_PENALTY / 2;
+const PENALTY_PER_SLOT_BEHIND: u64 = 10;
+const MAX_SYNC_DISTANCE_PENALTY: u64 = 40;
+const EL_OFFLINE_PENALTY: u64 = 30;
+/// The penalty of a beacon node for which every recent request failed.
+const MAX_ERROR_RATE_PENALTY: u64 = 30;
+
+/// The weight of a new sample in the moving averages of the latency and error rate.
+const LATENCY_SMOOTHING: f64 = 0.2;
+const ERROR_RATE_SMOOTHING: f64 = 0.1;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BeaconNodeHealth {
+    /// The moving average of the round-trip latency.
+    pub latency: Option<Duration>,
+    /// The distance of the head of the beacon node from the current slot.
+    pub sync_distance: Option<Slot>,
+    pub el_offline: Option<bool>,
+    /// The moving average of the proportion of failed requests.
+    pub error_rate: f64,
+}
+
+impl BeaconNodeHealth {
+    pub fn record_latency(&mut self, latency: Duration) {
+        self.latency = Some(match self.latency {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING)
+            }
+            None => latency,
+        });
+    }
+
+    pub fn record_sync_status(&mut self, sync_distance: Slot, el_offline: Option<bool>) {
+        self.sync_distance = Some(sync_distance);
+        self.el_offline = el_offline;
+    }
+
+    /// Record the outcome of a request to the beacon node.
+    pub fn record_request(&mut self, success: bool) {
+        let sample = if success { 0.0 } else { 1.0 };
+        self.error_rate =
+            self.error_rate * (1.0 - ERROR_RATE_SMOOTHING) + sample * ERROR_RATE_SMOOTHING;
+    }
+
+    /// Returns a score from 0 to `MAX_SCORE`, healthier beacon nodes have a higher score.
+    pub fn score(&self) -> u64 {
+        let latency_penalty = self.latency.map_or(UNKNOWN_LATENCY_PENALTY, |latency| {
+            std::cmp::min(
+                latency.as_millis() as u64 / LATENCY_MS_PER_POINT,
+                MAX_LATENCY_PENALTY,
+            )
+        });
+        let sync_distance_penalty = self.sync_distance.map_or(0, |distance| {
+            std::cmp::min(
+                distance.as_u64().saturating_mul(PENALTY_PER_SLOT_BEHIND),
+                MAX_SYNC_DISTANCE_PENALTY,
+            )
+        });
+        let el_offline_penalty = if self.el_offline == Some(true) {
+            EL_OFFLINE_PENALTY
+        } else {
+            0
+        };
+        let error_rate_penalty =
+            (self.error_rate.clamp(0.0, 1.0) * MAX_ERROR_RATE_PENALTY as f64).round() as u64;
+
+        MAX_SCORE
+            .saturating_sub(latency_penalty)
+            .saturating_sub(sync_distance_penalty)
+            .saturating_sub(el_offline_penalty)
+            .saturating_sub(error_rate_penalty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_health() {
+        assert_eq!(
+            BeaconNodeHealth::default().score(),
+            MAX_SCORE - UNKNOWN_LATENCY_PENALTY
+        );
+    }
+
+    #[test]
+    fn penalties() {
+        let mut health = BeaconNodeHealth::default();
+        health.record_latency(Duration::from_millis(100));
+        assert_eq!(health.score(), MAX_SCORE - 5);
+
+        health.record_sync_status(Slot::new(2), Some(false));
+        assert_eq!(health.score(), MAX_SCORE - 5 - 20);
+
+        health.record_sync_status(Slot::new(0), Some(true));
+        assert_eq!(health.score(), MAX_SCORE - 5 - EL_OFFLINE_PENALTY);
+
+        health.record_sync_status(Slot::new(1_000), Some(true));
+        health.record_latency(Duration::from_secs(1_000));
+        for _ in 0..100 {
+            health.record_request(false);
+        }
+        assert_eq!(health.score(), 0);
+    }
+
+    #[test]
+    fn error_rate_recovers() {
+        let mut health = BeaconNodeHealth::default();
+        health.record_request(false);
+        assert!(health.error_rate > 0.0);
+
+        for _ in 0..100 {
+            health.record_request(true);
+        }
+        assert!(health.error_rate < 0.001);
+    }
+
+    #[test]
+    fn latency_is_smoothed() {
+        let mut health = BeaconNodeHealth::default();
+        health.record_latency(Duration::from_millis(100));
+        health.record_latency(Duration::from_millis(600));
+        let latency = health.latency.unwrap().as_secs_f64();
+        assert!((latency - 0.2).abs() < 1e-6);
+    }
+}
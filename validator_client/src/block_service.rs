@@ -2,6 +2,7 @@ use crate::beacon_node_fallback::{Error as FallbackError, Errors};
 use crate::{
     beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced},
     determine_graffiti,
+    duty_history::delay_since_slot_start,
     graffiti_file::GraffitiFile,
     OfflineOnFailure,
 };
@@ -11,8 +12,10 @@ use crate::{
 };
 use bls::SignatureBytes;
 use environment::RuntimeContext;
-use eth2::types::{FullBlockContents, PublishBlockRequest};
+use eth2::lighthouse_vc::types::DutyKind;
+use eth2::types::{ExecutionClientVersion, FullBlockContents, PublishBlockRequest};
 use eth2::{BeaconNodeHttpClient, StatusCode};
+use parking_lot::Mutex;
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::fmt::Debug;
@@ -331,12 +334,14 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 self.inner.context.executor.spawn(
                     async move {
                         let result = service
+                            .clone()
                             .publish_block_v3(slot, validator_pubkey, builder_boost_factor)
                             .await;
 
                         match result {
                             Ok(_) => {}
                             Err(BlockError::Recoverable(e)) | Err(BlockError::Irrecoverable(e)) => {
+                                service.record_failed_proposal(validator_pubkey, slot, &e);
                                 error!(
                                     log,
                                     "Error whilst producing block";
@@ -377,6 +382,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                                         .publish_block(slot, validator_pubkey, false)
                                         .await
                                     {
+                                        service.record_failed_proposal(
+                                            validator_pubkey,
+                                            slot,
+                                            &format!("{:?}", e),
+                                        );
                                         // Log a `crit` since a full block
                                         // (non-builder) proposal failed.
                                         crit!(
@@ -392,6 +402,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                                     // Only log an `error` since it's common for
                                     // builders to timeout on their response, only
                                     // to publish the block successfully themselves.
+                                    service.record_failed_proposal(validator_pubkey, slot, &e);
                                     error!(
                                         log,
                                         "Error whilst producing block";
@@ -406,6 +417,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                             .publish_block(slot, validator_pubkey, false)
                             .await
                             {
+                                service.record_failed_proposal(
+                                    validator_pubkey,
+                                    slot,
+                                    &format!("{:?}", e),
+                                );
                                 // Log a `crit` since a full block (non-builder)
                                 // proposal failed.
                                 crit!(
@@ -475,6 +491,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             }
         };
 
+        self.validator_store.duty_history().signed(
+            *validator_pubkey,
+            DutyKind::Proposal,
+            slot,
+            delay_since_slot_start(self.slot_clock.as_ref(), slot),
+        );
+
         let signing_time_ms =
             Duration::from_secs_f64(signing_timer.map_or(0.0, |t| t.stop_and_record())).as_millis();
 
@@ -490,17 +513,31 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         // Try the proposer nodes first, since we've likely gone to efforts to
         // protect them from DoS attacks and they're most likely to successfully
         // publish a block.
+        let published_to = &Mutex::new(vec![]);
         proposer_fallback
             .request_proposers_first(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
                 |beacon_node| async {
-                    self.publish_signed_block_contents(&signed_block, beacon_node)
-                        .await
+                    let result = self
+                        .publish_signed_block_contents(&signed_block, beacon_node)
+                        .await;
+                    if result.is_ok() {
+                        published_to.lock().push(beacon_node.to_string());
+                    }
+                    result
                 },
             )
             .await?;
 
+        self.validator_store.duty_history().published(
+            *validator_pubkey,
+            DutyKind::Proposal,
+            slot,
+            published_to.lock().clone(),
+            delay_since_slot_start(self.slot_clock.as_ref(), slot),
+        );
+
         info!(
             log,
             "Successfully published block";
@@ -549,11 +586,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             }
         };
 
+        let execution_client = self.graffiti_execution_client(&validator_pubkey).await;
         let graffiti = determine_graffiti(
             &validator_pubkey,
             log,
             self.graffiti_file.clone(),
-            self.validator_store.graffiti(&validator_pubkey),
+            self.validator_store
+                .graffiti(&validator_pubkey, execution_client.as_ref()),
             self.graffiti,
         );
 
@@ -656,11 +695,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             }
         };
 
+        let execution_client = self.graffiti_execution_client(&validator_pubkey).await;
         let graffiti = determine_graffiti(
             &validator_pubkey,
             log,
             self.graffiti_file.clone(),
-            self.validator_store.graffiti(&validator_pubkey),
+            self.validator_store
+                .graffiti(&validator_pubkey, execution_client.as_ref()),
             self.graffiti,
         );
 
@@ -713,6 +754,48 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         Ok(())
     }
 
+    /// Records in the duty history that the proposal of `validator_pubkey` failed.
+    fn record_failed_proposal(&self, validator_pubkey: PublicKeyBytes, slot: Slot, error: &str) {
+        self.validator_store.duty_history().failed(
+            validator_pubkey,
+            DutyKind::Proposal,
+            slot,
+            error.to_string(),
+        );
+    }
+
+    /// Returns the execution client of the beacon nodes if the graffiti of the validator is a
+    /// template, which may refer to the execution client.
+    async fn graffiti_execution_client(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+    ) -> Option<ExecutionClientVersion> {
+        if !self.validator_store.has_graffiti_template(validator_pubkey) {
+            return None;
+        }
+
+        self.beacon_nodes
+            .first_success(
+                RequireSynced::No,
+                OfflineOnFailure::No,
+                |beacon_node| async move {
+                    beacon_node
+                        .get_node_version()
+                        .await
+                        .map(|response| response.data.execution_client)
+                },
+            )
+            .await
+            .unwrap_or_else(|e| {
+                debug!(
+                    self.context.log(),
+                    "Unable to get execution client for graffiti";
+                    "error" => %e,
+                );
+                None
+            })
+    }
+
     async fn publish_signed_block_contents(
         &self,
         signed_block: &SignedBlock<E>,
@@ -49,6 +49,15 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("enable-beacon-node-ranking")
+                .long("enable-beacon-node-ranking")
+                .help("Rank the beacon nodes by a health score, based on their latency, sync \
+                       distance, execution layer status and recent errors, and try the healthiest \
+                       synced beacon node first. By default the beacon nodes are tried in the \
+                       order of --beacon-nodes.")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("validators-dir")
                 .long("validators-dir")
@@ -373,6 +382,64 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     constructed by builders, regardless of payload value.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("exit-vault-dir")
+                .long("exit-vault-dir")
+                .value_name("DIR")
+                .help("If present, the validator client keeps an encrypted, pre-signed voluntary \
+                    exit for each of its validators in this directory. The exits are signed again \
+                    after any fork which changes the voluntary exit domain.")
+                .requires("exit-vault-password-file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("exit-vault-password-file")
+                .long("exit-vault-password-file")
+                .value_name("PATH")
+                .help("A file containing the password used to encrypt the exits in the \
+                    --exit-vault-dir.")
+                .requires("exit-vault-dir")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slashing-protection-journal")
+                .long("slashing-protection-journal")
+                .value_name("PATH")
+                .help("If present, the validator client replicates its slashing protection data \
+                    to standby validator clients via a journal at this path, which must be on a \
+                    filesystem shared by all validator clients of the group. Each block and \
+                    attestation is appended to the journal before it is signed.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("slashing-protection-standby")
+                .long("slashing-protection-standby")
+                .help("Start as a standby of the --slashing-protection-journal. A standby imports \
+                    the journal and does not sign anything until the validators have been \
+                    offline for --slashing-protection-failover-epochs, it then becomes active.")
+                .requires("slashing-protection-journal")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("slashing-protection-failover-epochs")
+                .long("slashing-protection-failover-epochs")
+                .value_name("EPOCHS")
+                .help("The number of consecutive epochs in which none of the validators are \
+                    live, according to the beacon node, before a standby becomes active. \
+                    [default: 3]")
+                .requires("slashing-protection-journal")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("duty-history-epochs")
+                .long("duty-history-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs for which the block proposals and attestations of \
+                    each validator are kept in memory and served by the \
+                    /lighthouse/validators/{pubkey}/history endpoint. Set to 0 to keep no \
+                    history. [default: 64]")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("disable-slashing-protection-web3signer")
                 .long("disable-slashing-protection-web3signer")
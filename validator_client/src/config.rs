@@ -1,5 +1,8 @@
 use crate::beacon_node_fallback::ApiTopic;
+use crate::duty_history::DEFAULT_DUTY_HISTORY_EPOCHS;
+use crate::exit_vault;
 use crate::graffiti_file::GraffitiFile;
+use crate::replication;
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
@@ -72,6 +75,8 @@ pub struct Config {
     pub beacon_nodes_tls_certs: Option<Vec<PathBuf>>,
     /// Enables broadcasting of various requests (by topic) to all beacon nodes.
     pub broadcast_topics: Vec<ApiTopic>,
+    /// Try the beacon nodes in order of their health score rather than in the configured order.
+    pub beacon_node_ranking: bool,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
@@ -86,6 +91,13 @@ pub struct Config {
     pub prefer_builder_proposals: bool,
     /// Whether we are running with distributed network support.
     pub distributed: bool,
+    /// If set, a pre-signed and encrypted voluntary exit is kept for each validator.
+    pub exit_vault: Option<exit_vault::Config>,
+    /// If set, slashing protection data is replicated between validator clients via a journal.
+    pub slashing_protection_replication: Option<replication::Config>,
+    /// The number of epochs of duties kept for the `/lighthouse/validators/{pubkey}/history`
+    /// endpoint.
+    pub duty_history_epochs: u64,
     pub web3_signer_keep_alive_timeout: Option<Duration>,
     pub web3_signer_max_idle_connections: Option<usize>,
 }
@@ -126,6 +138,7 @@ impl Default for Config {
             builder_registration_timestamp_override: None,
             gas_limit: None,
             broadcast_topics: vec![ApiTopic::Subscriptions],
+            beacon_node_ranking: false,
             enable_latency_measurement_service: true,
             validator_registration_batch_size: 500,
             enable_web3signer_slashing_protection: true,
@@ -133,6 +146,9 @@ impl Default for Config {
             builder_boost_factor: None,
             prefer_builder_proposals: false,
             distributed: false,
+            exit_vault: None,
+            slashing_protection_replication: None,
+            duty_history_epochs: DEFAULT_DUTY_HISTORY_EPOCHS,
             web3_signer_keep_alive_timeout: Some(Duration::from_secs(90)),
             web3_signer_max_idle_connections: None,
         }
@@ -260,6 +276,10 @@ impl Config {
                 .collect::<Result<_, _>>()?;
         }
 
+        if cli_args.is_present("enable-beacon-node-ranking") {
+            config.beacon_node_ranking = true;
+        }
+
         /*
          * Web3 signer
          */
@@ -408,6 +428,26 @@ impl Config {
 
         config.builder_boost_factor = parse_optional(cli_args, "builder-boost-factor")?;
 
+        if let Some(dir) = parse_optional(cli_args, "exit-vault-dir")? {
+            config.exit_vault = Some(exit_vault::Config {
+                dir,
+                password_file: parse_required(cli_args, "exit-vault-password-file")?,
+            });
+        }
+
+        if let Some(journal_path) = parse_optional(cli_args, "slashing-protection-journal")? {
+            config.slashing_protection_replication = Some(replication::Config {
+                journal_path,
+                standby: cli_args.is_present("slashing-protection-standby"),
+                failover_epochs: parse_optional(cli_args, "slashing-protection-failover-epochs")?
+                    .unwrap_or(replication::DEFAULT_FAILOVER_EPOCHS),
+            });
+        }
+
+        if let Some(duty_history_epochs) = parse_optional(cli_args, "duty-history-epochs")? {
+            config.duty_history_epochs = duty_history_epochs;
+        }
+
         config.enable_latency_measurement_service =
             parse_optional(cli_args, "latency-measurement-service")?.unwrap_or(true);
 
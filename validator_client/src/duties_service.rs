@@ -16,6 +16,7 @@ use crate::{
     validator_store::{DoppelgangerStatus, Error as ValidatorStoreError, ValidatorStore},
 };
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::DutyKind;
 use eth2::types::{
     AttesterData, BeaconCommitteeSubscription, DutiesResponse, ProposerData, StateId, ValidatorId,
 };
@@ -861,6 +862,12 @@ async fn poll_beacon_attesters_for_epoch<T: SlotClock + 'static, E: EthSpec>(
         .now_or_genesis()
         .unwrap_or_default();
     for duty in &new_duties {
+        duties_service.validator_store.duty_history().scheduled(
+            duty.pubkey,
+            DutyKind::Attestation,
+            duty.slot,
+        );
+
         let attester_map = attesters.entry(duty.pubkey).or_default();
 
         // Create initial entries in the map without selection proofs. We'll compute them in the
@@ -1254,6 +1261,14 @@ async fn poll_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
                     "num_relevant_duties" => relevant_duties.len(),
                 );
 
+                for duty in &relevant_duties {
+                    duties_service.validator_store.duty_history().scheduled(
+                        duty.pubkey,
+                        DutyKind::Proposal,
+                        duty.slot,
+                    );
+                }
+
                 if let Some((prior_dependent_root, _)) = duties_service
                     .proposers
                     .write()
@@ -0,0 +1,253 @@
+//! Keeps a record of the recent block proposals and attestations of each validator, served by the
+//! `/lighthouse/validators/{pubkey}/history` endpoint.
+//!
+//! Records are held in memory only and are lost when the validator client restarts. A duty which
+//! remains `Scheduled` after its slot was never signed, e.g. because the beacon node failed to
+//! produce the block or because the duty was re-orged out.
+
+use eth2::lighthouse_vc::types::{DutyKind, DutyRecord, DutyStatus};
+use parking_lot::Mutex;
+use slot_clock::SlotClock;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
+
+/// The default number of epochs for which duties are kept.
+pub const DEFAULT_DUTY_HISTORY_EPOCHS: u64 = 64;
+
+#[derive(Default)]
+struct Inner {
+    records: HashMap<PublicKeyBytes, BTreeMap<(Slot, DutyKind), DutyRecord>>,
+    /// The latest epoch seen, records older than `retention_epochs` before it are pruned.
+    latest_epoch: Epoch,
+}
+
+pub struct DutyHistory {
+    /// Keep the duties of this many epochs, no duties are kept if it is zero.
+    retention_epochs: u64,
+    slots_per_epoch: u64,
+    inner: Mutex<Inner>,
+}
+
+impl DutyHistory {
+    pub fn new<E: EthSpec>(retention_epochs: u64) -> Self {
+        Self {
+            retention_epochs,
+            slots_per_epoch: E::slots_per_epoch(),
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Record that `pubkey` is due to perform a duty, unless it is already known.
+    pub fn scheduled(&self, pubkey: PublicKeyBytes, kind: DutyKind, slot: Slot) {
+        self.update(pubkey, kind, slot, |_| ());
+    }
+
+    /// Record that the message of a duty has been signed.
+    pub fn signed(
+        &self,
+        pubkey: PublicKeyBytes,
+        kind: DutyKind,
+        slot: Slot,
+        delay: Option<Duration>,
+    ) {
+        self.update(pubkey, kind, slot, |record| {
+            record.status = DutyStatus::Signed;
+            record.signed_delay_ms = delay.map(|delay| delay.as_millis() as u64);
+            record.error = None;
+        });
+    }
+
+    /// Record that the message of a duty has been accepted by `beacon_nodes`.
+    pub fn published(
+        &self,
+        pubkey: PublicKeyBytes,
+        kind: DutyKind,
+        slot: Slot,
+        beacon_nodes: Vec<String>,
+        delay: Option<Duration>,
+    ) {
+        self.update(pubkey, kind, slot, |record| {
+            record.status = DutyStatus::Published;
+            record.beacon_nodes = beacon_nodes;
+            record.published_delay_ms = delay.map(|delay| delay.as_millis() as u64);
+            record.error = None;
+        });
+    }
+
+    /// Record that a duty could not be performed. A duty which has already been published is not
+    /// marked as failed.
+    pub fn failed(&self, pubkey: PublicKeyBytes, kind: DutyKind, slot: Slot, error: String) {
+        self.update(pubkey, kind, slot, |record| {
+            if record.status != DutyStatus::Published {
+                record.status = DutyStatus::Failed;
+                record.error = Some(error);
+            }
+        });
+    }
+
+    /// Returns the duties of `pubkey` between `from_epoch` and `to_epoch` (inclusive), ordered by
+    /// slot.
+    pub fn get(
+        &self,
+        pubkey: &PublicKeyBytes,
+        from_epoch: Option<Epoch>,
+        to_epoch: Option<Epoch>,
+    ) -> Vec<DutyRecord> {
+        let from_slot =
+            from_epoch.map_or(Slot::new(0), |epoch| epoch.start_slot(self.slots_per_epoch));
+        let to_slot = to_epoch.map_or(Slot::max_value(), |epoch| {
+            epoch.end_slot(self.slots_per_epoch)
+        });
+        if from_slot > to_slot {
+            return vec![];
+        }
+
+        self.inner
+            .lock()
+            .records
+            .get(pubkey)
+            .map(|records| {
+                records
+                    .range((from_slot, DutyKind::Proposal)..=(to_slot, DutyKind::Attestation))
+                    .map(|(_, record)| record.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn update<F>(&self, pubkey: PublicKeyBytes, kind: DutyKind, slot: Slot, f: F)
+    where
+        F: FnOnce(&mut DutyRecord),
+    {
+        if self.retention_epochs == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock();
+        let epoch = slot.epoch(self.slots_per_epoch);
+        if epoch > inner.latest_epoch {
+            inner.latest_epoch = epoch;
+            let min_slot = (epoch + 1)
+                .saturating_sub(self.retention_epochs)
+                .start_slot(self.slots_per_epoch);
+            inner.records.retain(|_, records| {
+                *records = records.split_off(&(min_slot, DutyKind::Proposal));
+                !records.is_empty()
+            });
+        }
+        if epoch + self.retention_epochs <= inner.latest_epoch {
+            return;
+        }
+
+        let record = inner
+            .records
+            .entry(pubkey)
+            .or_default()
+            .entry((slot, kind))
+            .or_insert_with(|| DutyRecord {
+                kind,
+                slot,
+                status: DutyStatus::Scheduled,
+                beacon_nodes: vec![],
+                signed_delay_ms: None,
+                published_delay_ms: None,
+                error: None,
+            });
+        f(record);
+    }
+}
+
+/// Returns the time elapsed since the start of `slot`, if it has started.
+pub fn delay_since_slot_start<T: SlotClock>(slot_clock: &T, slot: Slot) -> Option<Duration> {
+    slot_clock
+        .now_duration()?
+        .checked_sub(slot_clock.start_of(slot)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn pubkey(i: u8) -> PublicKeyBytes {
+        let mut bytes = [0; 48];
+        bytes[0] = i;
+        PublicKeyBytes::deserialize(&bytes).unwrap()
+    }
+
+    #[test]
+    fn records_duty_lifecycle() {
+        let history = DutyHistory::new::<E>(2);
+        let slot = Slot::new(40);
+
+        history.scheduled(pubkey(0), DutyKind::Attestation, slot);
+        history.scheduled(pubkey(0), DutyKind::Proposal, slot);
+        history.signed(
+            pubkey(0),
+            DutyKind::Attestation,
+            slot,
+            Some(Duration::from_millis(4_100)),
+        );
+        history.published(
+            pubkey(0),
+            DutyKind::Attestation,
+            slot,
+            vec!["bn-1".to_string()],
+            Some(Duration::from_millis(4_200)),
+        );
+        history.failed(pubkey(0), DutyKind::Attestation, slot, "late".to_string());
+        history.failed(pubkey(0), DutyKind::Proposal, slot, "offline".to_string());
+
+        let records = history.get(&pubkey(0), None, None);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].kind, DutyKind::Proposal);
+        assert_eq!(records[0].status, DutyStatus::Failed);
+        assert_eq!(records[0].error.as_deref(), Some("offline"));
+        assert_eq!(records[1].status, DutyStatus::Published);
+        assert_eq!(records[1].beacon_nodes, vec!["bn-1".to_string()]);
+        assert_eq!(records[1].signed_delay_ms, Some(4_100));
+        assert_eq!(records[1].published_delay_ms, Some(4_200));
+        assert_eq!(records[1].error, None);
+
+        assert!(history.get(&pubkey(1), None, None).is_empty());
+    }
+
+    #[test]
+    fn filters_and_prunes_by_epoch() {
+        let history = DutyHistory::new::<E>(2);
+        for epoch in 0..3 {
+            let slot = Epoch::new(epoch).start_slot(E::slots_per_epoch());
+            history.scheduled(pubkey(0), DutyKind::Attestation, slot);
+        }
+
+        let epochs = |from_epoch, to_epoch| {
+            history
+                .get(&pubkey(0), from_epoch, to_epoch)
+                .iter()
+                .map(|record| record.slot.epoch(E::slots_per_epoch()).as_u64())
+                .collect::<Vec<_>>()
+        };
+        // Epoch 0 has been pruned.
+        assert_eq!(epochs(None, None), vec![1, 2]);
+        assert_eq!(epochs(Some(Epoch::new(2)), None), vec![2]);
+        assert_eq!(epochs(None, Some(Epoch::new(1))), vec![1]);
+        assert_eq!(
+            epochs(Some(Epoch::new(2)), Some(Epoch::new(1))),
+            Vec::<u64>::new()
+        );
+
+        // Duties older than the retention period are ignored.
+        history.scheduled(pubkey(1), DutyKind::Attestation, Slot::new(0));
+        assert!(history.get(&pubkey(1), None, None).is_empty());
+    }
+
+    #[test]
+    fn disabled_with_zero_retention() {
+        let history = DutyHistory::new::<E>(0);
+        history.scheduled(pubkey(0), DutyKind::Attestation, Slot::new(1));
+        assert!(history.get(&pubkey(0), None, None).is_empty());
+    }
+}
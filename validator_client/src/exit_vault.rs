@@ -0,0 +1,363 @@
+//! Keeps an encrypted, pre-signed voluntary exit for each validator in a directory.
+//!
+//! Each exit is stored in `<dir>/<pubkey>.json`, encrypted with the EIP-2335 crypto module used by
+//! keystores. The domain the exit was signed with is stored in plain text alongside it, so that
+//! the exit can be signed again once a fork changes the voluntary exit domain. Since EIP-7044 the
+//! domain is fixed to Capella, so exits are only regenerated at forks up to and including Capella.
+
+use crate::http_api::create_signed_voluntary_exit::{
+    sign_voluntary_exit, Error as SignedExitError,
+};
+use crate::validator_store::{DoppelgangerStatus, ValidatorStore};
+use account_utils::read_password;
+use environment::RuntimeContext;
+use eth2_keystore::json_keystore::{
+    Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, Kdf, KdfModule,
+    Sha256Checksum,
+};
+use eth2_keystore::{decrypt, default_kdf, encrypt, PlainText, IV_SIZE, SALT_SIZE};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use slog::{debug, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::time::sleep;
+use types::{Epoch, EthSpec, Hash256, PublicKeyBytes, SignedVoluntaryExit};
+
+/// The extension of an entry which is being written, before it is moved to its final path.
+const TEMP_FILE_EXTENSION: &str = "json.tmp";
+
+/// Configuration of the pre-signed exit vault.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// The directory to which the encrypted exits are written.
+    pub dir: PathBuf,
+    /// A file containing the password used to encrypt the exits.
+    pub password_file: PathBuf,
+}
+
+/// A pre-signed voluntary exit, as stored in the vault directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultEntry {
+    pub pubkey: PublicKeyBytes,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub epoch: Epoch,
+    /// The voluntary exit domain the exit was signed with.
+    pub domain: Hash256,
+    /// The JSON encoded `SignedVoluntaryExit`, encrypted with the vault password.
+    pub crypto: Crypto,
+}
+
+impl VaultEntry {
+    /// Encrypts `signed_exit` using `password` (via `kdf`).
+    pub fn encrypt(
+        pubkey: PublicKeyBytes,
+        signed_exit: &SignedVoluntaryExit,
+        domain: Hash256,
+        password: &[u8],
+        kdf: Kdf,
+    ) -> Result<Self, String> {
+        let plain_text = PlainText::from(
+            serde_json::to_vec(signed_exit)
+                .map_err(|e| format!("Unable to serialize exit: {:?}", e))?,
+        );
+        let iv = rand::thread_rng().gen::<[u8; IV_SIZE]>().to_vec().into();
+        let cipher = Cipher::Aes128Ctr(Aes128Ctr { iv });
+        let (cipher_text, checksum) = encrypt(plain_text.as_bytes(), password, &kdf, &cipher)
+            .map_err(|e| format!("Unable to encrypt exit: {:?}", e))?;
+
+        Ok(Self {
+            pubkey,
+            validator_index: signed_exit.message.validator_index,
+            epoch: signed_exit.message.epoch,
+            domain,
+            crypto: Crypto {
+                kdf: KdfModule {
+                    function: kdf.function(),
+                    params: kdf,
+                    message: EmptyString,
+                },
+                checksum: ChecksumModule {
+                    function: Sha256Checksum::function(),
+                    params: EmptyMap,
+                    message: checksum.to_vec().into(),
+                },
+                cipher: CipherModule {
+                    function: cipher.function(),
+                    params: cipher,
+                    message: cipher_text.into(),
+                },
+            },
+        })
+    }
+
+    /// Decrypts the `SignedVoluntaryExit` using `password`.
+    pub fn decrypt(&self, password: &[u8]) -> Result<SignedVoluntaryExit, String> {
+        let plain_text = decrypt(password, &self.crypto)
+            .map_err(|e| format!("Unable to decrypt exit: {:?}", e))?;
+        serde_json::from_slice(plain_text.as_bytes())
+            .map_err(|e| format!("Unable to deserialize exit: {:?}", e))
+    }
+}
+
+/// The directory holding the pre-signed exits, along with the password to encrypt them.
+pub struct ExitVault {
+    dir: PathBuf,
+    password: PlainText,
+}
+
+impl ExitVault {
+    /// Opens the vault described by `config`, creating its directory if it does not exist.
+    pub fn open(config: &Config) -> Result<Self, String> {
+        let password = read_password(&config.password_file).map_err(|e| {
+            format!(
+                "Unable to read exit vault password file {:?}: {:?}",
+                config.password_file, e
+            )
+        })?;
+        if password.as_bytes().is_empty() {
+            return Err(format!(
+                "Exit vault password file {:?} is empty",
+                config.password_file
+            ));
+        }
+        fs::create_dir_all(&config.dir)
+            .map_err(|e| format!("Unable to create exit vault {:?}: {:?}", config.dir, e))?;
+
+        Ok(Self {
+            dir: config.dir.clone(),
+            password,
+        })
+    }
+
+    fn entry_path(&self, pubkey: &PublicKeyBytes) -> PathBuf {
+        self.dir.join(format!("{}.json", pubkey.as_hex_string()))
+    }
+
+    /// Reads the entry of `pubkey`, returning `None` if there is none.
+    pub fn read_entry(&self, pubkey: &PublicKeyBytes) -> Result<Option<VaultEntry>, String> {
+        let path = self.entry_path(pubkey);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes = fs::read(&path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| format!("Unable to parse {:?}: {:?}", path, e))
+    }
+
+    /// Encrypts `signed_exit` and writes it to the entry of `pubkey`, replacing any previous entry.
+    ///
+    /// This performs a key derivation and blocking file I/O.
+    pub fn write_entry(
+        &self,
+        pubkey: PublicKeyBytes,
+        signed_exit: &SignedVoluntaryExit,
+        domain: Hash256,
+    ) -> Result<(), String> {
+        let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>();
+        let entry = VaultEntry::encrypt(
+            pubkey,
+            signed_exit,
+            domain,
+            self.password.as_bytes(),
+            default_kdf(salt.to_vec()),
+        )?;
+        let bytes = serde_json::to_vec_pretty(&entry)
+            .map_err(|e| format!("Unable to serialize vault entry: {:?}", e))?;
+
+        // Write to a temporary file first so that a valid entry is never partially overwritten.
+        let path = self.entry_path(&pubkey);
+        let temp_path = path.with_extension(TEMP_FILE_EXTENSION);
+        filesystem::create_with_600_perms(&temp_path, &bytes)
+            .map_err(|e| format!("Unable to write {:?}: {:?}", temp_path, e))?;
+        fs::rename(&temp_path, &path)
+            .map_err(|e| format!("Unable to move {:?} to {:?}: {:?}", temp_path, path, e))
+    }
+
+    /// Signs and stores an exit for each validator which has no entry for the voluntary exit
+    /// domain of `epoch`.
+    async fn update<T: SlotClock + 'static, E: EthSpec>(
+        self: &Arc<Self>,
+        epoch: Epoch,
+        validator_store: &ValidatorStore<T, E>,
+        context: &RuntimeContext<E>,
+        log: &Logger,
+    ) {
+        let domain = validator_store.voluntary_exit_domain(epoch);
+        let pubkeys: Vec<PublicKeyBytes> =
+            validator_store.voting_pubkeys(DoppelgangerStatus::ignored);
+
+        let mut num_written = 0;
+        for pubkey in pubkeys {
+            match self.read_entry(&pubkey) {
+                Ok(Some(entry)) if entry.domain == domain => continue,
+                Ok(_) => {}
+                Err(e) => warn!(
+                    log,
+                    "Replacing unreadable exit vault entry";
+                    "validator" => ?pubkey,
+                    "error" => e,
+                ),
+            }
+
+            let signed_exit = match sign_voluntary_exit(pubkey, epoch, validator_store, log).await {
+                Ok(signed_exit) => signed_exit,
+                // The exit will be signed once the validator's deposit has been processed.
+                Err(SignedExitError::UnknownValidatorIndex(_)) => {
+                    debug!(
+                        log,
+                        "Skipping exit vault entry for unknown validator index";
+                        "validator" => ?pubkey,
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    error!(
+                        log,
+                        "Unable to sign exit for exit vault";
+                        "validator" => ?pubkey,
+                        "error" => ?e,
+                    );
+                    continue;
+                }
+            };
+
+            let vault = self.clone();
+            let write_result = match context.executor.spawn_blocking_handle(
+                move || vault.write_entry(pubkey, &signed_exit, domain),
+                "exit_vault_write",
+            ) {
+                Some(handle) => handle
+                    .await
+                    .map_err(|e| format!("Exit vault task failed: {:?}", e))
+                    .and_then(|result| result),
+                // The executor is shutting down.
+                None => return,
+            };
+
+            match write_result {
+                Ok(()) => num_written += 1,
+                Err(e) => error!(
+                    log,
+                    "Unable to write exit vault entry";
+                    "validator" => ?pubkey,
+                    "error" => e,
+                ),
+            }
+        }
+
+        if num_written > 0 {
+            info!(
+                log,
+                "Updated exit vault";
+                "exits_written" => num_written,
+                "epoch" => epoch,
+                "domain" => ?domain,
+            );
+        }
+    }
+}
+
+/// Starts a service which keeps a pre-signed exit for each validator in the vault described by
+/// `config`, checking for validators and exit domain changes at the start of each epoch.
+pub fn start_exit_vault_service<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    slot_clock: T,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    config: &Config,
+) -> Result<(), String> {
+    let log = context.log().clone();
+    let vault = Arc::new(ExitVault::open(config)?);
+    info!(
+        log,
+        "Exit vault service started";
+        "dir" => ?vault.dir,
+    );
+
+    let executor = context.executor.clone();
+    let future = async move {
+        loop {
+            if let Some(slot) = slot_clock.now() {
+                let epoch = slot.epoch(E::slots_per_epoch());
+                vault.update(epoch, &validator_store, &context, &log).await;
+            }
+
+            let sleep_time = slot_clock
+                .duration_to_next_epoch(E::slots_per_epoch())
+                .unwrap_or_else(|| slot_clock.slot_duration());
+            sleep(sleep_time).await;
+        }
+    };
+
+    executor.spawn(future, "exit_vault");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2_keystore::json_keystore::Scrypt;
+    use eth2_keystore::DKLEN;
+    use types::{Signature, VoluntaryExit};
+
+    /// A cheap and **insecure** KDF, to keep the tests fast.
+    fn insecure_kdf() -> Kdf {
+        Kdf::Scrypt(Scrypt {
+            dklen: DKLEN,
+            n: 2,
+            p: 1,
+            r: 8,
+            salt: vec![1; 32].into(),
+        })
+    }
+
+    fn signed_exit() -> SignedVoluntaryExit {
+        SignedVoluntaryExit {
+            message: VoluntaryExit {
+                epoch: Epoch::new(42),
+                validator_index: 7,
+            },
+            signature: Signature::empty(),
+        }
+    }
+
+    #[test]
+    fn entry_round_trip() {
+        let exit = signed_exit();
+        let domain = Hash256::repeat_byte(3);
+        let entry = VaultEntry::encrypt(
+            PublicKeyBytes::empty(),
+            &exit,
+            domain,
+            b"password",
+            insecure_kdf(),
+        )
+        .unwrap();
+
+        assert_eq!(entry.validator_index, 7);
+        assert_eq!(entry.epoch, Epoch::new(42));
+        assert_eq!(entry.domain, domain);
+
+        let json = serde_json::to_string(&entry).unwrap();
+        let decoded: VaultEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.decrypt(b"password").unwrap(), exit);
+    }
+
+    #[test]
+    fn entry_rejects_wrong_password() {
+        let entry = VaultEntry::encrypt(
+            PublicKeyBytes::empty(),
+            &signed_exit(),
+            Hash256::zero(),
+            b"password",
+            insecure_kdf(),
+        )
+        .unwrap();
+
+        assert!(entry.decrypt(b"wrong password").is_err());
+    }
+}
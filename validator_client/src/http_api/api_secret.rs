@@ -5,6 +5,8 @@ use rand::thread_rng;
 use ring::digest::{digest, SHA256};
 use std::fs;
 use std::path::{Path, PathBuf};
+use warp::http::Method;
+use warp::path::FullPath;
 use warp::Filter;
 
 /// The name of the file which stores the secret key.
@@ -23,6 +25,24 @@ pub const SK_LEN: usize = 32;
 /// value in a public forum.
 pub const PK_FILENAME: &str = "api-token.txt";
 
+/// The name of the file which stores the secret key of the threshold token.
+pub const THRESHOLD_SK_FILENAME: &str = ".threshold-secp-sk";
+
+/// The name of the file which stores the threshold token.
+///
+/// Unlike `PK_FILENAME`, this token only authorises requests for partial signatures of threshold
+/// validators, it is the token to share with the peers of a threshold validator.
+pub const THRESHOLD_PK_FILENAME: &str = "threshold-api-token.txt";
+
+/// Identifies one of the tokens of an `ApiSecret`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApiToken {
+    /// Authorises all requests.
+    Api,
+    /// Only authorises requests for partial signatures of threshold validators.
+    Threshold,
+}
+
 /// Contains a `secp256k1` keypair that is saved-to/loaded-from disk on instantiation. The keypair
 /// is used for authorization/authentication for requests/responses on the HTTP API.
 ///
@@ -35,130 +55,57 @@ pub const PK_FILENAME: &str = "api-token.txt";
 ///  The aforementioned scheme was first defined here:
 ///
 ///  https://github.com/sigp/lighthouse/issues/1269#issuecomment-649879855
+///
+/// A second keypair provides the threshold token, which is only accepted by the threshold
+/// signature route.
 pub struct ApiSecret {
     pk: PublicKey,
     sk: SecretKey,
     pk_path: PathBuf,
+    threshold_pk: PublicKey,
+    threshold_sk: SecretKey,
+    threshold_pk_path: PathBuf,
 }
 
 impl ApiSecret {
     /// If both the secret and public keys are already on-disk, parse them and ensure they're both
     /// from the same keypair.
     ///
-    /// The provided `dir` is a directory containing two files, `SK_FILENAME` and `PK_FILENAME`.
+    /// The provided `dir` is a directory containing two files, `SK_FILENAME` and `PK_FILENAME`, as
+    /// well as the `THRESHOLD_SK_FILENAME` and `THRESHOLD_PK_FILENAME` files of the threshold
+    /// token.
     ///
     /// If either the secret or public key files are missing on disk, create a new keypair and
     /// write it to disk (over-writing any existing files).
     pub fn create_or_open<P: AsRef<Path>>(dir: P) -> Result<Self, String> {
-        let sk_path = dir.as_ref().join(SK_FILENAME);
         let pk_path = dir.as_ref().join(PK_FILENAME);
+        let (pk, sk) = create_or_open_keypair(dir.as_ref(), SK_FILENAME, PK_FILENAME)?;
+        let threshold_pk_path = dir.as_ref().join(THRESHOLD_PK_FILENAME);
+        let (threshold_pk, threshold_sk) =
+            create_or_open_keypair(dir.as_ref(), THRESHOLD_SK_FILENAME, THRESHOLD_PK_FILENAME)?;
 
-        if !(sk_path.exists() && pk_path.exists()) {
-            let sk = SecretKey::random(&mut thread_rng());
-            let pk = PublicKey::from_secret_key(&sk);
-
-            // Create and write the secret key to file with appropriate permissions
-            create_with_600_perms(
-                &sk_path,
-                serde_utils::hex::encode(sk.serialize()).as_bytes(),
-            )
-            .map_err(|e| {
-                format!(
-                    "Unable to create file with permissions for {:?}: {:?}",
-                    sk_path, e
-                )
-            })?;
-
-            // Create and write the public key to file with appropriate permissions
-            create_with_600_perms(
-                &pk_path,
-                format!(
-                    "{}{}",
-                    PK_PREFIX,
-                    serde_utils::hex::encode(&pk.serialize_compressed()[..])
-                )
-                .as_bytes(),
-            )
-            .map_err(|e| {
-                format!(
-                    "Unable to create file with permissions for {:?}: {:?}",
-                    pk_path, e
-                )
-            })?;
-        }
-
-        let sk = fs::read(&sk_path)
-            .map_err(|e| format!("cannot read {}: {}", SK_FILENAME, e))
-            .and_then(|bytes| {
-                serde_utils::hex::decode(&String::from_utf8_lossy(&bytes))
-                    .map_err(|_| format!("{} should be 0x-prefixed hex", PK_FILENAME))
-            })
-            .and_then(|bytes| {
-                if bytes.len() == SK_LEN {
-                    let mut array = [0; SK_LEN];
-                    array.copy_from_slice(&bytes);
-                    SecretKey::parse(&array).map_err(|e| format!("invalid {}: {}", SK_FILENAME, e))
-                } else {
-                    Err(format!(
-                        "{} expected {} bytes not {}",
-                        SK_FILENAME,
-                        SK_LEN,
-                        bytes.len()
-                    ))
-                }
-            })?;
-
-        let pk = fs::read(&pk_path)
-            .map_err(|e| format!("cannot read {}: {}", PK_FILENAME, e))
-            .and_then(|bytes| {
-                let hex =
-                    String::from_utf8(bytes).map_err(|_| format!("{} is not utf8", SK_FILENAME))?;
-                if let Some(stripped) = hex.strip_prefix(PK_PREFIX) {
-                    serde_utils::hex::decode(stripped)
-                        .map_err(|_| format!("{} should be 0x-prefixed hex", SK_FILENAME))
-                } else {
-                    Err(format!("unable to parse {}", SK_FILENAME))
-                }
-            })
-            .and_then(|bytes| {
-                if bytes.len() == PK_LEN {
-                    let mut array = [0; PK_LEN];
-                    array.copy_from_slice(&bytes);
-                    PublicKey::parse_compressed(&array)
-                        .map_err(|e| format!("invalid {}: {}", PK_FILENAME, e))
-                } else {
-                    Err(format!(
-                        "{} expected {} bytes not {}",
-                        PK_FILENAME,
-                        PK_LEN,
-                        bytes.len()
-                    ))
-                }
-            })?;
-
-        // Ensure that the keys loaded from disk are indeed a pair.
-        if PublicKey::from_secret_key(&sk) != pk {
-            fs::remove_file(&sk_path)
-                .map_err(|e| format!("unable to remove {}: {}", SK_FILENAME, e))?;
-            fs::remove_file(&pk_path)
-                .map_err(|e| format!("unable to remove {}: {}", PK_FILENAME, e))?;
-            return Err(format!(
-                "{:?} does not match {:?} and the files have been deleted. Please try again.",
-                sk_path, pk_path
-            ));
-        }
-
-        Ok(Self { pk, sk, pk_path })
+        Ok(Self {
+            pk,
+            sk,
+            pk_path,
+            threshold_pk,
+            threshold_sk,
+            threshold_pk_path,
+        })
     }
 
-    /// Returns the public key of `self` as a 0x-prefixed hex string.
-    fn pubkey_string(&self) -> String {
-        serde_utils::hex::encode(&self.pk.serialize_compressed()[..])
+    /// Returns the public key of `token` as a 0x-prefixed hex string.
+    fn pubkey_string(&self, token: ApiToken) -> String {
+        let pk = match token {
+            ApiToken::Api => &self.pk,
+            ApiToken::Threshold => &self.threshold_pk,
+        };
+        serde_utils::hex::encode(&pk.serialize_compressed()[..])
     }
 
     /// Returns the API token.
     pub fn api_token(&self) -> String {
-        format!("{}{}", PK_PREFIX, self.pubkey_string())
+        format!("{}{}", PK_PREFIX, self.pubkey_string(ApiToken::Api))
     }
 
     /// Returns the path for the API token file
@@ -166,41 +113,68 @@ impl ApiSecret {
         self.pk_path.clone()
     }
 
-    /// Returns the values of the `Authorization` header which indicate a valid incoming HTTP
-    /// request.
+    /// Returns the threshold token, which only authorises requests for partial signatures.
+    pub fn threshold_api_token(&self) -> String {
+        format!("{}{}", PK_PREFIX, self.pubkey_string(ApiToken::Threshold))
+    }
+
+    /// Returns the path for the threshold token file
+    pub fn threshold_api_token_path(&self) -> PathBuf {
+        self.threshold_pk_path.clone()
+    }
+
+    /// Returns the values of the `Authorization` header which carry `token`.
     ///
     /// For backwards-compatibility we accept the token in a basic authentication style, but this is
     /// technically invalid according to RFC 7617 because the token is not a base64-encoded username
     /// and password. As such, bearer authentication should be preferred.
-    fn auth_header_values(&self) -> Vec<String> {
-        vec![
-            format!("Basic {}", self.api_token()),
-            format!("Bearer {}", self.api_token()),
-        ]
+    pub fn auth_header_values(&self, token: ApiToken) -> Vec<String> {
+        let token = match token {
+            ApiToken::Api => self.api_token(),
+            ApiToken::Threshold => self.threshold_api_token(),
+        };
+        vec![format!("Basic {}", token), format!("Bearer {}", token)]
     }
 
     /// Returns a `warp` header which filters out request that have a missing or inaccurate
     /// `Authorization` header.
+    ///
+    /// The threshold token is only accepted for `POST
+    /// lighthouse/validators/{validator_pubkey}/threshold_signature`.
     pub fn authorization_header_filter(&self) -> warp::filters::BoxedFilter<()> {
-        let expected = self.auth_header_values();
+        let expected = self.auth_header_values(ApiToken::Api);
+        let threshold_expected = self.auth_header_values(ApiToken::Threshold);
         warp::any()
-            .map(move || expected.clone())
+            .map(move || (expected.clone(), threshold_expected.clone()))
             .and(warp::filters::header::header("Authorization"))
-            .and_then(move |expected: Vec<String>, header: String| async move {
-                if expected.contains(&header) {
-                    Ok(())
-                } else {
-                    Err(warp_utils::reject::invalid_auth(header))
-                }
-            })
+            .and(warp::method())
+            .and(warp::path::full())
+            .and_then(
+                move |(expected, threshold_expected): (Vec<String>, Vec<String>),
+                      header: String,
+                      method: Method,
+                      path: FullPath| async move {
+                    if expected.contains(&header)
+                        || (threshold_expected.contains(&header)
+                            && is_threshold_signature_route(&method, &path))
+                    {
+                        Ok(())
+                    } else {
+                        Err(warp_utils::reject::invalid_auth(header))
+                    }
+                },
+            )
             .untuple_one()
             .boxed()
     }
 
-    /// Returns a closure which produces a signature over some bytes using the secret key in
-    /// `self`. The signature is a 32-byte hash formatted as a 0x-prefixed string.
-    pub fn signer(&self) -> impl Fn(&[u8]) -> String + Clone {
-        let sk = self.sk;
+    /// Returns a closure which produces a signature over some bytes using the secret key of
+    /// `token`. The signature is a 32-byte hash formatted as a 0x-prefixed string.
+    pub fn signer(&self, token: ApiToken) -> impl Fn(&[u8]) -> String + Clone {
+        let sk = match token {
+            ApiToken::Api => self.sk,
+            ApiToken::Threshold => self.threshold_sk,
+        };
         move |input: &[u8]| -> String {
             let message =
                 Message::parse_slice(digest(&SHA256, input).as_ref()).expect("sha256 is 32 bytes");
@@ -209,3 +183,126 @@ impl ApiSecret {
         }
     }
 }
+
+/// Returns `true` for requests to
+/// `POST lighthouse/validators/{validator_pubkey}/threshold_signature`.
+fn is_threshold_signature_route(method: &Method, path: &FullPath) -> bool {
+    let segments = path
+        .as_str()
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    method == Method::POST
+        && matches!(
+            segments.as_slice(),
+            ["lighthouse", "validators", _, "threshold_signature"]
+        )
+}
+
+/// Opens the keypair stored in the `sk_filename` and `pk_filename` files of `dir`, see
+/// `ApiSecret::create_or_open`.
+fn create_or_open_keypair(
+    dir: &Path,
+    sk_filename: &str,
+    pk_filename: &str,
+) -> Result<(PublicKey, SecretKey), String> {
+    let sk_path = dir.join(sk_filename);
+    let pk_path = dir.join(pk_filename);
+
+    if !(sk_path.exists() && pk_path.exists()) {
+        let sk = SecretKey::random(&mut thread_rng());
+        let pk = PublicKey::from_secret_key(&sk);
+
+        // Create and write the secret key to file with appropriate permissions
+        create_with_600_perms(
+            &sk_path,
+            serde_utils::hex::encode(sk.serialize()).as_bytes(),
+        )
+        .map_err(|e| {
+            format!(
+                "Unable to create file with permissions for {:?}: {:?}",
+                sk_path, e
+            )
+        })?;
+
+        // Create and write the public key to file with appropriate permissions
+        create_with_600_perms(
+            &pk_path,
+            format!(
+                "{}{}",
+                PK_PREFIX,
+                serde_utils::hex::encode(&pk.serialize_compressed()[..])
+            )
+            .as_bytes(),
+        )
+        .map_err(|e| {
+            format!(
+                "Unable to create file with permissions for {:?}: {:?}",
+                pk_path, e
+            )
+        })?;
+    }
+
+    let sk = fs::read(&sk_path)
+        .map_err(|e| format!("cannot read {}: {}", sk_filename, e))
+        .and_then(|bytes| {
+            serde_utils::hex::decode(&String::from_utf8_lossy(&bytes))
+                .map_err(|_| format!("{} should be 0x-prefixed hex", pk_filename))
+        })
+        .and_then(|bytes| {
+            if bytes.len() == SK_LEN {
+                let mut array = [0; SK_LEN];
+                array.copy_from_slice(&bytes);
+                SecretKey::parse(&array).map_err(|e| format!("invalid {}: {}", sk_filename, e))
+            } else {
+                Err(format!(
+                    "{} expected {} bytes not {}",
+                    sk_filename,
+                    SK_LEN,
+                    bytes.len()
+                ))
+            }
+        })?;
+
+    let pk = fs::read(&pk_path)
+        .map_err(|e| format!("cannot read {}: {}", pk_filename, e))
+        .and_then(|bytes| {
+            let hex =
+                String::from_utf8(bytes).map_err(|_| format!("{} is not utf8", sk_filename))?;
+            if let Some(stripped) = hex.strip_prefix(PK_PREFIX) {
+                serde_utils::hex::decode(stripped)
+                    .map_err(|_| format!("{} should be 0x-prefixed hex", sk_filename))
+            } else {
+                Err(format!("unable to parse {}", sk_filename))
+            }
+        })
+        .and_then(|bytes| {
+            if bytes.len() == PK_LEN {
+                let mut array = [0; PK_LEN];
+                array.copy_from_slice(&bytes);
+                PublicKey::parse_compressed(&array)
+                    .map_err(|e| format!("invalid {}: {}", pk_filename, e))
+            } else {
+                Err(format!(
+                    "{} expected {} bytes not {}",
+                    pk_filename,
+                    PK_LEN,
+                    bytes.len()
+                ))
+            }
+        })?;
+
+    // Ensure that the keys loaded from disk are indeed a pair.
+    if PublicKey::from_secret_key(&sk) != pk {
+        fs::remove_file(&sk_path)
+            .map_err(|e| format!("unable to remove {}: {}", sk_filename, e))?;
+        fs::remove_file(&pk_path)
+            .map_err(|e| format!("unable to remove {}: {}", pk_filename, e))?;
+        return Err(format!(
+            "{:?} does not match {:?} and the files have been deleted. Please try again.",
+            sk_path, pk_path
+        ));
+    }
+
+    Ok((pk, sk))
+}
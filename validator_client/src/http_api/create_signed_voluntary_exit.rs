@@ -1,4 +1,4 @@
-use crate::validator_store::ValidatorStore;
+use crate::validator_store::{Error as ValidatorStoreError, ValidatorStore};
 use bls::{PublicKey, PublicKeyBytes};
 use eth2::types::GenericResponse;
 use slog::{info, Logger};
@@ -6,6 +6,17 @@ use slot_clock::SlotClock;
 use std::sync::Arc;
 use types::{Epoch, EthSpec, SignedVoluntaryExit, VoluntaryExit};
 
+/// Errors which may occur whilst signing a voluntary exit.
+#[derive(Debug)]
+pub enum Error {
+    /// The validator is disabled or not managed by this validator client.
+    UnknownValidator(PublicKeyBytes),
+    /// The index of the validator is not yet known.
+    UnknownValidatorIndex(PublicKeyBytes),
+    /// The signing method failed to produce a signature.
+    UnableToSign(ValidatorStoreError),
+}
+
 pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: EthSpec>(
     pubkey: PublicKey,
     maybe_epoch: Option<Epoch>,
@@ -21,23 +32,42 @@ pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: Eth
     };
 
     let pubkey_bytes = PublicKeyBytes::from(pubkey);
+    let signed_voluntary_exit = sign_voluntary_exit(pubkey_bytes, epoch, &validator_store, &log)
+        .await
+        .map_err(|e| match e {
+            Error::UnknownValidator(pubkey) => warp_utils::reject::custom_not_found(format!(
+                "{} is disabled or not managed by this validator client",
+                pubkey.as_hex_string()
+            )),
+            Error::UnknownValidatorIndex(pubkey) => warp_utils::reject::custom_not_found(format!(
+                "The validator index for {} is not known. The validator client \
+                    may still be initializing or the validator has not yet had a \
+                    deposit processed.",
+                pubkey.as_hex_string()
+            )),
+            Error::UnableToSign(e) => warp_utils::reject::custom_server_error(format!(
+                "Failed to sign voluntary exit: {:?}",
+                e
+            )),
+        })?;
+
+    Ok(GenericResponse::from(signed_voluntary_exit))
+}
+
+/// Signs a voluntary exit at `epoch` for the validator with `pubkey_bytes`.
+pub async fn sign_voluntary_exit<T: 'static + SlotClock + Clone, E: EthSpec>(
+    pubkey_bytes: PublicKeyBytes,
+    epoch: Epoch,
+    validator_store: &ValidatorStore<T, E>,
+    log: &Logger,
+) -> Result<SignedVoluntaryExit, Error> {
     if !validator_store.has_validator(&pubkey_bytes) {
-        return Err(warp_utils::reject::custom_not_found(format!(
-            "{} is disabled or not managed by this validator client",
-            pubkey_bytes.as_hex_string()
-        )));
+        return Err(Error::UnknownValidator(pubkey_bytes));
     }
 
     let validator_index = validator_store
         .validator_index(&pubkey_bytes)
-        .ok_or_else(|| {
-            warp_utils::reject::custom_not_found(format!(
-                "The validator index for {} is not known. The validator client \
-                may still be initializing or the validator has not yet had a \
-                deposit processed.",
-                pubkey_bytes.as_hex_string()
-            ))
-        })?;
+        .ok_or(Error::UnknownValidatorIndex(pubkey_bytes))?;
 
     let voluntary_exit = VoluntaryExit {
         epoch,
@@ -51,17 +81,10 @@ pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: Eth
         "epoch" => epoch
     );
 
-    let signed_voluntary_exit = validator_store
+    validator_store
         .sign_voluntary_exit(pubkey_bytes, voluntary_exit)
         .await
-        .map_err(|e| {
-            warp_utils::reject::custom_server_error(format!(
-                "Failed to sign voluntary exit: {:?}",
-                e
-            ))
-        })?;
-
-    Ok(GenericResponse::from(signed_voluntary_exit))
+        .map_err(Error::UnableToSign)
 }
 
 /// Calculates the current epoch from the genesis time and current time.
@@ -2,7 +2,7 @@ use crate::validator_store::ValidatorStore;
 use bls::PublicKey;
 use slot_clock::SlotClock;
 use std::sync::Arc;
-use types::{graffiti::GraffitiString, EthSpec, Graffiti};
+use types::{graffiti::GraffitiTemplate, EthSpec, Graffiti};
 
 pub fn get_graffiti<T: 'static + SlotClock + Clone, E: EthSpec>(
     validator_pubkey: PublicKey,
@@ -16,7 +16,10 @@ pub fn get_graffiti<T: 'static + SlotClock + Clone, E: EthSpec>(
             "The key was not found on the server".to_string(),
         )),
         Some(_) => {
-            let Some(graffiti) = initialized_validators.graffiti(&validator_pubkey.into()) else {
+            // A graffiti template is resolved without the execution client, which is only known
+            // when a block is proposed.
+            let Some(graffiti) = initialized_validators.graffiti(&validator_pubkey.into(), None)
+            else {
                 return graffiti_flag.ok_or(warp_utils::reject::custom_server_error(
                     "No graffiti found, unable to return the process-wide default".to_string(),
                 ));
@@ -28,7 +31,7 @@ pub fn get_graffiti<T: 'static + SlotClock + Clone, E: EthSpec>(
 
 pub fn set_graffiti<T: 'static + SlotClock + Clone, E: EthSpec>(
     validator_pubkey: PublicKey,
-    graffiti: GraffitiString,
+    graffiti: GraffitiTemplate,
     validator_store: Arc<ValidatorStore<T, E>>,
 ) -> Result<(), warp::Rejection> {
     let initialized_validators_rw_lock = validator_store.initialized_validators();
@@ -38,7 +41,7 @@ pub fn set_graffiti<T: 'static + SlotClock + Clone, E: EthSpec>(
             "The key was not found on the server, nothing to update".to_string(),
         )),
         Some(initialized_validator) => {
-            if initialized_validator.get_graffiti() == Some(graffiti.clone().into()) {
+            if initialized_validator.get_graffiti() == Some(&graffiti) {
                 Ok(())
             } else {
                 initialized_validators
@@ -45,6 +45,11 @@ pub fn list<T: SlotClock + 'static, E: EthSpec>(
                         ..
                     } => (voting_keystore.path(), Some(false)),
                     SigningMethod::Web3Signer { .. } => (None, Some(true)),
+                    // Threshold validators are managed through their validator definition.
+                    SigningMethod::Threshold {
+                        ref voting_keystore,
+                        ..
+                    } => (voting_keystore.path(), Some(true)),
                 });
 
             SingleKeystoreResponse {
@@ -1,5 +1,5 @@
 mod api_secret;
-mod create_signed_voluntary_exit;
+pub(crate) mod create_signed_voluntary_exit;
 mod create_validator;
 mod graffiti;
 mod keystores;
@@ -11,12 +11,13 @@ pub mod test_utils;
 use crate::http_api::graffiti::{delete_graffiti, get_graffiti, set_graffiti};
 
 use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
-use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
+use crate::validator_store::Error as ValidatorStoreError;
+use crate::{determine_graffiti, BeaconNodeFallback, GraffitiFile, ValidatorStore};
 use account_utils::{
     mnemonic_from_phrase,
     validator_definitions::{SigningDefinition, ValidatorDefinition, Web3SignerDefinition},
 };
-pub use api_secret::ApiSecret;
+pub use api_secret::{ApiSecret, ApiToken};
 use create_validator::{
     create_validators_mnemonic, create_validators_web3signer, get_voting_password_storage,
 };
@@ -43,7 +44,7 @@ use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_vc;
 use task_executor::TaskExecutor;
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
-use types::{ChainSpec, ConfigAndPreset, EthSpec};
+use types::{graffiti::GraffitiTemplate, ChainSpec, ConfigAndPreset, EthSpec};
 use validator_dir::Builder as ValidatorDirBuilder;
 use warp::{
     http::{
@@ -80,6 +81,7 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub task_executor: TaskExecutor,
     pub api_secret: ApiSecret,
     pub validator_store: Option<Arc<ValidatorStore<T, E>>>,
+    pub beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     pub validator_dir: Option<PathBuf>,
     pub secrets_dir: Option<PathBuf>,
     pub graffiti_file: Option<GraffitiFile>,
@@ -176,9 +178,24 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         }
     };
 
-    let signer = ctx.api_secret.signer();
+    let signer = ctx.api_secret.signer(ApiToken::Api);
     let signer = warp::any().map(move || signer.clone());
 
+    // Responses to requests authorised by the threshold token are signed with its own key, so
+    // that peers holding only that token are able to authenticate them.
+    let threshold_signer = {
+        let api_signer = ctx.api_secret.signer(ApiToken::Api);
+        let threshold_signer = ctx.api_secret.signer(ApiToken::Threshold);
+        let threshold_headers = ctx.api_secret.auth_header_values(ApiToken::Threshold);
+        warp::header::<String>("Authorization").map(move |header: String| {
+            if threshold_headers.contains(&header) {
+                threshold_signer.clone()
+            } else {
+                api_signer.clone()
+            }
+        })
+    };
+
     let inner_validator_store = ctx.validator_store.clone();
     let validator_store_filter = warp::any()
         .map(move || inner_validator_store.clone())
@@ -190,6 +207,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    let inner_beacon_nodes = ctx.beacon_nodes.clone();
+    let beacon_nodes_filter = warp::any()
+        .map(move || inner_beacon_nodes.clone())
+        .and_then(|beacon_nodes: Option<_>| async move {
+            beacon_nodes.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "beacon nodes are not initialized.".to_string(),
+                )
+            })
+        });
+
     let inner_task_executor = ctx.task_executor.clone();
     let task_executor_filter = warp::any().map(move || inner_task_executor.clone());
 
@@ -275,6 +303,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             blocking_signed_json_task(signer, move || {
                 Ok(api_types::GenericResponse::from(api_types::VersionData {
                     version: version_with_platform(),
+                    execution_client: None,
                 }))
             })
         });
@@ -362,6 +391,68 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // GET lighthouse/validators/{validator_pubkey}/history
+    let get_lighthouse_validators_pubkey_history = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("history"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::DutyHistoryQuery>())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             query: api_types::DutyHistoryQuery,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    let known = validator_store
+                        .initialized_validators()
+                        .read()
+                        .validator_definitions()
+                        .iter()
+                        .any(|def| def.voting_public_key == validator_pubkey);
+                    if !known {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator for {:?}",
+                            validator_pubkey
+                        )));
+                    }
+
+                    Ok(api_types::GenericResponse::from(
+                        validator_store.duty_history().get(
+                            &PublicKeyBytes::from(&validator_pubkey),
+                            query.from_epoch,
+                            query.to_epoch,
+                        ),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/beacon/health
+    let get_lighthouse_beacon_health = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("health"))
+        .and(warp::path::end())
+        .and(beacon_nodes_filter)
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |beacon_nodes: Arc<BeaconNodeFallback<T, E>>, signer, task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let health = handle.block_on(beacon_nodes.health());
+                        Ok(api_types::GenericResponse::from(health))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
         .and(warp::path("ui"))
@@ -397,6 +488,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
              log| {
                 blocking_signed_json_task(signer, move || {
                     let mut result = HashMap::new();
+                    // Graffiti templates are resolved without the execution client, which is only
+                    // known when a block is proposed.
                     for (key, graffiti_definition) in validator_store
                         .initialized_validators()
                         .read()
@@ -625,7 +718,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                             .map(|web3signer| ValidatorDefinition {
                                 enabled: web3signer.enable,
                                 voting_public_key: web3signer.voting_public_key,
-                                graffiti: web3signer.graffiti,
+                                graffiti: web3signer.graffiti.map(Into::into),
                                 suggested_fee_recipient: web3signer.suggested_fee_recipient,
                                 gas_limit: web3signer.gas_limit,
                                 builder_proposals: web3signer.builder_proposals,
@@ -658,6 +751,56 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // POST lighthouse/validators/{validator_pubkey}/threshold_signature
+    let post_validators_threshold_signature = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("threshold_signature"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .and(threshold_signer)
+        .and(task_executor_filter.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             body: api_types::ThresholdSignatureRequest<E>,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             signer,
+             task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    let handle = task_executor.handle().ok_or_else(|| {
+                        warp_utils::reject::custom_server_error("Lighthouse shutting down".into())
+                    })?;
+                    let pubkey_bytes = validator_pubkey.compress();
+                    let (share_index, signature) = handle
+                        .block_on(validator_store.sign_threshold_share(
+                            pubkey_bytes,
+                            body.signing_root,
+                            body.message,
+                        ))
+                        .map_err(|e| match e {
+                            ValidatorStoreError::UnknownPubkey(_) => {
+                                warp_utils::reject::custom_not_found(format!(
+                                    "no validator for {:?}",
+                                    pubkey_bytes
+                                ))
+                            }
+                            e => warp_utils::reject::custom_bad_request(format!(
+                                "unable to sign threshold share: {:?}",
+                                e
+                            )),
+                        })?;
+
+                    Ok(GenericResponse::from(
+                        api_types::ThresholdSignatureResponse {
+                            share_index,
+                            signature,
+                        },
+                    ))
+                })
+            },
+        );
+
     // PATCH lighthouse/validators/{validator_pubkey}
     let patch_validators = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -683,7 +826,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         ));
                     }
 
-                    let maybe_graffiti = body.graffiti.clone().map(Into::into);
+                    let maybe_graffiti = body.graffiti.clone().map(GraffitiTemplate::from);
                     let initialized_validators_rw_lock = validator_store.initialized_validators();
                     let initialized_validators = initialized_validators_rw_lock.upgradable_read();
 
@@ -724,8 +867,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                     body.prefer_builder_proposals,
                                 )
                                 && equal_or_none(
-                                    initialized_validator.get_graffiti(),
-                                    maybe_graffiti,
+                                    initialized_validator.get_graffiti().cloned(),
+                                    maybe_graffiti.clone(),
                                 ) =>
                         {
                             Ok(())
@@ -1311,6 +1454,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
                         .or(get_lighthouse_validators_pubkey)
+                        .or(get_lighthouse_validators_pubkey_history)
+                        .or(get_lighthouse_beacon_health)
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_fee_recipient)
@@ -1325,6 +1470,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(post_validators_keystore)
                         .or(post_validators_mnemonic)
                         .or(post_validators_web3signer)
+                        .or(post_validators_threshold_signature)
                         .or(post_validators_voluntary_exits)
                         .or(post_fee_recipient)
                         .or(post_gas_limit)
@@ -1365,6 +1511,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         "HTTP API started";
         "listen_address" => listening_socket.to_string(),
         "api_token_file" => ?api_token_path,
+        "threshold_api_token_file" => ?ctx.api_secret.threshold_api_token_path(),
     );
 
     Ok((listening_socket, server))
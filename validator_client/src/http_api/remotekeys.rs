@@ -32,7 +32,9 @@ pub fn list<T: SlotClock + 'static, E: EthSpec>(
             let validating_pubkey = def.voting_public_key.compress();
 
             match &def.signing_definition {
-                SigningDefinition::LocalKeystore { .. } => None,
+                SigningDefinition::LocalKeystore { .. } | SigningDefinition::Threshold { .. } => {
+                    None
+                }
                 SigningDefinition::Web3Signer(Web3SignerDefinition { url, .. }) => {
                     Some(SingleListRemotekeysResponse {
                         pubkey: validating_pubkey,
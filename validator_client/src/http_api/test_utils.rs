@@ -112,6 +112,7 @@ impl ApiTester {
             Hash256::repeat_byte(42),
             spec,
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
+            None,
             slot_clock.clone(),
             &config,
             test_runtime.task_executor.clone(),
@@ -130,6 +131,7 @@ impl ApiTester {
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            beacon_nodes: None,
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec(),
@@ -266,6 +268,7 @@ impl ApiTester {
 
         let expected = VersionData {
             version: lighthouse_version::version_with_platform(),
+            execution_client: None,
         };
 
         assert_eq!(result, expected);
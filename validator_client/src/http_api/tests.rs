@@ -3,6 +3,7 @@
 
 mod keystores;
 
+use crate::beacon_node_fallback::{BeaconNodeFallback, CandidateBeaconNode};
 use crate::doppelganger_service::DoppelgangerService;
 use crate::{
     http_api::{ApiSecret, Config as HttpConfig, Context},
@@ -10,14 +11,19 @@ use crate::{
     Config, ValidatorDefinitions, ValidatorStore,
 };
 use account_utils::{
-    eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
-    random_password_string, ZeroizeString,
+    eth2_wallet::WalletBuilder,
+    mnemonic_from_phrase, random_mnemonic, random_password, random_password_string,
+    validator_definitions::{
+        SigningDefinition, ThresholdDefinition, ThresholdPeerDefinition, ValidatorDefinition,
+    },
+    ZeroizeString,
 };
+use bls::threshold::split_secret_key;
 use deposit_contract::decode_eth1_tx_data;
 use eth2::{
     lighthouse_vc::{http_client::ValidatorClientHttpClient, types::*},
     types::ErrorMessage as ApiErrorMessage,
-    Error as ApiError,
+    BeaconNodeHttpClient, Error as ApiError, Timeouts,
 };
 use eth2_keystore::KeystoreBuilder;
 use logging::test_logger;
@@ -33,7 +39,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use task_executor::test_utils::TestRuntime;
 use tempfile::{tempdir, TempDir};
-use types::graffiti::GraffitiString;
+use types::graffiti::{GraffitiString, GraffitiTemplate};
 
 const PASSWORD_BYTES: &[u8] = &[42, 50, 37];
 pub const TEST_DEFAULT_FEE_RECIPIENT: Address = Address::repeat_byte(42);
@@ -42,6 +48,7 @@ type E = MainnetEthSpec;
 
 struct ApiTester {
     client: ValidatorClientHttpClient,
+    threshold_client: ValidatorClientHttpClient,
     initialized_validators: Arc<RwLock<InitializedValidators>>,
     validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
     url: SensitiveUrl,
@@ -76,6 +83,7 @@ impl ApiTester {
 
         let api_secret = ApiSecret::create_or_open(validator_dir.path()).unwrap();
         let api_pubkey = api_secret.api_token();
+        let threshold_api_pubkey = api_secret.threshold_api_token();
 
         config.validator_dir = validator_dir.path().into();
         config.secrets_dir = secrets_dir.path().into();
@@ -100,6 +108,7 @@ impl ApiTester {
             Hash256::repeat_byte(42),
             spec.clone(),
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
+            None,
             slot_clock.clone(),
             &config,
             test_runtime.task_executor.clone(),
@@ -112,12 +121,31 @@ impl ApiTester {
 
         let initialized_validators = validator_store.initialized_validators();
 
+        // The beacon nodes are unreachable, their health is only ever reported.
+        let beacon_nodes = ["http://127.0.0.1:1", "http://127.0.0.1:2"]
+            .iter()
+            .map(|url| {
+                CandidateBeaconNode::new(BeaconNodeHttpClient::new(
+                    SensitiveUrl::parse(url).unwrap(),
+                    Timeouts::set_all(Duration::from_secs(1)),
+                ))
+            })
+            .collect();
+        let beacon_nodes = Arc::new(BeaconNodeFallback::new(
+            beacon_nodes,
+            vec![],
+            true,
+            spec.clone(),
+            log.clone(),
+        ));
+
         let context = Arc::new(Context {
             task_executor: test_runtime.task_executor.clone(),
             api_secret,
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            beacon_nodes: Some(beacon_nodes),
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec(),
@@ -148,9 +176,12 @@ impl ApiTester {
         .unwrap();
 
         let client = ValidatorClientHttpClient::new(url.clone(), api_pubkey).unwrap();
+        let threshold_client =
+            ValidatorClientHttpClient::new(url.clone(), threshold_api_pubkey).unwrap();
 
         Self {
             client,
+            threshold_client,
             initialized_validators,
             validator_store,
             url,
@@ -226,6 +257,7 @@ impl ApiTester {
 
         let expected = VersionData {
             version: lighthouse_version::version_with_platform(),
+            execution_client: None,
         };
 
         assert_eq!(result, expected);
@@ -761,7 +793,8 @@ impl ApiTester {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
         let graffiti_str = GraffitiString::from_str(graffiti).unwrap();
         assert_eq!(
-            self.validator_store.graffiti(&validator.voting_pubkey),
+            self.validator_store
+                .graffiti(&validator.voting_pubkey, None),
             Some(graffiti_str.into())
         );
 
@@ -781,6 +814,333 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_set_graffiti_template(self, index: usize, template: &str) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let template = GraffitiTemplate::from_str(template).unwrap();
+        let resp = self
+            .client
+            .set_graffiti_template(&validator.voting_pubkey, template)
+            .await;
+
+        assert!(resp.is_ok());
+
+        self
+    }
+
+    pub async fn test_get_beacon_health(self) -> Self {
+        let health = self
+            .client
+            .get_lighthouse_beacon_health()
+            .await
+            .unwrap()
+            .data;
+
+        // The beacon nodes have not been checked yet, they keep their configured order.
+        assert_eq!(health.len(), 2);
+        for (index, beacon_node) in health.iter().enumerate() {
+            assert_eq!(beacon_node.index, index);
+            assert_eq!(beacon_node.status, BeaconNodeStatus::Uninitialized);
+            assert_eq!(beacon_node.latency_ms, None);
+            assert_eq!(beacon_node.sync_distance, None);
+        }
+        assert_eq!(health[0].score, health[1].score);
+
+        self
+    }
+
+    pub async fn test_get_duty_history(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let pubkey = validator.voting_pubkey;
+        let slots_per_epoch = E::slots_per_epoch();
+
+        let history = self.validator_store.duty_history();
+        history.scheduled(pubkey, DutyKind::Proposal, Slot::new(3));
+        history.scheduled(
+            pubkey,
+            DutyKind::Attestation,
+            Slot::new(slots_per_epoch + 1),
+        );
+        history.signed(
+            pubkey,
+            DutyKind::Attestation,
+            Slot::new(slots_per_epoch + 1),
+            Some(Duration::from_millis(4_000)),
+        );
+        history.published(
+            pubkey,
+            DutyKind::Attestation,
+            Slot::new(slots_per_epoch + 1),
+            vec!["http://localhost:5052/".to_string()],
+            Some(Duration::from_millis(4_100)),
+        );
+
+        let records = self
+            .client
+            .get_lighthouse_validators_pubkey_history(&pubkey, None, None)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].kind, DutyKind::Proposal);
+        assert_eq!(records[0].status, DutyStatus::Scheduled);
+        assert_eq!(records[1].kind, DutyKind::Attestation);
+        assert_eq!(records[1].status, DutyStatus::Published);
+        assert_eq!(records[1].signed_delay_ms, Some(4_000));
+        assert_eq!(records[1].published_delay_ms, Some(4_100));
+
+        let records = self
+            .client
+            .get_lighthouse_validators_pubkey_history(&pubkey, Some(Epoch::new(1)), None)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].slot, Slot::new(slots_per_epoch + 1));
+
+        let records = self
+            .client
+            .get_lighthouse_validators_pubkey_history(&pubkey, None, Some(Epoch::new(0)))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].slot, Slot::new(3));
+
+        // Unknown validators are rejected.
+        let unknown = PublicKeyBytes::empty();
+        assert!(self
+            .client
+            .get_lighthouse_validators_pubkey_history(&unknown, None, None)
+            .await
+            .is_err());
+
+        self
+    }
+
+    /// Adds a validator holding share 1 of a 2-of-3 split key, with unreachable peers.
+    ///
+    /// Returns the keypair of the validator and the keypair of the local share.
+    pub async fn create_threshold_validator(&self) -> (Keypair, Keypair) {
+        let keypair = Keypair::random();
+        let share_keypairs = split_secret_key(&keypair.sk, 2, 3)
+            .unwrap()
+            .into_iter()
+            .map(|(_, sk)| Keypair::from_components(sk.public_key(), sk))
+            .collect::<Vec<_>>();
+
+        let dir = self._validator_dir.path();
+        let password = random_password_string();
+        let keystore = KeystoreBuilder::new(&share_keypairs[0], password.as_ref(), String::new())
+            .unwrap()
+            .build()
+            .unwrap();
+        let voting_keystore_path = dir.join("threshold-share-1.json");
+        keystore
+            .to_json_writer(std::fs::File::create(&voting_keystore_path).unwrap())
+            .unwrap();
+        let api_token_path = dir.join("threshold-peer-api-token.txt");
+        std::fs::write(&api_token_path, "api-token-0x00").unwrap();
+
+        let peers = share_keypairs[1..]
+            .iter()
+            .zip(2..)
+            .map(|(share_keypair, share_index)| ThresholdPeerDefinition {
+                share_index,
+                share_public_key: share_keypair.pk.clone(),
+                url: "http://127.0.0.1:1".to_string(),
+                api_token_path: api_token_path.clone(),
+            })
+            .collect();
+        let validator_def = ValidatorDefinition {
+            enabled: true,
+            voting_public_key: keypair.pk.clone(),
+            graffiti: None,
+            suggested_fee_recipient: None,
+            gas_limit: None,
+            builder_proposals: None,
+            builder_boost_factor: None,
+            prefer_builder_proposals: None,
+            description: String::new(),
+            signing_definition: SigningDefinition::Threshold {
+                voting_keystore_path,
+                voting_keystore_password_path: None,
+                voting_keystore_password: Some(password),
+                threshold: ThresholdDefinition {
+                    share_index: 1,
+                    threshold: 2,
+                    peers,
+                    request_timeout_ms: None,
+                },
+            },
+        };
+        self.validator_store
+            .add_validator(validator_def)
+            .await
+            .unwrap();
+
+        let share_keypair = share_keypairs.into_iter().next().unwrap();
+        (keypair, share_keypair)
+    }
+
+    pub async fn test_threshold_signature_slashing_protection(self) -> Self {
+        let (keypair, share_keypair) = self.create_threshold_validator().await;
+        let pubkey = keypair.pk.compress();
+
+        let spec = E::default_spec();
+        let genesis_validators_root = Hash256::repeat_byte(42);
+        let fork = spec.fork_at_epoch(Epoch::new(0));
+        let domain =
+            |domain| spec.get_domain(Epoch::new(0), domain, &fork, genesis_validators_root);
+        let request = |message: ThresholdSignableMessage<E>| {
+            let signing_root = match &message {
+                ThresholdSignableMessage::RandaoReveal { epoch } => {
+                    epoch.signing_root(domain(Domain::Randao))
+                }
+                ThresholdSignableMessage::BlockHeader(header) => {
+                    header.signing_root(domain(Domain::BeaconProposer))
+                }
+                ThresholdSignableMessage::AttestationData(data) => {
+                    data.signing_root(domain(Domain::BeaconAttester))
+                }
+                _ => unreachable!("not requested by this test"),
+            };
+            ThresholdSignatureRequest {
+                signing_root,
+                message,
+            }
+        };
+
+        let block = |slot, state_root| {
+            ThresholdSignableMessage::BlockHeader(BeaconBlockHeader {
+                slot: Slot::new(slot),
+                proposer_index: 0,
+                parent_root: Hash256::zero(),
+                state_root,
+                body_root: Hash256::zero(),
+            })
+        };
+        let attestation = |beacon_block_root| {
+            ThresholdSignableMessage::AttestationData(AttestationData {
+                slot: Slot::new(1),
+                index: 0,
+                beacon_block_root,
+                source: Checkpoint::default(),
+                target: Checkpoint::default(),
+            })
+        };
+
+        for message in [block(1, Hash256::zero()), attestation(Hash256::zero())] {
+            let request = request(message);
+            // Signing the same message twice is safe.
+            for _ in 0..2 {
+                let response = self
+                    .client
+                    .post_lighthouse_validators_threshold_signature(&pubkey, &request)
+                    .await
+                    .unwrap()
+                    .data;
+                assert_eq!(response.share_index, 1);
+                assert!(response
+                    .signature
+                    .verify(&share_keypair.pk, request.signing_root));
+            }
+        }
+
+        // Conflicting messages are refused by the slashing protection database.
+        for message in [
+            block(1, Hash256::repeat_byte(1)),
+            attestation(Hash256::repeat_byte(1)),
+        ] {
+            assert!(self
+                .client
+                .post_lighthouse_validators_threshold_signature(&pubkey, &request(message))
+                .await
+                .is_err());
+        }
+
+        // Messages which are not slashable are signed as well, but only for their own signing root.
+        let mut randao_reveal = request(ThresholdSignableMessage::RandaoReveal {
+            epoch: Epoch::new(0),
+        });
+        assert!(self
+            .client
+            .post_lighthouse_validators_threshold_signature(&pubkey, &randao_reveal)
+            .await
+            .is_ok());
+        randao_reveal.signing_root = Hash256::repeat_byte(3);
+        assert!(self
+            .client
+            .post_lighthouse_validators_threshold_signature(&pubkey, &randao_reveal)
+            .await
+            .is_err());
+
+        // The signing root must be that of the message, a mismatched request is not recorded.
+        let mut mismatched = request(block(2, Hash256::zero()));
+        mismatched.signing_root = Hash256::repeat_byte(2);
+        assert!(self
+            .client
+            .post_lighthouse_validators_threshold_signature(&pubkey, &mismatched)
+            .await
+            .is_err());
+        assert!(self
+            .client
+            .post_lighthouse_validators_threshold_signature(
+                &pubkey,
+                &request(block(2, Hash256::zero()))
+            )
+            .await
+            .is_ok());
+
+        self
+    }
+
+    pub async fn test_threshold_token(self) -> Self {
+        let (keypair, share_keypair) = self.create_threshold_validator().await;
+        let pubkey = keypair.pk.compress();
+
+        let spec = E::default_spec();
+        let fork = spec.fork_at_epoch(Epoch::new(0));
+        let domain = spec.get_domain(
+            Epoch::new(0),
+            Domain::Randao,
+            &fork,
+            Hash256::repeat_byte(42),
+        );
+        let request = ThresholdSignatureRequest::<E> {
+            signing_root: Epoch::new(0).signing_root(domain),
+            message: ThresholdSignableMessage::RandaoReveal {
+                epoch: Epoch::new(0),
+            },
+        };
+
+        // The threshold token authorises partial signatures, and the response is signed with its
+        // own key.
+        let response = self
+            .threshold_client
+            .post_lighthouse_validators_threshold_signature(&pubkey, &request)
+            .await
+            .unwrap()
+            .data;
+        assert!(response
+            .signature
+            .verify(&share_keypair.pk, request.signing_root));
+
+        // Any other request is refused.
+        assert!(self
+            .threshold_client
+            .get_lighthouse_validators()
+            .await
+            .is_err());
+        assert!(self.threshold_client.get_keystores().await.is_err());
+        assert!(self
+            .threshold_client
+            .patch_lighthouse_validators(&pubkey, Some(false), None, None, None, None, None)
+            .await
+            .is_err());
+
+        self
+    }
+
     pub async fn test_delete_graffiti(self, index: usize) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
         let resp = self.client.get_graffiti(&validator.voting_pubkey).await;
@@ -920,6 +1280,20 @@ async fn routes_with_invalid_auth() {
                 .await
         })
         .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_validators_threshold_signature(
+                    &PublicKeyBytes::empty(),
+                    &ThresholdSignatureRequest::<E> {
+                        signing_root: Hash256::zero(),
+                        message: ThresholdSignableMessage::RandaoReveal {
+                            epoch: Epoch::new(0),
+                        },
+                    },
+                )
+                .await
+        })
+        .await
         .test_with_invalid_auth(|client| async move { client.get_keystores().await })
         .await
         .test_with_invalid_auth(|client| async move {
@@ -1315,6 +1689,68 @@ async fn validator_graffiti_api() {
         .await;
 }
 
+#[tokio::test]
+async fn validator_graffiti_template_api() {
+    let expected_graffiti = format!(
+        "{}{}",
+        lighthouse_version::CLIENT_CODE,
+        lighthouse_version::version_number()
+    );
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_enabled_validators_count(2)
+        .test_set_graffiti_template(0, "{client}{version}{el_client}")
+        .await
+        // The execution client is only known when a block is proposed.
+        .test_get_graffiti(0, &expected_graffiti)
+        .await
+        .test_set_graffiti(0, "Uncle Bill was here")
+        .await
+        .test_get_graffiti(0, "Uncle Bill was here")
+        .await;
+}
+
+#[tokio::test]
+async fn beacon_health() {
+    ApiTester::new().await.test_get_beacon_health().await;
+}
+
+#[tokio::test]
+async fn validator_duty_history() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_enabled_validators_count(2)
+        .test_get_duty_history(0)
+        .await;
+}
+
+#[tokio::test]
+async fn threshold_signature_slashing_protection() {
+    ApiTester::new()
+        .await
+        .test_threshold_signature_slashing_protection()
+        .await;
+}
+
+#[tokio::test]
+async fn threshold_token_only_authorises_threshold_signatures() {
+    ApiTester::new().await.test_threshold_token().await;
+}
+
 #[tokio::test]
 async fn keystore_validator_creation() {
     ApiTester::new()
@@ -37,6 +37,7 @@ pub const ATTESTATION_SELECTION_PROOFS: &str = "attestation_selection_proofs";
 pub const SUBSCRIPTIONS: &str = "subscriptions";
 pub const LOCAL_KEYSTORE: &str = "local_keystore";
 pub const WEB3SIGNER: &str = "web3signer";
+pub const THRESHOLD: &str = "threshold";
 
 pub use lighthouse_metrics::*;
 
@@ -184,6 +185,11 @@ lazy_static::lazy_static! {
         "Duration to obtain a signature for a block",
     );
 
+    pub static ref SLASHING_PROTECTION_REPLICATION_ACTIVE: Result<IntGauge> = try_create_int_gauge(
+        "vc_slashing_protection_replication_active",
+        "Set to 1 if this validator client is the active client of a replication group",
+    );
+
     pub static ref ATTESTATION_DUTY: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "vc_attestation_duty_slot",
         "Attestation duty slot for all managed validators",
@@ -201,6 +207,11 @@ lazy_static::lazy_static! {
         "vc_beacon_node_latency_primary_endpoint",
         "Round-trip latency for the primary BN endpoint",
     );
+    pub static ref VC_BEACON_NODE_HEALTH_SCORE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "vc_beacon_node_health_score",
+        "Health score of each BN, from 0 to 100, used to rank the BNs",
+        &["endpoint"]
+    );
 }
 
 pub fn gather_prometheus_metrics<T: EthSpec>(
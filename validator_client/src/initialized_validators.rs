@@ -6,20 +6,23 @@
 //! The `InitializedValidators` struct in this file serves as the source-of-truth of which
 //! validators are managed by this validator client.
 
-use crate::signing_method::SigningMethod;
+use crate::signing_method::{SigningMethod, ThresholdPeer, ThresholdSigner};
 use account_utils::{
     read_password, read_password_from_user, read_password_string,
     validator_definitions::{
-        self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, Web3SignerDefinition,
-        CONFIG_FILENAME,
+        self, SigningDefinition, ThresholdDefinition, ValidatorDefinition, ValidatorDefinitions,
+        Web3SignerDefinition, CONFIG_FILENAME,
     },
     ZeroizeString,
 };
-use eth2_keystore::Keystore;
+use eth2::lighthouse_vc::http_client::ValidatorClientHttpClient;
+use eth2::types::ExecutionClientVersion;
+use eth2_keystore::{Keystore, PlainText};
 use lighthouse_metrics::set_gauge;
 use lockfile::{Lockfile, LockfileError};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use reqwest::{Certificate, Client, Error as ReqwestError, Identity};
+use sensitive_url::SensitiveUrl;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
@@ -27,7 +30,7 @@ use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use types::graffiti::GraffitiString;
+use types::graffiti::{GraffitiString, GraffitiTemplate, GraffitiTemplateFields};
 use types::{Address, Graffiti, Keypair, PublicKey, PublicKeyBytes};
 use url::{ParseError, Url};
 use validator_dir::Builder as ValidatorDirBuilder;
@@ -42,6 +45,12 @@ use crate::Config;
 /// that time is outside the synchronous assumptions of Eth2.
 const DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT: Duration = Duration::from_secs(12);
 
+/// Default timeout for a request to a threshold peer for a partial signature.
+///
+/// Peers are expected to respond quickly, a signature which takes several seconds to combine is
+/// likely to be included late.
+const DEFAULT_THRESHOLD_PEER_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
 // Use TTY instead of stdin to capture passwords from users.
 const USE_STDIN: bool = false;
 
@@ -117,6 +126,9 @@ pub enum Error {
     UnableToSaveKeyCache(key_cache::Error),
     UnableToDecryptKeyCache(key_cache::Error),
     UnableToDeletePasswordFile(PathBuf, io::Error),
+    /// The threshold definition is inconsistent, or one of its peers cannot be reached.
+    InvalidThresholdDefinition(String),
+    UnableToReadThresholdPeerApiToken(PathBuf, io::Error),
 }
 
 impl From<LockfileError> for Error {
@@ -128,7 +140,7 @@ impl From<LockfileError> for Error {
 /// A validator that is ready to sign messages.
 pub struct InitializedValidator {
     signing_method: Arc<SigningMethod>,
-    graffiti: Option<Graffiti>,
+    graffiti: Option<GraffitiTemplate>,
     suggested_fee_recipient: Option<Address>,
     gas_limit: Option<u64>,
     builder_proposals: Option<bool>,
@@ -145,6 +157,10 @@ impl InitializedValidator {
             SigningMethod::LocalKeystore {
                 ref voting_keystore_lockfile,
                 ..
+            }
+            | SigningMethod::Threshold {
+                ref voting_keystore_lockfile,
+                ..
             } => MutexGuard::try_map(voting_keystore_lockfile.lock(), |option_lockfile| {
                 option_lockfile.as_mut()
             })
@@ -178,8 +194,8 @@ impl InitializedValidator {
         self.index
     }
 
-    pub fn get_graffiti(&self) -> Option<Graffiti> {
-        self.graffiti
+    pub fn get_graffiti(&self) -> Option<&GraffitiTemplate> {
+        self.graffiti.as_ref()
     }
 }
 
@@ -238,34 +254,11 @@ impl InitializedValidator {
                     // to keep if off the core executor. This also has the fortunate effect of
                     // interrupting the potentially long-running task during shut down.
                     let (password, keypair) = tokio::task::spawn_blocking(move || {
-                        Result::<_, Error>::Ok(
-                            match (voting_keystore_password_path, voting_keystore_password) {
-                                // If the password is supplied, use it and ignore the path
-                                // (if supplied).
-                                (_, Some(password)) => (
-                                    password.as_ref().to_vec().into(),
-                                    keystore
-                                        .decrypt_keypair(password.as_ref())
-                                        .map_err(Error::UnableToDecryptKeystore)?,
-                                ),
-                                // If only the path is supplied, use the path.
-                                (Some(path), None) => {
-                                    let password = read_password(path)
-                                        .map_err(Error::UnableToReadVotingKeystorePassword)?;
-                                    let keypair = keystore
-                                        .decrypt_keypair(password.as_bytes())
-                                        .map_err(Error::UnableToDecryptKeystore)?;
-                                    (password, keypair)
-                                }
-                                // If there is no password available, maybe prompt for a password.
-                                (None, None) => {
-                                    let (password, keypair) = unlock_keystore_via_stdin_password(
-                                        &keystore,
-                                        &keystore_path,
-                                    )?;
-                                    (password.as_ref().to_vec().into(), keypair)
-                                }
-                            },
+                        decrypt_voting_keystore(
+                            &keystore,
+                            &keystore_path,
+                            voting_keystore_password_path,
+                            voting_keystore_password,
                         )
                     })
                     .await
@@ -343,11 +336,59 @@ impl InitializedValidator {
                     voting_public_key: def.voting_public_key,
                 }
             }
+            // Load and decrypt the keystore holding the local share. The share does not match the
+            // voting public key, so it is never added to the key cache.
+            SigningDefinition::Threshold {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+                threshold,
+            } => {
+                let peers = build_threshold_peers(&threshold)?;
+
+                use std::collections::hash_map::Entry::*;
+                let voting_keystore = match key_stores.entry(voting_keystore_path.clone()) {
+                    Vacant(entry) => entry.insert(open_keystore(&voting_keystore_path)?),
+                    Occupied(entry) => entry.into_mut(),
+                };
+
+                let keystore = voting_keystore.clone();
+                let keystore_path = voting_keystore_path.clone();
+                let (_password, share_keypair) = tokio::task::spawn_blocking(move || {
+                    decrypt_voting_keystore(
+                        &keystore,
+                        &keystore_path,
+                        voting_keystore_password_path,
+                        voting_keystore_password,
+                    )
+                })
+                .await
+                .map_err(Error::TokioJoin)??;
+
+                // Append a `.lock` suffix to the voting keystore.
+                let lockfile_path = get_lockfile_path(&voting_keystore_path)
+                    .ok_or_else(|| Error::BadVotingKeystorePath(voting_keystore_path.clone()))?;
+
+                let voting_keystore_lockfile = Mutex::new(Some(Lockfile::new(lockfile_path)?));
+
+                SigningMethod::Threshold {
+                    voting_keystore_path,
+                    voting_keystore_lockfile,
+                    voting_keystore: voting_keystore.clone(),
+                    threshold_signer: ThresholdSigner::new(
+                        def.voting_public_key,
+                        threshold.share_index,
+                        Arc::new(share_keypair),
+                        threshold.threshold,
+                        peers,
+                    ),
+                }
+            }
         };
 
         Ok(Self {
             signing_method: Arc::new(signing_method),
-            graffiti: def.graffiti.map(Into::into),
+            graffiti: def.graffiti,
             suggested_fee_recipient: def.suggested_fee_recipient,
             gas_limit: def.gas_limit,
             builder_proposals: def.builder_proposals,
@@ -364,10 +405,117 @@ impl InitializedValidator {
             SigningMethod::Web3Signer {
                 voting_public_key, ..
             } => voting_public_key,
+            SigningMethod::Threshold {
+                threshold_signer, ..
+            } => threshold_signer.voting_public_key(),
+        }
+    }
+}
+
+/// Decrypts the `keystore` at `keystore_path` using the password from its validator definition.
+///
+/// If the definition holds no password, the user may be prompted for one.
+fn decrypt_voting_keystore(
+    keystore: &Keystore,
+    keystore_path: &Path,
+    password_path: Option<PathBuf>,
+    password: Option<ZeroizeString>,
+) -> Result<(PlainText, Keypair), Error> {
+    match (password_path, password) {
+        // If the password is supplied, use it and ignore the path (if supplied).
+        (_, Some(password)) => Ok((
+            password.as_ref().to_vec().into(),
+            keystore
+                .decrypt_keypair(password.as_ref())
+                .map_err(Error::UnableToDecryptKeystore)?,
+        )),
+        // If only the path is supplied, use the path.
+        (Some(path), None) => {
+            let password =
+                read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?;
+            let keypair = keystore
+                .decrypt_keypair(password.as_bytes())
+                .map_err(Error::UnableToDecryptKeystore)?;
+            Ok((password, keypair))
+        }
+        // If there is no password available, maybe prompt for a password.
+        (None, None) => {
+            let (password, keypair) = unlock_keystore_via_stdin_password(keystore, keystore_path)?;
+            Ok((password.as_ref().to_vec().into(), keypair))
         }
     }
 }
 
+/// Checks the consistency of `threshold` and builds an authenticated HTTP client for each of its
+/// peers.
+fn build_threshold_peers(threshold: &ThresholdDefinition) -> Result<Vec<ThresholdPeer>, Error> {
+    let num_shares = threshold.peers.len() + 1;
+    // A threshold of half the shares or less allows two disjoint groups of shares to sign
+    // conflicting messages.
+    if threshold.threshold < 2
+        || threshold.threshold > num_shares
+        || 2 * threshold.threshold <= num_shares
+    {
+        return Err(Error::InvalidThresholdDefinition(format!(
+            "threshold {} is invalid for {} shares",
+            threshold.threshold, num_shares
+        )));
+    }
+
+    let mut share_indices = HashSet::new();
+    for share_index in std::iter::once(threshold.share_index)
+        .chain(threshold.peers.iter().map(|peer| peer.share_index))
+    {
+        if share_index == 0 || !share_indices.insert(share_index) {
+            return Err(Error::InvalidThresholdDefinition(format!(
+                "share index {} is zero or duplicated",
+                share_index
+            )));
+        }
+    }
+
+    let request_timeout = threshold
+        .request_timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_THRESHOLD_PEER_REQUEST_TIMEOUT);
+
+    threshold
+        .peers
+        .iter()
+        .map(|peer| {
+            let url = SensitiveUrl::parse(&peer.url).map_err(|e| {
+                Error::InvalidThresholdDefinition(format!("invalid peer URL: {:?}", e))
+            })?;
+            let api_token = fs::read_to_string(&peer.api_token_path).map_err(|e| {
+                Error::UnableToReadThresholdPeerApiToken(peer.api_token_path.clone(), e)
+            })?;
+            let http_client = Client::builder()
+                .timeout(request_timeout)
+                .build()
+                .map_err(|e| {
+                    Error::InvalidThresholdDefinition(format!(
+                        "unable to build peer client: {:?}",
+                        e
+                    ))
+                })?;
+            let client = ValidatorClientHttpClient::from_components(
+                url,
+                http_client,
+                api_token.trim().to_string(),
+            )
+            .map_err(|e| {
+                Error::InvalidThresholdDefinition(format!("invalid peer API token: {:?}", e))
+            })?;
+
+            Ok(ThresholdPeer {
+                share_index: peer.share_index,
+                share_public_key: peer.share_public_key.clone(),
+                client,
+            })
+        })
+        .collect()
+}
+
 pub fn load_pem_certificate<P: AsRef<Path>>(pem_path: P) -> Result<Certificate, Error> {
     let mut buf = Vec::new();
     File::open(&pem_path)
@@ -738,8 +886,34 @@ impl InitializedValidators {
     }
 
     /// Returns the `graffiti` for a given public key specified in the `ValidatorDefinitions`.
-    pub fn graffiti(&self, public_key: &PublicKeyBytes) -> Option<Graffiti> {
-        self.validators.get(public_key).and_then(|v| v.graffiti)
+    ///
+    /// The fields of a graffiti template are resolved, using `execution_client` for the fields
+    /// describing the execution client of the beacon node.
+    pub fn graffiti(
+        &self,
+        public_key: &PublicKeyBytes,
+        execution_client: Option<&ExecutionClientVersion>,
+    ) -> Option<Graffiti> {
+        let validator = self.validators.get(public_key)?;
+        let fields = GraffitiTemplateFields {
+            client: lighthouse_version::CLIENT_CODE.to_string(),
+            version: lighthouse_version::version_number().to_string(),
+            el_client: execution_client.map(|client| client.code.clone()),
+            el_version: execution_client.map(|client| client.version.clone()),
+            validator_index: validator.index,
+        };
+        validator
+            .graffiti
+            .as_ref()
+            .map(|template| template.render(&fields))
+    }
+
+    /// Returns `true` if the `graffiti` for a given public key is a template with fields.
+    pub fn has_graffiti_template(&self, public_key: &PublicKeyBytes) -> bool {
+        self.validators
+            .get(public_key)
+            .and_then(|v| v.graffiti.as_ref())
+            .map_or(false, GraffitiTemplate::has_fields)
     }
 
     /// Sets the `InitializedValidator` and `ValidatorDefinition` `graffiti` values.
@@ -753,7 +927,7 @@ impl InitializedValidators {
     pub fn set_graffiti(
         &mut self,
         voting_public_key: &PublicKey,
-        graffiti: GraffitiString,
+        graffiti: GraffitiTemplate,
     ) -> Result<(), Error> {
         if let Some(def) = self
             .definitions
@@ -768,7 +942,7 @@ impl InitializedValidators {
             .validators
             .get_mut(&PublicKeyBytes::from(voting_public_key))
         {
-            val.graffiti = Some(graffiti.into());
+            val.graffiti = Some(graffiti);
         }
 
         self.definitions
@@ -814,7 +988,7 @@ impl InitializedValidators {
     pub fn get_all_validators_graffiti(&self) -> HashMap<&PublicKeyBytes, Option<Graffiti>> {
         let mut result = HashMap::new();
         for public_key in self.validators.keys() {
-            result.insert(public_key, self.graffiti(public_key));
+            result.insert(public_key, self.graffiti(public_key, None));
         }
         result
     }
@@ -905,7 +1079,7 @@ impl InitializedValidators {
                 def.builder_proposals = Some(builder_proposals);
             }
             if let Some(graffiti) = graffiti.clone() {
-                def.graffiti = Some(graffiti);
+                def.graffiti = Some(graffiti.into());
             }
             if let Some(builder_boost_factor) = builder_boost_factor {
                 def.builder_boost_factor = Some(builder_boost_factor);
@@ -1123,8 +1297,8 @@ impl InitializedValidators {
                     };
                     definitions_map.insert(*key_store.uuid(), def);
                 }
-                // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
+                // Remote signer and threshold validators don't interact with the key cache.
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold { .. } => (),
             }
         }
 
@@ -1167,8 +1341,8 @@ impl InitializedValidators {
                     passwords.push(pw);
                     public_keys.push(def.voting_public_key.clone());
                 }
-                // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
+                // Remote signer and threshold validators don't interact with the key cache.
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold { .. } => (),
             };
         }
 
@@ -1323,6 +1497,41 @@ impl InitializedValidators {
                                     "validator" => format!("{:?}", def.voting_public_key)
                                 );
 
+                                // Exit on an invalid validator.
+                                return Err(e);
+                            }
+                        }
+                    }
+                    SigningDefinition::Threshold { .. } => {
+                        match InitializedValidator::from_definition(
+                            def.clone(),
+                            &mut key_cache,
+                            &mut key_stores,
+                            &mut None,
+                            &self.config,
+                        )
+                        .await
+                        {
+                            Ok(init) => {
+                                self.validators
+                                    .insert(init.voting_public_key().compress(), init);
+
+                                info!(
+                                    self.log,
+                                    "Enabled validator";
+                                    "signing_method" => "threshold",
+                                    "voting_pubkey" => format!("{:?}", def.voting_public_key),
+                                );
+                            }
+                            Err(e) => {
+                                error!(
+                                    self.log,
+                                    "Failed to initialize validator";
+                                    "error" => format!("{:?}", e),
+                                    "signing_method" => "threshold",
+                                    "validator" => format!("{:?}", def.voting_public_key)
+                                );
+
                                 // Exit on an invalid validator.
                                 return Err(e);
                             }
@@ -1340,8 +1549,8 @@ impl InitializedValidators {
                             disabled_uuids.insert(*key_store.uuid());
                         }
                     }
-                    // Remote signers do not interact with the key cache.
-                    SigningDefinition::Web3Signer { .. } => (),
+                    // Remote signers and threshold validators do not interact with the key cache.
+                    SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold { .. } => {}
                 }
 
                 info!(
@@ -1414,6 +1623,10 @@ impl InitializedValidators {
                 SigningDefinition::LocalKeystore {
                     ref mut voting_keystore_password,
                     ..
+                }
+                | SigningDefinition::Threshold {
+                    ref mut voting_keystore_password,
+                    ..
                 } => {
                     if let Some(password) = voting_keystore_password.take() {
                         passwords.insert(def.voting_public_key.clone(), password);
@@ -1437,3 +1650,53 @@ impl InitializedValidators {
         self.definitions.as_mut_slice()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use account_utils::validator_definitions::ThresholdPeerDefinition;
+
+    fn threshold_definition(threshold: usize, num_shares: u64) -> ThresholdDefinition {
+        ThresholdDefinition {
+            share_index: 1,
+            threshold,
+            peers: (2..=num_shares)
+                .map(|share_index| ThresholdPeerDefinition {
+                    share_index,
+                    share_public_key: Keypair::random().pk,
+                    url: format!("http://localhost:{}", 5061 + share_index),
+                    api_token_path: PathBuf::from("/nonexistent/api-token.txt"),
+                })
+                .collect(),
+            request_timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn threshold_must_be_a_majority_of_shares() {
+        for (threshold, num_shares) in [(1, 3), (2, 4), (3, 6), (4, 3)] {
+            assert!(
+                matches!(
+                    build_threshold_peers(&threshold_definition(threshold, num_shares)),
+                    Err(Error::InvalidThresholdDefinition(_))
+                ),
+                "{} of {} should be rejected",
+                threshold,
+                num_shares
+            );
+        }
+
+        // A valid threshold passes the consistency checks and fails on the missing API token.
+        for (threshold, num_shares) in [(2, 3), (3, 4), (3, 5)] {
+            assert!(
+                matches!(
+                    build_threshold_peers(&threshold_definition(threshold, num_shares)),
+                    Err(Error::UnableToReadThresholdPeerApiToken(..))
+                ),
+                "{} of {} should be accepted",
+                threshold,
+                num_shares
+            );
+        }
+    }
+}
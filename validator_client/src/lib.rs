@@ -1,5 +1,6 @@
 mod attestation_service;
 mod beacon_node_fallback;
+mod beacon_node_health;
 mod block_service;
 mod check_synced;
 mod cli;
@@ -15,8 +16,11 @@ mod signing_method;
 mod sync_committee_service;
 
 mod doppelganger_service;
+pub mod duty_history;
+pub mod exit_vault;
 pub mod http_api;
 pub mod initialized_validators;
+pub mod replication;
 pub mod validator_store;
 
 pub use beacon_node_fallback::ApiTopic;
@@ -35,6 +39,7 @@ use crate::beacon_node_fallback::{
 use crate::doppelganger_service::DoppelgangerService;
 use crate::graffiti_file::GraffitiFile;
 use crate::initialized_validators::Error::UnableToOpenVotingKeystore;
+use crate::replication::Replication;
 use account_utils::validator_definitions::ValidatorDefinitions;
 use attestation_service::{AttestationService, AttestationServiceBuilder};
 use block_service::{BlockService, BlockServiceBuilder};
@@ -371,6 +376,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         let mut beacon_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             candidates,
             config.broadcast_topics.clone(),
+            config.beacon_node_ranking,
             context.eth2_config.spec.clone(),
             log.clone(),
         );
@@ -378,6 +384,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         let mut proposer_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             proposer_candidates,
             config.broadcast_topics.clone(),
+            config.beacon_node_ranking,
             context.eth2_config.spec.clone(),
             log.clone(),
         );
@@ -419,12 +426,27 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             None
         };
 
+        let replication = config
+            .slashing_protection_replication
+            .as_ref()
+            .map(|replication_config| {
+                Replication::open(
+                    replication_config,
+                    genesis_validators_root,
+                    context.executor.clone(),
+                    log.clone(),
+                )
+                .map(Arc::new)
+            })
+            .transpose()?;
+
         let validator_store = Arc::new(ValidatorStore::new(
             validators,
             slashing_protection,
             genesis_validators_root,
             context.eth2_config.spec.clone(),
             doppelganger_service.clone(),
+            replication,
             slot_clock.clone(),
             &config,
             context.executor.clone(),
@@ -434,6 +456,26 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // Ensure all validators are registered in doppelganger protection.
         validator_store.register_all_in_doppelganger_protection_if_enabled()?;
 
+        // Catch up with the slashing protection journal before signing anything.
+        if let Some(replication_config) = &config.slashing_protection_replication {
+            if replication_config.standby {
+                validator_store.import_slashing_protection_journal()?;
+                info!(
+                    log,
+                    "Started as slashing protection standby";
+                    "journal" => ?replication_config.journal_path,
+                    "failover_epochs" => replication_config.failover_epochs,
+                );
+            } else {
+                validator_store.promote_standby()?;
+                info!(
+                    log,
+                    "Started as active slashing protection replica";
+                    "journal" => ?replication_config.journal_path,
+                );
+            }
+        }
+
         info!(
             log,
             "Loaded validator keypair store";
@@ -539,6 +581,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 task_executor: self.context.executor.clone(),
                 api_secret,
                 validator_store: Some(self.validator_store.clone()),
+                beacon_nodes: Some(self.beacon_nodes.clone()),
                 validator_dir: Some(self.config.validator_dir.clone()),
                 secrets_dir: Some(self.config.secrets_dir.clone()),
                 graffiti_file: self.config.graffiti_file.clone(),
@@ -606,6 +649,27 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             info!(log, "Doppelganger protection disabled.")
         }
 
+        if let Some(exit_vault_config) = &self.config.exit_vault {
+            exit_vault::start_exit_vault_service(
+                self.context.service_context("exit_vault".into()),
+                self.slot_clock.clone(),
+                self.validator_store.clone(),
+                exit_vault_config,
+            )
+            .map_err(|e| format!("Unable to start exit vault service: {}", e))?;
+        }
+
+        if let Some(replication_config) = &self.config.slashing_protection_replication {
+            replication::start_replication_service(
+                self.context
+                    .service_context("slashing_protection_replication".into()),
+                self.duties_service.slot_clock.clone(),
+                self.validator_store.clone(),
+                self.duties_service.beacon_nodes.clone(),
+                replication_config.failover_epochs,
+            );
+        }
+
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
         if self.config.enable_latency_measurement_service {
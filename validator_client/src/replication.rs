@@ -0,0 +1,436 @@
+//! Replicates slashing protection data from an active validator client to standby validator
+//! clients, via a journal on a filesystem shared between them.
+//!
+//! The active validator client appends each block and attestation to the journal before signing
+//! it. Concurrent signers share a single write and sync of the journal, on a blocking thread. The
+//! active validator client compacts the journal once it exceeds `JOURNAL_COMPACTION_SIZE`. A
+//! standby imports the journal into its own slashing protection database and does not sign
+//! anything. Once the validators have been offline for `failover_epochs` according to the beacon
+//! node, the standby imports the remainder of the journal and becomes active.
+//!
+//! ## Warning
+//!
+//! Failover relies on the active validator client being offline, not merely unable to reach its
+//! beacon nodes. An active validator client which finds entries written by another validator
+//! client in the journal stops signing, but it may have signed messages in the meantime.
+
+use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
+use crate::http_metrics::metrics;
+use crate::validator_store::{DoppelgangerStatus, ValidatorStore};
+use environment::RuntimeContext;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use slashing_protection::journal::{JournalEntry, JournalReader, JournalWriter};
+use slashing_protection::SlashingDatabase;
+use slog::{crit, debug, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::path::PathBuf;
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::{Epoch, EthSpec, Hash256, PublicKeyBytes, Slot};
+
+/// The number of epochs without any of the validators being live before a standby takes over.
+pub const DEFAULT_FAILOVER_EPOCHS: u64 = 3;
+
+/// The size of the journal, in bytes, beyond which the active validator client compacts it.
+const JOURNAL_COMPACTION_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// The journal shared between the active and standby validator clients.
+    pub journal_path: PathBuf,
+    /// Start as a standby rather than as the active validator client.
+    pub standby: bool,
+    pub failover_epochs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Active,
+    Standby,
+}
+
+/// Entries waiting to be appended to the journal, see `Replication::append_blocking`.
+#[derive(Default)]
+struct PendingEntries {
+    /// The sequence number of the most recently queued entry.
+    last_seq: u64,
+    entries: Vec<(u64, JournalEntry)>,
+}
+
+struct Writer {
+    journal: JournalWriter,
+    /// All entries up to this sequence number have been written.
+    written_seq: u64,
+}
+
+/// The replication state of a validator client.
+pub struct Replication {
+    /// Identifies the entries written by this validator client.
+    writer_id: u64,
+    role: RwLock<Role>,
+    pending: Mutex<PendingEntries>,
+    writer: Mutex<Writer>,
+    reader: Mutex<JournalReader>,
+    executor: TaskExecutor,
+    log: Logger,
+}
+
+impl Replication {
+    /// Open the journal in `config`.
+    ///
+    /// The validator client starts as a standby in any case, it becomes active once it has caught
+    /// up with the journal (see `ValidatorStore::import_slashing_protection_journal`).
+    pub fn open(
+        config: &Config,
+        genesis_validators_root: Hash256,
+        executor: TaskExecutor,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let writer =
+            JournalWriter::open(&config.journal_path, genesis_validators_root).map_err(|e| {
+                format!(
+                    "Unable to open slashing protection journal {:?}: {:?}",
+                    config.journal_path, e
+                )
+            })?;
+        let reader = JournalReader::new(config.journal_path.clone(), genesis_validators_root);
+
+        Ok(Self {
+            writer_id: rand::random(),
+            role: RwLock::new(Role::Standby),
+            pending: Mutex::new(PendingEntries::default()),
+            writer: Mutex::new(Writer {
+                journal: writer,
+                written_seq: 0,
+            }),
+            reader: Mutex::new(reader),
+            executor,
+            log,
+        })
+    }
+
+    pub fn role(&self) -> Role {
+        *self.role.read()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.role() == Role::Active
+    }
+
+    fn set_role(&self, role: Role) {
+        *self.role.write() = role;
+        metrics::set_gauge(
+            &metrics::SLASHING_PROTECTION_REPLICATION_ACTIVE,
+            (role == Role::Active) as i64,
+        );
+    }
+
+    /// Record a block in the journal, it must not be signed if this fails.
+    pub async fn record_block(
+        self: &Arc<Self>,
+        pubkey: PublicKeyBytes,
+        slot: Slot,
+        signing_root: Hash256,
+    ) -> Result<(), String> {
+        self.append(JournalEntry::block(
+            self.writer_id,
+            pubkey,
+            slot,
+            signing_root,
+        ))
+        .await
+    }
+
+    /// Record an attestation in the journal, it must not be signed if this fails.
+    pub async fn record_attestation(
+        self: &Arc<Self>,
+        pubkey: PublicKeyBytes,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: Hash256,
+    ) -> Result<(), String> {
+        self.append(JournalEntry::attestation(
+            self.writer_id,
+            pubkey,
+            source_epoch,
+            target_epoch,
+            signing_root,
+        ))
+        .await
+    }
+
+    /// Append `entry` to the journal on a blocking thread, returning once it is durably stored.
+    async fn append(self: &Arc<Self>, entry: JournalEntry) -> Result<(), String> {
+        let replication = self.clone();
+        self.executor
+            .spawn_blocking_handle(
+                move || replication.append_blocking(entry),
+                "slashing_protection_journal_append",
+            )
+            .ok_or("Unable to write slashing protection journal: shutting down")?
+            .await
+            .map_err(|e| format!("Slashing protection journal task failed: {:?}", e))?
+    }
+
+    /// Queue `entry` and append all queued entries to the journal, unless a concurrent call
+    /// already appended `entry`. Concurrent signers thereby share a single write and sync.
+    fn append_blocking(&self, entry: JournalEntry) -> Result<(), String> {
+        let seq = {
+            let mut pending = self.pending.lock();
+            pending.last_seq += 1;
+            let seq = pending.last_seq;
+            pending.entries.push((seq, entry));
+            seq
+        };
+
+        // Hold the lock whilst checking the role, so no entry is written after a demotion.
+        let mut writer = self.writer.lock();
+        if writer.written_seq >= seq {
+            return Ok(());
+        }
+        if !self.is_active() {
+            self.pending
+                .lock()
+                .entries
+                .retain(|(pending_seq, _)| *pending_seq != seq);
+            return Err("validator client is a standby".to_string());
+        }
+
+        let batch = std::mem::take(&mut self.pending.lock().entries);
+        let last_seq = batch.last().map_or(seq, |(last_seq, _)| *last_seq);
+        match writer
+            .journal
+            .append_all(batch.iter().map(|(_, entry)| entry))
+        {
+            Ok(()) => {
+                writer.written_seq = last_seq;
+                Ok(())
+            }
+            Err(e) => {
+                // The concurrent signers try again with their own entries.
+                let mut pending = self.pending.lock();
+                let newer = std::mem::take(&mut pending.entries);
+                pending.entries = batch
+                    .into_iter()
+                    .filter(|(pending_seq, _)| *pending_seq != seq)
+                    .chain(newer)
+                    .collect();
+                Err(format!(
+                    "Unable to write slashing protection journal: {:?}",
+                    e
+                ))
+            }
+        }
+    }
+
+    /// Compact the journal if this validator client is active and the journal has grown beyond
+    /// `JOURNAL_COMPACTION_SIZE`. Signing waits for the compaction to complete.
+    pub fn compact_journal(&self) -> Result<(), String> {
+        let mut writer = self.writer.lock();
+        if !self.is_active() {
+            return Ok(());
+        }
+        let size = writer
+            .journal
+            .size()
+            .map_err(|e| format!("Unable to read slashing protection journal: {:?}", e))?;
+        if size < JOURNAL_COMPACTION_SIZE {
+            return Ok(());
+        }
+
+        let num_entries = writer
+            .journal
+            .compact(self.writer_id)
+            .map_err(|e| format!("Unable to compact slashing protection journal: {:?}", e))?;
+        info!(
+            self.log,
+            "Compacted slashing protection journal";
+            "entries" => num_entries,
+            "size" => size,
+        );
+        Ok(())
+    }
+
+    /// Import the entries written by other validator clients into `slashing_protection`.
+    ///
+    /// If this validator client is active and another validator client has written to the
+    /// journal, this validator client becomes a standby.
+    pub fn import_new_entries(&self, slashing_protection: &SlashingDatabase) -> Result<(), String> {
+        let entries = self
+            .reader
+            .lock()
+            .read_new_entries()
+            .map_err(|e| format!("Unable to read slashing protection journal: {:?}", e))?
+            .into_iter()
+            // The entries written by this validator client are already in its database.
+            .filter(|entry| entry.writer_id != self.writer_id)
+            .collect::<Vec<_>>();
+
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        // Stop signing before importing, the imported entries may make some messages slashable.
+        if self.is_active() {
+            let _writer = self.writer.lock();
+            self.set_role(Role::Standby);
+            crit!(
+                self.log,
+                "Another validator client is signing";
+                "msg" => "this validator client is now a standby, check that only one validator \
+                    client of the group is active",
+                "entries" => entries.len(),
+            );
+        }
+
+        let num_entries = entries.len();
+        slashing_protection
+            .import_journal_entries(entries)
+            .map_err(|e| format!("Unable to import slashing protection journal: {:?}", e))?;
+        debug!(
+            self.log,
+            "Imported slashing protection journal";
+            "entries" => num_entries,
+        );
+        Ok(())
+    }
+
+    /// Import all entries of the journal and start signing.
+    pub fn promote(&self, slashing_protection: &SlashingDatabase) -> Result<(), String> {
+        self.import_new_entries(slashing_protection)?;
+        let _writer = self.writer.lock();
+        self.set_role(Role::Active);
+        Ok(())
+    }
+}
+
+/// Starts a service which imports the journal every slot and, whilst this validator client is a
+/// standby, takes over once its validators have been offline for `failover_epochs`.
+pub fn start_replication_service<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    slot_clock: T,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    failover_epochs: u64,
+) {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+
+    let future = async move {
+        let mut last_checked_epoch = None;
+        let mut offline_epochs = 0;
+
+        loop {
+            // Run halfway through each slot, so that the liveness of the previous epoch is known.
+            let sleep_time = slot_clock
+                .duration_to_next_slot()
+                .map(|next_slot| next_slot + slot_clock.slot_duration() / 2)
+                .unwrap_or_else(|| slot_clock.slot_duration());
+            sleep(sleep_time).await;
+
+            if let Err(e) = validator_store.import_slashing_protection_journal() {
+                error!(log, "Slashing protection replication failed"; "error" => e);
+                continue;
+            }
+
+            if validator_store.replication_allows_signing() {
+                offline_epochs = 0;
+
+                let validator_store = validator_store.clone();
+                let compaction = executor.spawn_blocking_handle(
+                    move || validator_store.compact_slashing_protection_journal(),
+                    "slashing_protection_journal_compaction",
+                );
+                if let Some(compaction) = compaction {
+                    match compaction.await {
+                        Ok(Ok(())) => (),
+                        Ok(Err(e)) => {
+                            error!(log, "Slashing protection replication failed"; "error" => e)
+                        }
+                        Err(e) => {
+                            error!(log, "Slashing protection replication failed"; "error" => ?e)
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let Some(current_epoch) = slot_clock
+                .now()
+                .map(|slot| slot.epoch(E::slots_per_epoch()))
+            else {
+                continue;
+            };
+            let previous_epoch = current_epoch.saturating_sub(1u64);
+            if last_checked_epoch >= Some(previous_epoch) {
+                continue;
+            }
+
+            let validator_indices = validator_store
+                .voting_pubkeys::<Vec<_>, _>(DoppelgangerStatus::ignored)
+                .iter()
+                .filter_map(|pubkey| validator_store.validator_index(pubkey))
+                .collect::<Vec<_>>();
+            if validator_indices.is_empty() {
+                continue;
+            }
+
+            let liveness = beacon_nodes
+                .first_success(
+                    RequireSynced::Yes,
+                    OfflineOnFailure::Yes,
+                    |beacon_node| async {
+                        beacon_node
+                            .post_validator_liveness_epoch(previous_epoch, &validator_indices)
+                            .await
+                            .map_err(|e| format!("Failed query for validator liveness: {:?}", e))
+                    },
+                )
+                .await;
+            let any_live = match liveness {
+                Ok(response) => response.data.iter().any(|validator| validator.is_live),
+                Err(e) => {
+                    // Unable to tell whether the active validator client is offline.
+                    warn!(
+                        log,
+                        "Unable to check validator liveness";
+                        "error" => %e,
+                    );
+                    continue;
+                }
+            };
+            last_checked_epoch = Some(previous_epoch);
+
+            if any_live {
+                offline_epochs = 0;
+            } else {
+                offline_epochs += 1;
+                warn!(
+                    log,
+                    "Validators offline";
+                    "msg" => "the standby takes over once the limit is reached",
+                    "offline_epochs" => offline_epochs,
+                    "limit" => failover_epochs,
+                    "epoch" => previous_epoch,
+                );
+            }
+
+            if offline_epochs >= failover_epochs {
+                match validator_store.promote_standby() {
+                    Ok(()) => info!(
+                        log,
+                        "Standby validator client is now active";
+                        "offline_epochs" => offline_epochs,
+                    ),
+                    Err(e) => error!(log, "Unable to activate standby"; "error" => e),
+                }
+            }
+        }
+    };
+
+    context
+        .executor
+        .spawn(future, "slashing_protection_replication");
+}
@@ -2,8 +2,10 @@
 //!
 //! - Via a local `Keypair`.
 //! - Via a remote signer (Web3Signer)
+//! - Via a share of a `Keypair`, combined with the shares of peer validator clients.
 
 use crate::http_metrics::metrics;
+use eth2::lighthouse_vc::types::ThresholdSignableMessage;
 use eth2_keystore::Keystore;
 use lockfile::Lockfile;
 use parking_lot::Mutex;
@@ -15,8 +17,10 @@ use types::*;
 use url::Url;
 use web3signer::{ForkInfo, SigningRequest, SigningResponse};
 
+pub use threshold::{ThresholdPeer, ThresholdSigner};
 pub use web3signer::Web3SignerObject;
 
+mod threshold;
 mod web3signer;
 
 #[derive(Debug, PartialEq)]
@@ -31,6 +35,7 @@ pub enum Error {
     TokioJoin(String),
     MergeForkNotSupported,
     GenesisForkVersionRequired,
+    ThresholdSigningFailed(String),
 }
 
 /// Enumerates all messages that can be signed by a validator.
@@ -93,6 +98,14 @@ pub enum SigningMethod {
         http_client: Client,
         voting_public_key: PublicKey,
     },
+    /// A validator whose key is split between several validator clients, defined by an EIP-2335
+    /// keystore holding the local share of the key.
+    Threshold {
+        voting_keystore_path: PathBuf,
+        voting_keystore_lockfile: Mutex<Option<Lockfile>>,
+        voting_keystore: Keystore,
+        threshold_signer: ThresholdSigner,
+    },
 }
 
 /// The additional information used to construct a signature. Mostly used for protection from replay
@@ -128,6 +141,9 @@ impl SigningMethod {
             // Slashing protection is only required for remote signer keys when the configuration
             // dictates that it is desired.
             SigningMethod::Web3Signer { .. } => enable_web3signer_slashing_protection,
+            // Slashing protection is always required for threshold keys, peers sign any message
+            // requested by this validator client.
+            SigningMethod::Threshold { .. } => true,
         }
     }
 
@@ -255,6 +271,51 @@ impl SigningMethod {
 
                 Ok(response.signature)
             }
+            SigningMethod::Threshold {
+                threshold_signer, ..
+            } => {
+                let _timer =
+                    metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::THRESHOLD]);
+
+                // Map the message into a type which peers rebuild the signing root from.
+                let message = match signable_message {
+                    SignableMessage::RandaoReveal(epoch) => {
+                        ThresholdSignableMessage::RandaoReveal { epoch }
+                    }
+                    SignableMessage::BeaconBlock(block) => {
+                        ThresholdSignableMessage::BlockHeader(block.block_header())
+                    }
+                    SignableMessage::AttestationData(a) => {
+                        ThresholdSignableMessage::AttestationData(a.clone())
+                    }
+                    SignableMessage::SignedAggregateAndProof(a) => {
+                        ThresholdSignableMessage::AggregateAndProof(a.clone())
+                    }
+                    SignableMessage::SelectionProof(slot) => {
+                        ThresholdSignableMessage::SelectionProof { slot }
+                    }
+                    SignableMessage::SyncSelectionProof(s) => {
+                        ThresholdSignableMessage::SyncSelectionProof(s.clone())
+                    }
+                    SignableMessage::SyncCommitteeSignature {
+                        beacon_block_root,
+                        slot,
+                    } => ThresholdSignableMessage::SyncCommitteeMessage {
+                        beacon_block_root,
+                        slot,
+                    },
+                    SignableMessage::SignedContributionAndProof(c) => {
+                        ThresholdSignableMessage::ContributionAndProof(c.clone())
+                    }
+                    SignableMessage::ValidatorRegistration(v) => {
+                        ThresholdSignableMessage::ValidatorRegistration(v.clone())
+                    }
+                    SignableMessage::VoluntaryExit(e) => {
+                        ThresholdSignableMessage::VoluntaryExit(e.clone())
+                    }
+                };
+                threshold_signer.sign(signing_root, message, executor).await
+            }
         }
     }
 }
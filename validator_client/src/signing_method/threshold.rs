@@ -0,0 +1,153 @@
+//! Signs messages with a share of a validator key, combining the partial signatures of the peer
+//! validator clients which hold the other shares.
+//!
+//! The shares are produced by `lighthouse validator-manager split-key`. Partial signatures are
+//! requested over the authenticated HTTP API of each peer.
+
+use super::Error;
+use eth2::lighthouse_vc::{
+    http_client::ValidatorClientHttpClient,
+    types::{ThresholdSignableMessage, ThresholdSignatureRequest, ThresholdSignatureResponse},
+};
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use types::{EthSpec, Hash256, Keypair, PublicKey, Signature};
+
+/// A validator client which holds another share of the validator key.
+pub struct ThresholdPeer {
+    pub share_index: u64,
+    pub share_public_key: PublicKey,
+    pub client: ValidatorClientHttpClient,
+}
+
+/// Holds a share of a validator key along with the peers which hold the other shares.
+pub struct ThresholdSigner {
+    voting_public_key: PublicKey,
+    share_index: u64,
+    share_keypair: Arc<Keypair>,
+    threshold: usize,
+    peers: Vec<ThresholdPeer>,
+}
+
+impl ThresholdSigner {
+    pub fn new(
+        voting_public_key: PublicKey,
+        share_index: u64,
+        share_keypair: Arc<Keypair>,
+        threshold: usize,
+        peers: Vec<ThresholdPeer>,
+    ) -> Self {
+        Self {
+            voting_public_key,
+            share_index,
+            share_keypair,
+            threshold,
+            peers,
+        }
+    }
+
+    pub fn voting_public_key(&self) -> &PublicKey {
+        &self.voting_public_key
+    }
+
+    pub fn share_index(&self) -> u64 {
+        self.share_index
+    }
+
+    /// Signs `signing_root` with the local share.
+    ///
+    /// The caller is responsible for checking slashable messages against the slashing protection
+    /// database first.
+    pub async fn sign_share(
+        &self,
+        signing_root: Hash256,
+        executor: &TaskExecutor,
+    ) -> Result<Signature, Error> {
+        let share_keypair = self.share_keypair.clone();
+        executor
+            .spawn_blocking_handle(
+                move || share_keypair.sk.sign(signing_root),
+                "threshold_share_signer",
+            )
+            .ok_or(Error::ShuttingDown)?
+            .await
+            .map_err(|e| Error::TokioJoin(e.to_string()))
+    }
+
+    /// Returns the signature of the validator for `signing_root`, combined from the signature of
+    /// the local share and the partial signatures of at least `threshold - 1` peers.
+    ///
+    /// The `message` is sent along with `signing_root`, so that each peer rebuilds the signing root
+    /// and checks slashable messages against its own slashing protection database. Since every
+    /// validator client of the group also signs on its own, this prevents the shares of
+    /// conflicting messages from being combined.
+    pub async fn sign<E: EthSpec>(
+        &self,
+        signing_root: Hash256,
+        message: ThresholdSignableMessage<E>,
+        executor: &TaskExecutor,
+    ) -> Result<Signature, Error> {
+        let local_signature = self.sign_share(signing_root, executor).await?;
+        let mut signatures = vec![(self.share_index, local_signature)];
+
+        let voting_pubkey = self.voting_public_key.compress();
+        let request = ThresholdSignatureRequest {
+            signing_root,
+            message,
+        };
+        let mut responses = self
+            .peers
+            .iter()
+            .map(|peer| async {
+                let result = peer
+                    .client
+                    .post_lighthouse_validators_threshold_signature(&voting_pubkey, &request)
+                    .await;
+                (peer, result)
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        // Stop as soon as enough partial signatures are known, there is no need to wait for
+        // slower peers.
+        let mut errors = vec![];
+        while signatures.len() < self.threshold {
+            let Some((peer, result)) = responses.next().await else {
+                break;
+            };
+            match result.map(|response| response.data) {
+                Ok(ThresholdSignatureResponse {
+                    share_index,
+                    signature,
+                }) if share_index == peer.share_index
+                    && signature.verify(&peer.share_public_key, signing_root) =>
+                {
+                    signatures.push((share_index, signature));
+                }
+                Ok(ThresholdSignatureResponse { share_index, .. }) => errors.push(format!(
+                    "invalid partial signature from share {} (responded as share {})",
+                    peer.share_index, share_index
+                )),
+                Err(e) => errors.push(format!("share {}: {:?}", peer.share_index, e)),
+            }
+        }
+
+        if signatures.len() < self.threshold {
+            return Err(Error::ThresholdSigningFailed(format!(
+                "received {} of {} required signatures: {}",
+                signatures.len(),
+                self.threshold,
+                errors.join(", ")
+            )));
+        }
+
+        let signature = bls::threshold::combine_signatures(&signatures)
+            .map_err(|e| Error::ThresholdSigningFailed(format!("{:?}", e)))?;
+        if !signature.verify(&self.voting_public_key, signing_root) {
+            return Err(Error::ThresholdSigningFailed(
+                "combined signature is invalid".to_string(),
+            ));
+        }
+        Ok(signature)
+    }
+}
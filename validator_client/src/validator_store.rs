@@ -1,11 +1,15 @@
 use crate::{
     doppelganger_service::DoppelgangerService,
+    duty_history::DutyHistory,
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
+    replication::Replication,
     signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
     Config,
 };
 use account_utils::validator_definitions::{PasswordStorage, ValidatorDefinition};
+use eth2::lighthouse_vc::types::ThresholdSignableMessage;
+use eth2::types::ExecutionClientVersion;
 use parking_lot::{Mutex, RwLock};
 use slashing_protection::{
     interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
@@ -40,6 +44,10 @@ pub enum Error {
     GreaterThanCurrentEpoch { epoch: Epoch, current_epoch: Epoch },
     UnableToSignAttestation(AttestationError),
     UnableToSign(SigningError),
+    NotThresholdValidator(PublicKeyBytes),
+    ThresholdSigningRootMismatch(Hash256),
+    Standby,
+    UnableToReplicate(String),
 }
 
 impl From<SigningError> for Error {
@@ -66,6 +74,8 @@ pub struct ValidatorStore<T, E: EthSpec> {
     spec: Arc<ChainSpec>,
     log: Logger,
     doppelganger_service: Option<Arc<DoppelgangerService>>,
+    replication: Option<Arc<Replication>>,
+    duty_history: DutyHistory,
     slot_clock: T,
     fee_recipient_process: Option<Address>,
     gas_limit: Option<u64>,
@@ -88,6 +98,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         genesis_validators_root: Hash256,
         spec: ChainSpec,
         doppelganger_service: Option<Arc<DoppelgangerService>>,
+        replication: Option<Arc<Replication>>,
         slot_clock: T,
         config: &Config,
         task_executor: TaskExecutor,
@@ -101,6 +112,8 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             spec: Arc::new(spec),
             log,
             doppelganger_service,
+            replication,
+            duty_history: DutyHistory::new::<E>(config.duty_history_epochs),
             slot_clock,
             fee_recipient_process: config.fee_recipient,
             gas_limit: config.gas_limit,
@@ -137,6 +150,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.clone()
     }
 
+    /// The recent duties of the validators, see `DutyHistory`.
+    pub fn duty_history(&self) -> &DutyHistory {
+        &self.duty_history
+    }
+
     /// Indicates if the `voting_public_key` exists in self and is enabled.
     pub fn has_validator(&self, voting_public_key: &PublicKeyBytes) -> bool {
         self.validators
@@ -270,6 +288,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                     // Allow signing on all pubkeys if doppelganger protection is disabled.
                     .unwrap_or_else(|| DoppelgangerStatus::SigningEnabled(pubkey))
             })
+            .map(|status| match status {
+                // A standby must not sign, but collects duties so it is ready to take over.
+                DoppelgangerStatus::SigningEnabled(pubkey)
+                    if !self.replication_allows_signing() =>
+                {
+                    DoppelgangerStatus::SigningDisabled(pubkey)
+                }
+                status => status,
+            })
             .filter_map(filter_func)
             .collect()
     }
@@ -298,19 +325,56 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .collect()
     }
 
-    /// Check if the `validator_pubkey` is permitted by the doppleganger protection to sign
-    /// messages.
+    /// Check if the `validator_pubkey` is permitted by the doppleganger protection (and slashing
+    /// protection replication) to sign messages.
     pub fn doppelganger_protection_allows_signing(&self, validator_pubkey: PublicKeyBytes) -> bool {
-        self.doppelganger_service
+        self.replication_allows_signing()
+            && self
+                .doppelganger_service
+                .as_ref()
+                // If there's no doppelganger service then we assume it is purposefully disabled and
+                // declare that all keys are safe with regard to it.
+                .map_or(true, |doppelganger_service| {
+                    doppelganger_service
+                        .validator_status(validator_pubkey)
+                        .only_safe()
+                        .is_some()
+                })
+    }
+
+    /// Returns `false` if this validator client is a slashing protection replication standby.
+    pub fn replication_allows_signing(&self) -> bool {
+        self.replication
             .as_ref()
-            // If there's no doppelganger service then we assume it is purposefully disabled and
-            // declare that all keys are safe with regard to it.
-            .map_or(true, |doppelganger_service| {
-                doppelganger_service
-                    .validator_status(validator_pubkey)
-                    .only_safe()
-                    .is_some()
-            })
+            .map_or(true, |replication| replication.is_active())
+    }
+
+    /// Import the slashing protection journal entries written by other validator clients.
+    ///
+    /// Has no effect if slashing protection replication is disabled.
+    pub fn import_slashing_protection_journal(&self) -> Result<(), String> {
+        self.replication.as_ref().map_or(Ok(()), |replication| {
+            replication.import_new_entries(&self.slashing_protection)
+        })
+    }
+
+    /// Compact the slashing protection journal if it has grown too large.
+    ///
+    /// Has no effect if slashing protection replication is disabled or this validator client is
+    /// a standby.
+    pub fn compact_slashing_protection_journal(&self) -> Result<(), String> {
+        self.replication
+            .as_ref()
+            .map_or(Ok(()), |replication| replication.compact_journal())
+    }
+
+    /// Import the remainder of the slashing protection journal and start signing.
+    ///
+    /// Has no effect if slashing protection replication is disabled.
+    pub fn promote_standby(&self) -> Result<(), String> {
+        self.replication.as_ref().map_or(Ok(()), |replication| {
+            replication.promote(&self.slashing_protection)
+        })
     }
 
     pub fn num_voting_validators(&self) -> usize {
@@ -331,6 +395,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         &self,
         validator_pubkey: PublicKeyBytes,
     ) -> Result<Arc<SigningMethod>, Error> {
+        if !self.replication_allows_signing() {
+            return Err(Error::Standby);
+        }
         if self.doppelganger_protection_allows_signing(validator_pubkey) {
             self.validators
                 .read()
@@ -410,8 +477,22 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(signature)
     }
 
-    pub fn graffiti(&self, validator_pubkey: &PublicKeyBytes) -> Option<Graffiti> {
-        self.validators.read().graffiti(validator_pubkey)
+    /// Returns the graffiti of the validator, resolving a graffiti template with
+    /// `execution_client`.
+    pub fn graffiti(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        execution_client: Option<&ExecutionClientVersion>,
+    ) -> Option<Graffiti> {
+        self.validators
+            .read()
+            .graffiti(validator_pubkey, execution_client)
+    }
+
+    pub fn has_graffiti_template(&self, validator_pubkey: &PublicKeyBytes) -> bool {
+        self.validators
+            .read()
+            .has_graffiti_template(validator_pubkey)
     }
 
     /// Returns the fee recipient for the given public key. The priority order for fetching
@@ -582,9 +663,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let signing_method = self.doppelganger_checked_signing_method(validator_pubkey)?;
 
         // Check for slashing conditions.
-        let slashing_status = if signing_method
-            .requires_local_slashing_protection(self.enable_web3signer_slashing_protection)
-        {
+        let local_slashing_protection = signing_method
+            .requires_local_slashing_protection(self.enable_web3signer_slashing_protection);
+        let slashing_status = if local_slashing_protection {
             self.slashing_protection.check_and_insert_block_proposal(
                 &validator_pubkey,
                 &block.block_header(),
@@ -597,6 +678,19 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         match slashing_status {
             // We can safely sign this block without slashing.
             Ok(Safe::Valid) => {
+                // Replicate the block before signing it, so that a standby never signs a
+                // conflicting block.
+                if let (Some(replication), true) = (&self.replication, local_slashing_protection) {
+                    replication
+                        .record_block(
+                            validator_pubkey,
+                            block.slot(),
+                            block.block_header().signing_root(domain_hash),
+                        )
+                        .await
+                        .map_err(Error::UnableToReplicate)?;
+                }
+
                 metrics::inc_counter_vec(&metrics::SIGNED_BLOCKS_TOTAL, &[metrics::SUCCESS]);
 
                 let signature = signing_method
@@ -661,9 +755,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let signing_epoch = attestation.data.target.epoch;
         let signing_context = self.signing_context(Domain::BeaconAttester, signing_epoch);
         let domain_hash = signing_context.domain_hash(&self.spec);
-        let slashing_status = if signing_method
-            .requires_local_slashing_protection(self.enable_web3signer_slashing_protection)
-        {
+        let local_slashing_protection = signing_method
+            .requires_local_slashing_protection(self.enable_web3signer_slashing_protection);
+        let slashing_status = if local_slashing_protection {
             self.slashing_protection.check_and_insert_attestation(
                 &validator_pubkey,
                 &attestation.data,
@@ -676,6 +770,20 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         match slashing_status {
             // We can safely sign this attestation.
             Ok(Safe::Valid) => {
+                // Replicate the attestation before signing it, so that a standby never signs a
+                // conflicting attestation.
+                if let (Some(replication), true) = (&self.replication, local_slashing_protection) {
+                    replication
+                        .record_attestation(
+                            validator_pubkey,
+                            attestation.data.source.epoch,
+                            attestation.data.target.epoch,
+                            attestation.data.signing_root(domain_hash),
+                        )
+                        .await
+                        .map_err(Error::UnableToReplicate)?;
+                }
+
                 let signature = signing_method
                     .get_signature::<E, BlindedPayload<E>>(
                         SignableMessage::AttestationData(&attestation.data),
@@ -732,6 +840,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         }
     }
 
+    /// Returns the domain with which a voluntary exit at `epoch` is signed.
+    ///
+    /// Since EIP-7044 this is the Capella domain for all later forks, so it only changes at forks
+    /// up to and including Capella.
+    pub fn voluntary_exit_domain(&self, epoch: Epoch) -> Hash256 {
+        self.signing_context(Domain::VoluntaryExit, epoch)
+            .domain_hash(&self.spec)
+    }
+
     pub async fn sign_voluntary_exit(
         &self,
         validator_pubkey: PublicKeyBytes,
@@ -758,6 +875,144 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         })
     }
 
+    /// Returns the signing root of a `message` requested by a threshold peer, with the domain this
+    /// validator client would sign it with.
+    fn threshold_message_signing_root(&self, message: &ThresholdSignableMessage<E>) -> Hash256 {
+        let slots_per_epoch = E::slots_per_epoch();
+        let domain_hash =
+            |domain, epoch| self.signing_context(domain, epoch).domain_hash(&self.spec);
+        match message {
+            ThresholdSignableMessage::RandaoReveal { epoch } => {
+                epoch.signing_root(domain_hash(Domain::Randao, *epoch))
+            }
+            ThresholdSignableMessage::BlockHeader(header) => header.signing_root(domain_hash(
+                Domain::BeaconProposer,
+                header.slot.epoch(slots_per_epoch),
+            )),
+            ThresholdSignableMessage::AttestationData(data) => {
+                data.signing_root(domain_hash(Domain::BeaconAttester, data.target.epoch))
+            }
+            ThresholdSignableMessage::AggregateAndProof(aggregate) => {
+                aggregate.signing_root(domain_hash(
+                    Domain::AggregateAndProof,
+                    aggregate.aggregate.data.slot.epoch(slots_per_epoch),
+                ))
+            }
+            ThresholdSignableMessage::SelectionProof { slot } => slot.signing_root(domain_hash(
+                Domain::SelectionProof,
+                slot.epoch(slots_per_epoch),
+            )),
+            ThresholdSignableMessage::SyncSelectionProof(data) => data.signing_root(domain_hash(
+                Domain::SyncCommitteeSelectionProof,
+                data.slot.epoch(slots_per_epoch),
+            )),
+            ThresholdSignableMessage::SyncCommitteeMessage {
+                beacon_block_root,
+                slot,
+            } => beacon_block_root.signing_root(domain_hash(
+                Domain::SyncCommittee,
+                slot.epoch(slots_per_epoch),
+            )),
+            ThresholdSignableMessage::ContributionAndProof(contribution) => contribution
+                .signing_root(domain_hash(
+                    Domain::ContributionAndProof,
+                    contribution.contribution.slot.epoch(slots_per_epoch),
+                )),
+            ThresholdSignableMessage::ValidatorRegistration(data) => {
+                data.signing_root(self.spec.get_builder_domain())
+            }
+            ThresholdSignableMessage::VoluntaryExit(exit) => {
+                exit.signing_root(domain_hash(Domain::VoluntaryExit, exit.epoch))
+            }
+        }
+    }
+
+    /// Signs `signing_root` with the local share of a threshold validator, on behalf of a peer
+    /// validator client which combines the partial signatures.
+    ///
+    /// The signing root of `message` is rebuilt with the domain of this validator client, and
+    /// must equal `signing_root`. Slashable messages are checked against, and recorded in, the
+    /// slashing protection database as if they were signed by this validator client. A message
+    /// which was already signed here is signed again, since a peer combining the same message is
+    /// harmless.
+    pub async fn sign_threshold_share(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+        signing_root: Hash256,
+        message: ThresholdSignableMessage<E>,
+    ) -> Result<(u64, Signature), Error> {
+        let signing_method = self.doppelganger_checked_signing_method(validator_pubkey)?;
+        let SigningMethod::Threshold {
+            threshold_signer, ..
+        } = signing_method.as_ref()
+        else {
+            return Err(Error::NotThresholdValidator(validator_pubkey));
+        };
+
+        if self.threshold_message_signing_root(&message) != signing_root {
+            return Err(Error::ThresholdSigningRootMismatch(signing_root));
+        }
+
+        let slashing_status = match &message {
+            ThresholdSignableMessage::BlockHeader(header) => {
+                let signing_epoch = header.slot.epoch(E::slots_per_epoch());
+                let domain_hash = self
+                    .signing_context(Domain::BeaconProposer, signing_epoch)
+                    .domain_hash(&self.spec);
+                let status = self.slashing_protection.check_and_insert_block_proposal(
+                    &validator_pubkey,
+                    header,
+                    domain_hash,
+                );
+                if let (Some(replication), Ok(Safe::Valid)) = (&self.replication, &status) {
+                    replication
+                        .record_block(validator_pubkey, header.slot, signing_root)
+                        .await
+                        .map_err(Error::UnableToReplicate)?;
+                }
+                Some(status)
+            }
+            ThresholdSignableMessage::AttestationData(data) => {
+                let domain_hash = self
+                    .signing_context(Domain::BeaconAttester, data.target.epoch)
+                    .domain_hash(&self.spec);
+                let status = self.slashing_protection.check_and_insert_attestation(
+                    &validator_pubkey,
+                    data,
+                    domain_hash,
+                );
+                if let (Some(replication), Ok(Safe::Valid)) = (&self.replication, &status) {
+                    replication
+                        .record_attestation(
+                            validator_pubkey,
+                            data.source.epoch,
+                            data.target.epoch,
+                            signing_root,
+                        )
+                        .await
+                        .map_err(Error::UnableToReplicate)?;
+                }
+                Some(status)
+            }
+            _ => None,
+        };
+
+        if let Some(Err(e)) = slashing_status {
+            crit!(
+                self.log,
+                "Not signing slashable threshold share";
+                "message" => ?message,
+                "error" => ?e
+            );
+            return Err(Error::Slashable(e));
+        }
+
+        let signature = threshold_signer
+            .sign_share(signing_root, &self.task_executor)
+            .await?;
+        Ok((threshold_signer.share_index(), signature))
+    }
+
     pub async fn sign_validator_registration_data(
         &self,
         validator_registration_data: ValidatorRegistrationData,
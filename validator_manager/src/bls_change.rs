@@ -0,0 +1,541 @@
+use super::common::*;
+use crate::DumpConfig;
+use account_utils::read_mnemonic_from_cli;
+use clap::{App, Arg, ArgMatches};
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
+};
+use eth2_key_derivation::DerivedKey;
+use eth2_keystore::keypair_from_secret;
+use eth2_wallet::{bip39::Seed, COIN_TYPE, PURPOSE};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::*;
+
+pub const CMD: &str = "bls-change";
+pub const VALIDATOR_INDICES_FLAG: &str = "validator-indices";
+pub const EXECUTION_ADDRESS_FLAG: &str = "execution-address";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
+pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const STATE_PATH_FLAG: &str = "state-path";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+pub const SUBMIT_FLAG: &str = "submit";
+
+const BEACON_NODE_HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Creates signed messages which change the withdrawal credentials of validators \
+                from BLS (0x00) credentials to an execution address. The withdrawal keys are \
+                derived from a BIP-39 mnemonic. The current withdrawal credentials of the \
+                validators are read from a beacon node or from a beacon state file. The signed \
+                messages can be written to a JSON file and/or submitted to the beacon node. \
+                A withdrawal credentials change cannot be reversed.",
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_INDICES_FLAG)
+                .long(VALIDATOR_INDICES_FLAG)
+                .value_name("INDICES")
+                .help("A comma-separated list of the indices of the validators to change.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXECUTION_ADDRESS_FLAG)
+                .long(EXECUTION_ADDRESS_FLAG)
+                .value_name("ETH1_ADDRESS")
+                .help(
+                    "The execution address which will receive the withdrawals of all the \
+                    validators. Make sure you control this address, it cannot be changed \
+                    afterwards.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help("The first of the consecutive key indexes searched for withdrawal keys.")
+                .takes_value(true)
+                .default_value("0"),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("KEY_COUNT")
+                .help(
+                    "The number of consecutive key indexes searched for withdrawal keys. The \
+                    search stops as soon as the keys of all validators are found.",
+                )
+                .takes_value(true)
+                .default_value("1024"),
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("If present, the mnemonic will be read in from this file.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+        .arg(
+            Arg::with_name(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a beacon node using the beacon-API. The withdrawal \
+                    credentials of the validators are read from its head state.",
+                )
+                .required_unless(STATE_PATH_FLAG)
+                .conflicts_with(STATE_PATH_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STATE_PATH_FLAG)
+                .long(STATE_PATH_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The path to an SSZ encoded beacon state from which the withdrawal \
+                    credentials of the validators are read, for use on an offline machine.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("FILE")
+                .help(
+                    "The path of a JSON file to write the signed changes to. The file must not \
+                    exist. It can be submitted to a beacon node at \
+                    /eth/v1/beacon/pool/bls_to_execution_changes.",
+                )
+                .required_unless(SUBMIT_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SUBMIT_FLAG)
+                .long(SUBMIT_FLAG)
+                .help(
+                    "If present, the signed changes are submitted to the pool of the beacon \
+                    node given by --beacon-node.",
+                )
+                .requires(BEACON_NODE_FLAG)
+                .takes_value(false),
+        )
+}
+
+/// The CLI arguments are parsed into this struct before running the application. This step of
+/// indirection allows for testing the underlying logic without needing to parse CLI arguments.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BlsChangeConfig {
+    pub validator_indices: Vec<u64>,
+    pub execution_address: Address,
+    pub first_index: u32,
+    pub count: u32,
+    pub mnemonic_path: Option<PathBuf>,
+    pub stdin_inputs: bool,
+    pub bn_url: Option<SensitiveUrl>,
+    pub state_path: Option<PathBuf>,
+    pub output_path: Option<PathBuf>,
+    pub submit: bool,
+}
+
+impl BlsChangeConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validator_indices = matches
+            .value_of(VALIDATOR_INDICES_FLAG)
+            .ok_or_else(|| format!("--{} is required", VALIDATOR_INDICES_FLAG))?
+            .split(',')
+            .map(|index| {
+                index
+                    .trim()
+                    .parse()
+                    .map_err(|e| format!("Invalid validator index {:?}: {:?}", index, e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            validator_indices,
+            execution_address: clap_utils::parse_required(matches, EXECUTION_ADDRESS_FLAG)?,
+            first_index: clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?,
+            count: clap_utils::parse_required(matches, COUNT_FLAG)?,
+            mnemonic_path: clap_utils::parse_optional(matches, MNEMONIC_FLAG)?,
+            stdin_inputs: cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG),
+            bn_url: clap_utils::parse_optional(matches, BEACON_NODE_FLAG)?,
+            state_path: clap_utils::parse_optional(matches, STATE_PATH_FLAG)?,
+            output_path: clap_utils::parse_optional(matches, OUTPUT_PATH_FLAG)?,
+            submit: matches.is_present(SUBMIT_FLAG),
+        })
+    }
+}
+
+pub async fn cli_run<'a, T: EthSpec>(
+    matches: &'a ArgMatches<'a>,
+    spec: &ChainSpec,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = BlsChangeConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run::<T>(config, spec).await
+    }
+}
+
+/// The validators to be changed, as found in a beacon state.
+struct ChainValidators {
+    genesis_validators_root: Hash256,
+    /// The current withdrawal credentials of each validator, in the order of the requested
+    /// indices.
+    withdrawal_credentials: Vec<(u64, Hash256)>,
+}
+
+impl ChainValidators {
+    async fn from_beacon_node(
+        client: &BeaconNodeHttpClient,
+        validator_indices: &[u64],
+        spec: &ChainSpec,
+    ) -> Result<Self, String> {
+        let genesis = client
+            .get_beacon_genesis()
+            .await
+            .map_err(|e| format!("Failed to get genesis from beacon node: {:?}", e))?
+            .data;
+        if genesis.genesis_fork_version != spec.genesis_fork_version {
+            return Err("Beacon node appears to be on the wrong network".to_string());
+        }
+
+        let mut withdrawal_credentials = Vec::with_capacity(validator_indices.len());
+        for &index in validator_indices {
+            let validator = client
+                .get_beacon_states_validator_id(StateId::Head, &ValidatorId::Index(index))
+                .await
+                .map_err(|e| format!("Failed to get validator {}: {:?}", index, e))?
+                .ok_or_else(|| format!("Validator {} is unknown to the beacon node", index))?
+                .data
+                .validator;
+            withdrawal_credentials.push((index, validator.withdrawal_credentials));
+        }
+
+        Ok(Self {
+            genesis_validators_root: genesis.genesis_validators_root,
+            withdrawal_credentials,
+        })
+    }
+
+    fn from_state_file<T: EthSpec>(
+        path: &Path,
+        validator_indices: &[u64],
+        spec: &ChainSpec,
+    ) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+        let state = BeaconState::<T>::from_ssz_bytes(&bytes, spec)
+            .map_err(|e| format!("Unable to decode beacon state from {:?}: {:?}", path, e))?;
+
+        let withdrawal_credentials = validator_indices
+            .iter()
+            .map(|&index| {
+                state
+                    .validators()
+                    .get(index as usize)
+                    .map(|validator| (index, validator.withdrawal_credentials))
+                    .ok_or_else(|| format!("Validator {} is not in the beacon state", index))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            genesis_validators_root: state.genesis_validators_root(),
+            withdrawal_credentials,
+        })
+    }
+}
+
+/// Derives the withdrawal keys at `first_index..first_index + count` from `seed` and signs a
+/// change to `execution_address` for each of the `withdrawal_credentials`.
+///
+/// Returns an error if any of the validators does not have BLS withdrawal credentials or if its
+/// withdrawal key is not found.
+fn sign_changes(
+    seed: &[u8],
+    first_index: u32,
+    count: u32,
+    withdrawal_credentials: &[(u64, Hash256)],
+    execution_address: Address,
+    genesis_validators_root: Hash256,
+    spec: &ChainSpec,
+) -> Result<Vec<SignedBlsToExecutionChange>, String> {
+    for (validator_index, credentials) in withdrawal_credentials {
+        if credentials.as_bytes()[0] != spec.bls_withdrawal_prefix_byte {
+            return Err(format!(
+                "Validator {} does not have BLS withdrawal credentials: {:?}",
+                validator_index, credentials
+            ));
+        }
+    }
+
+    // Withdrawal keys are derived at m/12381/3600/{index}/0 as per EIP-2334.
+    let coin_type_key = DerivedKey::from_seed(seed)
+        .map_err(|e| format!("Unable to derive keys from mnemonic: {:?}", e))?
+        .child(PURPOSE)
+        .child(COIN_TYPE);
+
+    let mut keypairs = HashMap::with_capacity(withdrawal_credentials.len());
+    for derivation_index in first_index..first_index.saturating_add(count) {
+        if withdrawal_credentials
+            .iter()
+            .all(|(_, credentials)| keypairs.contains_key(credentials))
+        {
+            break;
+        }
+
+        let withdrawal_key = coin_type_key.child(derivation_index).child(0);
+        let keypair = keypair_from_secret(withdrawal_key.secret()).map_err(|e| {
+            format!(
+                "Unable to derive withdrawal key at index {}: {:?}",
+                derivation_index, e
+            )
+        })?;
+        let credentials: Hash256 = WithdrawalCredentials::bls(&keypair.pk, spec).into();
+        if withdrawal_credentials
+            .iter()
+            .any(|(_, expected)| *expected == credentials)
+        {
+            eprintln!(
+                "Found withdrawal key at derivation index {}",
+                derivation_index
+            );
+            keypairs.insert(credentials, keypair);
+        }
+    }
+
+    withdrawal_credentials
+        .iter()
+        .map(|(validator_index, credentials)| {
+            let keypair = keypairs.get(credentials).ok_or_else(|| {
+                format!(
+                    "The withdrawal key of validator {} was not found in the {} keys \
+                    starting at index {}. Check the mnemonic or try a larger --{}.",
+                    validator_index, count, first_index, COUNT_FLAG
+                )
+            })?;
+            let change = BlsToExecutionChange {
+                validator_index: *validator_index,
+                from_bls_pubkey: keypair.pk.compress(),
+                to_execution_address: execution_address,
+            };
+            Ok(change.sign(&keypair.sk, genesis_validators_root, spec))
+        })
+        .collect()
+}
+
+async fn run<'a, T: EthSpec>(config: BlsChangeConfig, spec: &ChainSpec) -> Result<(), String> {
+    let BlsChangeConfig {
+        validator_indices,
+        execution_address,
+        first_index,
+        count,
+        mnemonic_path,
+        stdin_inputs,
+        bn_url,
+        state_path,
+        output_path,
+        submit,
+    } = config;
+
+    if validator_indices.is_empty() {
+        return Err(format!("--{} cannot be empty", VALIDATOR_INDICES_FLAG));
+    }
+    if let Some(output_path) = &output_path {
+        if output_path.exists() {
+            return Err(format!(
+                "{:?} already exists, refusing to overwrite",
+                output_path
+            ));
+        }
+    }
+
+    let bn_http_client = bn_url.map(|bn_url| {
+        BeaconNodeHttpClient::new(bn_url, Timeouts::set_all(BEACON_NODE_HTTP_TIMEOUT))
+    });
+
+    let chain_validators = match (&bn_http_client, &state_path) {
+        (Some(bn_http_client), _) => {
+            ChainValidators::from_beacon_node(bn_http_client, &validator_indices, spec).await?
+        }
+        (None, Some(state_path)) => {
+            ChainValidators::from_state_file::<T>(state_path, &validator_indices, spec)?
+        }
+        (None, None) => {
+            return Err(format!(
+                "Either --{} or --{} is required",
+                BEACON_NODE_FLAG, STATE_PATH_FLAG
+            ))
+        }
+    };
+
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+
+    let changes = sign_changes(
+        seed.as_bytes(),
+        first_index,
+        count,
+        &chain_validators.withdrawal_credentials,
+        execution_address,
+        chain_validators.genesis_validators_root,
+        spec,
+    )?;
+
+    eprintln!(
+        "Signed {} changes to execution address {:?}",
+        changes.len(),
+        execution_address
+    );
+
+    if let Some(output_path) = &output_path {
+        write_to_json_file(output_path, &changes)?;
+        eprintln!("Wrote signed changes to {:?}", output_path);
+    }
+
+    if submit {
+        let bn_http_client = bn_http_client
+            .ok_or_else(|| format!("--{} requires --{}", SUBMIT_FLAG, BEACON_NODE_FLAG))?;
+        bn_http_client
+            .post_beacon_pool_bls_to_execution_changes(&changes)
+            .await
+            .map_err(|e| format!("Failed to submit changes to beacon node: {:?}", e))?;
+        eprintln!("Submitted {} changes to the beacon node", changes.len());
+    }
+
+    Ok(())
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use account_utils::random_password_string;
+    use eth2_wallet::{bip39::Language, bip39::Mnemonic, WalletBuilder};
+    use std::str::FromStr;
+
+    type E = MainnetEthSpec;
+
+    const MNEMONIC: &str = "test test test test test test test test test test test waste";
+
+    fn seed() -> Seed {
+        let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English).unwrap();
+        Seed::new(&mnemonic, "")
+    }
+
+    /// Returns the BLS withdrawal credentials of the validator at `derivation_index`, as they are
+    /// created by the `create` command.
+    fn wallet_withdrawal_credentials(derivation_index: u32, spec: &ChainSpec) -> Hash256 {
+        let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English).unwrap();
+        let password = random_password_string();
+        let mut wallet = WalletBuilder::from_mnemonic(&mnemonic, password.as_ref(), "".into())
+            .unwrap()
+            .build()
+            .unwrap();
+        wallet.set_nextaccount(derivation_index).unwrap();
+        let keystores = wallet
+            .next_validator(password.as_ref(), password.as_ref(), password.as_ref())
+            .unwrap();
+        let withdrawal_keypair = keystores
+            .withdrawal
+            .decrypt_keypair(password.as_ref())
+            .unwrap();
+        WithdrawalCredentials::bls(&withdrawal_keypair.pk, spec).into()
+    }
+
+    #[test]
+    fn signs_changes_for_derived_keys() {
+        let spec = E::default_spec();
+        let address = Address::from_str("0x0f51bb10119727a7e5ea3538074fb341f56b09ad").unwrap();
+        let genesis_validators_root = Hash256::repeat_byte(42);
+        let credentials = vec![
+            (7, wallet_withdrawal_credentials(3, &spec)),
+            (5, wallet_withdrawal_credentials(1, &spec)),
+        ];
+
+        let changes = sign_changes(
+            seed().as_bytes(),
+            0,
+            4,
+            &credentials,
+            address,
+            genesis_validators_root,
+            &spec,
+        )
+        .unwrap();
+
+        assert_eq!(changes.len(), 2);
+        let domain = spec.compute_domain(
+            Domain::BlsToExecutionChange,
+            spec.genesis_fork_version,
+            genesis_validators_root,
+        );
+        for (change, (validator_index, credentials)) in changes.iter().zip(&credentials) {
+            let pubkey = change.message.from_bls_pubkey.decompress().unwrap();
+            assert_eq!(change.message.validator_index, *validator_index);
+            assert_eq!(change.message.to_execution_address, address);
+            assert_eq!(
+                Hash256::from(WithdrawalCredentials::bls(&pubkey, &spec)),
+                *credentials
+            );
+            assert!(change
+                .signature
+                .verify(&pubkey, change.message.signing_root(domain)));
+        }
+    }
+
+    #[test]
+    fn missing_withdrawal_key() {
+        let spec = E::default_spec();
+        let credentials = vec![(0, wallet_withdrawal_credentials(3, &spec))];
+
+        assert!(sign_changes(
+            seed().as_bytes(),
+            0,
+            3,
+            &credentials,
+            Address::zero(),
+            Hash256::zero(),
+            &spec,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn execution_credentials_are_rejected() {
+        let spec = E::default_spec();
+        let credentials = vec![(
+            0,
+            WithdrawalCredentials::eth1(Address::zero(), &spec).into(),
+        )];
+
+        assert!(sign_changes(
+            seed().as_bytes(),
+            0,
+            1,
+            &credentials,
+            Address::zero(),
+            Hash256::zero(),
+            &spec,
+        )
+        .is_err());
+    }
+}
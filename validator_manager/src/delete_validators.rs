@@ -0,0 +1,246 @@
+use super::common::*;
+use crate::DumpConfig;
+use clap::{App, Arg, ArgMatches};
+use eth2::{
+    lighthouse_vc::std_types::{DeleteKeystoreStatus, DeleteKeystoresRequest},
+    SensitiveUrl,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+use types::PublicKeyBytes;
+
+pub const CMD: &str = "delete";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const SLASHING_PROTECTION_PATH_FLAG: &str = "slashing-protection-path";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Deletes validator keystores from a validator client using the keymanager-API. \
+                The slashing protection data of the deleted validators returned by the \
+                validator client is always saved to a file. Keep this file, it is required to \
+                safely run the validators again on any validator client.",
+        )
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a validator client using the keymanager-API.")
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "A comma-separated list of the 0x-prefixed pubkeys of the validators to \
+                    delete.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SLASHING_PROTECTION_PATH_FLAG)
+                .long(SLASHING_PROTECTION_PATH_FLAG)
+                .value_name("PATH_TO_JSON_FILE")
+                .help(
+                    "The path of a file to write the EIP-3076 slashing protection interchange \
+                    of the deleted validators to. The file must not exist.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DeleteConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub validators: Vec<PublicKeyBytes>,
+    pub slashing_protection_path: PathBuf,
+}
+
+impl DeleteConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validators = matches
+            .value_of(VALIDATORS_FLAG)
+            .ok_or_else(|| format!("--{} is required", VALIDATORS_FLAG))?
+            .split(',')
+            .map(PublicKeyBytes::from_str)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            validators,
+            slashing_protection_path: clap_utils::parse_required(
+                matches,
+                SLASHING_PROTECTION_PATH_FLAG,
+            )?,
+        })
+    }
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = DeleteConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run<'a>(config: DeleteConfig) -> Result<(), String> {
+    let DeleteConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        slashing_protection_path,
+    } = config;
+
+    if validators.is_empty() {
+        return Err(format!("--{} cannot be empty", VALIDATORS_FLAG));
+    }
+    // Check the file before deleting anything, the slashing protection data is only returned once.
+    if slashing_protection_path.exists() {
+        return Err(format!(
+            "{:?} already exists, refusing to overwrite",
+            slashing_protection_path
+        ));
+    }
+
+    let (http_client, _keystores) = vc_http_client(vc_url, &vc_token_path).await?;
+
+    let request = DeleteKeystoresRequest {
+        pubkeys: validators.clone(),
+    };
+    let response = http_client
+        .delete_keystores(&request)
+        .await
+        .map_err(|e| format!("Failed to delete keystores: {:?}", e))?;
+
+    write_to_json_file(&slashing_protection_path, &response.slashing_protection)?;
+    eprintln!(
+        "Saved slashing protection data for {} validators to {:?}",
+        response.slashing_protection.data.len(),
+        slashing_protection_path
+    );
+
+    if response.data.len() != validators.len() {
+        return Err(format!(
+            "Validator client returned {} statuses for {} validators",
+            response.data.len(),
+            validators.len()
+        ));
+    }
+
+    let mut failures = 0;
+    for (pubkey, status) in validators.iter().zip(&response.data) {
+        match status.status {
+            DeleteKeystoreStatus::Deleted => eprintln!("Deleted {:?}", pubkey),
+            DeleteKeystoreStatus::NotActive => eprintln!(
+                "{:?} was not active, its slashing protection data was saved",
+                pubkey
+            ),
+            DeleteKeystoreStatus::NotFound => {
+                failures += 1;
+                eprintln!("{:?} was not found on the validator client", pubkey);
+            }
+            DeleteKeystoreStatus::Error => {
+                failures += 1;
+                eprintln!(
+                    "Failed to delete {:?}: {}",
+                    pubkey,
+                    status.message.as_deref().unwrap_or("unknown error")
+                );
+            }
+        }
+    }
+
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to delete {} of {} validators",
+            failures,
+            validators.len()
+        ))
+    }
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
+    use eth2::lighthouse_vc::std_types::Interchange;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn deletes_validators_and_saves_slashing_protection() {
+        let vc = ImportTestBuilder::new()
+            .await
+            .create_validators(3, 0)
+            .await
+            .import_validators_without_checks()
+            .await
+            .vc;
+        let dir = tempdir().unwrap();
+        let vc_token_path = dir.path().join("vc_token.json");
+        fs::write(&vc_token_path, &vc.api_token).unwrap();
+        let slashing_protection_path = dir.path().join("slashing_protection.json");
+
+        let keystores = vc.client.get_keystores().await.unwrap().data;
+        let to_delete = vec![
+            keystores[0].validating_pubkey,
+            keystores[2].validating_pubkey,
+        ];
+
+        let config = DeleteConfig {
+            vc_url: vc.url.clone(),
+            vc_token_path,
+            validators: to_delete.clone(),
+            slashing_protection_path: slashing_protection_path.clone(),
+        };
+        assert_eq!(run(config.clone()).await, Ok(()));
+
+        let remaining = vc.client.get_keystores().await.unwrap().data;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(
+            remaining[0].validating_pubkey,
+            keystores[1].validating_pubkey
+        );
+
+        let contents = fs::read_to_string(&slashing_protection_path).unwrap();
+        let interchange: Interchange = serde_json::from_str(&contents).unwrap();
+        let mut saved = interchange
+            .data
+            .iter()
+            .map(|data| data.pubkey)
+            .collect::<Vec<_>>();
+        let mut expected = to_delete;
+        saved.sort_unstable_by_key(PublicKeyBytes::serialize);
+        expected.sort_unstable_by_key(PublicKeyBytes::serialize);
+        assert_eq!(saved, expected);
+
+        // The interchange file is never overwritten.
+        assert!(run(config).await.is_err());
+    }
+}
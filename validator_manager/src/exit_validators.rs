@@ -0,0 +1,494 @@
+use super::common::*;
+use crate::move_validators::Validators;
+use crate::DumpConfig;
+use account_utils::read_input_from_user;
+use clap::{App, Arg, ArgMatches};
+use eth2::{BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::{Epoch, PublicKeyBytes, SignedVoluntaryExit};
+
+pub const CMD: &str = "exit";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const EXIT_EPOCH_FLAG: &str = "exit-epoch";
+pub const EXITS_FILE_FLAG: &str = "exits-file";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const BROADCAST_INTERVAL_FLAG: &str = "broadcast-interval";
+pub const NO_CONFIRMATION_FLAG: &str = "no-confirmation";
+
+pub const CONFIRMATION_PHRASE: &str = "Exit my validators";
+
+const BEACON_NODE_HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Creates voluntary exits for validators using the HTTP API of a validator client. \
+                The signed exits can be written to a JSON file to be broadcast later and/or \
+                broadcast to a beacon node at a limited rate. Exits previously written to a \
+                file can be broadcast with --exits-file. A voluntary exit cannot be reversed.",
+        )
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a validator client using the keymanager-API. \
+                    This validator client signs the exits.",
+                )
+                .default_value("http://localhost:5062")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .required_unless(EXITS_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "The validators to be exited. Either a list of 0x-prefixed \
+                    validator pubkeys or the keyword \"all\".",
+                )
+                .required_unless(EXITS_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXIT_EPOCH_FLAG)
+                .long(EXIT_EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch of the exits. Defaults to the current epoch of the validator \
+                    client. Exits signed for the current epoch remain valid until the next \
+                    fork which changes the exit signature domain.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXITS_FILE_FLAG)
+                .long(EXITS_FILE_FLAG)
+                .value_name("PATH_TO_JSON_FILE")
+                .help(
+                    "The path to a JSON file of signed exits previously created with \
+                    --output-path. The exits are broadcast without contacting a validator \
+                    client.",
+                )
+                .conflicts_with_all(&[VC_TOKEN_FLAG, VALIDATORS_FLAG, EXIT_EPOCH_FLAG])
+                .requires(BEACON_NODE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("PATH_TO_JSON_FILE")
+                .help(
+                    "The path of a JSON file to write the signed exits to. The file must not \
+                    exist.",
+                )
+                .required_unless(BEACON_NODE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a beacon node using the beacon-API. If present, the \
+                    signed exits are broadcast to this beacon node.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BROADCAST_INTERVAL_FLAG)
+                .long(BROADCAST_INTERVAL_FLAG)
+                .value_name("MILLISECONDS")
+                .help("The delay between broadcasting two exits to the beacon node.")
+                .default_value("1000")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(NO_CONFIRMATION_FLAG)
+                .long(NO_CONFIRMATION_FLAG)
+                .help("Broadcast the exits without asking for the exit phrase.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ExitConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: Option<PathBuf>,
+    pub validators: Option<Validators>,
+    pub exit_epoch: Option<Epoch>,
+    pub exits_file_path: Option<PathBuf>,
+    pub output_path: Option<PathBuf>,
+    pub bn_url: Option<SensitiveUrl>,
+    pub broadcast_interval_millis: u64,
+    pub no_confirmation: bool,
+    pub stdin_inputs: bool,
+}
+
+impl ExitConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validators = match matches.value_of(VALIDATORS_FLAG) {
+            None => None,
+            Some("all") => Some(Validators::All),
+            Some(pubkeys) => pubkeys
+                .split(',')
+                .map(PublicKeyBytes::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Validators::Specific)
+                .map(Some)?,
+        };
+
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_optional(matches, VC_TOKEN_FLAG)?,
+            validators,
+            exit_epoch: clap_utils::parse_optional(matches, EXIT_EPOCH_FLAG)?,
+            exits_file_path: clap_utils::parse_optional(matches, EXITS_FILE_FLAG)?,
+            output_path: clap_utils::parse_optional(matches, OUTPUT_PATH_FLAG)?,
+            bn_url: clap_utils::parse_optional(matches, BEACON_NODE_FLAG)?,
+            broadcast_interval_millis: clap_utils::parse_required(
+                matches,
+                BROADCAST_INTERVAL_FLAG,
+            )?,
+            no_confirmation: matches.is_present(NO_CONFIRMATION_FLAG),
+            stdin_inputs: cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG),
+        })
+    }
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = ExitConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+/// Requests a signed exit for each of the `validators` from the validator client.
+async fn sign_exits(
+    vc_url: SensitiveUrl,
+    vc_token_path: PathBuf,
+    validators: Validators,
+    exit_epoch: Option<Epoch>,
+) -> Result<Vec<SignedVoluntaryExit>, String> {
+    let (http_client, keystores) = vc_http_client(vc_url.clone(), &vc_token_path).await?;
+
+    let pubkeys: Vec<PublicKeyBytes> = match validators {
+        Validators::All => keystores.iter().map(|v| v.validating_pubkey).collect(),
+        Validators::Count(count) => {
+            return Err(format!(
+                "Exiting a count of {} validators is not supported",
+                count
+            ))
+        }
+        Validators::Specific(request_pubkeys) => {
+            let vc_pubkeys: HashSet<_> = keystores.iter().map(|v| &v.validating_pubkey).collect();
+            let missing = request_pubkeys
+                .iter()
+                .filter(|pubkey| !vc_pubkeys.contains(pubkey))
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                for pubkey in &missing {
+                    eprintln!("{:?} is not present on {:?}", pubkey, vc_url);
+                }
+                return Err(format!(
+                    "{} validators not found on {:?}",
+                    missing.len(),
+                    vc_url
+                ));
+            }
+            request_pubkeys
+        }
+    };
+
+    if pubkeys.is_empty() {
+        return Err("No validators to exit".to_string());
+    }
+
+    let count = pubkeys.len();
+    let mut exits = Vec::with_capacity(count);
+    for (i, pubkey) in pubkeys.iter().enumerate() {
+        let exit = http_client
+            .post_validator_voluntary_exit(pubkey, exit_epoch)
+            .await
+            .map_err(|e| format!("Failed to sign exit for {:?}: {:?}", pubkey, e))?
+            .data;
+        eprintln!(
+            "Signed exit {}/{} for validator {} at epoch {}",
+            i.saturating_add(1),
+            count,
+            exit.message.validator_index,
+            exit.message.epoch
+        );
+        exits.push(exit);
+    }
+
+    Ok(exits)
+}
+
+/// Publishes `exits` to the beacon node, waiting `interval` between two exits.
+///
+/// Failures are reported without stopping the broadcast of the remaining exits.
+async fn broadcast_exits(
+    bn_url: SensitiveUrl,
+    exits: &[SignedVoluntaryExit],
+    interval: Duration,
+) -> Result<(), String> {
+    let bn_http_client =
+        BeaconNodeHttpClient::new(bn_url, Timeouts::set_all(BEACON_NODE_HTTP_TIMEOUT));
+
+    let mut failures = 0;
+    for (i, exit) in exits.iter().enumerate() {
+        if i > 0 {
+            sleep(interval).await;
+        }
+        match bn_http_client.post_beacon_pool_voluntary_exits(exit).await {
+            Ok(()) => eprintln!(
+                "Broadcast exit {}/{} for validator {}",
+                i.saturating_add(1),
+                exits.len(),
+                exit.message.validator_index
+            ),
+            Err(e) => {
+                failures += 1;
+                eprintln!(
+                    "Failed to broadcast exit for validator {}: {:?}",
+                    exit.message.validator_index, e
+                );
+            }
+        }
+    }
+
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to broadcast {} of {} exits",
+            failures,
+            exits.len()
+        ))
+    }
+}
+
+async fn run<'a>(config: ExitConfig) -> Result<(), String> {
+    let ExitConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        exit_epoch,
+        exits_file_path,
+        output_path,
+        bn_url,
+        broadcast_interval_millis,
+        no_confirmation,
+        stdin_inputs,
+    } = config;
+
+    if output_path.is_none() && bn_url.is_none() {
+        return Err(format!(
+            "At least one of --{} or --{} is required",
+            OUTPUT_PATH_FLAG, BEACON_NODE_FLAG
+        ));
+    }
+    if let Some(output_path) = &output_path {
+        if output_path.exists() {
+            return Err(format!(
+                "{:?} already exists, refusing to overwrite",
+                output_path
+            ));
+        }
+    }
+
+    let exits: Vec<SignedVoluntaryExit> = if let Some(exits_file_path) = exits_file_path {
+        let contents = fs::read_to_string(&exits_file_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", exits_file_path, e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Unable to parse {:?}: {:?}", exits_file_path, e))?
+    } else {
+        let vc_token_path =
+            vc_token_path.ok_or_else(|| format!("--{} is required", VC_TOKEN_FLAG))?;
+        let validators = validators.ok_or_else(|| format!("--{} is required", VALIDATORS_FLAG))?;
+        sign_exits(vc_url, vc_token_path, validators, exit_epoch).await?
+    };
+
+    if let Some(output_path) = &output_path {
+        write_to_json_file(output_path, &exits)?;
+    }
+
+    if let Some(bn_url) = bn_url {
+        if !no_confirmation {
+            eprintln!(
+                "Broadcasting voluntary exits for {} validators.\n",
+                exits.len()
+            );
+            eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
+            eprintln!("Enter \"{}\" to confirm the exits: ", CONFIRMATION_PHRASE);
+            if read_input_from_user(stdin_inputs)? != CONFIRMATION_PHRASE {
+                return Err(
+                    "Exits were not broadcast since the exit phrase was incorrect. \
+                    Any exits written to --output-path can be broadcast later with \
+                    --exits-file."
+                        .to_string(),
+                );
+            }
+        }
+
+        broadcast_exits(
+            bn_url,
+            &exits,
+            Duration::from_millis(broadcast_interval_millis),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
+    use tempfile::{tempdir, TempDir};
+    use validator_client::http_api::test_utils::ApiTester;
+
+    const VC_TOKEN_FILE_NAME: &str = "vc_token.json";
+    const EXITS_FILE_NAME: &str = "exits.json";
+
+    struct TestBuilder {
+        vc: ApiTester,
+        dir: TempDir,
+        config: ExitConfig,
+    }
+
+    impl TestBuilder {
+        /// Imports `count` validators to a VC and assigns them the validator indices
+        /// `0..count`.
+        async fn with_validators(count: u32) -> Self {
+            let vc = ImportTestBuilder::new()
+                .await
+                .create_validators(count, 0)
+                .await
+                .import_validators_without_checks()
+                .await
+                .vc;
+
+            let keystores = vc.client.get_keystores().await.unwrap().data;
+            for (index, keystore) in keystores.iter().enumerate() {
+                vc.initialized_validators
+                    .write()
+                    .set_index(&keystore.validating_pubkey, index as u64);
+            }
+
+            let dir = tempdir().unwrap();
+            let vc_token_path = dir.path().join(VC_TOKEN_FILE_NAME);
+            fs::write(&vc_token_path, &vc.api_token).unwrap();
+
+            let config = ExitConfig {
+                vc_url: vc.url.clone(),
+                vc_token_path: Some(vc_token_path),
+                validators: Some(Validators::All),
+                exit_epoch: None,
+                exits_file_path: None,
+                output_path: Some(dir.path().join(EXITS_FILE_NAME)),
+                bn_url: None,
+                broadcast_interval_millis: 0,
+                no_confirmation: true,
+                stdin_inputs: false,
+            };
+
+            Self { vc, dir, config }
+        }
+
+        fn mutate_config<F: Fn(&mut ExitConfig)>(mut self, func: F) -> Self {
+            func(&mut self.config);
+            self
+        }
+
+        async fn run_test(self) -> (Result<(), String>, Vec<SignedVoluntaryExit>) {
+            let result = run(self.config).await;
+            let exits_path = self.dir.path().join(EXITS_FILE_NAME);
+            let exits = if exits_path.exists() {
+                serde_json::from_str(&fs::read_to_string(exits_path).unwrap()).unwrap()
+            } else {
+                vec![]
+            };
+            drop(self.vc);
+            (result, exits)
+        }
+    }
+
+    #[tokio::test]
+    async fn exits_all_validators_to_file() {
+        let (result, exits) = TestBuilder::with_validators(3).await.run_test().await;
+        assert_eq!(result, Ok(()));
+
+        let mut indices = exits
+            .iter()
+            .map(|exit| exit.message.validator_index)
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert!(exits.iter().all(|exit| exit.message.epoch == Epoch::new(0)));
+    }
+
+    #[tokio::test]
+    async fn exits_specific_validators_at_epoch() {
+        let builder = TestBuilder::with_validators(2).await;
+        let keystores = builder.vc.client.get_keystores().await.unwrap().data;
+        let pubkey = keystores[1].validating_pubkey;
+
+        let (result, exits) = builder
+            .mutate_config(|config| {
+                config.validators = Some(Validators::Specific(vec![pubkey]));
+                config.exit_epoch = Some(Epoch::new(42));
+            })
+            .run_test()
+            .await;
+        assert_eq!(result, Ok(()));
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].message.validator_index, 1);
+        assert_eq!(exits[0].message.epoch, Epoch::new(42));
+    }
+
+    #[tokio::test]
+    async fn unknown_validator() {
+        let (result, exits) = TestBuilder::with_validators(1)
+            .await
+            .mutate_config(|config| {
+                config.validators = Some(Validators::Specific(vec![PublicKeyBytes::empty()]));
+            })
+            .run_test()
+            .await;
+        assert!(result.unwrap_err().contains("validators not found"));
+        assert!(exits.is_empty());
+    }
+}
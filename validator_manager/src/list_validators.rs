@@ -0,0 +1,230 @@
+use super::common::*;
+use crate::DumpConfig;
+use clap::{App, Arg, ArgMatches};
+use eth2::{
+    lighthouse_vc::http_client::ValidatorClientHttpClient,
+    types::{StateId, ValidatorId, ValidatorStatus},
+    BeaconNodeHttpClient, SensitiveUrl, StatusCode, Timeouts,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use types::{Address, PublicKeyBytes};
+
+pub const CMD: &str = "list";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+
+const BEACON_NODE_HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
+/// The number of validators requested from the beacon node at once, to keep URLs short.
+const BEACON_NODE_BATCH_SIZE: usize = 64;
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Lists the validators of a validator client using the HTTP API. The status, index \
+                and balance of each validator are read from a beacon node if one is given. The \
+                list is printed to stdout as JSON.",
+        )
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a validator client using the keymanager-API.")
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a beacon node using the beacon-API. If present, the \
+                    status, index and balance of each validator are read from its head state.",
+                )
+                .takes_value(true),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ListConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub bn_url: Option<SensitiveUrl>,
+}
+
+impl ListConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            bn_url: clap_utils::parse_optional(matches, BEACON_NODE_FLAG)?,
+        })
+    }
+}
+
+/// A validator of the validator client, along with its state on the beacon chain.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ValidatorListing {
+    pub pubkey: PublicKeyBytes,
+    pub derivation_path: Option<String>,
+    pub readonly: Option<bool>,
+    pub fee_recipient: Option<Address>,
+    /// The fields below are only known when a beacon node is given and knows the validator.
+    pub status: Option<ValidatorStatus>,
+    pub index: Option<u64>,
+    pub balance: Option<u64>,
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = ListConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run<'a>(config: ListConfig) -> Result<(), String> {
+    let validators = list_validators(config).await?;
+    let json = serde_json::to_string_pretty(&validators)
+        .map_err(|e| format!("Failed to serialize validators: {:?}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+async fn list_validators(config: ListConfig) -> Result<Vec<ValidatorListing>, String> {
+    let ListConfig {
+        vc_url,
+        vc_token_path,
+        bn_url,
+    } = config;
+
+    let (http_client, keystores) = vc_http_client(vc_url, &vc_token_path).await?;
+
+    let mut validators = Vec::with_capacity(keystores.len());
+    for keystore in keystores {
+        let fee_recipient = get_fee_recipient(&http_client, &keystore.validating_pubkey).await?;
+        validators.push(ValidatorListing {
+            pubkey: keystore.validating_pubkey,
+            derivation_path: keystore.derivation_path,
+            readonly: keystore.readonly,
+            fee_recipient,
+            status: None,
+            index: None,
+            balance: None,
+        });
+    }
+
+    if let Some(bn_url) = bn_url {
+        let bn_http_client =
+            BeaconNodeHttpClient::new(bn_url, Timeouts::set_all(BEACON_NODE_HTTP_TIMEOUT));
+
+        let mut chain_validators = HashMap::new();
+        for chunk in validators.chunks(BEACON_NODE_BATCH_SIZE) {
+            let ids = chunk
+                .iter()
+                .map(|validator| ValidatorId::PublicKey(validator.pubkey))
+                .collect::<Vec<_>>();
+            let response = bn_http_client
+                .get_beacon_states_validators(StateId::Head, Some(&ids), None)
+                .await
+                .map_err(|e| format!("Failed to get validators from beacon node: {:?}", e))?
+                .ok_or("Beacon node does not have a head state")?;
+            chain_validators.extend(
+                response
+                    .data
+                    .into_iter()
+                    .map(|data| (data.validator.pubkey, data)),
+            );
+        }
+
+        for validator in &mut validators {
+            if let Some(data) = chain_validators.get(&validator.pubkey) {
+                validator.status = Some(data.status);
+                validator.index = Some(data.index);
+                validator.balance = Some(data.balance);
+            }
+        }
+        eprintln!(
+            "{} of {} validators are known to the beacon node",
+            chain_validators.len(),
+            validators.len()
+        );
+    }
+
+    Ok(validators)
+}
+
+/// Returns the fee recipient of `pubkey`, or `None` if the validator client has none configured.
+async fn get_fee_recipient(
+    http_client: &ValidatorClientHttpClient,
+    pubkey: &PublicKeyBytes,
+) -> Result<Option<Address>, String> {
+    match http_client.get_fee_recipient(pubkey).await {
+        Ok(response) => Ok(Some(response.ethaddress)),
+        Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => Ok(None),
+        Err(e) => Err(format!(
+            "Failed to get fee recipient of {:?}: {:?}",
+            pubkey, e
+        )),
+    }
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
+    use std::fs;
+    use tempfile::tempdir;
+    use validator_client::http_api::test_utils::TEST_DEFAULT_FEE_RECIPIENT;
+
+    #[tokio::test]
+    async fn lists_imported_validators() {
+        let vc = ImportTestBuilder::new()
+            .await
+            .create_validators(3, 0)
+            .await
+            .import_validators_without_checks()
+            .await
+            .vc;
+        let dir = tempdir().unwrap();
+        let vc_token_path = dir.path().join("vc_token.json");
+        fs::write(&vc_token_path, &vc.api_token).unwrap();
+
+        let validators = list_validators(ListConfig {
+            vc_url: vc.url.clone(),
+            vc_token_path,
+            bn_url: None,
+        })
+        .await
+        .unwrap();
+
+        let keystores = vc.client.get_keystores().await.unwrap().data;
+        assert_eq!(validators.len(), keystores.len());
+        for (validator, keystore) in validators.iter().zip(&keystores) {
+            assert_eq!(validator.pubkey, keystore.validating_pubkey);
+            assert_eq!(validator.derivation_path, keystore.derivation_path);
+            assert_eq!(validator.fee_recipient, Some(TEST_DEFAULT_FEE_RECIPIENT));
+            assert_eq!(validator.status, None);
+            assert_eq!(validator.index, None);
+            assert_eq!(validator.balance, None);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use super::common::*;
+use crate::DumpConfig;
+use account_utils::{read_password_from_user, read_password_string, ZeroizeString};
+use bls::threshold::split_secret_key;
+use clap::{App, Arg, ArgMatches};
+use eth2_keystore::{Keystore, KeystoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use types::{Keypair, PublicKeyBytes};
+
+pub const CMD: &str = "split-key";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+pub const THRESHOLD_FLAG: &str = "threshold";
+pub const SHARES_FLAG: &str = "shares";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+
+pub const THRESHOLD_FILENAME: &str = "threshold.json";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Splits the key of a validator keystore into shares for threshold signing. Each \
+                share is written to a keystore encrypted with the password of the original \
+                keystore, along with a threshold.json file describing the shares. Each share \
+                must be imported into a different validator client.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("PATH_TO_KEYSTORE")
+                .help("The path of the EIP-2335 keystore of the validator to split.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "A file containing the password of the keystore. If absent, the password \
+                    is prompted for.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(THRESHOLD_FLAG)
+                .long(THRESHOLD_FLAG)
+                .value_name("INTEGER")
+                .help(
+                    "The number of shares required to produce a signature. Must be at least 2 \
+                    and more than half of --shares.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SHARES_FLAG)
+                .long(SHARES_FLAG)
+                .value_name("INTEGER")
+                .help("The number of shares to split the key into.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_DIR_FLAG)
+                .long(OUTPUT_DIR_FLAG)
+                .value_name("DIRECTORY")
+                .help(
+                    "The directory to write the share keystores to. It is created if it does \
+                    not exist, existing files are never overwritten.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SplitKeyConfig {
+    pub keystore_path: PathBuf,
+    pub password_path: Option<PathBuf>,
+    pub threshold: usize,
+    pub shares: usize,
+    pub output_dir: PathBuf,
+    pub stdin_inputs: bool,
+}
+
+impl SplitKeyConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(Self {
+            keystore_path: clap_utils::parse_required(matches, KEYSTORE_FLAG)?,
+            password_path: clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?,
+            threshold: clap_utils::parse_required(matches, THRESHOLD_FLAG)?,
+            shares: clap_utils::parse_required(matches, SHARES_FLAG)?,
+            output_dir: clap_utils::parse_required(matches, OUTPUT_DIR_FLAG)?,
+            stdin_inputs: cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG),
+        })
+    }
+}
+
+/// Describes the shares of a split validator key, written to `THRESHOLD_FILENAME`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ThresholdShares {
+    pub voting_public_key: PublicKeyBytes,
+    pub threshold: usize,
+    pub shares: Vec<ThresholdShare>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ThresholdShare {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub share_index: u64,
+    pub share_public_key: PublicKeyBytes,
+    pub keystore_filename: String,
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = SplitKeyConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config)
+    }
+}
+
+fn run(config: SplitKeyConfig) -> Result<(), String> {
+    let SplitKeyConfig {
+        keystore_path,
+        password_path,
+        threshold,
+        shares,
+        output_dir,
+        stdin_inputs,
+    } = config;
+
+    // A single share could sign on its own, which defeats the purpose of splitting the key. A
+    // threshold of half the shares or less allows two disjoint groups of shares to sign
+    // conflicting messages without any share seeing both.
+    if threshold < 2 || threshold > shares || 2 * threshold <= shares {
+        return Err(format!(
+            "--{} must be at least 2, more than half of --{} and at most --{}",
+            THRESHOLD_FLAG, SHARES_FLAG, SHARES_FLAG
+        ));
+    }
+
+    let keystore = Keystore::from_json_file(&keystore_path)
+        .map_err(|e| format!("Unable to read keystore {:?}: {:?}", keystore_path, e))?;
+    let password: ZeroizeString = if let Some(password_path) = password_path {
+        read_password_string(&password_path)?
+    } else {
+        eprintln!("Enter the password for keystore {:?}:", keystore_path);
+        read_password_from_user(stdin_inputs)?
+    };
+    let keypair = keystore
+        .decrypt_keypair(password.as_ref())
+        .map_err(|e| format!("Unable to decrypt keystore {:?}: {:?}", keystore_path, e))?;
+
+    let secret_shares = split_secret_key(&keypair.sk, threshold, shares)
+        .map_err(|e| format!("Unable to split key: {:?}", e))?;
+
+    if !output_dir.exists() {
+        fs::create_dir_all(&output_dir)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", output_dir, e))?;
+    }
+
+    let mut threshold_shares = ThresholdShares {
+        voting_public_key: keypair.pk.compress(),
+        threshold,
+        shares: Vec::with_capacity(shares),
+    };
+    for (share_index, secret_key) in secret_shares {
+        let share_keypair = Keypair::from_components(secret_key.public_key(), secret_key);
+        let share_keystore = KeystoreBuilder::new(
+            &share_keypair,
+            password.as_ref(),
+            keystore.path().unwrap_or_default(),
+        )
+        .and_then(|builder| {
+            builder
+                .description(format!(
+                    "Share {} of {} of {:?}",
+                    share_index,
+                    shares,
+                    keypair.pk.compress()
+                ))
+                .build()
+        })
+        .map_err(|e| {
+            format!(
+                "Unable to build keystore for share {}: {:?}",
+                share_index, e
+            )
+        })?;
+
+        let keystore_filename = format!("share-{}.json", share_index);
+        write_to_json_file(output_dir.join(&keystore_filename), &share_keystore)?;
+        threshold_shares.shares.push(ThresholdShare {
+            share_index,
+            share_public_key: share_keypair.pk.compress(),
+            keystore_filename,
+        });
+    }
+
+    write_to_json_file(output_dir.join(THRESHOLD_FILENAME), &threshold_shares)?;
+    eprintln!(
+        "Split {:?} into {} shares with a threshold of {}",
+        threshold_shares.voting_public_key, shares, threshold
+    );
+    eprintln!(
+        "Store each share on a different host and securely delete {:?} once the shares are \
+        in use",
+        keystore_path
+    );
+
+    Ok(())
+}
+
+// The key derivation function of the share keystores is slow in debug builds.
+#[cfg(all(test, not(debug_assertions)))]
+mod test {
+    use super::*;
+    use bls::threshold::combine_signatures;
+    use tempfile::tempdir;
+    use types::Hash256;
+
+    #[test]
+    fn split_key_shares_combine_to_original_signature() {
+        let dir = tempdir().unwrap();
+        let keypair = Keypair::random();
+        let password = "hunter2";
+        let keystore = KeystoreBuilder::new(&keypair, password.as_bytes(), String::new())
+            .unwrap()
+            .build()
+            .unwrap();
+        let keystore_path = dir.path().join("keystore.json");
+        fs::write(&keystore_path, keystore.to_json_string().unwrap()).unwrap();
+        let password_path = dir.path().join("password.txt");
+        fs::write(&password_path, password).unwrap();
+        let output_dir = dir.path().join("shares");
+
+        let config = SplitKeyConfig {
+            keystore_path,
+            password_path: Some(password_path),
+            threshold: 2,
+            shares: 3,
+            output_dir: output_dir.clone(),
+            stdin_inputs: false,
+        };
+        assert_eq!(run(config.clone()), Ok(()));
+
+        let contents = fs::read_to_string(output_dir.join(THRESHOLD_FILENAME)).unwrap();
+        let threshold_shares: ThresholdShares = serde_json::from_str(&contents).unwrap();
+        assert_eq!(threshold_shares.voting_public_key, keypair.pk.compress());
+        assert_eq!(threshold_shares.shares.len(), 3);
+
+        let message = Hash256::repeat_byte(42);
+        let signatures = threshold_shares.shares[1..]
+            .iter()
+            .map(|share| {
+                let share_keystore =
+                    Keystore::from_json_file(output_dir.join(&share.keystore_filename)).unwrap();
+                let share_keypair = share_keystore.decrypt_keypair(password.as_bytes()).unwrap();
+                assert_eq!(share_keypair.pk.compress(), share.share_public_key);
+                (share.share_index, share_keypair.sk.sign(message))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            combine_signatures(&signatures).unwrap(),
+            keypair.sk.sign(message)
+        );
+
+        // Existing shares are never overwritten.
+        assert!(run(config).is_err());
+    }
+
+    #[test]
+    fn split_key_rejects_minority_threshold() {
+        let dir = tempdir().unwrap();
+        let keypair = Keypair::random();
+        let password = "hunter2";
+        let keystore = KeystoreBuilder::new(&keypair, password.as_bytes(), String::new())
+            .unwrap()
+            .build()
+            .unwrap();
+        let keystore_path = dir.path().join("keystore.json");
+        fs::write(&keystore_path, keystore.to_json_string().unwrap()).unwrap();
+        let password_path = dir.path().join("password.txt");
+        fs::write(&password_path, password).unwrap();
+        let output_dir = dir.path().join("shares");
+
+        // Two disjoint pairs of a 2-of-4 split could sign conflicting messages.
+        let config = SplitKeyConfig {
+            keystore_path,
+            password_path: Some(password_path),
+            threshold: 2,
+            shares: 4,
+            output_dir: output_dir.clone(),
+            stdin_inputs: false,
+        };
+        assert!(run(config).is_err());
+        assert!(!output_dir.exists());
+    }
+}